@@ -0,0 +1,240 @@
+/*
+ * Exercises the C API on an ELF64 file, /proc/self/exe by default.
+ */
+#include <assert.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "rs_elf.h"
+
+static uint8_t *read_file(const char *path, size_t *len) {
+    FILE *f = fopen(path, "rb");
+    uint8_t *buf = NULL;
+    size_t cap = 0;
+
+    assert(f != NULL);
+    *len = 0;
+    for (;;) {
+        if (*len == cap) {
+            cap = cap ? cap * 2 : 65536;
+            buf = realloc(buf, cap);
+            assert(buf != NULL);
+        }
+        size_t n = fread(buf + *len, 1, cap - *len, f);
+        if (n == 0) {
+            break;
+        }
+        *len += n;
+    }
+    fclose(f);
+    return buf;
+}
+
+static void test_errors(void) {
+    static const uint8_t garbage[64] = {0x7f, 'E', 'L', 'F'};
+    RsElfHeader header;
+
+    assert(rs_elf64_parse(garbage, sizeof(garbage)) == NULL);
+    assert(rs_elf_last_error() == RS_ELF_ERROR_PARSE_ERROR);
+    assert(rs_elf_last_error_message() != NULL);
+    printf("garbage: %s\n", rs_elf_last_error_message());
+
+    assert(rs_elf64_get_header(NULL, &header) == RS_ELF_ERROR_NULL_POINTER);
+    assert(rs_elf_last_error() == RS_ELF_ERROR_NULL_POINTER);
+    assert(rs_elf64_get_number_of_segments(NULL) == 0);
+}
+
+static void test_file(const Elf64 *e) {
+    RsElfHeader header;
+    RsElfSegment segment;
+    RsElfSectionHeader section;
+    RsElfSymbol symbol;
+    RsElfDynamic dynamic;
+    RsElfNote note;
+    size_t i, n, idx;
+
+    assert(rs_elf64_get_header(e, &header) == RS_ELF_ERROR_OK);
+    assert(header.ei_class == 2);
+    printf("type %u machine %u entry 0x%llx\n", header.e_type, header.e_machine,
+           (unsigned long long)header.e_entry);
+
+    n = rs_elf64_get_number_of_segments(e);
+    assert(n == header.e_phnum);
+    for (i = 0; i < n; i++) {
+        assert(rs_elf64_get_segment(e, i, &segment) == RS_ELF_ERROR_OK);
+        printf("segment %zu: type 0x%x flags %x vaddr 0x%llx memsz 0x%llx\n", i,
+               segment.p_type, segment.p_flags, (unsigned long long)segment.p_vaddr,
+               (unsigned long long)segment.p_memsz);
+    }
+    assert(rs_elf64_get_segment(e, n, &segment) == RS_ELF_ERROR_OUT_OF_BOUNDS);
+
+    n = rs_elf64_get_number_of_section_headers(e);
+    assert(n == header.e_shnum);
+    for (i = 0; i < n; i++) {
+        assert(rs_elf64_get_section_header(e, i, &section) == RS_ELF_ERROR_OK);
+        printf("section %zu: %s size 0x%llx\n", i, section.name,
+               (unsigned long long)section.sh_size);
+    }
+
+    assert(rs_elf64_find_section(e, ".text", &idx) == RS_ELF_ERROR_OK);
+    assert(rs_elf64_get_section_header(e, idx, &section) == RS_ELF_ERROR_OK);
+    assert(strcmp(section.name, ".text") == 0);
+    assert(rs_elf64_find_section(e, ".no_such_section", &idx) ==
+           RS_ELF_ERROR_MISSING_SECTION);
+
+    assert(rs_elf64_get_number_of_symbols(e, RS_ELF_SYMBOL_TABLE_DYNSYM, &n) == RS_ELF_ERROR_OK);
+    printf("%zu dynamic symbols\n", n);
+    for (i = 0; i < n; i++) {
+        assert(rs_elf64_get_symbol(e, RS_ELF_SYMBOL_TABLE_DYNSYM, i, &symbol) ==
+               RS_ELF_ERROR_OK);
+        if (symbol.name[0] != '\0') {
+            printf("  %016llx %s\n", (unsigned long long)symbol.value, symbol.name);
+        }
+    }
+    assert(rs_elf64_get_symbol(e, RS_ELF_SYMBOL_TABLE_DYNSYM, n, &symbol) !=
+           RS_ELF_ERROR_OK);
+
+    assert(rs_elf64_get_number_of_dynamic_entries(e, &n) == RS_ELF_ERROR_OK);
+    for (i = 0; i < n; i++) {
+        assert(rs_elf64_get_dynamic_entry(e, i, &dynamic) == RS_ELF_ERROR_OK);
+        printf("dynamic 0x%llx 0x%llx %s\n", (unsigned long long)dynamic.tag,
+               (unsigned long long)dynamic.val, dynamic.string ? dynamic.string : "");
+    }
+
+    assert(rs_elf64_get_number_of_notes(e, &n) == RS_ELF_ERROR_OK);
+    for (i = 0; i < n; i++) {
+        assert(rs_elf64_get_note(e, i, &note) == RS_ELF_ERROR_OK);
+        printf("note %.*s type %u size %zu\n", (int)note.name_len, note.name, note.n_type,
+               note.desc_len);
+    }
+}
+
+static void test_generic(const uint8_t *buf, size_t len) {
+    RsElfIdent ident;
+    RsElfHeader header;
+    RsElfSymbol symbol;
+    uint8_t *swapped;
+    size_t n, i, idx;
+    ElfHandle *e;
+
+    assert(rs_elf_identify(buf, len, &ident) == RS_ELF_ERROR_OK);
+    printf("class %u data %u machine %u\n", ident.ei_class, ident.ei_data, ident.e_machine);
+
+    e = rs_elf_parse(buf, len);
+    assert(e != NULL);
+    assert(rs_elf_get_class(e) == ident.ei_class);
+    assert(rs_elf_get_endianness(e) == ident.ei_data);
+    assert(rs_elf_get_machine(e) == ident.e_machine);
+    assert(rs_elf_get_header(e, &header) == RS_ELF_ERROR_OK);
+    assert(header.e_machine == ident.e_machine);
+    assert(rs_elf_get_number_of_segments(e) == header.e_phnum);
+    assert(rs_elf_find_section(e, ".text", &idx) == RS_ELF_ERROR_OK);
+
+    assert(rs_elf_get_number_of_symbols(e, RS_ELF_SYMBOL_TABLE_DYNSYM, &n) == RS_ELF_ERROR_OK);
+    assert(rs_elf_get_number_of_symbols(e, RS_ELF_SYMBOL_TABLE_DYNSYM, NULL) ==
+           RS_ELF_ERROR_NULL_POINTER);
+    for (i = 0; i < n; i++) {
+        assert(rs_elf_get_symbol(e, RS_ELF_SYMBOL_TABLE_DYNSYM, i, &symbol) == RS_ELF_ERROR_OK);
+    }
+    rs_elf_free(e);
+
+    /* Big-endian files are identified but not parsed */
+    swapped = malloc(len);
+    assert(swapped != NULL);
+    memcpy(swapped, buf, len);
+    swapped[5] = 2;
+    assert(rs_elf_identify(swapped, len, &ident) == RS_ELF_ERROR_OK);
+    assert(ident.ei_data == 2);
+    assert(rs_elf_parse(swapped, len) == NULL);
+    assert(rs_elf_last_error() == RS_ELF_ERROR_UNSUPPORTED);
+    free(swapped);
+}
+
+static void test_owning(const char *path, const uint8_t *buf, size_t len) {
+    const uint8_t *data;
+    uint8_t *copy;
+    size_t idx, size;
+    ElfHandle *e;
+
+    assert(rs_elf_parse(NULL, len) == NULL);
+    assert(rs_elf_last_error() == RS_ELF_ERROR_NULL_POINTER);
+    assert(rs_elf_open_path("/nonexistent") == NULL);
+    assert(rs_elf_last_error() == RS_ELF_ERROR_IO);
+
+    /* The copy owns its data, the buffer can be released right away */
+    copy = malloc(len);
+    assert(copy != NULL);
+    memcpy(copy, buf, len);
+    e = rs_elf_parse_copy(copy, len);
+    memset(copy, 0, len);
+    free(copy);
+    assert(e != NULL);
+    assert(rs_elf_get_data(e, 0, 4, &data) == RS_ELF_ERROR_OK);
+    assert(memcmp(data, "\177ELF", 4) == 0);
+    assert(rs_elf_get_data(e, len - 1, 2, &data) == RS_ELF_ERROR_OUT_OF_BOUNDS);
+    assert(rs_elf_get_data(e, (size_t)-1, 2, &data) == RS_ELF_ERROR_OUT_OF_BOUNDS);
+    rs_elf_free(e);
+
+    e = rs_elf_open_path(path);
+    assert(e != NULL);
+    assert(rs_elf_find_section(e, ".text", &idx) == RS_ELF_ERROR_OK);
+    assert(rs_elf_get_section_data(e, idx, &data, &size) == RS_ELF_ERROR_OK);
+    assert(size > 0 && data != NULL);
+    if (rs_elf_find_section(e, ".bss", &idx) == RS_ELF_ERROR_OK) {
+        assert(rs_elf_get_section_data(e, idx, &data, &size) == RS_ELF_ERROR_OK);
+        assert(size == 0);
+    }
+    assert(rs_elf_get_section_data(e, 100000, &data, &size) == RS_ELF_ERROR_OUT_OF_BOUNDS);
+    rs_elf_free(e);
+}
+
+static void test_bad_names(const uint8_t *buf, size_t len) {
+    RsElfHeader header;
+    RsElfSectionHeader section;
+    uint8_t *copy;
+    ElfHandle *e;
+
+    /* Section names out of the string table are NULL */
+    e = rs_elf_parse(buf, len);
+    assert(e != NULL);
+    assert(rs_elf_get_header(e, &header) == RS_ELF_ERROR_OK);
+    rs_elf_free(e);
+    assert(header.e_shnum > 1);
+
+    copy = malloc(len);
+    assert(copy != NULL);
+    memcpy(copy, buf, len);
+    memset(copy + header.e_shoff + header.e_shentsize, 0xff, 4);
+    e = rs_elf_parse(copy, len);
+    assert(e != NULL);
+    assert(rs_elf_get_section_header(e, 1, &section) == RS_ELF_ERROR_OK);
+    assert(section.sh_name == 0xffffffff);
+    assert(section.name == NULL);
+    rs_elf_free(e);
+    free(copy);
+}
+
+int main(int argc, char **argv) {
+    const char *path = argc > 1 ? argv[1] : "/proc/self/exe";
+    size_t len;
+    uint8_t *buf = read_file(path, &len);
+    Elf64 *e;
+
+    test_errors();
+
+    e = rs_elf64_parse(buf, len);
+    if (e == NULL) {
+        fprintf(stderr, "%s: %s\n", path, rs_elf_last_error_message());
+        return 1;
+    }
+    test_file(e);
+    rs_elf64_free_exe(e);
+    test_generic(buf, len);
+    test_owning(path, buf, len);
+    test_bad_names(buf, len);
+    free(buf);
+
+    printf("ok\n");
+    return 0;
+}
@@ -0,0 +1,704 @@
+/*
+ * C API of the elf crate.
+ *
+ * Ownership:
+ * - rs_elf_parse, rs_elf32_parse and rs_elf64_parse borrow the caller's
+ *   buffer, which must stay valid and unmodified until the handle is
+ *   released.
+ * - rs_elf_parse_copy and rs_elf_open_path return handles owning the file
+ *   data, the caller's buffer may be released right away.
+ * - Handles of the rs_elf_* functions are released with rs_elf_free, those
+ *   of rs_elf32_parse and rs_elf64_parse with rs_elf32_free_exe and
+ *   rs_elf64_free_exe.
+ * - Names and data pointers stored in output structures point into the file
+ *   data, and are valid as long as it is.
+ *
+ * Errors: on failure, functions return NULL or an RsElfError, and record a
+ * description available through rs_elf_last_error_message.
+ */
+
+#ifndef RS_ELF_H
+#define RS_ELF_H
+
+/* Generated with cbindgen from the crate sources, do not edit. Regenerate with: cbindgen --config cbindgen.toml --output include/rs_elf.h */
+
+#include <stddef.h>
+#include <stdint.h>
+
+struct Elf32;
+struct Elf64;
+void rs_elf32_free_exe(struct Elf32 *e);
+void rs_elf64_free_exe(struct Elf64 *e);
+
+typedef enum RsElfError {
+  RS_ELF_ERROR_OK = 0,
+  RS_ELF_ERROR_NULL_POINTER = 1,
+  RS_ELF_ERROR_PARSE_ERROR = 2,
+  RS_ELF_ERROR_MISSING_SECTION = 3,
+  RS_ELF_ERROR_UNSUPPORTED = 4,
+  RS_ELF_ERROR_OUT_OF_BOUNDS = 5,
+  RS_ELF_ERROR_IO = 6,
+} RsElfError;
+
+/**
+ * Symbol table selector.
+ */
+typedef enum RsElfSymbolTable {
+  RS_ELF_SYMBOL_TABLE_SYMTAB = 0,
+  RS_ELF_SYMBOL_TABLE_DYNSYM = 1,
+} RsElfSymbolTable;
+
+typedef struct Elf32 Elf32;
+
+typedef struct Elf64 Elf64;
+
+/**
+ * Handle of the `rs_elf_*` functions: a parsed file of either class, and
+ * the data it was parsed from when the handle owns it.
+ */
+typedef struct ElfHandle ElfHandle;
+
+/**
+ * File header, with addresses and offsets widened to 64 bits.
+ */
+typedef struct RsElfHeader {
+  uint8_t ei_class;
+  uint8_t ei_data;
+  uint8_t ei_osabi;
+  uint8_t ei_abiversion;
+  uint16_t e_type;
+  uint16_t e_machine;
+  uint32_t e_version;
+  uint64_t e_entry;
+  uint64_t e_phoff;
+  uint64_t e_shoff;
+  uint32_t e_flags;
+  uint16_t e_ehsize;
+  uint16_t e_phentsize;
+  uint16_t e_phnum;
+  uint16_t e_shentsize;
+  uint16_t e_shnum;
+  uint16_t e_shstrndx;
+} RsElfHeader;
+
+typedef struct RsElfSegment {
+  uint32_t p_type;
+  uint32_t p_flags;
+  uint64_t p_offset;
+  uint64_t p_vaddr;
+  uint64_t p_paddr;
+  uint64_t p_filesz;
+  uint64_t p_memsz;
+  uint64_t p_align;
+} RsElfSegment;
+
+typedef struct RsElfSectionHeader {
+  /**
+   * NUL-terminated name in the file data, or NULL.
+   */
+  const char *name;
+  uint32_t sh_name;
+  uint32_t sh_type;
+  uint64_t sh_flags;
+  uint64_t sh_addr;
+  uint64_t sh_offset;
+  uint64_t sh_size;
+  uint32_t sh_link;
+  uint32_t sh_info;
+  uint64_t sh_addralign;
+  uint64_t sh_entsize;
+} RsElfSectionHeader;
+
+typedef struct RsElfSymbol {
+  /**
+   * NUL-terminated name in the file data.
+   */
+  const char *name;
+  uint64_t value;
+  uint64_t size;
+  uint8_t bind;
+  uint8_t kind;
+  uint8_t visibility;
+  uint16_t shndx;
+} RsElfSymbol;
+
+typedef struct RsElfDynamic {
+  int64_t tag;
+  uint64_t val;
+  /**
+   * NUL-terminated string for `DT_NEEDED`, `DT_SONAME`, `DT_RPATH` and
+   * `DT_RUNPATH`, NULL otherwise.
+   */
+  const char *string;
+} RsElfDynamic;
+
+typedef struct RsElfNote {
+  uint32_t n_type;
+  /**
+   * Owner name in the file data, not necessarily NUL-terminated.
+   */
+  const char *name;
+  size_t name_len;
+  const uint8_t *desc;
+  size_t desc_len;
+} RsElfNote;
+
+/**
+ * Class, data encoding and machine of a file, readable for any ELF file.
+ */
+typedef struct RsElfIdent {
+  uint8_t ei_class;
+  uint8_t ei_data;
+  uint16_t e_machine;
+} RsElfIdent;
+
+/**
+ * Parses a 32-bit file, returning NULL on failure. The handle borrows `i`.
+ *
+ * # Safety
+ *
+ * `i` must be NULL or valid for reading `len` bytes, and stay valid and
+ * unmodified until the handle is released with `rs_elf32_free_exe`.
+ */
+struct Elf32 *rs_elf32_parse(const uint8_t *i, size_t len);
+
+/**
+ * Fills `out` with the file header.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle of `rs_elf32_parse` not released with
+ * `rs_elf32_free_exe` yet, and `out` NULL or valid for writing an
+ * `RsElfHeader`.
+ */
+enum RsElfError rs_elf32_get_header(const struct Elf32 *e, struct RsElfHeader *out);
+
+/**
+ * Returns the number of program headers, 0 for a NULL handle.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle of `rs_elf32_parse` not released with
+ * `rs_elf32_free_exe` yet.
+ */
+size_t rs_elf32_get_number_of_segments(const struct Elf32 *e);
+
+/**
+ * Fills `out` with the program header `idx`.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle of `rs_elf32_parse` not released with
+ * `rs_elf32_free_exe` yet, and `out` NULL or valid for writing an
+ * `RsElfSegment`.
+ */
+enum RsElfError rs_elf32_get_segment(const struct Elf32 *e, size_t idx, struct RsElfSegment *out);
+
+/**
+ * Returns the number of section headers, 0 for a NULL handle.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle of `rs_elf32_parse` not released with
+ * `rs_elf32_free_exe` yet.
+ */
+size_t rs_elf32_get_number_of_section_headers(const struct Elf32 *e);
+
+/**
+ * Fills `out` with the section header `idx` and its name.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle of `rs_elf32_parse` not released with
+ * `rs_elf32_free_exe` yet, and `out` NULL or valid for writing an
+ * `RsElfSectionHeader`.
+ */
+enum RsElfError rs_elf32_get_section_header(const struct Elf32 *e,
+                                            size_t idx,
+                                            struct RsElfSectionHeader *out);
+
+/**
+ * Stores in `idx` the index of the first section named `name`.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle of `rs_elf32_parse` not released with
+ * `rs_elf32_free_exe` yet, `name` NULL or a NUL-terminated string and
+ * `idx` NULL or valid for writing a `size_t`.
+ */
+enum RsElfError rs_elf32_find_section(const struct Elf32 *e, const char *name, size_t *idx);
+
+/**
+ * Stores in `count` the number of entries of a symbol table.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle of `rs_elf32_parse` not released with
+ * `rs_elf32_free_exe` yet, and `count` NULL or valid for writing a
+ * `size_t`.
+ */
+enum RsElfError rs_elf32_get_number_of_symbols(const struct Elf32 *e,
+                                               enum RsElfSymbolTable table,
+                                               size_t *count);
+
+/**
+ * Fills `out` with the entry `idx` of a symbol table.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle of `rs_elf32_parse` not released with
+ * `rs_elf32_free_exe` yet, and `out` NULL or valid for writing an
+ * `RsElfSymbol`.
+ */
+enum RsElfError rs_elf32_get_symbol(const struct Elf32 *e,
+                                    enum RsElfSymbolTable table,
+                                    size_t idx,
+                                    struct RsElfSymbol *out);
+
+/**
+ * Stores in `count` the number of dynamic entries before `DT_NULL`.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle of `rs_elf32_parse` not released with
+ * `rs_elf32_free_exe` yet, and `count` NULL or valid for writing a
+ * `size_t`.
+ */
+enum RsElfError rs_elf32_get_number_of_dynamic_entries(const struct Elf32 *e, size_t *count);
+
+/**
+ * Fills `out` with the dynamic entry `idx`. Unlike
+ * `rs_elf_get_dynamic_entry`, the entries are parsed again on each call.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle of `rs_elf32_parse` not released with
+ * `rs_elf32_free_exe` yet, and `out` NULL or valid for writing an
+ * `RsElfDynamic`.
+ */
+enum RsElfError rs_elf32_get_dynamic_entry(const struct Elf32 *e,
+                                           size_t idx,
+                                           struct RsElfDynamic *out);
+
+/**
+ * Stores in `count` the number of notes.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle of `rs_elf32_parse` not released with
+ * `rs_elf32_free_exe` yet, and `count` NULL or valid for writing a
+ * `size_t`.
+ */
+enum RsElfError rs_elf32_get_number_of_notes(const struct Elf32 *e, size_t *count);
+
+/**
+ * Fills `out` with the note `idx`. Unlike `rs_elf_get_note`, the notes are
+ * parsed again on each call.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle of `rs_elf32_parse` not released with
+ * `rs_elf32_free_exe` yet, and `out` NULL or valid for writing an
+ * `RsElfNote`.
+ */
+enum RsElfError rs_elf32_get_note(const struct Elf32 *e, size_t idx, struct RsElfNote *out);
+
+/**
+ * Parses a 64-bit file, returning NULL on failure. The handle borrows `i`.
+ *
+ * # Safety
+ *
+ * `i` must be NULL or valid for reading `len` bytes, and stay valid and
+ * unmodified until the handle is released with `rs_elf64_free_exe`.
+ */
+struct Elf64 *rs_elf64_parse(const uint8_t *i, size_t len);
+
+/**
+ * Fills `out` with the file header.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle of `rs_elf64_parse` not released with
+ * `rs_elf64_free_exe` yet, and `out` NULL or valid for writing an
+ * `RsElfHeader`.
+ */
+enum RsElfError rs_elf64_get_header(const struct Elf64 *e, struct RsElfHeader *out);
+
+/**
+ * Returns the number of program headers, 0 for a NULL handle.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle of `rs_elf64_parse` not released with
+ * `rs_elf64_free_exe` yet.
+ */
+size_t rs_elf64_get_number_of_segments(const struct Elf64 *e);
+
+/**
+ * Fills `out` with the program header `idx`.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle of `rs_elf64_parse` not released with
+ * `rs_elf64_free_exe` yet, and `out` NULL or valid for writing an
+ * `RsElfSegment`.
+ */
+enum RsElfError rs_elf64_get_segment(const struct Elf64 *e, size_t idx, struct RsElfSegment *out);
+
+/**
+ * Returns the number of section headers, 0 for a NULL handle.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle of `rs_elf64_parse` not released with
+ * `rs_elf64_free_exe` yet.
+ */
+size_t rs_elf64_get_number_of_section_headers(const struct Elf64 *e);
+
+/**
+ * Fills `out` with the section header `idx` and its name.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle of `rs_elf64_parse` not released with
+ * `rs_elf64_free_exe` yet, and `out` NULL or valid for writing an
+ * `RsElfSectionHeader`.
+ */
+enum RsElfError rs_elf64_get_section_header(const struct Elf64 *e,
+                                            size_t idx,
+                                            struct RsElfSectionHeader *out);
+
+/**
+ * Stores in `idx` the index of the first section named `name`.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle of `rs_elf64_parse` not released with
+ * `rs_elf64_free_exe` yet, `name` NULL or a NUL-terminated string and
+ * `idx` NULL or valid for writing a `size_t`.
+ */
+enum RsElfError rs_elf64_find_section(const struct Elf64 *e, const char *name, size_t *idx);
+
+/**
+ * Stores in `count` the number of entries of a symbol table.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle of `rs_elf64_parse` not released with
+ * `rs_elf64_free_exe` yet, and `count` NULL or valid for writing a
+ * `size_t`.
+ */
+enum RsElfError rs_elf64_get_number_of_symbols(const struct Elf64 *e,
+                                               enum RsElfSymbolTable table,
+                                               size_t *count);
+
+/**
+ * Fills `out` with the entry `idx` of a symbol table.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle of `rs_elf64_parse` not released with
+ * `rs_elf64_free_exe` yet, and `out` NULL or valid for writing an
+ * `RsElfSymbol`.
+ */
+enum RsElfError rs_elf64_get_symbol(const struct Elf64 *e,
+                                    enum RsElfSymbolTable table,
+                                    size_t idx,
+                                    struct RsElfSymbol *out);
+
+/**
+ * Stores in `count` the number of dynamic entries before `DT_NULL`.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle of `rs_elf64_parse` not released with
+ * `rs_elf64_free_exe` yet, and `count` NULL or valid for writing a
+ * `size_t`.
+ */
+enum RsElfError rs_elf64_get_number_of_dynamic_entries(const struct Elf64 *e, size_t *count);
+
+/**
+ * Fills `out` with the dynamic entry `idx`. Unlike
+ * `rs_elf_get_dynamic_entry`, the entries are parsed again on each call.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle of `rs_elf64_parse` not released with
+ * `rs_elf64_free_exe` yet, and `out` NULL or valid for writing an
+ * `RsElfDynamic`.
+ */
+enum RsElfError rs_elf64_get_dynamic_entry(const struct Elf64 *e,
+                                           size_t idx,
+                                           struct RsElfDynamic *out);
+
+/**
+ * Stores in `count` the number of notes.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle of `rs_elf64_parse` not released with
+ * `rs_elf64_free_exe` yet, and `count` NULL or valid for writing a
+ * `size_t`.
+ */
+enum RsElfError rs_elf64_get_number_of_notes(const struct Elf64 *e, size_t *count);
+
+/**
+ * Fills `out` with the note `idx`. Unlike `rs_elf_get_note`, the notes are
+ * parsed again on each call.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle of `rs_elf64_parse` not released with
+ * `rs_elf64_free_exe` yet, and `out` NULL or valid for writing an
+ * `RsElfNote`.
+ */
+enum RsElfError rs_elf64_get_note(const struct Elf64 *e, size_t idx, struct RsElfNote *out);
+
+/**
+ * Returns the code of the last error of the calling thread.
+ */
+enum RsElfError rs_elf_last_error(void);
+
+/**
+ * Returns the description of the last error of the calling thread, or NULL.
+ * The string is valid until the next error on this thread.
+ */
+const char *rs_elf_last_error_message(void);
+
+/**
+ * Fills `out` with the class, data encoding and machine of the file in
+ * `buf`, including files `rs_elf_parse` does not support.
+ *
+ * # Safety
+ *
+ * `buf` must be NULL or valid for reading `len` bytes during the call, and
+ * `out` NULL or valid for writing an `RsElfIdent`.
+ */
+enum RsElfError rs_elf_identify(const uint8_t *buf, size_t len, struct RsElfIdent *out);
+
+/**
+ * Parses a 32 or 64-bit file, returning NULL on failure. The handle
+ * borrows `buf`.
+ *
+ * # Safety
+ *
+ * `buf` must be NULL or valid for reading `len` bytes, and stay valid and
+ * unmodified until the handle is released with `rs_elf_free`.
+ */
+struct ElfHandle *rs_elf_parse(const uint8_t *buf, size_t len);
+
+/**
+ * Parses a copy of `buf`, returning NULL on failure. The buffer may be
+ * released as soon as the function returns.
+ *
+ * # Safety
+ *
+ * `buf` must be NULL or valid for reading `len` bytes during the call.
+ */
+struct ElfHandle *rs_elf_parse_copy(const uint8_t *buf, size_t len);
+
+/**
+ * Maps, or reads when it cannot be mapped, and parses the file at `path`,
+ * returning NULL on failure. The handle owns the file contents.
+ *
+ * # Safety
+ *
+ * `path` must be NULL or a NUL-terminated string.
+ */
+struct ElfHandle *rs_elf_open_path(const char *path);
+
+/**
+ * Releases a handle, and the data it owns. Pointers obtained from the
+ * handle are invalid afterwards.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle not released yet. Neither the handle nor
+ * the pointers obtained from it may be used afterwards.
+ */
+void rs_elf_free(struct ElfHandle *e);
+
+/**
+ * Returns `ELFCLASS32` or `ELFCLASS64`, 0 for a NULL handle.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle not released with `rs_elf_free` yet.
+ */
+uint8_t rs_elf_get_class(const struct ElfHandle *e);
+
+/**
+ * Returns `ELFDATA2LSB` or `ELFDATA2MSB`, 0 for a NULL handle.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle not released with `rs_elf_free` yet.
+ */
+uint8_t rs_elf_get_endianness(const struct ElfHandle *e);
+
+/**
+ * Returns `e_machine`, `EM_NONE` for a NULL handle.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle not released with `rs_elf_free` yet.
+ */
+uint16_t rs_elf_get_machine(const struct ElfHandle *e);
+
+/**
+ * Fills `out` with the file header.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle not released with `rs_elf_free` yet, and
+ * `out` NULL or valid for writing an `RsElfHeader`.
+ */
+enum RsElfError rs_elf_get_header(const struct ElfHandle *e, struct RsElfHeader *out);
+
+/**
+ * Returns the number of program headers, 0 for a NULL handle.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle not released with `rs_elf_free` yet.
+ */
+size_t rs_elf_get_number_of_segments(const struct ElfHandle *e);
+
+/**
+ * Fills `out` with the program header `idx`.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle not released with `rs_elf_free` yet, and
+ * `out` NULL or valid for writing an `RsElfSegment`.
+ */
+enum RsElfError rs_elf_get_segment(const struct ElfHandle *e, size_t idx, struct RsElfSegment *out);
+
+/**
+ * Returns the number of section headers, 0 for a NULL handle.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle not released with `rs_elf_free` yet.
+ */
+size_t rs_elf_get_number_of_section_headers(const struct ElfHandle *e);
+
+/**
+ * Fills `out` with the section header `idx` and its name.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle not released with `rs_elf_free` yet, and
+ * `out` NULL or valid for writing an `RsElfSectionHeader`.
+ */
+enum RsElfError rs_elf_get_section_header(const struct ElfHandle *e,
+                                          size_t idx,
+                                          struct RsElfSectionHeader *out);
+
+/**
+ * Stores in `idx` the index of the first section named `name`.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle not released with `rs_elf_free` yet, `name`
+ * NULL or a NUL-terminated string and `idx` NULL or valid for writing a
+ * `size_t`.
+ */
+enum RsElfError rs_elf_find_section(const struct ElfHandle *e, const char *name, size_t *idx);
+
+/**
+ * Stores in `count` the number of entries of a symbol table.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle not released with `rs_elf_free` yet, and
+ * `count` NULL or valid for writing a `size_t`.
+ */
+enum RsElfError rs_elf_get_number_of_symbols(const struct ElfHandle *e,
+                                             enum RsElfSymbolTable table,
+                                             size_t *count);
+
+/**
+ * Fills `out` with the entry `idx` of a symbol table.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle not released with `rs_elf_free` yet, and
+ * `out` NULL or valid for writing an `RsElfSymbol`.
+ */
+enum RsElfError rs_elf_get_symbol(const struct ElfHandle *e,
+                                  enum RsElfSymbolTable table,
+                                  size_t idx,
+                                  struct RsElfSymbol *out);
+
+/**
+ * Stores in `count` the number of dynamic entries before `DT_NULL`.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle not released with `rs_elf_free` yet, and
+ * `count` NULL or valid for writing a `size_t`.
+ */
+enum RsElfError rs_elf_get_number_of_dynamic_entries(const struct ElfHandle *e, size_t *count);
+
+/**
+ * Fills `out` with the dynamic entry `idx`.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle not released with `rs_elf_free` yet, and
+ * `out` NULL or valid for writing an `RsElfDynamic`.
+ */
+enum RsElfError rs_elf_get_dynamic_entry(const struct ElfHandle *e,
+                                         size_t idx,
+                                         struct RsElfDynamic *out);
+
+/**
+ * Stores in `count` the number of notes.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle not released with `rs_elf_free` yet, and
+ * `count` NULL or valid for writing a `size_t`.
+ */
+enum RsElfError rs_elf_get_number_of_notes(const struct ElfHandle *e, size_t *count);
+
+/**
+ * Fills `out` with the note `idx`.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle not released with `rs_elf_free` yet, and
+ * `out` NULL or valid for writing an `RsElfNote`.
+ */
+enum RsElfError rs_elf_get_note(const struct ElfHandle *e, size_t idx, struct RsElfNote *out);
+
+/**
+ * Stores in `out` a pointer to the `len` bytes at `offset` in the file data.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle not released with `rs_elf_free` yet, and
+ * `out` NULL or valid for writing a pointer.
+ */
+enum RsElfError rs_elf_get_data(const struct ElfHandle *e,
+                                size_t offset,
+                                size_t len,
+                                const uint8_t **out);
+
+/**
+ * Stores in `out` and `len` the contents of the section `idx` in the file,
+ * empty for `SHT_NOBITS` sections.
+ *
+ * # Safety
+ *
+ * `e` must be NULL or a handle not released with `rs_elf_free` yet, `out`
+ * NULL or valid for writing a pointer and `len` NULL or valid for writing
+ * a `size_t`.
+ */
+enum RsElfError rs_elf_get_section_data(const struct ElfHandle *e,
+                                        size_t idx,
+                                        const uint8_t **out,
+                                        size_t *len);
+
+#endif /* RS_ELF_H */
@@ -0,0 +1,298 @@
+use nom;
+use std::str;
+
+use {parse_elf32, parse_elf64, Elf32, Elf64};
+
+pub const AR_MAGIC: &[u8] = b"!<arch>\n";
+pub const AR_THIN_MAGIC: &[u8] = b"!<thin>\n";
+
+/// Raw header of an archive member. Numeric fields are ASCII in the file.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ArchiveHeader<'a> {
+    pub ar_name: &'a [u8],
+    pub ar_date: u64,
+    pub ar_uid: u32,
+    pub ar_gid: u32,
+    pub ar_mode: u32,
+    pub ar_size: u64,
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ArchiveMember<'a> {
+    /// Offset of the member header in the archive, as referenced by the
+    /// symbol map.
+    pub offset: usize,
+    pub header: ArchiveHeader<'a>,
+    /// Member name, or path relative to the archive in thin archives.
+    pub name: &'a str,
+    /// Member contents, `None` in thin archives where members are stored in
+    /// separate files.
+    pub data: Option<&'a [u8]>,
+}
+
+impl<'a> ArchiveMember<'a> {
+    pub fn parse_elf32(&self) -> Option<Elf32<'a>> {
+        self.data.and_then(|d| parse_elf32(d).ok()).map(|(_, e)| e)
+    }
+
+    pub fn parse_elf64(&self) -> Option<Elf64<'a>> {
+        self.data.and_then(|d| parse_elf64(d).ok()).map(|(_, e)| e)
+    }
+}
+
+#[derive(Debug)]
+pub struct Archive<'a> {
+    pub data: &'a [u8],
+    pub thin: bool,
+    pub members: Vec<ArchiveMember<'a>>,
+    /// Symbol map entries: a symbol name and the offset of the header of the
+    /// member defining it.
+    pub symbols: Vec<(&'a str, usize)>,
+}
+
+impl<'a> Archive<'a> {
+    /// Returns the member whose header starts at `offset`.
+    pub fn member_at(&self, offset: usize) -> Option<&ArchiveMember<'a>> {
+        self.members
+            .binary_search_by_key(&offset, |m| m.offset)
+            .ok()
+            .map(|idx| &self.members[idx])
+    }
+
+    /// Returns the member defining `symbol` according to the symbol map.
+    pub fn member_for_symbol(&self, symbol: &str) -> Option<&ArchiveMember<'a>> {
+        self.symbols
+            .iter()
+            .find(|s| s.0 == symbol)
+            .and_then(|s| self.member_at(s.1))
+    }
+}
+
+fn ascii_number(field: &[u8], radix: u32) -> Option<u64> {
+    let s = str::from_utf8(field).ok()?.trim();
+    if s.is_empty() {
+        Some(0)
+    } else {
+        u64::from_str_radix(s, radix).ok()
+    }
+}
+
+macro_rules! ascii_field {
+    ($i:expr, $len:expr, $radix:expr) => {
+        map_opt!($i, take!($len), |f| ascii_number(f, $radix))
+    };
+}
+
+named!(pub parse_archive_header<ArchiveHeader>,
+    do_parse!(
+            _ar_name:   take!(16)
+        >>  _ar_date:   ascii_field!(12, 10)
+        >>  _ar_uid:    ascii_field!(6, 10)
+        >>  _ar_gid:    ascii_field!(6, 10)
+        >>  _ar_mode:   ascii_field!(8, 8)
+        >>  _ar_size:   ascii_field!(10, 10)
+        >>  tag!("`\n")
+        >>  ( ArchiveHeader {
+            ar_name:    _ar_name,
+            ar_date:    _ar_date,
+            ar_uid:     _ar_uid as u32,
+            ar_gid:     _ar_gid as u32,
+            ar_mode:    _ar_mode as u32,
+            ar_size:    _ar_size,
+        })
+    )
+);
+
+fn verify_error<T>(i: &[u8]) -> nom::IResult<&[u8], T> {
+    Err(nom::Err::Error(error_position!(i, nom::ErrorKind::Verify)))
+}
+
+fn be_number(i: &[u8], size: usize) -> nom::IResult<&[u8], u64> {
+    if size == 8 {
+        nom::be_u64(i)
+    } else {
+        nom::be_u32(i).map(|(r, x)| (r, u64::from(x)))
+    }
+}
+
+fn le_number(i: &[u8], size: usize) -> nom::IResult<&[u8], u64> {
+    if size == 8 {
+        nom::le_u64(i)
+    } else {
+        nom::le_u32(i).map(|(r, x)| (r, u64::from(x)))
+    }
+}
+
+fn cstr_at(i: &[u8]) -> Option<&str> {
+    let len = i.iter().position(|&c| c == 0).unwrap_or(i.len());
+    str::from_utf8(&i[..len]).ok()
+}
+
+/// Parses the GNU/System V symbol index (`/` or `/SYM64/`): a big-endian
+/// count, member offsets, and the NUL-terminated names.
+fn parse_gnu_symbols(i: &[u8], size: usize) -> nom::IResult<&[u8], Vec<(&str, usize)>> {
+    let (mut rest, count) = be_number(i, size)?;
+    if count > (i.len() / size) as u64 {
+        return verify_error(i);
+    }
+    let mut offsets = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let (r, off) = be_number(rest, size)?;
+        offsets.push(off as usize);
+        rest = r;
+    }
+
+    let mut symbols = Vec::with_capacity(offsets.len());
+    for off in offsets {
+        let name = match cstr_at(rest) {
+            Some(name) => name,
+            None => return verify_error(i),
+        };
+        rest = &rest[(name.len() + 1).min(rest.len())..];
+        symbols.push((name, off));
+    }
+    Ok((rest, symbols))
+}
+
+/// Parses the BSD symbol index (`__.SYMDEF`): an array of `ranlib` entries
+/// followed by their string table, in native (little-endian) byte order.
+fn parse_bsd_symbols(i: &[u8], size: usize) -> nom::IResult<&[u8], Vec<(&str, usize)>> {
+    let (rest, ranlib_size) = le_number(i, size)?;
+    let (rest, mut ranlibs) = take!(rest, ranlib_size as usize)?;
+    let (rest, strtab_size) = le_number(rest, size)?;
+    let (rest, strtab) = take!(rest, strtab_size as usize)?;
+
+    let mut symbols = Vec::new();
+    while ranlibs.len() >= 2 * size {
+        let (r, strx) = le_number(ranlibs, size)?;
+        let (r, off) = le_number(r, size)?;
+        let name = match strtab.get(strx as usize..).and_then(cstr_at) {
+            Some(name) => name,
+            None => return verify_error(i),
+        };
+        symbols.push((name, off as usize));
+        ranlibs = r;
+    }
+    Ok((rest, symbols))
+}
+
+/// Parses a System V/GNU, BSD or thin `ar` archive.
+pub fn parse_archive<'a>(i: &'a [u8]) -> nom::IResult<&'a [u8], Archive<'a>> {
+    let thin = if i.starts_with(AR_MAGIC) {
+        false
+    } else if i.starts_with(AR_THIN_MAGIC) {
+        true
+    } else {
+        return Err(nom::Err::Error(error_position!(i, nom::ErrorKind::Tag)));
+    };
+
+    let mut offset = AR_MAGIC.len();
+    let mut members = Vec::new();
+    let mut symbols = Vec::new();
+    let mut long_names: &[u8] = &[];
+
+    while offset + 60 <= i.len() {
+        let (rest, header) = parse_archive_header(&i[offset..])?;
+        let raw_name = header.ar_name;
+        let special = raw_name.starts_with(b"/ ")
+            || raw_name.starts_with(b"// ")
+            || raw_name.starts_with(b"/SYM64/ ");
+
+        // Members of thin archives live outside of it, except the symbol
+        // index and name table.
+        let stored = !thin || special;
+        let size = if stored { header.ar_size as usize } else { 0 };
+        if rest.len() < size {
+            return Err(nom::Err::Incomplete(nom::Needed::Size(size)));
+        }
+        let mut data = &rest[..size];
+        let header_offset = offset;
+        offset += 60 + size + (size & 1);
+
+        let name = if raw_name.starts_with(b"/ ") {
+            symbols = parse_gnu_symbols(data, 4)?.1;
+            continue;
+        } else if raw_name.starts_with(b"/SYM64/ ") {
+            symbols = parse_gnu_symbols(data, 8)?.1;
+            continue;
+        } else if raw_name.starts_with(b"// ") {
+            long_names = data;
+            continue;
+        } else if raw_name.starts_with(b"#1/") {
+            let len = match ascii_number(&raw_name[3..], 10) {
+                Some(len) if len as usize <= data.len() => len as usize,
+                _ => return verify_error(raw_name),
+            };
+            let (name, rest) = data.split_at(len);
+            data = rest;
+            let name = match cstr_at(name) {
+                Some(name) => name,
+                None => return verify_error(raw_name),
+            };
+            if name.starts_with("__.SYMDEF") {
+                let size = if name.starts_with("__.SYMDEF_64") {
+                    8
+                } else {
+                    4
+                };
+                symbols = parse_bsd_symbols(data, size)?.1;
+                continue;
+            }
+            name
+        } else if raw_name[0] == b'/' && raw_name.len() > 1 && raw_name[1].is_ascii_digit() {
+            let start = match ascii_number(&raw_name[1..], 10) {
+                Some(start) if (start as usize) < long_names.len() => start as usize,
+                _ => return verify_error(raw_name),
+            };
+            let table = &long_names[start..];
+            let end = table
+                .iter()
+                .position(|&c| c == b'\n')
+                .unwrap_or(table.len());
+            let name = &table[..end];
+            let name = if name.ends_with(b"/") {
+                &name[..name.len() - 1]
+            } else {
+                name
+            };
+            match str::from_utf8(name) {
+                Ok(name) => name,
+                Err(_) => return verify_error(raw_name),
+            }
+        } else {
+            let name = match raw_name.iter().position(|&c| c == b'/') {
+                Some(end) => &raw_name[..end],
+                None => raw_name,
+            };
+            let name = match str::from_utf8(name) {
+                Ok(name) => name.trim_end(),
+                Err(_) => return verify_error(raw_name),
+            };
+            if name == "__.SYMDEF" || name == "__.SYMDEF SORTED" {
+                symbols = parse_bsd_symbols(data, 4)?.1;
+                continue;
+            }
+            name
+        };
+
+        members.push(ArchiveMember {
+            offset: header_offset,
+            header: header,
+            name: name,
+            data: if stored { Some(data) } else { None },
+        });
+    }
+
+    let rest = &i[offset.min(i.len())..];
+    Ok((
+        rest,
+        Archive {
+            data: i,
+            thin: thin,
+            members: members,
+            symbols: symbols,
+        },
+    ))
+}
@@ -0,0 +1,246 @@
+extern crate clap;
+extern crate elf;
+
+use clap::{App, Arg, ArgMatches};
+use elf::*;
+use std::process;
+
+/// What to report and how.
+struct Options {
+    source: String,
+    rows: usize,
+    demangle: bool,
+}
+
+impl Options {
+    fn from_args(args: &ArgMatches) -> Result<Self, String> {
+        let rows = args.value_of("rows").unwrap_or("20");
+        Ok(Options {
+            source: String::from(args.value_of("data-source").unwrap_or("sections")),
+            rows: rows
+                .parse()
+                .map_err(|_| format!("invalid number of rows: {}", rows))?,
+            demangle: args.is_present("demangle"),
+        })
+    }
+
+    fn entries<'r>(&self, report: &'r SizeReport) -> &'r [SizeEntry] {
+        match self.source.as_str() {
+            "segments" => &report.segments,
+            "symbols" => &report.symbols,
+            _ => &report.sections,
+        }
+    }
+
+    fn name(&self, name: &str) -> String {
+        if self.demangle {
+            demangle(name).unwrap_or_else(|| String::from(name))
+        } else {
+            String::from(name)
+        }
+    }
+}
+
+/// Formats a size the way Bloaty does, with three significant digits.
+fn human(size: u64) -> String {
+    if size < 1024 {
+        return format!("{}", size);
+    }
+    let mut value = size as f64 / 1024.0;
+    let mut unit = "Ki";
+    for next in &["Mi", "Gi", "Ti"] {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next;
+    }
+    if value < 10.0 {
+        format!("{:.2}{}", value, unit)
+    } else if value < 100.0 {
+        format!("{:.1}{}", value, unit)
+    } else {
+        format!("{:.0}{}", value, unit)
+    }
+}
+
+fn human_delta(delta: i64) -> String {
+    match delta {
+        0 => String::from("0"),
+        _ if delta < 0 => format!("-{}", human(delta.unsigned_abs())),
+        _ => format!("+{}", human(delta as u64)),
+    }
+}
+
+fn percent(part: u64, total: u64) -> String {
+    if total == 0 {
+        String::from("0.0%")
+    } else {
+        format!("{:.1}%", part as f64 * 100.0 / total as f64)
+    }
+}
+
+fn change(old: u64, new: u64) -> String {
+    if old == new {
+        String::from("[ = ]")
+    } else if old == 0 {
+        String::from("[NEW]")
+    } else if new == 0 {
+        String::from("[DEL]")
+    } else {
+        format!("{:+.1}%", (new as f64 - old as f64) * 100.0 / old as f64)
+    }
+}
+
+fn print_header() {
+    println!("    FILE SIZE        VM SIZE    ");
+    println!(" --------------  -------------- ");
+}
+
+fn print_row(file: (&str, &str), vm: (&str, &str), name: &str) {
+    println!(
+        " {:>6} {:>7}  {:>6} {:>7}    {}",
+        file.0, file.1, vm.0, vm.1, name
+    );
+}
+
+fn print_report(report: &SizeReport, opts: &Options) {
+    let entries = opts.entries(report);
+    let shown = if opts.rows == 0 || entries.len() <= opts.rows {
+        entries.len()
+    } else {
+        opts.rows
+    };
+    let vm_total: u64 = entries.iter().map(|e| e.vm_size).sum();
+    print_header();
+    let row = |file_size: u64, vm_size: u64, name: &str| {
+        print_row(
+            (&percent(file_size, report.file_size), &human(file_size)),
+            (&percent(vm_size, vm_total), &human(vm_size)),
+            name,
+        )
+    };
+    for e in &entries[..shown] {
+        row(e.file_size, e.vm_size, &opts.name(&e.name));
+    }
+    let others = &entries[shown..];
+    if !others.is_empty() {
+        row(
+            others.iter().map(|e| e.file_size).sum(),
+            others.iter().map(|e| e.vm_size).sum(),
+            &format!("[{} Others]", others.len()),
+        );
+    }
+    row(report.file_size, vm_total, "TOTAL");
+}
+
+fn print_diff(report: &SizeReport, base: &SizeReport, opts: &Options) {
+    let deltas = diff_sizes(opts.entries(base), opts.entries(report));
+    let shown = if opts.rows == 0 || deltas.len() <= opts.rows {
+        deltas.len()
+    } else {
+        opts.rows
+    };
+    print_header();
+    for d in &deltas[..shown] {
+        print_row(
+            (
+                &change(d.old_file_size, d.new_file_size),
+                &human_delta(d.file_delta()),
+            ),
+            (
+                &change(d.old_vm_size, d.new_vm_size),
+                &human_delta(d.vm_delta()),
+            ),
+            &opts.name(&d.name),
+        );
+    }
+    let others = &deltas[shown..];
+    if !others.is_empty() {
+        print_row(
+            (
+                "",
+                &human_delta(others.iter().map(|d| d.file_delta()).sum()),
+            ),
+            ("", &human_delta(others.iter().map(|d| d.vm_delta()).sum())),
+            &format!("[{} Others]", others.len()),
+        );
+    }
+    let vm_total = |r: &SizeReport| opts.entries(r).iter().map(|e| e.vm_size).sum();
+    let (old_vm, new_vm) = (vm_total(base), vm_total(report));
+    print_row(
+        (
+            &change(base.file_size, report.file_size),
+            &human_delta(report.file_size as i64 - base.file_size as i64),
+        ),
+        (
+            &change(old_vm, new_vm),
+            &human_delta(new_vm as i64 - old_vm as i64),
+        ),
+        "TOTAL",
+    );
+}
+
+fn report(path: &str) -> Result<SizeReport, Error> {
+    let file = ElfFile::open(path)?;
+    let elf = file.parse()?;
+    elf.size_report()
+}
+
+fn run(args: &ArgMatches) -> Result<(), String> {
+    let opts = Options::from_args(args)?;
+    let path = args.value_of("elf").unwrap_or("");
+    let new = report(path).map_err(|e| format!("{}: {}", path, e))?;
+    match args.value_of("diff") {
+        Some(base_path) => {
+            let base = report(base_path).map_err(|e| format!("{}: {}", base_path, e))?;
+            print_diff(&new, &base, &opts);
+        }
+        None => print_report(&new, &opts),
+    }
+    Ok(())
+}
+
+fn main() {
+    let args = App::new("bloatelf")
+        .version("1.0")
+        .author("Thomas WACHE")
+        .about("Breaks down the size of an ELF file, like Bloaty")
+        .arg(
+            Arg::with_name("elf")
+                .required(true)
+                .help("File to report on"),
+        )
+        .arg(
+            Arg::with_name("data-source")
+                .short("d")
+                .long("data-source")
+                .takes_value(true)
+                .possible_values(&["sections", "segments", "symbols"])
+                .help("Break down by sections (default), segments or symbols"),
+        )
+        .arg(
+            Arg::with_name("rows")
+                .short("n")
+                .takes_value(true)
+                .help("Number of rows to show, 0 for all (default 20)"),
+        )
+        .arg(
+            Arg::with_name("demangle")
+                .short("C")
+                .long("demangle")
+                .help("Demangle symbol names"),
+        )
+        .arg(
+            Arg::with_name("diff")
+                .long("diff")
+                .takes_value(true)
+                .value_name("BASE")
+                .help("Show the size changes from BASE to the file"),
+        )
+        .get_matches();
+    if let Err(e) = run(&args) {
+        eprintln!("bloatelf: {}", e);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,1986 @@
+extern crate clap;
+extern crate elf;
+extern crate enum_primitive;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+#[cfg(feature = "serde")]
+extern crate serde_json;
+
+use clap::{App, Arg, ArgMatches};
+use elf::*;
+use enum_primitive::FromPrimitive;
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::process;
+
+const DF_1_PIE: u64 = 0x0800_0000;
+
+/// What to display, following the readelf options of the same name.
+struct Options {
+    header: bool,
+    segments: bool,
+    sections: bool,
+    symbols: bool,
+    relocs: bool,
+    dynamic: bool,
+    notes: bool,
+    versions: bool,
+    demangle: bool,
+    debug: bool,
+    json: bool,
+}
+
+impl Options {
+    fn from_args(args: &ArgMatches) -> Self {
+        let all = args.is_present("all");
+        let headers = all || args.is_present("headers");
+        Options {
+            header: headers || args.is_present("file-header"),
+            segments: headers || args.is_present("program-headers"),
+            sections: headers || args.is_present("section-headers"),
+            symbols: all || args.is_present("syms"),
+            relocs: all || args.is_present("relocs"),
+            dynamic: all || args.is_present("dynamic"),
+            notes: all || args.is_present("notes"),
+            versions: all || args.is_present("version-info"),
+            demangle: args.is_present("demangle"),
+            debug: args.is_present("debug"),
+            json: args.is_present("json"),
+        }
+    }
+
+    fn any(&self) -> bool {
+        self.header
+            || self.segments
+            || self.sections
+            || self.symbols
+            || self.relocs
+            || self.dynamic
+            || self.notes
+            || self.versions
+            || self.debug
+            || self.json
+    }
+}
+
+/// A section header of either class.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+struct Section<'a> {
+    name: &'a str,
+    sh_type: u32,
+    flags: u64,
+    addr: u64,
+    offset: u64,
+    size: u64,
+    link: u32,
+    info: u32,
+    align: u64,
+    entsize: u64,
+}
+
+/// A program header of either class.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+struct Segment {
+    p_type: u32,
+    flags: u32,
+    offset: u64,
+    vaddr: u64,
+    paddr: u64,
+    filesz: u64,
+    memsz: u64,
+    align: u64,
+}
+
+/// Evaluates `$body` with `$e` bound to the parsed file of either class.
+macro_rules! with_elf {
+    ($dump:expr, $e:ident => $body:expr) => {
+        match $dump.elf {
+            Elf::Elf32(ref $e) => $body,
+            Elf::Elf64(ref $e) => $body,
+        }
+    };
+}
+
+/// Writes to the output of the dump like `print!`, returning the I/O errors,
+/// such as a closed pipe, from the enclosing function instead of panicking.
+macro_rules! out {
+    ($dump:expr, $($arg:tt)*) => {
+        write!($dump.out.borrow_mut(), $($arg)*)?
+    };
+}
+
+/// Writes a line to the output of the dump like `println!`.
+macro_rules! outln {
+    ($dump:expr) => {
+        writeln!($dump.out.borrow_mut())?
+    };
+    ($dump:expr, $($arg:tt)*) => {
+        writeln!($dump.out.borrow_mut(), $($arg)*)?
+    };
+}
+
+struct Dump<'a> {
+    elf: Elf<'a>,
+    is_64: bool,
+    sections: Vec<Section<'a>>,
+    segments: Vec<Segment>,
+    opts: &'a Options,
+    out: RefCell<&'a mut dyn Write>,
+}
+
+impl<'a> Dump<'a> {
+    fn new(elf: Elf<'a>, opts: &'a Options, out: &'a mut dyn Write) -> Self {
+        let (sections, segments) = match elf {
+            Elf::Elf32(ref e) => (
+                e.sections
+                    .iter()
+                    .map(|s| Section {
+                        name: e.section_name(s).unwrap_or(""),
+                        sh_type: s.sh_type,
+                        flags: u64::from(s.sh_flags),
+                        addr: u64::from(s.sh_addr),
+                        offset: u64::from(s.sh_offset),
+                        size: u64::from(s.sh_size),
+                        link: s.sh_link,
+                        info: s.sh_info,
+                        align: u64::from(s.sh_addralign),
+                        entsize: u64::from(s.sh_entsize),
+                    })
+                    .collect(),
+                e.segments
+                    .iter()
+                    .map(|p| Segment {
+                        p_type: p.p_type,
+                        flags: p.p_flags,
+                        offset: u64::from(p.p_offset),
+                        vaddr: u64::from(p.p_vaddr),
+                        paddr: u64::from(p.p_paddr),
+                        filesz: u64::from(p.p_filesz),
+                        memsz: u64::from(p.p_memsz),
+                        align: u64::from(p.p_align),
+                    })
+                    .collect(),
+            ),
+            Elf::Elf64(ref e) => (
+                e.sections
+                    .iter()
+                    .map(|s| Section {
+                        name: e.section_name(s).unwrap_or(""),
+                        sh_type: s.sh_type,
+                        flags: s.sh_flags,
+                        addr: s.sh_addr,
+                        offset: s.sh_offset,
+                        size: s.sh_size,
+                        link: s.sh_link,
+                        info: s.sh_info,
+                        align: s.sh_addralign,
+                        entsize: s.sh_entsize,
+                    })
+                    .collect(),
+                e.segments
+                    .iter()
+                    .map(|p| Segment {
+                        p_type: p.p_type,
+                        flags: p.p_flags,
+                        offset: p.p_offset,
+                        vaddr: p.p_vaddr,
+                        paddr: p.p_paddr,
+                        filesz: p.p_filesz,
+                        memsz: p.p_memsz,
+                        align: p.p_align,
+                    })
+                    .collect(),
+            ),
+        };
+        Dump {
+            is_64: elf.class() == ElfClass::Class64,
+            elf: elf,
+            sections: sections,
+            segments: segments,
+            opts: opts,
+            out: RefCell::new(out),
+        }
+    }
+
+    fn ident(&self) -> &ElfIdent {
+        with_elf!(self, e => &e.header.e_ident)
+    }
+
+    fn e_type(&self) -> u16 {
+        with_elf!(self, e => e.header.e_type)
+    }
+
+    fn entry(&self) -> u64 {
+        with_elf!(self, e => u64::from(e.header.e_entry))
+    }
+
+    fn section_data(&self, idx: usize) -> Option<&'a [u8]> {
+        with_elf!(self, e => e.section_data(&e.sections[idx]))
+    }
+
+    fn symbols(&self, idx: usize) -> Result<Vec<Symbol<'a>>, Error> {
+        with_elf!(self, e => e.symbols(&e.sections[idx]))
+    }
+
+    fn relocations(&self, idx: usize) -> Result<Vec<Relocation>, Error> {
+        with_elf!(self, e => e.relocations(&e.sections[idx]))
+    }
+
+    fn segment_sections(&self, idx: usize) -> Vec<usize> {
+        with_elf!(self, e => e.segment_sections(idx))
+    }
+
+    fn section_name(&self, idx: usize) -> &'a str {
+        self.sections.get(idx).map_or("", |s| s.name)
+    }
+
+    /// Returns the name readelf displays for a symbol, which is the section
+    /// name for unnamed section symbols.
+    fn symbol_name(&self, sym: &Symbol<'a>) -> String {
+        if sym.name.is_empty() && sym.kind == STT_SECTION && sym.shndx < SHN_LORESERVE {
+            return String::from(self.section_name(sym.shndx as usize));
+        }
+        sym.display_name(!self.opts.demangle).into_owned()
+    }
+
+    fn is_pie(&self) -> bool {
+        self.elf.dynamic().ok().map_or(false, |entries| {
+            entries
+                .iter()
+                .any(|d| d.tag == DT_FLAGS_1 && d.val & DF_1_PIE != 0)
+        })
+    }
+
+    fn file_type(&self) -> String {
+        let e_type = self.e_type();
+        match ElfType::from_u16(e_type) {
+            Some(ElfType::None) => String::from("NONE (None)"),
+            Some(ElfType::Rel) => String::from("REL (Relocatable file)"),
+            Some(ElfType::Exec) => String::from("EXEC (Executable file)"),
+            Some(ElfType::Dyn) if self.is_pie() => {
+                String::from("DYN (Position-Independent Executable file)")
+            }
+            Some(ElfType::Dyn) => String::from("DYN (Shared object file)"),
+            Some(ElfType::Core) => String::from("CORE (Core file)"),
+            _ if e_type >= 0xff00 => format!("Processor Specific: ({:x})", e_type),
+            _ if e_type >= 0xfe00 => format!("OS Specific: ({:x})", e_type),
+            _ => format!("<unknown>: {:x}", e_type),
+        }
+    }
+
+    fn print_file_header(&self) -> Result<(), Error> {
+        outln!(self, "ELF Header:");
+        out!(self, "  Magic:   ");
+        for b in &self.elf.data()[..16] {
+            out!(self, "{:02x} ", b);
+        }
+        outln!(self);
+        let field = |label: &str, value: String| -> Result<(), Error> {
+            outln!(self, "  {:<35}{}", label, value);
+            Ok(())
+        };
+        with_elf!(self, e => {
+            let h = &e.header;
+            field(
+                "Class:",
+                String::from(if self.is_64 { "ELF64" } else { "ELF32" }),
+            )?;
+            field("Data:", String::from("2's complement, little endian"))?;
+            field("Version:", String::from("1 (current)"))?;
+            field("OS/ABI:", String::from(osabi_name(&h.e_ident.osabi)))?;
+            field("ABI Version:", format!("{}", h.e_ident.abiversion))?;
+            field("Type:", self.file_type())?;
+            field("Machine:", machine_name(h.e_machine))?;
+            field("Version:", format!("{:#x}", h.e_version))?;
+            field("Entry point address:", format!("{:#x}", h.e_entry))?;
+            field(
+                "Start of program headers:",
+                format!("{} (bytes into file)", h.e_phoff),
+            )?;
+            field(
+                "Start of section headers:",
+                format!("{} (bytes into file)", h.e_shoff),
+            )?;
+            field("Flags:", format!("{:#x}", h.e_flags))?;
+            field("Size of this header:", format!("{} (bytes)", h.e_ehsize))?;
+            field(
+                "Size of program headers:",
+                format!("{} (bytes)", h.e_phentsize),
+            )?;
+            field("Number of program headers:", format!("{}", h.e_phnum))?;
+            field(
+                "Size of section headers:",
+                format!("{} (bytes)", h.e_shentsize),
+            )?;
+            field("Number of section headers:", format!("{}", h.e_shnum))?;
+            field(
+                "Section header string table index:",
+                format!("{}", h.e_shstrndx),
+            )?;
+        });
+        Ok(())
+    }
+
+    fn print_section_headers(&self) -> Result<(), Error> {
+        if self.sections.is_empty() {
+            outln!(self, "\nThere are no sections in this file.");
+            return Ok(());
+        }
+        if !self.opts.header {
+            let shoff = with_elf!(self, e => u64::from(e.header.e_shoff));
+            if self.sections.len() == 1 {
+                outln!(
+                    self,
+                    "There is 1 section header, starting at offset {:#x}:",
+                    shoff
+                );
+            } else {
+                outln!(
+                    self,
+                    "There are {} section headers, starting at offset {:#x}:",
+                    self.sections.len(),
+                    shoff
+                );
+            }
+        }
+        outln!(self, "\nSection Headers:");
+        if self.is_64 {
+            outln!(self, "  [Nr] Name              Type            Address          Off    Size   ES Flg Lk Inf Al");
+        } else {
+            outln!(
+                self,
+                "  [Nr] Name              Type            Addr     Off    Size   ES Flg Lk Inf Al"
+            );
+        }
+        let machine = self.elf.machine();
+        let osabi = self.ident().osabi;
+        for (idx, s) in self.sections.iter().enumerate() {
+            let addr = if self.is_64 {
+                format!("{:016x}", s.addr)
+            } else {
+                format!("{:08x}", s.addr)
+            };
+            outln!(
+                self,
+                "  [{:2}] {:<17} {:<15} {} {:06x} {:06x} {:02x} {:>3} {:2} {:3} {:2}",
+                idx,
+                s.name,
+                section_type_name(s.sh_type, machine),
+                addr,
+                s.offset,
+                s.size,
+                s.entsize,
+                section_flags(s.flags, machine, &osabi),
+                s.link,
+                s.info,
+                s.align
+            );
+        }
+        outln!(self, "Key to Flags:");
+        outln!(
+            self,
+            "  W (write), A (alloc), X (execute), M (merge), S (strings), I (info),"
+        );
+        outln!(
+            self,
+            "  L (link order), O (extra OS processing required), G (group), T (TLS),"
+        );
+        outln!(
+            self,
+            "  C (compressed), x (unknown), o (OS specific), E (exclude),"
+        );
+        out!(self, "  ");
+        if osabi == ElfOSAbi::OSAbiGnu || osabi == ElfOSAbi::OSAbiFreebsd {
+            out!(self, "R (retain), ");
+        }
+        if osabi == ElfOSAbi::OSAbiSysv
+            || osabi == ElfOSAbi::OSAbiGnu
+            || osabi == ElfOSAbi::OSAbiFreebsd
+        {
+            out!(self, "D (mbind), ");
+        }
+        match ElfMachine::from_u16(machine) {
+            Some(ElfMachine::MachineX8664)
+            | Some(ElfMachine::MachineL10m)
+            | Some(ElfMachine::MachineK10m) => out!(self, "l (large), "),
+            Some(ElfMachine::MachineArm) => out!(self, "y (purecode), "),
+            Some(ElfMachine::MachinePpc) => out!(self, "v (VLE), "),
+            _ => {}
+        }
+        outln!(self, "p (processor specific)");
+        Ok(())
+    }
+
+    fn print_program_headers(&self) -> Result<(), Error> {
+        if self.segments.is_empty() {
+            outln!(self, "\nThere are no program headers in this file.");
+            return Ok(());
+        }
+        if !self.opts.header {
+            let phoff = with_elf!(self, e => u64::from(e.header.e_phoff));
+            outln!(self, "\nElf file type is {}", self.file_type());
+            outln!(self, "Entry point {:#x}", self.entry());
+            if self.segments.len() == 1 {
+                outln!(
+                    self,
+                    "There is 1 program header, starting at offset {}",
+                    phoff
+                );
+            } else {
+                outln!(
+                    self,
+                    "There are {} program headers, starting at offset {}",
+                    self.segments.len(),
+                    phoff
+                );
+            }
+        }
+        outln!(self, "\nProgram Headers:");
+        if self.is_64 {
+            outln!(self, "  Type           Offset   VirtAddr           PhysAddr           FileSiz  MemSiz   Flg Align");
+        } else {
+            outln!(
+                self,
+                "  Type           Offset   VirtAddr   PhysAddr   FileSiz MemSiz  Flg Align"
+            );
+        }
+        let machine = self.elf.machine();
+        for p in &self.segments {
+            let flags = format!(
+                "{}{}{}",
+                if p.flags & PF_R != 0 { 'R' } else { ' ' },
+                if p.flags & PF_W != 0 { 'W' } else { ' ' },
+                if p.flags & PF_X != 0 { 'E' } else { ' ' }
+            );
+            let align = if p.align == 0 {
+                String::from("0")
+            } else {
+                format!("{:#x}", p.align)
+            };
+            if self.is_64 {
+                outln!(
+                    self,
+                    "  {:<14} 0x{:06x} 0x{:016x} 0x{:016x} 0x{:06x} 0x{:06x} {} {}",
+                    segment_type_name(p.p_type, machine),
+                    p.offset,
+                    p.vaddr,
+                    p.paddr,
+                    p.filesz,
+                    p.memsz,
+                    flags,
+                    align
+                );
+            } else {
+                outln!(
+                    self,
+                    "  {:<14} 0x{:06x} 0x{:08x} 0x{:08x} 0x{:05x} 0x{:05x} {} {}",
+                    segment_type_name(p.p_type, machine),
+                    p.offset,
+                    p.vaddr,
+                    p.paddr,
+                    p.filesz,
+                    p.memsz,
+                    flags,
+                    align
+                );
+            }
+            if p.p_type == PT_INTERP {
+                let interp = self.elf.data().get(p.offset as usize..).and_then(|d| {
+                    let len = d.iter().position(|&c| c == 0)?;
+                    ::std::str::from_utf8(&d[..len]).ok()
+                });
+                if let Some(interp) = interp {
+                    outln!(self, "      [Requesting program interpreter: {}]", interp);
+                }
+            }
+        }
+        if self.sections.is_empty() {
+            return Ok(());
+        }
+        outln!(self, "\n Section to Segment mapping:");
+        outln!(self, "  Segment Sections...");
+        for idx in 0..self.segments.len() {
+            out!(self, "   {:02}     ", idx);
+            for s in self.segment_sections(idx) {
+                out!(self, "{} ", self.section_name(s));
+            }
+            outln!(self);
+        }
+        Ok(())
+    }
+
+    fn print_dynamic(&self) -> Result<(), Error> {
+        let entries = self.elf.dynamic()?;
+        let offset = match self.sections.iter().find(|s| s.sh_type == SHT_DYNAMIC) {
+            Some(s) => Some(s.offset),
+            None => self
+                .segments
+                .iter()
+                .find(|p| p.p_type == PT_DYNAMIC)
+                .map(|p| p.offset),
+        };
+        let offset = match offset {
+            Some(offset) => offset,
+            None => {
+                outln!(self, "\nThere is no dynamic section in this file.");
+                return Ok(());
+            }
+        };
+        let count = entries.len() + 1;
+        outln!(
+            self,
+            "\nDynamic section at offset {:#x} contains {} {}:",
+            offset,
+            count,
+            if count == 1 { "entry" } else { "entries" }
+        );
+        outln!(self, "  Tag        Type                         Name/Value");
+        let machine = self.elf.machine();
+        for d in entries.iter().chain(Some(&Dynamic::new(DT_NULL, 0))) {
+            let name = dynamic_tag_name(d.tag, machine);
+            let pad = if self.is_64 { 19 } else { 27 };
+            if self.is_64 {
+                out!(self, " 0x{:016x}", d.tag as u64);
+            } else {
+                out!(self, " 0x{:08x}", d.tag as u32);
+            }
+            out!(
+                self,
+                " ({}){:>width$}",
+                name,
+                " ",
+                width = pad.max(name.len() + 1) - name.len()
+            );
+            outln!(self, "{}", self.dynamic_value(d, machine));
+        }
+        Ok(())
+    }
+
+    fn dynamic_value(&self, d: &Dynamic, machine: u16) -> String {
+        let string = || self.elf.dynamic_string(d.val).unwrap_or("<corrupt>");
+        match d.tag {
+            DT_NEEDED => format!("Shared library: [{}]", string()),
+            DT_SONAME => format!("Library soname: [{}]", string()),
+            DT_RPATH => format!("Library rpath: [{}]", string()),
+            DT_RUNPATH => format!("Library runpath: [{}]", string()),
+            DT_AUXILIARY => format!("Auxiliary library: [{}]", string()),
+            DT_FILTER => format!("Filter library: [{}]", string()),
+            DT_CONFIG => format!("Configuration file: [{}]", string()),
+            DT_DEPAUDIT => format!("Dependency audit library: [{}]", string()),
+            DT_AUDIT => format!("Audit library: [{}]", string()),
+            DT_PLTREL => dynamic_tag_name(d.val as i64, machine),
+            DT_BIND_NOW => String::new(),
+            DT_FLAGS => (0..64)
+                .filter(|bit| d.val & (1 << bit) != 0)
+                .map(|bit| DT_FLAGS_NAMES.get(bit).cloned().unwrap_or("unknown"))
+                .collect::<Vec<_>>()
+                .join(" "),
+            DT_FLAGS_1 => flags_1_names(d.val),
+            DT_PLTRELSZ | DT_RELASZ | DT_STRSZ | DT_RELSZ | DT_RELAENT | DT_SYMENT | DT_RELENT
+            | DT_INIT_ARRAYSZ | DT_FINI_ARRAYSZ | DT_PREINIT_ARRAYSZ | DT_RELRSZ | DT_RELRENT
+            | DT_SYMINSZ | DT_SYMINENT | DT_MOVEENT | DT_MOVESZ | DT_PLTPADSZ
+            | DT_GNU_CONFLICTSZ | DT_GNU_LIBLISTSZ => format!("{} (bytes)", d.val),
+            DT_VERDEFNUM | DT_VERNEEDNUM | DT_RELACOUNT | DT_RELCOUNT => format!("{}", d.val),
+            _ => format!("{:#x}", d.val),
+        }
+    }
+
+    fn print_relocations(&self) -> Result<(), Error> {
+        let machine = self.elf.machine();
+        let versions = self.elf_versions()?;
+        let mut found = false;
+        for (idx, s) in self.sections.iter().enumerate() {
+            if s.sh_type == SHT_RELR {
+                found = true;
+                self.print_relr(idx)?;
+                continue;
+            }
+            if s.sh_type != SHT_REL && s.sh_type != SHT_RELA {
+                continue;
+            }
+            found = true;
+            let rela = s.sh_type == SHT_RELA;
+            let relocations = self.relocations(idx)?;
+            let symtab = s.link as usize;
+            let symbols = match self.sections.get(symtab) {
+                Some(t) if t.sh_type == SHT_SYMTAB || t.sh_type == SHT_DYNSYM => {
+                    self.symbols(symtab)?
+                }
+                _ => Vec::new(),
+            };
+            let is_dynsym = self
+                .sections
+                .get(symtab)
+                .map_or(false, |t| t.sh_type == SHT_DYNSYM);
+            outln!(
+                self,
+                "\nRelocation section '{}' at offset {:#x} contains {} {}:",
+                s.name,
+                s.offset,
+                relocations.len(),
+                if relocations.len() == 1 {
+                    "entry"
+                } else {
+                    "entries"
+                }
+            );
+            match (self.is_64, rela) {
+                (true, true) => outln!(self, "    Offset             Info             Type               Symbol's Value  Symbol's Name + Addend"),
+                (true, false) => outln!(self, "    Offset             Info             Type               Symbol's Value  Symbol's Name"),
+                (false, true) => outln!(self, " Offset     Info    Type                Sym. Value  Symbol's Name + Addend"),
+                (false, false) => outln!(self, " Offset     Info    Type                Sym. Value  Symbol's Name"),
+            }
+            for r in &relocations {
+                if self.is_64 {
+                    out!(self, "{:016x}  {:016x} ", r.offset, r.info);
+                } else {
+                    out!(self, "{:08x}  {:08x} ", r.offset, r.info);
+                }
+                match relocation_type_name(machine, r.kind) {
+                    Some(name) => out!(self, "{:<22}", name),
+                    None => out!(self, "unrecognized: {:<7x}", r.kind),
+                }
+                let addend = r.addend.unwrap_or(0);
+                match symbols.get(r.sym as usize) {
+                    Some(sym) if r.sym != 0 => {
+                        let mut name = self.symbol_name(sym);
+                        let len = name.len();
+                        if is_dynsym {
+                            if let Some(v) = versions.symbol_version(r.sym as usize, sym) {
+                                let public = v.file.is_none() && !v.hidden;
+                                name.push_str(if public { "@@" } else { "@" });
+                                name.push_str(v.name);
+                            }
+                        }
+                        if sym.kind == STT_GNU_IFUNC {
+                            // The resolver name takes the place of the value.
+                            let width = if self.is_64 { 14 } else { 8 };
+                            let pad = if len <= width { width + 1 - len } else { 1 };
+                            out!(self, " {}(){:pad$}{}", name, "", name, pad = pad);
+                        } else if self.is_64 {
+                            out!(self, " {:016x} {}", sym.value, name);
+                        } else {
+                            out!(self, " {:08x}   {}", sym.value, name);
+                        }
+                        if rela {
+                            if addend < 0 {
+                                out!(self, " - {:x}", addend.wrapping_neg());
+                            } else {
+                                out!(self, " + {:x}", addend);
+                            }
+                        }
+                    }
+                    _ if rela => {
+                        out!(
+                            self,
+                            "{:>width$}",
+                            " ",
+                            width = if self.is_64 { 20 } else { 12 }
+                        );
+                        if addend < 0 {
+                            out!(self, "-{:x}", addend.wrapping_neg());
+                        } else {
+                            out!(self, "{:x}", addend);
+                        }
+                    }
+                    _ => {}
+                }
+                outln!(self);
+            }
+        }
+        if !found {
+            outln!(self, "\nThere are no relocations in this file.");
+        }
+        Ok(())
+    }
+
+    fn print_relr(&self, idx: usize) -> Result<(), Error> {
+        let s = &self.sections[idx];
+        let word_size = if self.is_64 { 8 } else { 4 };
+        let data = self
+            .section_data(idx)
+            .ok_or_else(|| Error::ParseError(String::from("SHT_RELR is out of bounds")))?;
+        let count = data.len() / word_size;
+        outln!(
+            self,
+            "\nRelocation section '{}' at offset {:#x} contains {} {}:",
+            s.name,
+            s.offset,
+            count,
+            if count == 1 { "entry" } else { "entries" }
+        );
+        let offsets = parse_relr(data, word_size)?;
+        outln!(self, "  {} offsets", offsets.len());
+        for offset in offsets {
+            if self.is_64 {
+                outln!(self, "{:016x}", offset);
+            } else {
+                outln!(self, "{:08x}", offset);
+            }
+        }
+        Ok(())
+    }
+
+    fn elf_versions(&self) -> Result<Versions<'a>, Error> {
+        with_elf!(self, e => e.versions())
+    }
+
+    fn print_symbols(&self) -> Result<(), Error> {
+        let versions = self.elf_versions()?;
+        let osabi = self.ident().osabi;
+        for (idx, s) in self.sections.iter().enumerate() {
+            if s.sh_type != SHT_SYMTAB && s.sh_type != SHT_DYNSYM {
+                continue;
+            }
+            let symbols = self.symbols(idx)?;
+            outln!(
+                self,
+                "\nSymbol table '{}' contains {} {}:",
+                s.name,
+                symbols.len(),
+                if symbols.len() == 1 {
+                    "entry"
+                } else {
+                    "entries"
+                }
+            );
+            if self.is_64 {
+                outln!(
+                    self,
+                    "   Num:    Value          Size Type    Bind   Vis      Ndx Name"
+                );
+            } else {
+                outln!(
+                    self,
+                    "   Num:    Value  Size Type    Bind   Vis      Ndx Name"
+                );
+            }
+            for (num, sym) in symbols.iter().enumerate() {
+                let size = if sym.size > 99_999 {
+                    format!("{:#x}", sym.size)
+                } else {
+                    format!("{:5}", sym.size)
+                };
+                if self.is_64 {
+                    out!(self, "{:6}: {:016x} {}", num, sym.value, size);
+                } else {
+                    out!(self, "{:6}: {:08x} {}", num, sym.value, size);
+                }
+                out!(
+                    self,
+                    " {:<7} {:<6} {:<7} {:>4} {}",
+                    symbol_type_name(sym.kind, &osabi),
+                    symbol_bind_name(sym.bind, &osabi),
+                    symbol_visibility_name(sym.visibility),
+                    symbol_index_name(sym.shndx),
+                    self.symbol_name(sym)
+                );
+                if s.sh_type == SHT_DYNSYM {
+                    if let Some(v) = versions.symbol_version(num, sym) {
+                        if v.file.is_some() {
+                            out!(self, "@{} ({})", v.name, v.index);
+                        } else if v.hidden {
+                            out!(self, "@{}", v.name);
+                        } else {
+                            out!(self, "@@{}", v.name);
+                        }
+                    }
+                }
+                outln!(self);
+            }
+        }
+        Ok(())
+    }
+
+    fn print_versions(&self) -> Result<(), Error> {
+        let versions = self.elf_versions()?;
+        let mut found = false;
+        for (idx, s) in self.sections.iter().enumerate() {
+            let title = match s.sh_type {
+                SHT_GNU_VERSYM => "Version symbols",
+                SHT_GNU_VERDEF => "Version definition",
+                SHT_GNU_VERNEED => "Version needs",
+                _ => continue,
+            };
+            found = true;
+            let count = if s.sh_type == SHT_GNU_VERSYM {
+                versions.symbols.len()
+            } else {
+                s.info as usize
+            };
+            outln!(
+                self,
+                "\n{} section '{}' contains {} {}:",
+                title,
+                s.name,
+                count,
+                if count == 1 { "entry" } else { "entries" }
+            );
+            outln!(
+                self,
+                " Addr: 0x{:016x}  Offset: 0x{:08x}  Link: {} ({})",
+                s.addr,
+                s.offset,
+                s.link,
+                self.section_name(s.link as usize)
+            );
+            match s.sh_type {
+                SHT_GNU_VERSYM => self.print_version_symbols(idx, &versions)?,
+                SHT_GNU_VERDEF => {
+                    for def in &versions.definitions {
+                        outln!(
+                            self,
+                            "  {}: Rev: {}  Flags: {}  Index: {}  Cnt: {}  Name: {}",
+                            version_offset(def.offset),
+                            def.version,
+                            version_flags(def.flags),
+                            def.index,
+                            def.names.len(),
+                            def.name().unwrap_or("")
+                        );
+                        for (parent, &(offset, name)) in def.names.iter().enumerate().skip(1) {
+                            outln!(
+                                self,
+                                "  {}: Parent {}: {}",
+                                version_offset(offset),
+                                parent,
+                                name
+                            );
+                        }
+                    }
+                }
+                _ => {
+                    for need in &versions.needs {
+                        outln!(
+                            self,
+                            "  {}: Version: {}  File: {}  Cnt: {}",
+                            version_offset(need.offset),
+                            need.version,
+                            need.file,
+                            need.versions.len()
+                        );
+                        for aux in &need.versions {
+                            outln!(
+                                self,
+                                "  {}:   Name: {}  Flags: {}  Version: {}",
+                                version_offset(aux.offset),
+                                aux.name,
+                                version_flags(aux.flags),
+                                aux.index
+                            );
+                        }
+                    }
+                }
+            }
+        }
+        if !found {
+            outln!(self, "\nNo version information found in this file.");
+        }
+        Ok(())
+    }
+
+    fn print_version_symbols(&self, section: usize, versions: &Versions) -> Result<(), Error> {
+        let link = self.sections[section].link as usize;
+        let symbols = match self.sections.get(link) {
+            Some(t) if t.sh_type == SHT_DYNSYM => self.symbols(link)?,
+            _ => Vec::new(),
+        };
+        for (row, chunk) in versions.symbols.chunks(4).enumerate() {
+            out!(self, "  {:03x}:", row * 4);
+            for (col, &versym) in chunk.iter().enumerate() {
+                let entry = match versym {
+                    0 => String::from("   0 (*local*)    "),
+                    1 => String::from("   1 (*global*)   "),
+                    _ => {
+                        let index = versym & VERSYM_VERSION;
+                        let hidden = if versym & VERSYM_HIDDEN != 0 {
+                            'h'
+                        } else {
+                            ' '
+                        };
+                        let mut entry = format!("{:4x}{}", index, hidden);
+                        let undefined = symbols
+                            .get(row * 4 + col)
+                            .map_or(false, |s| s.is_undefined());
+                        let need = versions
+                            .needs
+                            .iter()
+                            .flat_map(|n| n.versions.iter())
+                            .find(|v| v.index == index)
+                            .map(|v| v.name);
+                        let def = versions
+                            .definitions
+                            .iter()
+                            .find(|d| d.index == index)
+                            .and_then(|d| d.name());
+                        let name = if undefined { need } else { def.or(need) };
+                        if let Some(name) = name {
+                            entry.push_str(&format!(
+                                "({}{:<width$}",
+                                name,
+                                ")",
+                                width = (12 - name.len() as isize).abs().max(1) as usize
+                            ));
+                        }
+                        format!("{:<18}", entry)
+                    }
+                };
+                out!(self, "{}", entry);
+            }
+            outln!(self);
+        }
+        Ok(())
+    }
+
+    fn print_notes(&self) -> Result<(), Error> {
+        if self.sections.is_empty() {
+            for p in self.segments.iter().filter(|p| p.p_type == PT_NOTE) {
+                outln!(
+                    self,
+                    "\nDisplaying notes found at file offset 0x{:08x} with length 0x{:08x}:",
+                    p.offset,
+                    p.filesz
+                );
+                let start = p.offset as usize;
+                let data = self
+                    .elf
+                    .data()
+                    .get(start..start.saturating_add(p.filesz as usize))
+                    .ok_or_else(|| Error::ParseError(String::from("PT_NOTE is out of bounds")))?;
+                self.print_note_entries(data, p.align as usize)?;
+            }
+            return Ok(());
+        }
+        for (idx, s) in self.sections.iter().enumerate() {
+            if s.sh_type != SHT_NOTE {
+                continue;
+            }
+            outln!(self, "\nDisplaying notes found in: {}", s.name);
+            let data = self
+                .section_data(idx)
+                .ok_or_else(|| Error::ParseError(String::from("SHT_NOTE is out of bounds")))?;
+            self.print_note_entries(data, s.align as usize)?;
+        }
+        Ok(())
+    }
+
+    fn print_note_entries(&self, data: &[u8], align: usize) -> Result<(), Error> {
+        outln!(self, "  Owner                Data size \tDescription");
+        for note in iter_notes(data, align) {
+            let note = note?;
+            out!(
+                self,
+                "  {:<20} 0x{:08x}\t{}\t",
+                note.name,
+                note.desc.len(),
+                self.note_type_name(&note)
+            );
+            match self.note_details(&note) {
+                Some(details) => outln!(self, "{}", details),
+                None => {
+                    if !note.desc.is_empty() {
+                        out!(self, "   description data: {}", hex_bytes(note.desc));
+                    }
+                    outln!(self);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn note_type_name(&self, note: &Note) -> String {
+        let name = match (note.name, note.n_type) {
+            ("GNU", NT_GNU_ABI_TAG) => "NT_GNU_ABI_TAG (ABI version tag)",
+            ("GNU", NT_GNU_HWCAP) => "NT_GNU_HWCAP (DSO-supplied software HWCAP info)",
+            ("GNU", NT_GNU_BUILD_ID) => "NT_GNU_BUILD_ID (unique build ID bitstring)",
+            ("GNU", NT_GNU_GOLD_VERSION) => "NT_GNU_GOLD_VERSION (gold version)",
+            ("GNU", NT_GNU_PROPERTY_TYPE_0) => "NT_GNU_PROPERTY_TYPE_0",
+            ("GNU", 0x100) => "NT_GNU_BUILD_ATTRIBUTE_OPEN",
+            ("GNU", 0x101) => "NT_GNU_BUILD_ATTRIBUTE_FUNC",
+            ("GNU", _) => return format!("Unknown note type: (0x{:08x})", note.n_type),
+            ("stapsdt", NT_STAPSDT) => "NT_STAPSDT (SystemTap probe descriptors)",
+            ("FDO", NT_FDO_PACKAGING_METADATA) => "FDO_PACKAGING_METADATA",
+            ("Go", 4) => "GO BUILDID",
+            (_, 1) => "NT_VERSION (version)",
+            (_, 2) => "NT_ARCH (architecture)",
+            _ => return format!("Unknown note type: (0x{:08x})", note.n_type),
+        };
+        String::from(name)
+    }
+
+    /// Returns the decoded description of the GNU and SystemTap notes, or
+    /// `None` for the notes only displayed as raw bytes.
+    fn note_details(&self, note: &Note) -> Option<String> {
+        match note.name {
+            "GNU" => {}
+            "stapsdt" if note.n_type == NT_STAPSDT => return self.stapsdt_details(note.desc),
+            "FDO" if note.n_type == NT_FDO_PACKAGING_METADATA => {
+                let len = note
+                    .desc
+                    .iter()
+                    .position(|&c| c == 0)
+                    .unwrap_or(note.desc.len());
+                return Some(format!(
+                    "    Packaging Metadata: {}",
+                    String::from_utf8_lossy(&note.desc[..len])
+                ));
+            }
+            _ => return None,
+        }
+        let word = |i: usize| read_word(note.desc, i * 4, 4).map(|w| w as u32);
+        Some(match note.n_type {
+            NT_GNU_BUILD_ID => format!("    Build ID: {}", hex_string(note.desc)),
+            NT_GNU_ABI_TAG => match (word(0), word(1), word(2), word(3)) {
+                (Some(os), Some(major), Some(minor), Some(sub)) => {
+                    let os = match os {
+                        GNU_ABI_TAG_LINUX => "Linux",
+                        GNU_ABI_TAG_HURD => "Hurd",
+                        GNU_ABI_TAG_SOLARIS => "Solaris",
+                        GNU_ABI_TAG_FREEBSD => "FreeBSD",
+                        GNU_ABI_TAG_NETBSD => "NetBSD",
+                        GNU_ABI_TAG_SYLLABLE => "Syllable",
+                        GNU_ABI_TAG_NACL => "NaCl",
+                        _ => "Unknown",
+                    };
+                    format!("    OS: {}, ABI: {}.{}.{}", os, major, minor, sub)
+                }
+                _ => String::from("    <corrupt GNU_ABI_TAG>"),
+            },
+            NT_GNU_GOLD_VERSION => format!(
+                "    Version: {}",
+                String::from_utf8_lossy(note.desc).trim_end_matches('\0')
+            ),
+            NT_GNU_PROPERTY_TYPE_0 => {
+                format!("      Properties: {}", self.gnu_properties(note.desc))
+            }
+            NT_GNU_HWCAP | 0x100 | 0x101 => return None,
+            _ => format!("    Description data: {}", hex_bytes(note.desc)),
+        })
+    }
+
+    fn stapsdt_details(&self, desc: &[u8]) -> Option<String> {
+        let size = if self.is_64 { 8 } else { 4 };
+        let addr = |i: usize| {
+            read_word(desc, i * size, size).map(|a| {
+                if self.is_64 {
+                    format!("0x{:016x}", a)
+                } else {
+                    format!("0x{:08x}", a)
+                }
+            })
+        };
+        let (pc, base, semaphore) = (addr(0)?, addr(1)?, addr(2)?);
+        let mut strings = desc.get(3 * size..)?.split(|&c| c == 0);
+        let mut string = || strings.next().map(String::from_utf8_lossy);
+        let (provider, name, args) = (string()?, string()?, string()?);
+        Some(format!(
+            "    Provider: {}\n    Name: {}\n    Location: {}, Base: {}, Semaphore: {}\n    Arguments: {}",
+            provider, name, pc, base, semaphore, args
+        ))
+    }
+
+    fn gnu_properties(&self, desc: &[u8]) -> String {
+        let align = if self.is_64 { 8 } else { 4 };
+        let machine = ElfMachine::from_u16(self.elf.machine());
+        let x86 = match machine {
+            Some(ElfMachine::Machine386)
+            | Some(ElfMachine::MachineIamcu)
+            | Some(ElfMachine::MachineX8664) => true,
+            _ => false,
+        };
+        let mut properties = Vec::new();
+        let mut rest = desc;
+        while rest.len() >= 8 {
+            let word = |b: &[u8]| read_word(b, 0, 4).unwrap_or(0) as u32;
+            let (pr_type, datasz) = (word(rest), word(&rest[4..]) as usize);
+            rest = &rest[8..];
+            if datasz > rest.len() {
+                properties.push(format!(
+                    "<corrupt type ({:#x}) datasz: {:#x}>",
+                    pr_type, datasz
+                ));
+                break;
+            }
+            let data = &rest[..datasz];
+            let value = if datasz == 4 { Some(word(data)) } else { None };
+            properties.push(match (pr_type, value) {
+                (GNU_PROPERTY_STACK_SIZE, _) if datasz == align => {
+                    format!("stack size: {:#x}", read_word(data, 0, align).unwrap_or(0))
+                }
+                (GNU_PROPERTY_NO_COPY_ON_PROTECTED, _) if datasz == 0 => {
+                    String::from("no copy on protected")
+                }
+                (GNU_PROPERTY_1_NEEDED, Some(v)) => {
+                    if v & 1 != 0 {
+                        String::from("1_needed: indirect external access")
+                    } else {
+                        format!("1_needed: {:#x}", v)
+                    }
+                }
+                (GNU_PROPERTY_X86_ISA_1_NEEDED, Some(v)) if x86 => {
+                    format!("x86 ISA needed: {}", bit_names(v, X86_ISA_NAMES))
+                }
+                (GNU_PROPERTY_X86_ISA_1_USED, Some(v)) if x86 => {
+                    format!("x86 ISA used: {}", bit_names(v, X86_ISA_NAMES))
+                }
+                (GNU_PROPERTY_X86_FEATURE_1_AND, Some(v)) if x86 => {
+                    format!("x86 feature: {}", bit_names(v, X86_FEATURE_1_NAMES))
+                }
+                (GNU_PROPERTY_X86_FEATURE_2_USED, Some(v)) if x86 => {
+                    format!("x86 feature used: {}", bit_names(v, X86_FEATURE_2_NAMES))
+                }
+                (GNU_PROPERTY_X86_FEATURE_2_NEEDED, Some(v)) if x86 => {
+                    format!("x86 feature needed: {}", bit_names(v, X86_FEATURE_2_NAMES))
+                }
+                (GNU_PROPERTY_AARCH64_FEATURE_1_AND, Some(v))
+                    if machine == Some(ElfMachine::MachineAarch64) =>
+                {
+                    format!("AArch64 feature: {}", bit_names(v, AARCH64_FEATURE_1_NAMES))
+                }
+                _ => {
+                    let kind = if pr_type < 0xc000_0000 {
+                        "unknown"
+                    } else if pr_type < 0xe000_0000 {
+                        "processor-specific"
+                    } else {
+                        "application-specific"
+                    };
+                    format!("<{} type {:#x} data: {}>", kind, pr_type, hex_bytes(data))
+                }
+            });
+            let padded = (datasz + align - 1) & !(align - 1);
+            rest = &rest[padded.min(rest.len())..];
+        }
+        properties.join(", ")
+    }
+
+    fn print(&self) -> Result<(), Error> {
+        if self.opts.debug {
+            match self.elf {
+                Elf::Elf32(ref e) => outln!(self, "ELF32 = {:#?}", e),
+                Elf::Elf64(ref e) => outln!(self, "ELF64 = {:#?}", e),
+            }
+        }
+        if self.opts.header {
+            self.print_file_header()?;
+        }
+        if self.opts.sections {
+            self.print_section_headers()?;
+        }
+        if self.opts.segments {
+            self.print_program_headers()?;
+        }
+        if self.opts.dynamic {
+            self.print_dynamic()?;
+        }
+        if self.opts.relocs {
+            self.print_relocations()?;
+        }
+        if self.opts.symbols {
+            self.print_symbols()?;
+        }
+        if self.opts.versions {
+            self.print_versions()?;
+        }
+        if self.opts.notes {
+            self.print_notes()?;
+        }
+        Ok(())
+    }
+}
+
+/// Version of the `--json` document, bumped on incompatible changes.
+#[cfg(feature = "serde")]
+const JSON_SCHEMA: u32 = 1;
+
+/// The document `--json` prints for each file. Addresses, offsets and sizes
+/// are numbers whatever the class, so both classes share the same schema.
+#[cfg(feature = "serde")]
+#[derive(Serialize)]
+struct Document<'a> {
+    schema: u32,
+    file: &'a str,
+    class: &'static str,
+    header: serde_json::Value,
+    sections: &'a [Section<'a>],
+    segments: &'a [Segment],
+    dynamic: Vec<DynamicEntry<'a>>,
+    symbols: Vec<SymbolTable<'a>>,
+    relocations: Vec<RelocationTable<'a>>,
+    relr: Vec<RelrTable<'a>>,
+    notes: Vec<NoteTable<'a>>,
+    versions: Versions<'a>,
+}
+
+/// A dynamic entry, with the string it refers to for string tags.
+#[cfg(feature = "serde")]
+#[derive(Serialize)]
+struct DynamicEntry<'a> {
+    tag: i64,
+    val: u64,
+    string: Option<&'a str>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Serialize)]
+struct SymbolTable<'a> {
+    section: usize,
+    name: &'a str,
+    symbols: Vec<Symbol<'a>>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Serialize)]
+struct RelocationTable<'a> {
+    section: usize,
+    name: &'a str,
+    /// Section of the symbol table the relocations refer to.
+    symbols: u32,
+    relocations: Vec<Relocation>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Serialize)]
+struct RelrTable<'a> {
+    section: usize,
+    name: &'a str,
+    offsets: Vec<u64>,
+}
+
+/// The notes of a `SHT_NOTE` section, or of a `PT_NOTE` segment when the file
+/// has no section headers.
+#[cfg(feature = "serde")]
+#[derive(Serialize)]
+struct NoteTable<'a> {
+    section: Option<usize>,
+    segment: Option<usize>,
+    name: &'a str,
+    notes: Vec<Note<'a>>,
+}
+
+#[cfg(feature = "serde")]
+impl<'a> Dump<'a> {
+    fn print_json(&self, path: &str) -> Result<(), Error> {
+        let header = with_elf!(self, e => serde_json::to_value(&e.header));
+        let dynamic = self
+            .elf
+            .dynamic()?
+            .iter()
+            .map(|d| DynamicEntry {
+                tag: d.tag,
+                val: d.val,
+                string: if d.is_string() {
+                    self.elf.dynamic_string(d.val)
+                } else {
+                    None
+                },
+            })
+            .collect();
+        let mut symbols = Vec::new();
+        let mut relocations = Vec::new();
+        let mut relr = Vec::new();
+        let mut notes = Vec::new();
+        let word_size = if self.is_64 { 8 } else { 4 };
+        for (idx, s) in self.sections.iter().enumerate() {
+            match s.sh_type {
+                SHT_SYMTAB | SHT_DYNSYM => symbols.push(SymbolTable {
+                    section: idx,
+                    name: s.name,
+                    symbols: self.symbols(idx)?,
+                }),
+                SHT_REL | SHT_RELA => relocations.push(RelocationTable {
+                    section: idx,
+                    name: s.name,
+                    symbols: s.link,
+                    relocations: self.relocations(idx)?,
+                }),
+                SHT_RELR => {
+                    let data = self.section_data(idx).ok_or_else(|| {
+                        Error::ParseError(String::from("SHT_RELR is out of bounds"))
+                    })?;
+                    relr.push(RelrTable {
+                        section: idx,
+                        name: s.name,
+                        offsets: parse_relr(data, word_size)?,
+                    });
+                }
+                SHT_NOTE => {
+                    let data = self.section_data(idx).ok_or_else(|| {
+                        Error::ParseError(String::from("SHT_NOTE is out of bounds"))
+                    })?;
+                    notes.push(NoteTable {
+                        section: Some(idx),
+                        segment: None,
+                        name: s.name,
+                        notes: parse_notes(data, s.align as usize)?,
+                    });
+                }
+                _ => {}
+            }
+        }
+        if self.sections.is_empty() {
+            for (idx, p) in self.segments.iter().enumerate() {
+                if p.p_type != PT_NOTE {
+                    continue;
+                }
+                let start = p.offset as usize;
+                let data = self
+                    .elf
+                    .data()
+                    .get(start..start.saturating_add(p.filesz as usize))
+                    .ok_or_else(|| Error::ParseError(String::from("PT_NOTE is out of bounds")))?;
+                notes.push(NoteTable {
+                    section: None,
+                    segment: Some(idx),
+                    name: "",
+                    notes: parse_notes(data, p.align as usize)?,
+                });
+            }
+        }
+        let document = Document {
+            schema: JSON_SCHEMA,
+            file: path,
+            class: if self.is_64 { "ELF64" } else { "ELF32" },
+            header: header.map_err(|e| Error::ParseError(e.to_string()))?,
+            sections: &self.sections,
+            segments: &self.segments,
+            dynamic: dynamic,
+            symbols: symbols,
+            relocations: relocations,
+            relr: relr,
+            notes: notes,
+            versions: self.elf_versions()?,
+        };
+        let json =
+            serde_json::to_string(&document).map_err(|e| Error::ParseError(e.to_string()))?;
+        outln!(self, "{}", json);
+        Ok(())
+    }
+}
+
+const NT_STAPSDT: u32 = 3;
+const NT_FDO_PACKAGING_METADATA: u32 = 0xcafe_1a7e;
+
+const GNU_PROPERTY_STACK_SIZE: u32 = 1;
+const GNU_PROPERTY_NO_COPY_ON_PROTECTED: u32 = 2;
+const GNU_PROPERTY_1_NEEDED: u32 = 0xb000_8000;
+const GNU_PROPERTY_AARCH64_FEATURE_1_AND: u32 = 0xc000_0000;
+const GNU_PROPERTY_X86_FEATURE_1_AND: u32 = 0xc000_0002;
+const GNU_PROPERTY_X86_ISA_1_NEEDED: u32 = 0xc000_8002;
+const GNU_PROPERTY_X86_FEATURE_2_NEEDED: u32 = 0xc000_8001;
+const GNU_PROPERTY_X86_ISA_1_USED: u32 = 0xc001_0002;
+const GNU_PROPERTY_X86_FEATURE_2_USED: u32 = 0xc001_0001;
+
+const X86_ISA_NAMES: &[&str] = &["x86-64-baseline", "x86-64-v2", "x86-64-v3", "x86-64-v4"];
+const X86_FEATURE_1_NAMES: &[&str] = &["IBT", "SHSTK", "LAM_U48", "LAM_U57"];
+const X86_FEATURE_2_NAMES: &[&str] = &[
+    "x86", "x87", "MMX", "XMM", "YMM", "ZMM", "FXSR", "XSAVE", "XSAVEOPT", "XSAVEC", "TMM", "MASK",
+];
+const AARCH64_FEATURE_1_NAMES: &[&str] = &["BTI", "PAC"];
+
+/// Joins the names of the bits set in `value`, the way readelf displays
+/// GNU properties.
+fn bit_names(value: u32, names: &[&str]) -> String {
+    if value == 0 {
+        return String::from("<None>");
+    }
+    (0..32)
+        .filter(|bit| value & (1 << bit) != 0)
+        .map(|bit| match names.get(bit) {
+            Some(name) => String::from(*name),
+            None => format!("<unknown: {:x}>", 1u32 << bit),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+const DT_FLAGS_NAMES: &[&str] = &["ORIGIN", "SYMBOLIC", "TEXTREL", "BIND_NOW", "STATIC_TLS"];
+const DT_FLAGS_1_NAMES: &[&str] = &[
+    "NOW",
+    "GLOBAL",
+    "GROUP",
+    "NODELETE",
+    "LOADFLTR",
+    "INITFIRST",
+    "NOOPEN",
+    "ORIGIN",
+    "DIRECT",
+    "TRANS",
+    "INTERPOSE",
+    "NODEFLIB",
+    "NODUMP",
+    "CONFALT",
+    "ENDFILTEE",
+    "DISPRELDNE",
+    "DISPRELPND",
+    "NODIRECT",
+    "IGNMULDEF",
+    "NOKSYMS",
+    "NOHDR",
+    "EDITED",
+    "NORELOC",
+    "SYMINTPOSE",
+    "GLOBAUDIT",
+    "SINGLETON",
+    "STUB",
+    "PIE",
+    "KMOD",
+    "WEAKFILTER",
+    "NOCOMMON",
+];
+
+fn flags_1_names(value: u64) -> String {
+    if value == 0 {
+        return String::from("Flags: None");
+    }
+    let mut s = String::from("Flags:");
+    let mut unknown = 0;
+    for bit in 0..64 {
+        if value & (1 << bit) == 0 {
+            continue;
+        }
+        match DT_FLAGS_1_NAMES.get(bit) {
+            Some(name) => {
+                s.push(' ');
+                s.push_str(name);
+            }
+            None => unknown |= 1 << bit,
+        }
+    }
+    if unknown != 0 {
+        s.push_str(&format!(" {:x}", unknown));
+    }
+    s
+}
+
+/// Reads the little-endian word of `size` bytes at `offset`.
+fn read_word(data: &[u8], offset: usize, size: usize) -> Option<u64> {
+    let bytes = data.get(offset..offset.checked_add(size)?)?;
+    Some(
+        bytes
+            .iter()
+            .rev()
+            .fold(0, |word, &b| word << 8 | u64::from(b)),
+    )
+}
+
+fn hex_string(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hex_bytes(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{:02x} ", b)).collect()
+}
+
+/// Formats an offset in a version section as C's `%#06x` does.
+fn version_offset(offset: usize) -> String {
+    if offset == 0 {
+        String::from("000000")
+    } else {
+        format!("{:#06x}", offset)
+    }
+}
+
+fn version_flags(flags: u16) -> String {
+    if flags == 0 {
+        return String::from("none");
+    }
+    let mut names = Vec::new();
+    if flags & VER_FLG_BASE != 0 {
+        names.push("BASE");
+    }
+    if flags & VER_FLG_WEAK != 0 {
+        names.push("WEAK");
+    }
+    if flags & VER_FLG_INFO != 0 {
+        names.push("INFO");
+    }
+    if flags & !(VER_FLG_BASE | VER_FLG_WEAK | VER_FLG_INFO) != 0 {
+        names.push("<unknown>");
+    }
+    names.join(" | ")
+}
+
+fn osabi_name(osabi: &ElfOSAbi) -> &'static str {
+    match *osabi {
+        ElfOSAbi::OSAbiSysv => "UNIX - System V",
+        ElfOSAbi::OSAbiHpux => "UNIX - HP-UX",
+        ElfOSAbi::OSAbiNetbsd => "UNIX - NetBSD",
+        ElfOSAbi::OSAbiGnu => "UNIX - GNU",
+        ElfOSAbi::OSAbiSolaris => "UNIX - Solaris",
+        ElfOSAbi::OSAbiAix => "UNIX - AIX",
+        ElfOSAbi::OSAbiIrix => "UNIX - IRIX",
+        ElfOSAbi::OSAbiFreebsd => "UNIX - FreeBSD",
+        ElfOSAbi::OSAbiTru64 => "UNIX - TRU64",
+        ElfOSAbi::OSAbiModesto => "Novell - Modesto",
+        ElfOSAbi::OSAbiOpenbsd => "UNIX - OpenBSD",
+        ElfOSAbi::OSAbiArmAeabi => "<unknown: 40>",
+        ElfOSAbi::OSAbiArm => "ARM",
+        ElfOSAbi::OSAbiStandalone => "Standalone App",
+    }
+}
+
+fn machine_name(e_machine: u16) -> String {
+    let name = match ElfMachine::from_u16(e_machine) {
+        Some(ElfMachine::MachineNone) => "None",
+        Some(ElfMachine::MachineM32) => "WE32100",
+        Some(ElfMachine::MachineSparc) => "Sparc",
+        Some(ElfMachine::Machine386) => "Intel 80386",
+        Some(ElfMachine::Machine68k) => "MC68000",
+        Some(ElfMachine::Machine88k) => "MC88000",
+        Some(ElfMachine::MachineIamcu) => "Intel MCU",
+        Some(ElfMachine::Machine860) => "Intel 80860",
+        Some(ElfMachine::MachineMips) => "MIPS R3000",
+        Some(ElfMachine::MachineS370) => "IBM System/370",
+        Some(ElfMachine::MachineMipsRs3Le) => "MIPS R4000 big-endian",
+        Some(ElfMachine::MachineParisc) => "HPPA",
+        Some(ElfMachine::MachineSparc32plus) => "Sparc v8+",
+        Some(ElfMachine::Machine960) => "Intel 80960",
+        Some(ElfMachine::MachinePpc) => "PowerPC",
+        Some(ElfMachine::MachinePpc64) => "PowerPC64",
+        Some(ElfMachine::MachineS390) => "IBM S/390",
+        Some(ElfMachine::MachineSpu) => "SPU",
+        Some(ElfMachine::MachineArm) => "ARM",
+        Some(ElfMachine::MachineSh) => "Renesas / SuperH SH",
+        Some(ElfMachine::MachineSparcv9) => "Sparc v9",
+        Some(ElfMachine::MachineIa64) => "Intel IA-64",
+        Some(ElfMachine::MachineX8664) => "Advanced Micro Devices X86-64",
+        Some(ElfMachine::MachineAvr) => "Atmel AVR 8-bit microcontroller",
+        Some(ElfMachine::MachineMsp430) => "Texas Instruments msp430 microcontroller",
+        Some(ElfMachine::MachineXtensa) => "Tensilica Xtensa Processor",
+        Some(ElfMachine::MachineAlteraNios2) => "Altera Nios II",
+        Some(ElfMachine::MachineAarch64) => "AArch64",
+        Some(ElfMachine::MachineMicroblaze) => "Xilinx MicroBlaze",
+        Some(ElfMachine::MachineCuda) => "NVIDIA CUDA architecture",
+        Some(ElfMachine::MachineAmdgpu) => "AMD GPU",
+        Some(ElfMachine::MachineRiscv) => "RISC-V",
+        Some(ElfMachine::MachineBpf) => "Linux BPF",
+        Some(ElfMachine::MachineAlpha) => "Alpha",
+        _ => return format!("<unknown>: {:#x}", e_machine),
+    };
+    String::from(name)
+}
+
+fn section_type_name(sh_type: u32, e_machine: u16) -> String {
+    let name = match sh_type {
+        SHT_NULL => "NULL",
+        SHT_PROGBITS => "PROGBITS",
+        SHT_SYMTAB => "SYMTAB",
+        SHT_STRTAB => "STRTAB",
+        SHT_RELA => "RELA",
+        SHT_HASH => "HASH",
+        SHT_DYNAMIC => "DYNAMIC",
+        SHT_NOTE => "NOTE",
+        SHT_NOBITS => "NOBITS",
+        SHT_REL => "REL",
+        SHT_SHLIB => "SHLIB",
+        SHT_DYNSYM => "DYNSYM",
+        SHT_INIT_ARRAY => "INIT_ARRAY",
+        SHT_FINI_ARRAY => "FINI_ARRAY",
+        SHT_PREINIT_ARRAY => "PREINIT_ARRAY",
+        SHT_GROUP => "GROUP",
+        SHT_SYMTAB_SHNDX => "SYMTAB SECTION INDICES",
+        SHT_RELR => "RELR",
+        SHT_GNU_ATTRIBUTES => "GNU_ATTRIBUTES",
+        SHT_GNU_HASH => "GNU_HASH",
+        SHT_GNU_LIBLIST => "GNU_LIBLIST",
+        SHT_GNU_VERDEF => "VERDEF",
+        SHT_GNU_VERNEED => "VERNEED",
+        SHT_GNU_VERSYM => "VERSYM",
+        SHT_LLVM_ADDRSIG => "LLVM_ADDRSIG",
+        SHT_X86_64_UNWIND if e_machine == ElfMachine::MachineX8664 as u16 => "X86_64_UNWIND",
+        SHT_ARM_EXIDX if e_machine == ElfMachine::MachineArm as u16 => "ARM_EXIDX",
+        SHT_ARM_ATTRIBUTES if e_machine == ElfMachine::MachineArm as u16 => "ARM_ATTRIBUTES",
+        SHT_RISCV_ATTRIBUTES if e_machine == ElfMachine::MachineRiscv as u16 => "RISCV_ATTRIBUTES",
+        0x7000_0000..=0x7fff_ffff => return format!("LOPROC+{:#x}", sh_type - 0x7000_0000),
+        0x6000_0000..=0x6fff_ffff => return format!("LOOS+{:#x}", sh_type - 0x6000_0000),
+        0x8000_0000..=0xffff_ffff => return format!("LOUSER+{:#x}", sh_type - 0x8000_0000),
+        _ => return format!("{:08x}: <unknown>", sh_type),
+    };
+    String::from(name)
+}
+
+const SHT_LLVM_ADDRSIG: u32 = 0x6fff_4c03;
+const SHT_X86_64_UNWIND: u32 = 0x7000_0001;
+const SHT_ARM_EXIDX: u32 = 0x7000_0001;
+const SHT_ARM_ATTRIBUTES: u32 = 0x7000_0003;
+const SHT_RISCV_ATTRIBUTES: u32 = 0x7000_0003;
+
+const SHF_GNU_RETAIN: u64 = 0x20_0000;
+const SHF_GNU_MBIND: u64 = 0x100_0000;
+const SHF_X86_64_LARGE: u64 = 0x1000_0000;
+const SHF_EXCLUDE: u64 = 0x8000_0000;
+
+/// Returns the readelf flag letters of a section, in the order of their
+/// bits.
+fn section_flags(flags: u64, e_machine: u16, osabi: &ElfOSAbi) -> String {
+    let (retain, mbind) = match *osabi {
+        ElfOSAbi::OSAbiGnu | ElfOSAbi::OSAbiFreebsd => (SHF_GNU_RETAIN, SHF_GNU_MBIND),
+        ElfOSAbi::OSAbiSysv => (0, SHF_GNU_MBIND),
+        _ => (0, 0),
+    };
+    let large = if e_machine == ElfMachine::MachineX8664 as u16 {
+        SHF_X86_64_LARGE
+    } else {
+        0
+    };
+    let letters = [
+        (SHF_WRITE, 'W'),
+        (SHF_ALLOC, 'A'),
+        (SHF_EXECINSTR, 'X'),
+        (SHF_MERGE, 'M'),
+        (SHF_STRINGS, 'S'),
+        (SHF_INFO_LINK, 'I'),
+        (SHF_LINK_ORDER, 'L'),
+        (SHF_OS_NONCONFORMING, 'O'),
+        (SHF_GROUP, 'G'),
+        (SHF_TLS, 'T'),
+        (SHF_COMPRESSED, 'C'),
+        (retain, 'R'),
+        (mbind, 'D'),
+        (large, 'l'),
+        (SHF_EXCLUDE, 'E'),
+    ];
+    let mut s = String::new();
+    let mut rest = flags;
+    for &(flag, letter) in letters.iter() {
+        if flag != 0 && flags & flag != 0 {
+            s.push(letter);
+            rest &= !flag;
+        }
+    }
+    if rest & 0x0ff0_0000 != 0 {
+        s.push('o');
+    }
+    if rest & 0xf000_0000 != 0 {
+        s.push('p');
+    }
+    if rest & !0xfff0_0000 != 0 {
+        s.push('x');
+    }
+    s
+}
+
+fn segment_type_name(p_type: u32, e_machine: u16) -> String {
+    let name = match p_type {
+        PT_NULL => "NULL",
+        PT_LOAD => "LOAD",
+        PT_DYNAMIC => "DYNAMIC",
+        PT_INTERP => "INTERP",
+        PT_NOTE => "NOTE",
+        PT_SHLIB => "SHLIB",
+        PT_PHDR => "PHDR",
+        PT_TLS => "TLS",
+        PT_GNU_EH_FRAME => "GNU_EH_FRAME",
+        PT_GNU_STACK => "GNU_STACK",
+        PT_GNU_RELRO => "GNU_RELRO",
+        PT_GNU_PROPERTY => "GNU_PROPERTY",
+        PT_GNU_SFRAME => "GNU_SFRAME",
+        PT_ARM_EXIDX if e_machine == ElfMachine::MachineArm as u16 => "EXIDX",
+        PT_RISCV_ATTRIBUTES if e_machine == ElfMachine::MachineRiscv as u16 => "RISCV_ATTRIBUTES",
+        0x7000_0000..=0x7fff_ffff => return format!("LOPROC+{:#x}", p_type - 0x7000_0000),
+        0x6000_0000..=0x6fff_ffff => return format!("LOOS+{:#x}", p_type - 0x6000_0000),
+        _ => return format!("<unknown>: {:x}", p_type),
+    };
+    String::from(name)
+}
+
+const PT_GNU_SFRAME: u32 = 0x6474_e554;
+const PT_ARM_EXIDX: u32 = 0x7000_0001;
+const PT_RISCV_ATTRIBUTES: u32 = 0x7000_0003;
+
+const DT_GNU_PRELINKED: i64 = 0x6fff_fdf5;
+const DT_GNU_CONFLICTSZ: i64 = 0x6fff_fdf6;
+const DT_GNU_LIBLISTSZ: i64 = 0x6fff_fdf7;
+const DT_CHECKSUM: i64 = 0x6fff_fdf8;
+const DT_PLTPADSZ: i64 = 0x6fff_fdf9;
+const DT_MOVEENT: i64 = 0x6fff_fdfa;
+const DT_MOVESZ: i64 = 0x6fff_fdfb;
+const DT_FEATURE: i64 = 0x6fff_fdfc;
+const DT_POSFLAG_1: i64 = 0x6fff_fdfd;
+const DT_SYMINSZ: i64 = 0x6fff_fdfe;
+const DT_SYMINENT: i64 = 0x6fff_fdff;
+const DT_TLSDESC_PLT: i64 = 0x6fff_fef6;
+const DT_TLSDESC_GOT: i64 = 0x6fff_fef7;
+const DT_GNU_CONFLICT: i64 = 0x6fff_fef8;
+const DT_GNU_LIBLIST: i64 = 0x6fff_fef9;
+const DT_CONFIG: i64 = 0x6fff_fefa;
+const DT_DEPAUDIT: i64 = 0x6fff_fefb;
+const DT_AUDIT: i64 = 0x6fff_fefc;
+const DT_PLTPAD: i64 = 0x6fff_fefd;
+const DT_MOVETAB: i64 = 0x6fff_fefe;
+const DT_SYMINFO: i64 = 0x6fff_feff;
+const DT_AUXILIARY: i64 = 0x7fff_fffd;
+const DT_FILTER: i64 = 0x7fff_ffff;
+
+fn dynamic_tag_name(tag: i64, _e_machine: u16) -> String {
+    let name = match tag {
+        DT_NULL => "NULL",
+        DT_NEEDED => "NEEDED",
+        DT_PLTRELSZ => "PLTRELSZ",
+        DT_PLTGOT => "PLTGOT",
+        DT_HASH => "HASH",
+        DT_STRTAB => "STRTAB",
+        DT_SYMTAB => "SYMTAB",
+        DT_RELA => "RELA",
+        DT_RELASZ => "RELASZ",
+        DT_RELAENT => "RELAENT",
+        DT_STRSZ => "STRSZ",
+        DT_SYMENT => "SYMENT",
+        DT_INIT => "INIT",
+        DT_FINI => "FINI",
+        DT_SONAME => "SONAME",
+        DT_RPATH => "RPATH",
+        DT_SYMBOLIC => "SYMBOLIC",
+        DT_REL => "REL",
+        DT_RELSZ => "RELSZ",
+        DT_RELENT => "RELENT",
+        DT_PLTREL => "PLTREL",
+        DT_DEBUG => "DEBUG",
+        DT_TEXTREL => "TEXTREL",
+        DT_JMPREL => "JMPREL",
+        DT_BIND_NOW => "BIND_NOW",
+        DT_INIT_ARRAY => "INIT_ARRAY",
+        DT_FINI_ARRAY => "FINI_ARRAY",
+        DT_INIT_ARRAYSZ => "INIT_ARRAYSZ",
+        DT_FINI_ARRAYSZ => "FINI_ARRAYSZ",
+        DT_RUNPATH => "RUNPATH",
+        DT_FLAGS => "FLAGS",
+        DT_PREINIT_ARRAY => "PREINIT_ARRAY",
+        DT_PREINIT_ARRAYSZ => "PREINIT_ARRAYSZ",
+        DT_SYMTAB_SHNDX => "SYMTAB_SHNDX",
+        DT_RELRSZ => "RELRSZ",
+        DT_RELR => "RELR",
+        DT_RELRENT => "RELRENT",
+        DT_GNU_PRELINKED => "GNU_PRELINKED",
+        DT_GNU_CONFLICTSZ => "GNU_CONFLICTSZ",
+        DT_GNU_LIBLISTSZ => "GNU_LIBLISTSZ",
+        DT_CHECKSUM => "CHECKSUM",
+        DT_PLTPADSZ => "PLTPADSZ",
+        DT_MOVEENT => "MOVEENT",
+        DT_MOVESZ => "MOVESZ",
+        DT_FEATURE => "FEATURE",
+        DT_POSFLAG_1 => "POSFLAG_1",
+        DT_SYMINSZ => "SYMINSZ",
+        DT_SYMINENT => "SYMINENT",
+        DT_GNU_HASH => "GNU_HASH",
+        DT_TLSDESC_PLT => "TLSDESC_PLT",
+        DT_TLSDESC_GOT => "TLSDESC_GOT",
+        DT_GNU_CONFLICT => "GNU_CONFLICT",
+        DT_GNU_LIBLIST => "GNU_LIBLIST",
+        DT_CONFIG => "CONFIG",
+        DT_DEPAUDIT => "DEPAUDIT",
+        DT_AUDIT => "AUDIT",
+        DT_PLTPAD => "PLTPAD",
+        DT_MOVETAB => "MOVETAB",
+        DT_SYMINFO => "SYMINFO",
+        DT_VERSYM => "VERSYM",
+        DT_RELACOUNT => "RELACOUNT",
+        DT_RELCOUNT => "RELCOUNT",
+        DT_FLAGS_1 => "FLAGS_1",
+        DT_VERDEF => "VERDEF",
+        DT_VERDEFNUM => "VERDEFNUM",
+        DT_VERNEED => "VERNEED",
+        DT_VERNEEDNUM => "VERNEEDNUM",
+        DT_AUXILIARY => "AUXILIARY",
+        DT_FILTER => "FILTER",
+        0x7000_0000..=0x7fff_ffff => return format!("Processor Specific: {:x}", tag),
+        0x6000_000d..=0x6fff_f000 => return format!("Operating System specific: {:x}", tag),
+        _ => return format!("<unknown>: {:x}", tag),
+    };
+    String::from(name)
+}
+
+fn symbol_type_name(kind: u8, osabi: &ElfOSAbi) -> String {
+    let gnu = *osabi == ElfOSAbi::OSAbiSysv
+        || *osabi == ElfOSAbi::OSAbiGnu
+        || *osabi == ElfOSAbi::OSAbiFreebsd;
+    let name = match kind {
+        STT_NOTYPE => "NOTYPE",
+        STT_OBJECT => "OBJECT",
+        STT_FUNC => "FUNC",
+        STT_SECTION => "SECTION",
+        STT_FILE => "FILE",
+        STT_COMMON => "COMMON",
+        STT_TLS => "TLS",
+        STT_GNU_IFUNC if gnu => "IFUNC",
+        13..=15 => return format!("<processor specific>: {}", kind),
+        10..=12 => return format!("<OS specific>: {}", kind),
+        _ => return format!("<unknown>: {}", kind),
+    };
+    String::from(name)
+}
+
+fn symbol_bind_name(bind: u8, osabi: &ElfOSAbi) -> String {
+    let gnu = *osabi == ElfOSAbi::OSAbiGnu;
+    let name = match bind {
+        STB_LOCAL => "LOCAL",
+        STB_GLOBAL => "GLOBAL",
+        STB_WEAK => "WEAK",
+        STB_GNU_UNIQUE if gnu => "UNIQUE",
+        13..=15 => return format!("<processor specific>: {}", bind),
+        10..=12 => return format!("<OS specific>: {}", bind),
+        _ => return format!("<unknown>: {}", bind),
+    };
+    String::from(name)
+}
+
+fn symbol_visibility_name(visibility: u8) -> &'static str {
+    match visibility {
+        STV_DEFAULT => "DEFAULT",
+        STV_INTERNAL => "INTERNAL",
+        STV_HIDDEN => "HIDDEN",
+        _ => "PROTECTED",
+    }
+}
+
+fn symbol_index_name(shndx: u16) -> String {
+    match shndx {
+        SHN_UNDEF => String::from("UND"),
+        SHN_ABS => String::from("ABS"),
+        SHN_COMMON => String::from("COM"),
+        0xff00..=0xff1f => format!("PRC[0x{:04x}]", shndx),
+        0xff20..=0xff3f => format!("OS [0x{:04x}]", shndx),
+        SHN_LORESERVE..=0xffff => format!("RSV[0x{:04x}]", shndx),
+        _ => format!("{:3}", shndx),
+    }
+}
+
+fn dump_file(path: &str, opts: &Options, title: bool, out: &mut dyn Write) -> Result<(), Error> {
+    let file = ElfFile::open(path)?;
+    let elf = file.parse()?;
+    let dump = Dump::new(elf, opts, out);
+    #[cfg(feature = "serde")]
+    {
+        if opts.json {
+            return dump.print_json(path);
+        }
+    }
+    if title {
+        outln!(dump, "\nFile: {}", path);
+    }
+    dump.print()?;
+    dump.out.borrow_mut().flush()?;
+    Ok(())
+}
+
+fn main() {
+    let app = App::new("dumpelf")
+        .version("1.0")
+        .author("Thomas WACHE")
+        .about("Displays information about ELF files, like readelf -W")
+        .help_short("H")
+        .version_short("v")
+        .arg(
+            Arg::with_name("elf")
+                .required(true)
+                .multiple(true)
+                .help("Files to display"),
+        )
+        .arg(
+            Arg::with_name("all")
+                .short("a")
+                .long("all")
+                .help("Equivalent to -h -l -S -s -r -d -V -n"),
+        )
+        .arg(
+            Arg::with_name("headers")
+                .short("e")
+                .long("headers")
+                .help("Equivalent to -h -l -S"),
+        )
+        .arg(
+            Arg::with_name("file-header")
+                .short("h")
+                .long("file-header")
+                .help("Display the ELF file header"),
+        )
+        .arg(
+            Arg::with_name("program-headers")
+                .short("l")
+                .long("program-headers")
+                .help("Display the program headers"),
+        )
+        .arg(
+            Arg::with_name("section-headers")
+                .short("S")
+                .long("section-headers")
+                .help("Display the section headers"),
+        )
+        .arg(
+            Arg::with_name("syms")
+                .short("s")
+                .long("syms")
+                .help("Display the symbol tables"),
+        )
+        .arg(
+            Arg::with_name("relocs")
+                .short("r")
+                .long("relocs")
+                .help("Display the relocations"),
+        )
+        .arg(
+            Arg::with_name("dynamic")
+                .short("d")
+                .long("dynamic")
+                .help("Display the dynamic section"),
+        )
+        .arg(
+            Arg::with_name("notes")
+                .short("n")
+                .long("notes")
+                .help("Display the notes"),
+        )
+        .arg(
+            Arg::with_name("version-info")
+                .short("V")
+                .long("version-info")
+                .help("Display the symbol versioning sections"),
+        )
+        .arg(
+            Arg::with_name("demangle")
+                .short("C")
+                .long("demangle")
+                .help("Demangle symbol names"),
+        )
+        .arg(
+            Arg::with_name("debug")
+                .long("debug")
+                .help("Dump the parsed structures"),
+        )
+        .arg(
+            Arg::with_name("wide")
+                .short("W")
+                .long("wide")
+                .help("Accepted for compatibility, the output is always wide"),
+        );
+    #[cfg(feature = "serde")]
+    let app = app.arg(
+        Arg::with_name("json")
+            .long("json")
+            .help("Print one JSON document per file instead"),
+    );
+    let args = app.get_matches();
+    let opts = Options::from_args(&args);
+    if !opts.any() {
+        eprintln!("dumpelf: Warning: Nothing to do.");
+        process::exit(1);
+    }
+    let paths: Vec<&str> = args.values_of("elf").map_or(Vec::new(), |v| v.collect());
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let mut status = 0;
+    for path in &paths {
+        match dump_file(path, &opts, paths.len() > 1, &mut out) {
+            Ok(()) => {}
+            // The reader went away, as with `dumpelf -a file | head`.
+            Err(Error::IOError(ref e)) if e.kind() == io::ErrorKind::BrokenPipe => break,
+            Err(e) => {
+                eprintln!("dumpelf: Error: {}: {}", path, e);
+                status = 1;
+            }
+        }
+    }
+    process::exit(status);
+}
@@ -0,0 +1,278 @@
+extern crate clap;
+extern crate elf;
+
+use clap::{App, Arg, ArgMatches};
+use elf::*;
+use std::path::Path;
+use std::process;
+
+#[derive(PartialEq)]
+enum Sort {
+    Name,
+    Address,
+    Size,
+    None,
+}
+
+/// What to list and how, following the nm options of the same name.
+struct Options {
+    dynamic: bool,
+    defined_only: bool,
+    undefined_only: bool,
+    demangle: bool,
+    print_size: bool,
+    reverse: bool,
+    sort: Sort,
+}
+
+impl Options {
+    fn from_args(args: &ArgMatches) -> Self {
+        let sort = if args.is_present("no-sort") {
+            Sort::None
+        } else if args.is_present("numeric-sort") {
+            Sort::Address
+        } else if args.is_present("size-sort") {
+            Sort::Size
+        } else {
+            Sort::Name
+        };
+        Options {
+            dynamic: args.is_present("dynamic"),
+            defined_only: args.is_present("defined-only"),
+            undefined_only: args.is_present("undefined-only"),
+            demangle: args.is_present("demangle"),
+            print_size: args.is_present("print-size"),
+            reverse: args.is_present("reverse-sort"),
+            sort: sort,
+        }
+    }
+}
+
+/// A symbol as listed, with its type letter and, for dynamic symbols, its
+/// version suffix.
+struct Entry<'a> {
+    symbol: Symbol<'a>,
+    letter: char,
+    version: String,
+}
+
+fn entries<'a>(elf: &Elf<'a>, opts: &Options) -> Result<Vec<Entry<'a>>, Error> {
+    let (symbols, versions) = if opts.dynamic {
+        (elf.dynsym()?, elf.versions()?)
+    } else {
+        (elf.symtab()?, Versions::default())
+    };
+    let mut entries = Vec::new();
+    for (idx, symbol) in symbols.into_iter().enumerate().skip(1) {
+        // Like nm, section and file symbols are debugging information.
+        if symbol.kind == STT_SECTION || symbol.kind == STT_FILE {
+            continue;
+        }
+        if (opts.defined_only && symbol.is_undefined())
+            || (opts.undefined_only && !symbol.is_undefined())
+        {
+            continue;
+        }
+        let version = match versions.symbol_version(idx, &symbol) {
+            // The symbol naming a version definition is not versioned.
+            Some(ref v) if v.name == symbol.name => String::new(),
+            Some(ref v) if v.file.is_some() || v.hidden || symbol.is_undefined() => {
+                format!("@{}", v.name)
+            }
+            Some(v) => format!("@@{}", v.name),
+            None => String::new(),
+        };
+        entries.push(Entry {
+            letter: elf.symbol_type_letter(&symbol),
+            symbol: symbol,
+            version: version,
+        });
+    }
+    Ok(entries)
+}
+
+/// Returns the value nm displays for `sym`, which is the size for common
+/// symbols, whose value is their alignment.
+fn value(sym: &Symbol) -> u64 {
+    if sym.shndx == SHN_COMMON {
+        sym.size
+    } else {
+        sym.value
+    }
+}
+
+fn sort(entries: &mut Vec<Entry>, opts: &Options) {
+    match opts.sort {
+        Sort::Name => entries.sort_by(|a, b| a.symbol.name.cmp(b.symbol.name)),
+        // Undefined symbols have no address and come first.
+        Sort::Address => entries.sort_by(|a, b| {
+            (!a.symbol.is_undefined(), value(&a.symbol), a.symbol.name).cmp(&(
+                !b.symbol.is_undefined(),
+                value(&b.symbol),
+                b.symbol.name,
+            ))
+        }),
+        Sort::Size => {
+            entries.retain(|e| e.symbol.size != 0 && !e.symbol.is_undefined());
+            entries.sort_by(|a, b| {
+                (a.symbol.size, a.symbol.name).cmp(&(b.symbol.size, b.symbol.name))
+            });
+        }
+        Sort::None => {}
+    }
+    if opts.reverse && opts.sort != Sort::None {
+        entries.reverse();
+    }
+}
+
+fn print_entries(entries: &[Entry], is_64: bool, opts: &Options) {
+    let width = if is_64 { 16 } else { 8 };
+    for e in entries {
+        let sym = &e.symbol;
+        if sym.is_undefined() {
+            print!("{:width$}", "", width = width);
+        } else if opts.sort == Sort::Size && !opts.print_size {
+            // nm shows the size instead of the value when sorting by size.
+            print!("{:0width$x}", sym.size, width = width);
+        } else {
+            print!("{:0width$x}", value(sym), width = width);
+        }
+        if opts.print_size && !sym.is_undefined() && sym.size != 0 {
+            print!(" {:0width$x}", sym.size, width = width);
+        }
+        println!(
+            " {} {}{}",
+            e.letter,
+            sym.display_name(!opts.demangle),
+            e.version
+        );
+    }
+}
+
+fn list_elf(elf: &Elf, name: &str, opts: &Options) -> Result<(), Error> {
+    let mut entries = entries(elf, opts)?;
+    if entries.is_empty() {
+        eprintln!("nmelf: {}: no symbols", name);
+        return Ok(());
+    }
+    sort(&mut entries, opts);
+    print_entries(&entries, elf.class() == ElfClass::Class64, opts);
+    Ok(())
+}
+
+fn list_archive(path: &str, data: &[u8], opts: &Options) -> Result<(), Error> {
+    let archive = parse_archive(data)?.1;
+    let dir = Path::new(path).parent().unwrap_or_else(|| Path::new(""));
+    for member in &archive.members {
+        // Members of thin archives are stored next to the archive.
+        let file;
+        let (name, data) = match member.data {
+            Some(data) => (String::from(member.name), data),
+            None => {
+                let member_path = dir.join(member.name);
+                file = ElfFile::open(&member_path)?;
+                (member_path.display().to_string(), file.data())
+            }
+        };
+        println!("\n{}:", name);
+        match Elf::parse(data) {
+            Ok(elf) => list_elf(&elf, &name, opts)?,
+            Err(_) => eprintln!("nmelf: {}: file format not recognized", name),
+        }
+    }
+    Ok(())
+}
+
+fn list_file(path: &str, opts: &Options, title: bool) -> Result<(), Error> {
+    let file = ElfFile::open(path)?;
+    let data = file.data();
+    if data.starts_with(AR_MAGIC) || data.starts_with(AR_THIN_MAGIC) {
+        if title {
+            println!("\n{}:", path);
+        }
+        return list_archive(path, data, opts);
+    }
+    let elf = file.parse()?;
+    if title {
+        println!("\n{}:", path);
+    }
+    list_elf(&elf, path, opts)
+}
+
+fn main() {
+    let args = App::new("nmelf")
+        .version("1.0")
+        .author("Thomas WACHE")
+        .about("Lists the symbols of ELF files and archives, like nm")
+        .arg(
+            Arg::with_name("elf")
+                .required(true)
+                .multiple(true)
+                .help("Files or archives to list"),
+        )
+        .arg(
+            Arg::with_name("dynamic")
+                .short("D")
+                .long("dynamic")
+                .help("List the dynamic symbols instead of the symbol table"),
+        )
+        .arg(
+            Arg::with_name("defined-only")
+                .long("defined-only")
+                .help("List only defined symbols"),
+        )
+        .arg(
+            Arg::with_name("undefined-only")
+                .short("u")
+                .long("undefined-only")
+                .conflicts_with("defined-only")
+                .help("List only undefined symbols"),
+        )
+        .arg(
+            Arg::with_name("numeric-sort")
+                .short("n")
+                .long("numeric-sort")
+                .help("Sort by address"),
+        )
+        .arg(
+            Arg::with_name("size-sort")
+                .long("size-sort")
+                .conflicts_with("numeric-sort")
+                .help("Sort by size, listing only symbols with a size"),
+        )
+        .arg(
+            Arg::with_name("no-sort")
+                .short("p")
+                .long("no-sort")
+                .help("Keep the symbol table order"),
+        )
+        .arg(
+            Arg::with_name("reverse-sort")
+                .short("r")
+                .long("reverse-sort")
+                .help("Reverse the sort order"),
+        )
+        .arg(
+            Arg::with_name("print-size")
+                .short("S")
+                .long("print-size")
+                .help("Print the size of defined symbols"),
+        )
+        .arg(
+            Arg::with_name("demangle")
+                .short("C")
+                .long("demangle")
+                .help("Demangle symbol names"),
+        )
+        .get_matches();
+    let opts = Options::from_args(&args);
+    let paths: Vec<&str> = args.values_of("elf").map_or(Vec::new(), |v| v.collect());
+    let mut status = 0;
+    for path in &paths {
+        if let Err(e) = list_file(path, &opts, paths.len() > 1) {
+            eprintln!("nmelf: {}: {}", path, e);
+            status = 1;
+        }
+    }
+    process::exit(status);
+}
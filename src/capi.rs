@@ -0,0 +1,967 @@
+//! C bindings for files of either class, and the types and helpers shared
+//! with the class-specific bindings.
+//!
+//! Every accessor returns an `RsElfError`; on failure, a description is kept
+//! until the next failure on the same thread and can be fetched with
+//! `rs_elf_last_error_message`.
+//!
+//! The exported functions are unsafe: handles must come from the matching
+//! parse function and not be released yet, and other pointers must be NULL
+//! or valid for the access the `# Safety` section of each function lists.
+
+use libc::{c_char, size_t};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::ptr;
+use std::slice;
+use std::sync::OnceLock;
+
+use dynamic::Dynamic;
+use error::Error;
+use file::{Elf, ElfFile};
+use header::{parse_elf_ident, ElfData};
+use notes::Note;
+use symbols::Symbol;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RsElfError {
+    Ok = 0,
+    NullPointer = 1,
+    ParseError = 2,
+    MissingSection = 3,
+    Unsupported = 4,
+    OutOfBounds = 5,
+    Io = 6,
+}
+
+/// Symbol table selector.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RsElfSymbolTable {
+    Symtab = 0,
+    Dynsym = 1,
+}
+
+/// File header, with addresses and offsets widened to 64 bits.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct RsElfHeader {
+    pub ei_class: u8,
+    pub ei_data: u8,
+    pub ei_osabi: u8,
+    pub ei_abiversion: u8,
+    pub e_type: u16,
+    pub e_machine: u16,
+    pub e_version: u32,
+    pub e_entry: u64,
+    pub e_phoff: u64,
+    pub e_shoff: u64,
+    pub e_flags: u32,
+    pub e_ehsize: u16,
+    pub e_phentsize: u16,
+    pub e_phnum: u16,
+    pub e_shentsize: u16,
+    pub e_shnum: u16,
+    pub e_shstrndx: u16,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct RsElfSegment {
+    pub p_type: u32,
+    pub p_flags: u32,
+    pub p_offset: u64,
+    pub p_vaddr: u64,
+    pub p_paddr: u64,
+    pub p_filesz: u64,
+    pub p_memsz: u64,
+    pub p_align: u64,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct RsElfSectionHeader {
+    /// NUL-terminated name in the file data, or NULL.
+    pub name: *const c_char,
+    pub sh_name: u32,
+    pub sh_type: u32,
+    pub sh_flags: u64,
+    pub sh_addr: u64,
+    pub sh_offset: u64,
+    pub sh_size: u64,
+    pub sh_link: u32,
+    pub sh_info: u32,
+    pub sh_addralign: u64,
+    pub sh_entsize: u64,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct RsElfSymbol {
+    /// NUL-terminated name in the file data.
+    pub name: *const c_char,
+    pub value: u64,
+    pub size: u64,
+    pub bind: u8,
+    pub kind: u8,
+    pub visibility: u8,
+    pub shndx: u16,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct RsElfDynamic {
+    pub tag: i64,
+    pub val: u64,
+    /// NUL-terminated string for `DT_NEEDED`, `DT_SONAME`, `DT_RPATH` and
+    /// `DT_RUNPATH`, NULL otherwise.
+    pub string: *const c_char,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct RsElfNote {
+    pub n_type: u32,
+    /// Owner name in the file data, not necessarily NUL-terminated.
+    pub name: *const c_char,
+    pub name_len: size_t,
+    pub desc: *const u8,
+    pub desc_len: size_t,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<(RsElfError, CString)>> = RefCell::new(None);
+}
+
+pub(crate) fn fail(code: RsElfError, message: &str) -> RsElfError {
+    let message = CString::new(message.replace('\0', "")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some((code, message)));
+    code
+}
+
+pub(crate) fn fail_with(e: &Error) -> RsElfError {
+    let code = match *e {
+        Error::ParseError(_) => RsElfError::ParseError,
+        Error::MissingSection(_) => RsElfError::MissingSection,
+        Error::Unsupported(_) => RsElfError::Unsupported,
+        Error::IOError(_) => RsElfError::Io,
+    };
+    fail(code, &format!("{}", e))
+}
+
+/// Returns the code of the last error of the calling thread.
+#[no_mangle]
+pub extern "C" fn rs_elf_last_error() -> RsElfError {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(RsElfError::Ok, |e| e.0))
+}
+
+/// Returns the description of the last error of the calling thread, or NULL.
+/// The string is valid until the next error on this thread.
+#[no_mangle]
+pub extern "C" fn rs_elf_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(ptr::null(), |e| e.1.as_ptr()))
+}
+
+/// Class-specific lookups behind the C accessors, with values converted to
+/// the class-neutral C structures.
+pub(crate) trait CApi<'a> {
+    fn c_header(&self) -> RsElfHeader;
+    fn c_number_of_segments(&self) -> usize;
+    fn c_segment(&self, idx: usize) -> Option<RsElfSegment>;
+    fn c_number_of_sections(&self) -> usize;
+    fn c_section(&self, idx: usize) -> Option<RsElfSectionHeader>;
+    fn c_find_section(&self, name: &str) -> Option<usize>;
+    fn c_number_of_symbols(&self, table: RsElfSymbolTable) -> Result<usize, Error>;
+    fn c_symbol(&self, table: RsElfSymbolTable, idx: usize) -> Result<Symbol<'a>, Error>;
+    fn c_dynamic(&self) -> Result<Cow<[Dynamic]>, Error>;
+    fn c_dynamic_string(&self, offset: u64) -> Option<&'a str>;
+    fn c_notes(&self) -> Result<Cow<[Note<'a>]>, Error>;
+}
+
+/// Dynamic entries and notes of an `ElfHandle`, parsed on the first access
+/// so that iterating over them is linear.
+#[derive(Debug, Default)]
+pub(crate) struct CTables<'a> {
+    dynamic: OnceLock<Vec<Dynamic>>,
+    notes: OnceLock<Vec<Note<'a>>>,
+}
+
+fn cached<T, F>(cell: &OnceLock<Vec<T>>, parse: F) -> Result<&[T], Error>
+where
+    F: FnOnce() -> Result<Vec<T>, Error>,
+{
+    if let Some(table) = cell.get() {
+        return Ok(table);
+    }
+    // Failures are not cached, and parsed again by the next call
+    let table = parse()?;
+    Ok(cell.get_or_init(|| table))
+}
+
+impl<'a> CTables<'a> {
+    pub(crate) fn dynamic<F>(&self, parse: F) -> Result<&[Dynamic], Error>
+    where
+        F: FnOnce() -> Result<Vec<Dynamic>, Error>,
+    {
+        cached(&self.dynamic, parse)
+    }
+
+    pub(crate) fn notes<F>(&self, parse: F) -> Result<&[Note<'a>], Error>
+    where
+        F: FnOnce() -> Result<Vec<Note<'a>>, Error>,
+    {
+        cached(&self.notes, parse)
+    }
+}
+
+unsafe fn with_elf<'e, T, F>(e: *const T, out_is_null: bool, f: F) -> RsElfError
+where
+    T: 'e,
+    F: FnOnce(&'e T) -> RsElfError,
+{
+    if e.is_null() || out_is_null {
+        return fail(RsElfError::NullPointer, "NULL pointer argument");
+    }
+    f(unsafe { &*e })
+}
+
+fn out_of_bounds(what: &str, idx: usize) -> RsElfError {
+    fail(
+        RsElfError::OutOfBounds,
+        &format!("no {} at index {}", what, idx),
+    )
+}
+
+/// Returns a pointer to `s`, which must be empty or followed by a NUL byte
+/// in the file data.
+pub(crate) fn file_str(s: &str) -> *const c_char {
+    if s.is_empty() {
+        b"\0".as_ptr() as *const c_char
+    } else {
+        s.as_ptr() as *const c_char
+    }
+}
+
+pub(crate) unsafe fn get_header<'a, T: CApi<'a>>(e: *const T, out: *mut RsElfHeader) -> RsElfError {
+    with_elf(e, out.is_null(), |e| {
+        unsafe { *out = e.c_header() };
+        RsElfError::Ok
+    })
+}
+
+pub(crate) unsafe fn get_number_of_segments<'a, T: CApi<'a>>(e: *const T) -> size_t {
+    if e.is_null() {
+        return 0;
+    }
+    unsafe { (*e).c_number_of_segments() }
+}
+
+pub(crate) unsafe fn get_segment<'a, T: CApi<'a>>(
+    e: *const T,
+    idx: size_t,
+    out: *mut RsElfSegment,
+) -> RsElfError {
+    with_elf(e, out.is_null(), |e| match e.c_segment(idx) {
+        Some(s) => {
+            unsafe { *out = s };
+            RsElfError::Ok
+        }
+        None => out_of_bounds("segment", idx),
+    })
+}
+
+pub(crate) unsafe fn get_number_of_section_headers<'a, T: CApi<'a>>(e: *const T) -> size_t {
+    if e.is_null() {
+        return 0;
+    }
+    unsafe { (*e).c_number_of_sections() }
+}
+
+pub(crate) unsafe fn get_section_header<'a, T: CApi<'a>>(
+    e: *const T,
+    idx: size_t,
+    out: *mut RsElfSectionHeader,
+) -> RsElfError {
+    with_elf(e, out.is_null(), |e| match e.c_section(idx) {
+        Some(s) => {
+            unsafe { *out = s };
+            RsElfError::Ok
+        }
+        None => out_of_bounds("section", idx),
+    })
+}
+
+pub(crate) unsafe fn find_section<'a, T: CApi<'a>>(
+    e: *const T,
+    name: *const c_char,
+    idx: *mut size_t,
+) -> RsElfError {
+    with_elf(e, name.is_null() || idx.is_null(), |e| {
+        let name = match unsafe { CStr::from_ptr(name) }.to_str() {
+            Ok(name) => name,
+            Err(_) => return fail(RsElfError::MissingSection, "section name is not UTF-8"),
+        };
+        match e.c_find_section(name) {
+            Some(found) => {
+                unsafe { *idx = found };
+                RsElfError::Ok
+            }
+            None => fail_with(&Error::MissingSection(String::from(name))),
+        }
+    })
+}
+
+unsafe fn store_count(count: Result<usize, Error>, out: *mut size_t) -> RsElfError {
+    match count {
+        Ok(n) => {
+            unsafe { *out = n };
+            RsElfError::Ok
+        }
+        Err(err) => fail_with(&err),
+    }
+}
+
+pub(crate) unsafe fn get_number_of_symbols<'a, T: CApi<'a>>(
+    e: *const T,
+    table: RsElfSymbolTable,
+    count: *mut size_t,
+) -> RsElfError {
+    with_elf(e, count.is_null(), |e| {
+        store_count(e.c_number_of_symbols(table), count)
+    })
+}
+
+pub(crate) unsafe fn get_symbol<'a, T: CApi<'a>>(
+    e: *const T,
+    table: RsElfSymbolTable,
+    idx: size_t,
+    out: *mut RsElfSymbol,
+) -> RsElfError {
+    with_elf(e, out.is_null(), |e| match e.c_symbol(table, idx) {
+        Ok(s) => {
+            unsafe {
+                *out = RsElfSymbol {
+                    name: file_str(s.name),
+                    value: s.value,
+                    size: s.size,
+                    bind: s.bind,
+                    kind: s.kind,
+                    visibility: s.visibility,
+                    shndx: s.shndx,
+                }
+            };
+            RsElfError::Ok
+        }
+        Err(err) => fail_with(&err),
+    })
+}
+
+pub(crate) unsafe fn get_number_of_dynamic_entries<'a, T: CApi<'a>>(
+    e: *const T,
+    count: *mut size_t,
+) -> RsElfError {
+    with_elf(e, count.is_null(), |e| {
+        store_count(e.c_dynamic().map(|d| d.len()), count)
+    })
+}
+
+pub(crate) unsafe fn get_dynamic_entry<'a, T: CApi<'a>>(
+    e: *const T,
+    idx: size_t,
+    out: *mut RsElfDynamic,
+) -> RsElfError {
+    with_elf(e, out.is_null(), |e| {
+        let entries = match e.c_dynamic() {
+            Ok(entries) => entries,
+            Err(err) => return fail_with(&err),
+        };
+        match entries.get(idx) {
+            Some(d) => {
+                let string = if d.is_string() {
+                    e.c_dynamic_string(d.val).map_or(ptr::null(), file_str)
+                } else {
+                    ptr::null()
+                };
+                unsafe {
+                    *out = RsElfDynamic {
+                        tag: d.tag,
+                        val: d.val,
+                        string: string,
+                    }
+                };
+                RsElfError::Ok
+            }
+            None => out_of_bounds("dynamic entry", idx),
+        }
+    })
+}
+
+pub(crate) unsafe fn get_number_of_notes<'a, T: CApi<'a>>(
+    e: *const T,
+    count: *mut size_t,
+) -> RsElfError {
+    with_elf(e, count.is_null(), |e| {
+        store_count(e.c_notes().map(|n| n.len()), count)
+    })
+}
+
+pub(crate) unsafe fn get_note<'a, T: CApi<'a>>(
+    e: *const T,
+    idx: size_t,
+    out: *mut RsElfNote,
+) -> RsElfError {
+    with_elf(e, out.is_null(), |e| {
+        let notes = match e.c_notes() {
+            Ok(notes) => notes,
+            Err(err) => return fail_with(&err),
+        };
+        match notes.get(idx) {
+            Some(n) => {
+                unsafe {
+                    *out = RsElfNote {
+                        n_type: n.n_type,
+                        name: file_str(n.name),
+                        name_len: n.name.len(),
+                        desc: n.desc.as_ptr(),
+                        desc_len: n.desc.len(),
+                    }
+                };
+                RsElfError::Ok
+            }
+            None => out_of_bounds("note", idx),
+        }
+    })
+}
+
+impl<'a> CApi<'a> for Elf<'a> {
+    fn c_header(&self) -> RsElfHeader {
+        dispatch!(self, e => e.c_header())
+    }
+
+    fn c_number_of_segments(&self) -> usize {
+        dispatch!(self, e => e.c_number_of_segments())
+    }
+
+    fn c_segment(&self, idx: usize) -> Option<RsElfSegment> {
+        dispatch!(self, e => e.c_segment(idx))
+    }
+
+    fn c_number_of_sections(&self) -> usize {
+        dispatch!(self, e => e.c_number_of_sections())
+    }
+
+    fn c_section(&self, idx: usize) -> Option<RsElfSectionHeader> {
+        dispatch!(self, e => e.c_section(idx))
+    }
+
+    fn c_find_section(&self, name: &str) -> Option<usize> {
+        dispatch!(self, e => e.c_find_section(name))
+    }
+
+    fn c_number_of_symbols(&self, table: RsElfSymbolTable) -> Result<usize, Error> {
+        dispatch!(self, e => e.c_number_of_symbols(table))
+    }
+
+    fn c_symbol(&self, table: RsElfSymbolTable, idx: usize) -> Result<Symbol<'a>, Error> {
+        dispatch!(self, e => e.c_symbol(table, idx))
+    }
+
+    fn c_dynamic(&self) -> Result<Cow<[Dynamic]>, Error> {
+        dispatch!(self, e => e.c_dynamic())
+    }
+
+    fn c_dynamic_string(&self, offset: u64) -> Option<&'a str> {
+        dispatch!(self, e => e.c_dynamic_string(offset))
+    }
+
+    fn c_notes(&self) -> Result<Cow<[Note<'a>]>, Error> {
+        dispatch!(self, e => e.c_notes())
+    }
+}
+
+/// Handle of the `rs_elf_*` functions: a parsed file of either class, and
+/// the data it was parsed from when the handle owns it.
+pub struct ElfHandle {
+    // Declared before the data they borrow, so that they are dropped first
+    elf: Elf<'static>,
+    tables: CTables<'static>,
+    _data: Option<ElfFile>,
+}
+
+impl CApi<'static> for ElfHandle {
+    fn c_header(&self) -> RsElfHeader {
+        self.elf.c_header()
+    }
+
+    fn c_number_of_segments(&self) -> usize {
+        self.elf.c_number_of_segments()
+    }
+
+    fn c_segment(&self, idx: usize) -> Option<RsElfSegment> {
+        self.elf.c_segment(idx)
+    }
+
+    fn c_number_of_sections(&self) -> usize {
+        self.elf.c_number_of_sections()
+    }
+
+    fn c_section(&self, idx: usize) -> Option<RsElfSectionHeader> {
+        self.elf.c_section(idx)
+    }
+
+    fn c_find_section(&self, name: &str) -> Option<usize> {
+        self.elf.c_find_section(name)
+    }
+
+    fn c_number_of_symbols(&self, table: RsElfSymbolTable) -> Result<usize, Error> {
+        self.elf.c_number_of_symbols(table)
+    }
+
+    fn c_symbol(&self, table: RsElfSymbolTable, idx: usize) -> Result<Symbol<'static>, Error> {
+        self.elf.c_symbol(table, idx)
+    }
+
+    fn c_dynamic(&self) -> Result<Cow<[Dynamic]>, Error> {
+        self.tables.dynamic(|| self.elf.dynamic()).map(Cow::Borrowed)
+    }
+
+    fn c_dynamic_string(&self, offset: u64) -> Option<&'static str> {
+        self.elf.c_dynamic_string(offset)
+    }
+
+    fn c_notes(&self) -> Result<Cow<[Note<'static>]>, Error> {
+        self.tables.notes(|| self.elf.notes()).map(Cow::Borrowed)
+    }
+}
+
+fn new_handle(buf: &'static [u8], data: Option<ElfFile>) -> *mut ElfHandle {
+    match Elf::parse(buf) {
+        Ok(elf) => Box::into_raw(Box::new(ElfHandle {
+            elf: elf,
+            tables: CTables::default(),
+            _data: data,
+        })),
+        Err(e) => {
+            fail_with(&e);
+            ptr::null_mut()
+        }
+    }
+}
+
+fn new_owning_handle(data: ElfFile) -> *mut ElfHandle {
+    // The mapped or buffered data does not move with the handle, and
+    // outlives the parsed file borrowing it.
+    let buf = unsafe { slice::from_raw_parts(data.data().as_ptr(), data.data().len()) };
+    new_handle(buf, Some(data))
+}
+
+/// Returns the slice of `len` bytes at `buf`, or records an error for NULL
+/// or oversized buffers.
+pub(crate) unsafe fn input_slice<'a>(buf: *const u8, len: size_t) -> Option<&'a [u8]> {
+    if buf.is_null() {
+        fail(RsElfError::NullPointer, "NULL pointer argument");
+        None
+    } else if len > isize::max_value() as usize {
+        fail(RsElfError::OutOfBounds, "buffer length is too large");
+        None
+    } else {
+        Some(unsafe { slice::from_raw_parts(buf, len) })
+    }
+}
+
+/// Class, data encoding and machine of a file, readable for any ELF file.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct RsElfIdent {
+    pub ei_class: u8,
+    pub ei_data: u8,
+    pub e_machine: u16,
+}
+
+/// Fills `out` with the class, data encoding and machine of the file in
+/// `buf`, including files `rs_elf_parse` does not support.
+///
+/// # Safety
+///
+/// `buf` must be NULL or valid for reading `len` bytes during the call, and
+/// `out` NULL or valid for writing an `RsElfIdent`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf_identify(
+    buf: *const u8,
+    len: size_t,
+    out: *mut RsElfIdent,
+) -> RsElfError {
+    if out.is_null() {
+        return fail(RsElfError::NullPointer, "NULL pointer argument");
+    }
+    let buf = match input_slice(buf, len) {
+        Some(buf) => buf,
+        None => return rs_elf_last_error(),
+    };
+    let ident = match parse_elf_ident(buf) {
+        Ok((_, ident)) => ident,
+        Err(e) => return fail_with(&Error::from(e)),
+    };
+    // e_machine follows e_type, right after the identification
+    let machine = match buf.get(18..20) {
+        Some(m) if ident.data == ElfData::DataLSB => u16::from(m[0]) | u16::from(m[1]) << 8,
+        Some(m) => u16::from(m[1]) | u16::from(m[0]) << 8,
+        None => return fail(RsElfError::ParseError, "truncated header"),
+    };
+    unsafe {
+        *out = RsElfIdent {
+            ei_class: ident.class as u8,
+            ei_data: ident.data as u8,
+            e_machine: machine,
+        }
+    };
+    RsElfError::Ok
+}
+
+/// Parses a 32 or 64-bit file, returning NULL on failure. The handle
+/// borrows `buf`.
+///
+/// # Safety
+///
+/// `buf` must be NULL or valid for reading `len` bytes, and stay valid and
+/// unmodified until the handle is released with `rs_elf_free`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf_parse(buf: *const u8, len: size_t) -> *mut ElfHandle {
+    match input_slice(buf, len) {
+        Some(buf) => new_handle(buf, None),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Parses a copy of `buf`, returning NULL on failure. The buffer may be
+/// released as soon as the function returns.
+///
+/// # Safety
+///
+/// `buf` must be NULL or valid for reading `len` bytes during the call.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf_parse_copy(buf: *const u8, len: size_t) -> *mut ElfHandle {
+    match input_slice(buf, len) {
+        Some(buf) => new_owning_handle(ElfFile::from_vec(buf.to_vec())),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Maps, or reads when it cannot be mapped, and parses the file at `path`,
+/// returning NULL on failure. The handle owns the file contents.
+///
+/// # Safety
+///
+/// `path` must be NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf_open_path(path: *const c_char) -> *mut ElfHandle {
+    if path.is_null() {
+        fail(RsElfError::NullPointer, "NULL pointer argument");
+        return ptr::null_mut();
+    }
+    let path = match unsafe { CStr::from_ptr(path) }.to_str() {
+        Ok(path) => path,
+        Err(_) => {
+            fail(RsElfError::Io, "path is not UTF-8");
+            return ptr::null_mut();
+        }
+    };
+    match ElfFile::open(path) {
+        Ok(data) => new_owning_handle(data),
+        Err(e) => {
+            fail(RsElfError::Io, &format!("{}: {}", path, e));
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Releases a handle, and the data it owns. Pointers obtained from the
+/// handle are invalid afterwards.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle not released yet. Neither the handle nor
+/// the pointers obtained from it may be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf_free(e: *mut ElfHandle) {
+    if !e.is_null() {
+        unsafe { drop(Box::from_raw(e)) }
+    }
+}
+
+/// Returns `ELFCLASS32` or `ELFCLASS64`, 0 for a NULL handle.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle not released with `rs_elf_free` yet.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf_get_class(e: *const ElfHandle) -> u8 {
+    if e.is_null() {
+        return 0;
+    }
+    unsafe { (*e).elf.class() as u8 }
+}
+
+/// Returns `ELFDATA2LSB` or `ELFDATA2MSB`, 0 for a NULL handle.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle not released with `rs_elf_free` yet.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf_get_endianness(e: *const ElfHandle) -> u8 {
+    if e.is_null() {
+        return 0;
+    }
+    unsafe { (*e).elf.endianness() as u8 }
+}
+
+/// Returns `e_machine`, `EM_NONE` for a NULL handle.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle not released with `rs_elf_free` yet.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf_get_machine(e: *const ElfHandle) -> u16 {
+    if e.is_null() {
+        return 0;
+    }
+    unsafe { (*e).elf.machine() }
+}
+
+/// Fills `out` with the file header.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle not released with `rs_elf_free` yet, and
+/// `out` NULL or valid for writing an `RsElfHeader`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf_get_header(
+    e: *const ElfHandle,
+    out: *mut RsElfHeader,
+) -> RsElfError {
+    get_header(e, out)
+}
+
+/// Returns the number of program headers, 0 for a NULL handle.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle not released with `rs_elf_free` yet.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf_get_number_of_segments(e: *const ElfHandle) -> size_t {
+    get_number_of_segments(e)
+}
+
+/// Fills `out` with the program header `idx`.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle not released with `rs_elf_free` yet, and
+/// `out` NULL or valid for writing an `RsElfSegment`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf_get_segment(
+    e: *const ElfHandle,
+    idx: size_t,
+    out: *mut RsElfSegment,
+) -> RsElfError {
+    get_segment(e, idx, out)
+}
+
+/// Returns the number of section headers, 0 for a NULL handle.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle not released with `rs_elf_free` yet.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf_get_number_of_section_headers(e: *const ElfHandle) -> size_t {
+    get_number_of_section_headers(e)
+}
+
+/// Fills `out` with the section header `idx` and its name.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle not released with `rs_elf_free` yet, and
+/// `out` NULL or valid for writing an `RsElfSectionHeader`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf_get_section_header(
+    e: *const ElfHandle,
+    idx: size_t,
+    out: *mut RsElfSectionHeader,
+) -> RsElfError {
+    get_section_header(e, idx, out)
+}
+
+/// Stores in `idx` the index of the first section named `name`.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle not released with `rs_elf_free` yet, `name`
+/// NULL or a NUL-terminated string and `idx` NULL or valid for writing a
+/// `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf_find_section(
+    e: *const ElfHandle,
+    name: *const c_char,
+    idx: *mut size_t,
+) -> RsElfError {
+    find_section(e, name, idx)
+}
+
+/// Stores in `count` the number of entries of a symbol table.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle not released with `rs_elf_free` yet, and
+/// `count` NULL or valid for writing a `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf_get_number_of_symbols(
+    e: *const ElfHandle,
+    table: RsElfSymbolTable,
+    count: *mut size_t,
+) -> RsElfError {
+    get_number_of_symbols(e, table, count)
+}
+
+/// Fills `out` with the entry `idx` of a symbol table.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle not released with `rs_elf_free` yet, and
+/// `out` NULL or valid for writing an `RsElfSymbol`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf_get_symbol(
+    e: *const ElfHandle,
+    table: RsElfSymbolTable,
+    idx: size_t,
+    out: *mut RsElfSymbol,
+) -> RsElfError {
+    get_symbol(e, table, idx, out)
+}
+
+/// Stores in `count` the number of dynamic entries before `DT_NULL`.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle not released with `rs_elf_free` yet, and
+/// `count` NULL or valid for writing a `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf_get_number_of_dynamic_entries(
+    e: *const ElfHandle,
+    count: *mut size_t,
+) -> RsElfError {
+    get_number_of_dynamic_entries(e, count)
+}
+
+/// Fills `out` with the dynamic entry `idx`.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle not released with `rs_elf_free` yet, and
+/// `out` NULL or valid for writing an `RsElfDynamic`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf_get_dynamic_entry(
+    e: *const ElfHandle,
+    idx: size_t,
+    out: *mut RsElfDynamic,
+) -> RsElfError {
+    get_dynamic_entry(e, idx, out)
+}
+
+/// Stores in `count` the number of notes.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle not released with `rs_elf_free` yet, and
+/// `count` NULL or valid for writing a `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf_get_number_of_notes(
+    e: *const ElfHandle,
+    count: *mut size_t,
+) -> RsElfError {
+    get_number_of_notes(e, count)
+}
+
+/// Fills `out` with the note `idx`.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle not released with `rs_elf_free` yet, and
+/// `out` NULL or valid for writing an `RsElfNote`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf_get_note(
+    e: *const ElfHandle,
+    idx: size_t,
+    out: *mut RsElfNote,
+) -> RsElfError {
+    get_note(e, idx, out)
+}
+
+/// Stores in `out` a pointer to the `len` bytes at `offset` in the file data.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle not released with `rs_elf_free` yet, and
+/// `out` NULL or valid for writing a pointer.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf_get_data(
+    e: *const ElfHandle,
+    offset: size_t,
+    len: size_t,
+    out: *mut *const u8,
+) -> RsElfError {
+    with_elf(e, out.is_null(), |e| {
+        match offset
+            .checked_add(len)
+            .and_then(|end| e.elf.data().get(offset..end))
+        {
+            Some(data) => {
+                unsafe { *out = data.as_ptr() };
+                RsElfError::Ok
+            }
+            None => fail(
+                RsElfError::OutOfBounds,
+                &format!("{} bytes at {:#x} are out of the file", len, offset),
+            ),
+        }
+    })
+}
+
+/// Stores in `out` and `len` the contents of the section `idx` in the file,
+/// empty for `SHT_NOBITS` sections.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle not released with `rs_elf_free` yet, `out`
+/// NULL or valid for writing a pointer and `len` NULL or valid for writing
+/// a `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf_get_section_data(
+    e: *const ElfHandle,
+    idx: size_t,
+    out: *mut *const u8,
+    len: *mut size_t,
+) -> RsElfError {
+    with_elf(e, out.is_null() || len.is_null(), |e| {
+        let data = match e.elf {
+            Elf::Elf32(ref e) => e.sections.get(idx).map(|s| e.section_data(s)),
+            Elf::Elf64(ref e) => e.sections.get(idx).map(|s| e.section_data(s)),
+        };
+        match data {
+            Some(Some(data)) => {
+                unsafe {
+                    *out = data.as_ptr();
+                    *len = data.len();
+                }
+                RsElfError::Ok
+            }
+            Some(None) => fail(
+                RsElfError::OutOfBounds,
+                &format!("section {} is out of the file", idx),
+            ),
+            None => out_of_bounds("section", idx),
+        }
+    })
+}
@@ -0,0 +1,27 @@
+#[cfg(feature = "demangle")]
+use cpp_demangle;
+#[cfg(feature = "demangle")]
+use rustc_demangle;
+
+/// Demangles a Rust (legacy `_ZN...E` or v0 `_R...`) or Itanium C++ (`_Z...`)
+/// symbol name.
+///
+/// Returns `None` if `name` is not mangled, or if the crate was built without
+/// the `demangle` feature.
+#[cfg(feature = "demangle")]
+pub fn demangle(name: &str) -> Option<String> {
+    if let Ok(d) = rustc_demangle::try_demangle(name) {
+        return Some(format!("{:#}", d));
+    }
+    if name.starts_with("_Z") {
+        if let Ok(sym) = cpp_demangle::Symbol::new(name) {
+            return Some(sym.to_string());
+        }
+    }
+    None
+}
+
+#[cfg(not(feature = "demangle"))]
+pub fn demangle(_name: &str) -> Option<String> {
+    None
+}
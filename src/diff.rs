@@ -0,0 +1,335 @@
+use std::collections::BTreeMap;
+
+use error::Error;
+use file::Elf;
+use header::*;
+use symbols::Symbol;
+use versions::{exported_symbols, ExportedSymbol, VER_FLG_BASE};
+
+/// A difference between two files for an item identified by a key, such as
+/// a section name.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change<T> {
+    Added(T),
+    Removed(T),
+    /// The old and new item.
+    Changed(T, T),
+}
+
+impl<T> Change<T> {
+    /// Returns the item in the old file, if any.
+    pub fn old_item(&self) -> Option<&T> {
+        match *self {
+            Change::Added(_) => None,
+            Change::Removed(ref old) | Change::Changed(ref old, _) => Some(old),
+        }
+    }
+
+    /// Returns the item in the new file, if any.
+    pub fn new_item(&self) -> Option<&T> {
+        match *self {
+            Change::Removed(_) => None,
+            Change::Added(ref new) | Change::Changed(_, ref new) => Some(new),
+        }
+    }
+}
+
+/// A header field whose value differs, with `e_ident` fields as numbers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeaderChange {
+    pub field: &'static str,
+    pub old: u64,
+    pub new: u64,
+}
+
+/// The properties of a section compared by `ElfDiff`, independent of the
+/// file class. Sections moving around is not a change, so the address is
+/// informative only.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectionSummary<'a> {
+    pub name: &'a str,
+    pub sh_type: u32,
+    pub flags: u64,
+    pub addr: u64,
+    pub size: u64,
+}
+
+/// The properties of a segment compared by `ElfDiff`, independent of the
+/// file class. The address is informative only.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentSummary {
+    pub p_type: u32,
+    pub flags: u32,
+    pub vaddr: u64,
+    pub filesz: u64,
+    pub memsz: u64,
+    pub align: u64,
+}
+
+/// Structural differences between two files, typically two builds of the
+/// same shared object.
+///
+/// Sections are matched by name and segments by type, in file order.
+/// Symbols are the defined symbols of `.symtab`, or of `.dynsym` for
+/// stripped files, matched by name, binding and section name. The exported
+/// ABI is made of the symbols `.dynsym` exports, matched by name and
+/// version, and of the version definitions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElfDiff<'a> {
+    pub header: Vec<HeaderChange>,
+    pub sections: Vec<Change<SectionSummary<'a>>>,
+    pub segments: Vec<Change<SegmentSummary>>,
+    pub symbols: Vec<Change<Symbol<'a>>>,
+    pub needed: Vec<Change<&'a str>>,
+    pub soname: Option<Change<&'a str>>,
+    pub rpath: Option<Change<&'a str>>,
+    pub runpath: Option<Change<&'a str>>,
+    pub exports: Vec<Change<ExportedSymbol<'a>>>,
+    pub version_definitions: Vec<Change<&'a str>>,
+}
+
+fn header_fields(elf: &Elf) -> Vec<(&'static str, u64)> {
+    dispatch!(elf, e => {
+        let h = &e.header;
+        vec![
+            ("class", h.e_ident.class as u64),
+            ("osabi", h.e_ident.osabi as u64),
+            ("abiversion", u64::from(h.e_ident.abiversion)),
+            ("e_type", u64::from(h.e_type)),
+            ("e_machine", u64::from(h.e_machine)),
+            ("e_version", u64::from(h.e_version)),
+            ("e_entry", u64::from(h.e_entry)),
+            ("e_flags", u64::from(h.e_flags)),
+            ("e_phnum", u64::from(h.e_phnum)),
+            ("e_shnum", u64::from(h.e_shnum)),
+        ]
+    })
+}
+
+fn sections<'a>(elf: &Elf<'a>) -> Vec<SectionSummary<'a>> {
+    dispatch!(elf, e => e
+        .sections
+        .iter()
+        .skip(1)
+        .map(|s| SectionSummary {
+            name: e.section_name(s).unwrap_or(""),
+            sh_type: s.sh_type,
+            flags: u64::from(s.sh_flags),
+            addr: u64::from(s.sh_addr),
+            size: u64::from(s.sh_size),
+        })
+        .collect())
+}
+
+fn segments(elf: &Elf) -> Vec<SegmentSummary> {
+    dispatch!(elf, e => e
+        .segments
+        .iter()
+        .map(|p| SegmentSummary {
+            p_type: p.p_type,
+            flags: p.p_flags,
+            vaddr: u64::from(p.p_vaddr),
+            filesz: u64::from(p.p_filesz),
+            memsz: u64::from(p.p_memsz),
+            align: u64::from(p.p_align),
+        })
+        .collect())
+}
+
+/// Numbers the items sharing the same key in file order, so that repeated
+/// section names or segment types are matched one to one.
+fn numbered<K: Ord + Clone, T, F: Fn(&T) -> K>(items: Vec<T>, key: F) -> BTreeMap<(K, usize), T> {
+    let mut counts: BTreeMap<K, usize> = BTreeMap::new();
+    let mut map = BTreeMap::new();
+    for item in items {
+        let k = key(&item);
+        let count = counts.entry(k.clone()).or_insert(0);
+        map.insert((k, *count), item);
+        *count += 1;
+    }
+    map
+}
+
+/// Matches the items of both maps by key, in key order.
+fn diff_maps<K: Ord, T, F: Fn(&T, &T) -> bool>(
+    old: BTreeMap<K, T>,
+    mut new: BTreeMap<K, T>,
+    changed: F,
+) -> Vec<Change<T>> {
+    let mut changes = Vec::new();
+    for (key, o) in old {
+        match new.remove(&key) {
+            Some(n) => {
+                if changed(&o, &n) {
+                    changes.push((key, Change::Changed(o, n)));
+                }
+            }
+            None => changes.push((key, Change::Removed(o))),
+        }
+    }
+    changes.extend(new.into_iter().map(|(key, n)| (key, Change::Added(n))));
+    changes.sort_by(|a, b| a.0.cmp(&b.0));
+    changes.into_iter().map(|(_, change)| change).collect()
+}
+
+fn diff_option<'a>(old: Option<&'a str>, new: Option<&'a str>) -> Option<Change<&'a str>> {
+    match (old, new) {
+        (Some(o), Some(n)) if o != n => Some(Change::Changed(o, n)),
+        (Some(o), None) => Some(Change::Removed(o)),
+        (None, Some(n)) => Some(Change::Added(n)),
+        _ => None,
+    }
+}
+
+/// The name, binding and section name identifying a defined symbol.
+type SymbolKey<'a> = (&'a str, u8, &'a str);
+
+/// Returns the defined symbols, numbered by name, binding and section name
+/// since local symbols of different translation units may share all three.
+fn defined_symbols<'a>(
+    elf: &Elf<'a>,
+) -> Result<BTreeMap<(SymbolKey<'a>, usize), Symbol<'a>>, Error> {
+    let mut symbols = elf.symtab()?;
+    if symbols.is_empty() {
+        symbols = elf.dynsym()?;
+    }
+    symbols.retain(|s| {
+        !(s.is_undefined() || s.name.is_empty() || s.kind == STT_SECTION || s.kind == STT_FILE)
+    });
+    let section = |shndx: u16| -> &'a str {
+        dispatch!(elf, e => e
+            .sections
+            .get(usize::from(shndx))
+            .filter(|_| shndx < SHN_LORESERVE)
+            .and_then(|s| e.section_name(s))
+            .unwrap_or(""))
+    };
+    Ok(numbered(symbols, |s| (s.name, s.bind, section(s.shndx))))
+}
+
+fn version_definitions<'a>(elf: &Elf<'a>) -> Result<BTreeMap<&'a str, &'a str>, Error> {
+    Ok(elf
+        .versions()?
+        .definitions
+        .iter()
+        .filter(|d| d.flags & VER_FLG_BASE == 0)
+        .filter_map(|d| d.name())
+        .map(|name| (name, name))
+        .collect())
+}
+
+impl<'a> ElfDiff<'a> {
+    pub fn new(old: &Elf<'a>, new: &Elf<'a>) -> Result<Self, Error> {
+        let header = header_fields(old)
+            .into_iter()
+            .zip(header_fields(new))
+            .filter(|&((_, o), (_, n))| o != n)
+            .map(|((field, o), (_, n))| HeaderChange {
+                field: field,
+                old: o,
+                new: n,
+            })
+            .collect();
+
+        let sections = diff_maps(
+            numbered(sections(old), |s| s.name),
+            numbered(sections(new), |s| s.name),
+            |o, n| o.sh_type != n.sh_type || o.flags != n.flags || o.size != n.size,
+        );
+        let segments = diff_maps(
+            numbered(segments(old), |p| p.p_type),
+            numbered(segments(new), |p| p.p_type),
+            |o, n| {
+                o.flags != n.flags
+                    || o.filesz != n.filesz
+                    || o.memsz != n.memsz
+                    || o.align != n.align
+            },
+        );
+        let symbols = diff_maps(defined_symbols(old)?, defined_symbols(new)?, |o, n| {
+            o.size != n.size || o.kind != n.kind
+        });
+
+        let set = |names: Vec<&'a str>| names.into_iter().map(|n| (n, n)).collect();
+        let needed = diff_maps(set(old.needed()?), set(new.needed()?), |_, _| false);
+
+        let key = |e: &ExportedSymbol<'a>| (e.name, e.version);
+        let exports = diff_maps(
+            exported_symbols(old)?
+                .into_iter()
+                .map(|e| (key(&e), e))
+                .collect(),
+            exported_symbols(new)?
+                .into_iter()
+                .map(|e| (key(&e), e))
+                .collect(),
+            |o, n| {
+                o.kind != n.kind
+                    || o.hidden != n.hidden
+                    || ((o.kind == STT_OBJECT || o.kind == STT_TLS) && o.size != n.size)
+            },
+        );
+        let version_definitions = diff_maps(
+            version_definitions(old)?,
+            version_definitions(new)?,
+            |_, _| false,
+        );
+
+        Ok(ElfDiff {
+            header: header,
+            sections: sections,
+            segments: segments,
+            symbols: symbols,
+            needed: needed,
+            soname: diff_option(old.soname()?, new.soname()?),
+            rpath: diff_option(old.rpath()?, new.rpath()?),
+            runpath: diff_option(old.runpath()?, new.runpath()?),
+            exports: exports,
+            version_definitions: version_definitions,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.header.is_empty()
+            && self.sections.is_empty()
+            && self.segments.is_empty()
+            && self.symbols.is_empty()
+            && self.needed.is_empty()
+            && self.soname.is_none()
+            && self.rpath.is_none()
+            && self.runpath.is_none()
+            && self.exports.is_empty()
+            && self.version_definitions.is_empty()
+    }
+
+    /// Returns the changes of the exported ABI that break existing users:
+    /// removed exports, exports changing type, default version or object
+    /// size, removed version definitions, and a new soname, class or
+    /// machine. Added exports and versions are compatible.
+    pub fn breaks_abi(&self) -> bool {
+        let incompatible_header = self
+            .header
+            .iter()
+            .any(|h| h.field == "class" || h.field == "e_machine");
+        let removed_soname = match self.soname {
+            Some(Change::Added(_)) | None => false,
+            _ => true,
+        };
+        incompatible_header
+            || removed_soname
+            || self.abi_breaks().next().is_some()
+            || self
+                .version_definitions
+                .iter()
+                .any(|c| c.new_item().is_none())
+    }
+
+    /// Iterates over the changes of exported symbols that break existing
+    /// users, see `breaks_abi`.
+    pub fn abi_breaks<'s>(&'s self) -> impl Iterator<Item = &'s Change<ExportedSymbol<'a>>> + 's {
+        self.exports.iter().filter(|c| match **c {
+            Change::Added(_) => false,
+            _ => true,
+        })
+    }
+}
@@ -0,0 +1,1142 @@
+use nom;
+use std::collections::{hash_map, HashMap};
+
+use dwarf::reader::*;
+use error::Error;
+
+pub const DW_EH_PE_ABSPTR: u8 = 0x00;
+pub const DW_EH_PE_ULEB128: u8 = 0x01;
+pub const DW_EH_PE_UDATA2: u8 = 0x02;
+pub const DW_EH_PE_UDATA4: u8 = 0x03;
+pub const DW_EH_PE_UDATA8: u8 = 0x04;
+pub const DW_EH_PE_SLEB128: u8 = 0x09;
+pub const DW_EH_PE_SDATA2: u8 = 0x0a;
+pub const DW_EH_PE_SDATA4: u8 = 0x0b;
+pub const DW_EH_PE_SDATA8: u8 = 0x0c;
+pub const DW_EH_PE_PCREL: u8 = 0x10;
+pub const DW_EH_PE_TEXTREL: u8 = 0x20;
+pub const DW_EH_PE_DATAREL: u8 = 0x30;
+pub const DW_EH_PE_FUNCREL: u8 = 0x40;
+pub const DW_EH_PE_ALIGNED: u8 = 0x50;
+pub const DW_EH_PE_INDIRECT: u8 = 0x80;
+pub const DW_EH_PE_OMIT: u8 = 0xff;
+
+pub const DW_CFA_ADVANCE_LOC: u8 = 0x40;
+pub const DW_CFA_OFFSET: u8 = 0x80;
+pub const DW_CFA_RESTORE: u8 = 0xc0;
+pub const DW_CFA_NOP: u8 = 0x00;
+pub const DW_CFA_SET_LOC: u8 = 0x01;
+pub const DW_CFA_ADVANCE_LOC1: u8 = 0x02;
+pub const DW_CFA_ADVANCE_LOC2: u8 = 0x03;
+pub const DW_CFA_ADVANCE_LOC4: u8 = 0x04;
+pub const DW_CFA_OFFSET_EXTENDED: u8 = 0x05;
+pub const DW_CFA_RESTORE_EXTENDED: u8 = 0x06;
+pub const DW_CFA_UNDEFINED: u8 = 0x07;
+pub const DW_CFA_SAME_VALUE: u8 = 0x08;
+pub const DW_CFA_REGISTER: u8 = 0x09;
+pub const DW_CFA_REMEMBER_STATE: u8 = 0x0a;
+pub const DW_CFA_RESTORE_STATE: u8 = 0x0b;
+pub const DW_CFA_DEF_CFA: u8 = 0x0c;
+pub const DW_CFA_DEF_CFA_REGISTER: u8 = 0x0d;
+pub const DW_CFA_DEF_CFA_OFFSET: u8 = 0x0e;
+pub const DW_CFA_DEF_CFA_EXPRESSION: u8 = 0x0f;
+pub const DW_CFA_EXPRESSION: u8 = 0x10;
+pub const DW_CFA_OFFSET_EXTENDED_SF: u8 = 0x11;
+pub const DW_CFA_DEF_CFA_SF: u8 = 0x12;
+pub const DW_CFA_DEF_CFA_OFFSET_SF: u8 = 0x13;
+pub const DW_CFA_VAL_OFFSET: u8 = 0x14;
+pub const DW_CFA_VAL_OFFSET_SF: u8 = 0x15;
+pub const DW_CFA_VAL_EXPRESSION: u8 = 0x16;
+pub const DW_CFA_AARCH64_NEGATE_RA_STATE: u8 = 0x2d;
+pub const DW_CFA_GNU_ARGS_SIZE: u8 = 0x2e;
+pub const DW_CFA_GNU_NEGATIVE_OFFSET_EXTENDED: u8 = 0x2f;
+
+pub const DW_OP_ADDR: u8 = 0x03;
+pub const DW_OP_DEREF: u8 = 0x06;
+pub const DW_OP_CONST1U: u8 = 0x08;
+pub const DW_OP_CONST1S: u8 = 0x09;
+pub const DW_OP_CONST2U: u8 = 0x0a;
+pub const DW_OP_CONST2S: u8 = 0x0b;
+pub const DW_OP_CONST4U: u8 = 0x0c;
+pub const DW_OP_CONST4S: u8 = 0x0d;
+pub const DW_OP_CONST8U: u8 = 0x0e;
+pub const DW_OP_CONST8S: u8 = 0x0f;
+pub const DW_OP_CONSTU: u8 = 0x10;
+pub const DW_OP_CONSTS: u8 = 0x11;
+pub const DW_OP_DUP: u8 = 0x12;
+pub const DW_OP_DROP: u8 = 0x13;
+pub const DW_OP_OVER: u8 = 0x14;
+pub const DW_OP_PICK: u8 = 0x15;
+pub const DW_OP_SWAP: u8 = 0x16;
+pub const DW_OP_ROT: u8 = 0x17;
+pub const DW_OP_ABS: u8 = 0x19;
+pub const DW_OP_AND: u8 = 0x1a;
+pub const DW_OP_DIV: u8 = 0x1b;
+pub const DW_OP_MINUS: u8 = 0x1c;
+pub const DW_OP_MOD: u8 = 0x1d;
+pub const DW_OP_MUL: u8 = 0x1e;
+pub const DW_OP_NEG: u8 = 0x1f;
+pub const DW_OP_NOT: u8 = 0x20;
+pub const DW_OP_OR: u8 = 0x21;
+pub const DW_OP_PLUS: u8 = 0x22;
+pub const DW_OP_PLUS_UCONST: u8 = 0x23;
+pub const DW_OP_SHL: u8 = 0x24;
+pub const DW_OP_SHR: u8 = 0x25;
+pub const DW_OP_SHRA: u8 = 0x26;
+pub const DW_OP_XOR: u8 = 0x27;
+pub const DW_OP_BRA: u8 = 0x28;
+pub const DW_OP_EQ: u8 = 0x29;
+pub const DW_OP_GE: u8 = 0x2a;
+pub const DW_OP_GT: u8 = 0x2b;
+pub const DW_OP_LE: u8 = 0x2c;
+pub const DW_OP_LT: u8 = 0x2d;
+pub const DW_OP_NE: u8 = 0x2e;
+pub const DW_OP_SKIP: u8 = 0x2f;
+pub const DW_OP_LIT0: u8 = 0x30;
+pub const DW_OP_LIT31: u8 = 0x4f;
+pub const DW_OP_REG0: u8 = 0x50;
+pub const DW_OP_REG31: u8 = 0x6f;
+pub const DW_OP_BREG0: u8 = 0x70;
+pub const DW_OP_BREG31: u8 = 0x8f;
+pub const DW_OP_REGX: u8 = 0x90;
+pub const DW_OP_BREGX: u8 = 0x92;
+pub const DW_OP_NOP: u8 = 0x96;
+
+/// Which flavour of call frame information a section holds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FrameKind {
+    /// `.eh_frame`, as used for exception handling.
+    EhFrame,
+    /// `.debug_frame`, as described by the DWARF standard.
+    DebugFrame,
+}
+
+/// Addresses that `DW_EH_PE_*` encoded pointers may be relative to.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PointerBases {
+    /// Virtual address of the section holding the pointers.
+    pub section: u64,
+    /// Virtual address of `.text`.
+    pub text: u64,
+    /// Virtual address of `.got`, or of `.eh_frame_hdr` for its own table.
+    pub data: u64,
+}
+
+/// Parses a pointer encoded with `encoding`. `i` must be a suffix of
+/// `section`, whose virtual address is given by `bases`. Indirect pointers
+/// are rejected since their target is only known at run time.
+pub fn parse_pointer<'a>(
+    section: &'a [u8],
+    i: &'a [u8],
+    encoding: u8,
+    address_size: u8,
+    bases: &PointerBases,
+) -> nom::IResult<&'a [u8], u64> {
+    if encoding == DW_EH_PE_OMIT {
+        return Ok((i, 0));
+    }
+    if encoding & DW_EH_PE_INDIRECT != 0 {
+        return Err(nom::Err::Error(error_position!(i, nom::ErrorKind::Switch)));
+    }
+    let field = bases.section + offset_in(section, i) as u64;
+
+    let (rest, value) = match encoding & 0x0f {
+        DW_EH_PE_ABSPTR => parse_address(i, address_size)?,
+        DW_EH_PE_ULEB128 => parse_uleb128(i)?,
+        DW_EH_PE_UDATA2 => parse_address(i, 2)?,
+        DW_EH_PE_UDATA4 => parse_address(i, 4)?,
+        DW_EH_PE_UDATA8 => parse_address(i, 8)?,
+        DW_EH_PE_SLEB128 => parse_sleb128(i).map(|(r, x)| (r, x as u64))?,
+        DW_EH_PE_SDATA2 => nom::le_i16(i).map(|(r, x)| (r, i64::from(x) as u64))?,
+        DW_EH_PE_SDATA4 => nom::le_i32(i).map(|(r, x)| (r, i64::from(x) as u64))?,
+        DW_EH_PE_SDATA8 => nom::le_i64(i).map(|(r, x)| (r, x as u64))?,
+        _ => return Err(nom::Err::Error(error_position!(i, nom::ErrorKind::Switch))),
+    };
+
+    let value = match encoding & 0x70 {
+        0 => value,
+        DW_EH_PE_PCREL => field.wrapping_add(value),
+        DW_EH_PE_TEXTREL => bases.text.wrapping_add(value),
+        DW_EH_PE_DATAREL => bases.data.wrapping_add(value),
+        _ => return Err(nom::Err::Error(error_position!(i, nom::ErrorKind::Switch))),
+    };
+    Ok((rest, value))
+}
+
+/// A Common Information Entry.
+#[derive(Debug, Clone)]
+pub struct Cie<'a> {
+    pub offset: usize,
+    pub version: u8,
+    pub augmentation: String,
+    pub address_size: u8,
+    pub code_alignment_factor: u64,
+    pub data_alignment_factor: i64,
+    pub return_address_register: u16,
+    pub fde_encoding: u8,
+    pub lsda_encoding: u8,
+    pub personality: Option<u64>,
+    /// Whether `personality` is the address of a pointer to the routine,
+    /// such as a `DW.ref` or GOT slot, rather than the routine itself.
+    pub personality_indirect: bool,
+    pub signal_frame: bool,
+    pub initial_instructions: &'a [u8],
+}
+
+/// A Frame Description Entry, covering the addresses
+/// `[initial_location, initial_location + address_range)`.
+#[derive(Debug, Clone)]
+pub struct Fde<'a> {
+    pub offset: usize,
+    pub cie: Cie<'a>,
+    pub initial_location: u64,
+    pub address_range: u64,
+    pub lsda: Option<u64>,
+    pub instructions: &'a [u8],
+}
+
+impl<'a> Fde<'a> {
+    pub fn contains(&self, pc: u64) -> bool {
+        self.initial_location <= pc && pc - self.initial_location < self.address_range
+    }
+}
+
+/// An entry of a frame section.
+#[derive(Debug)]
+enum Entry<'a> {
+    Cie,
+    Fde(usize, u64, &'a [u8]),
+    Terminator,
+}
+
+/// A `.eh_frame` or `.debug_frame` section.
+#[derive(Debug, Clone, Copy)]
+pub struct FrameSection<'a> {
+    pub data: &'a [u8],
+    pub kind: FrameKind,
+    pub address_size: u8,
+    pub bases: PointerBases,
+}
+
+impl<'a> FrameSection<'a> {
+    pub fn new(data: &'a [u8], kind: FrameKind, address_size: u8, bases: PointerBases) -> Self {
+        FrameSection {
+            data: data,
+            kind: kind,
+            address_size: address_size,
+            bases: bases,
+        }
+    }
+
+    /// Reads the header of the entry at `offset`, returning the entry and
+    /// the offset of the next one.
+    fn entry(&self, offset: usize) -> Result<(Entry<'a>, usize), Error> {
+        if offset > self.data.len() {
+            return Err(Error::ParseError(format!(
+                "frame entry offset {:#x} out of bounds",
+                offset
+            )));
+        }
+        let i = &self.data[offset..];
+        let (rest, (length, format64)) = parse_initial_length(i)?;
+        if length == 0 {
+            return Ok((Entry::Terminator, self.data.len() - rest.len()));
+        }
+        let (after, body) = take_unit(rest, length)?;
+        let next = self.data.len() - after.len();
+        let id_offset = offset_in(self.data, body);
+        let (body_rest, id) = parse_offset(body, format64)?;
+
+        let entry = match self.kind {
+            FrameKind::EhFrame if id == 0 => Entry::Cie,
+            FrameKind::EhFrame => {
+                let cie = (id_offset as u64).checked_sub(id).ok_or_else(|| {
+                    Error::ParseError(format!("bad CIE pointer in FDE at {:#x}", offset))
+                })?;
+                Entry::Fde(offset, cie, body_rest)
+            }
+            FrameKind::DebugFrame if id == 0xffff_ffff || id == !0u64 => Entry::Cie,
+            FrameKind::DebugFrame => Entry::Fde(offset, id, body_rest),
+        };
+        Ok((entry, next))
+    }
+
+    pub fn parse_cie(&self, offset: usize) -> Result<Cie<'a>, Error> {
+        if offset > self.data.len() {
+            return Err(Error::ParseError(format!(
+                "CIE offset {:#x} out of bounds",
+                offset
+            )));
+        }
+        let i = &self.data[offset..];
+        let (rest, (length, format64)) = parse_initial_length(i)?;
+        let (_, body) = take_unit(rest, length)?;
+        let (r, _id) = parse_offset(body, format64)?;
+        let (r, version) = nom::le_u8(r)?;
+        let (r, augmentation) = parse_cstr(r)?;
+        let augmentation = String::from_utf8_lossy(augmentation).into_owned();
+
+        let (r, address_size) = if version >= 4 {
+            let (r, address_size) = nom::le_u8(r)?;
+            let (r, _segment_size) = nom::le_u8(r)?;
+            (r, address_size)
+        } else {
+            (r, self.address_size)
+        };
+        let (r, code_alignment_factor) = parse_uleb128(r)?;
+        let (r, data_alignment_factor) = parse_sleb128(r)?;
+        let (mut r, return_address_register) = if version == 1 {
+            nom::le_u8(r).map(|(r, x)| (r, u64::from(x)))?
+        } else {
+            parse_uleb128(r)?
+        };
+
+        let mut cie = Cie {
+            offset: offset,
+            version: version,
+            augmentation: augmentation,
+            address_size: address_size,
+            code_alignment_factor: code_alignment_factor,
+            data_alignment_factor: data_alignment_factor,
+            return_address_register: return_address_register as u16,
+            fde_encoding: DW_EH_PE_ABSPTR,
+            lsda_encoding: DW_EH_PE_OMIT,
+            personality: None,
+            personality_indirect: false,
+            signal_frame: false,
+            initial_instructions: &[],
+        };
+
+        if cie.augmentation.starts_with('z') {
+            let (rest, len) = parse_uleb128(r)?;
+            let (rest, mut aug) = take_unit(rest, len)?;
+            for c in cie.augmentation.clone().chars().skip(1) {
+                match c {
+                    'L' => {
+                        let (a, enc) = nom::le_u8(aug)?;
+                        cie.lsda_encoding = enc;
+                        aug = a;
+                    }
+                    'R' => {
+                        let (a, enc) = nom::le_u8(aug)?;
+                        cie.fde_encoding = enc;
+                        aug = a;
+                    }
+                    'P' => {
+                        let (a, enc) = nom::le_u8(aug)?;
+                        let direct = if enc == DW_EH_PE_OMIT {
+                            enc
+                        } else {
+                            enc & !DW_EH_PE_INDIRECT
+                        };
+                        let (a, personality) =
+                            parse_pointer(self.data, a, direct, address_size, &self.bases)?;
+                        cie.personality = Some(personality);
+                        cie.personality_indirect = enc != direct;
+                        aug = a;
+                    }
+                    'S' => cie.signal_frame = true,
+                    _ => break,
+                }
+            }
+            r = rest;
+        } else if !cie.augmentation.is_empty() && cie.augmentation != "eh" {
+            return Err(Error::Unsupported(format!(
+                "CIE augmentation {:?}",
+                cie.augmentation
+            )));
+        }
+
+        cie.initial_instructions = r;
+        Ok(cie)
+    }
+
+    fn parse_fde(&self, offset: usize, cie: Cie<'a>, i: &'a [u8]) -> Result<Fde<'a>, Error> {
+        let size = cie.address_size;
+        let (r, initial_location) =
+            parse_pointer(self.data, i, cie.fde_encoding, size, &self.bases)?;
+        let (mut r, address_range) =
+            parse_pointer(self.data, r, cie.fde_encoding & 0x0f, size, &self.bases)?;
+
+        let mut lsda = None;
+        if cie.augmentation.starts_with('z') {
+            let (rest, len) = parse_uleb128(r)?;
+            let (rest, aug) = take_unit(rest, len)?;
+            if cie.lsda_encoding != DW_EH_PE_OMIT && !aug.is_empty() {
+                let (_, x) = parse_pointer(self.data, aug, cie.lsda_encoding, size, &self.bases)?;
+                lsda = Some(x);
+            }
+            r = rest;
+        }
+
+        Ok(Fde {
+            offset: offset,
+            cie: cie,
+            initial_location: initial_location,
+            address_range: address_range,
+            lsda: lsda,
+            instructions: r,
+        })
+    }
+
+    /// Parses the FDE at `offset` in the section.
+    pub fn fde_at(&self, offset: usize) -> Result<Fde<'a>, Error> {
+        match self.entry(offset)? {
+            (Entry::Fde(offset, cie, i), _) => {
+                let cie = self.parse_cie(cie as usize)?;
+                self.parse_fde(offset, cie, i)
+            }
+            _ => Err(Error::ParseError(format!("no FDE at offset {:#x}", offset))),
+        }
+    }
+
+    /// Parses every FDE of the section.
+    pub fn fdes(&self) -> Result<Vec<Fde<'a>>, Error> {
+        let mut cies: HashMap<u64, Cie<'a>> = HashMap::new();
+        let mut fdes = Vec::new();
+        let mut offset = 0;
+
+        while offset < self.data.len() {
+            let (entry, next) = self.entry(offset)?;
+            match entry {
+                Entry::Terminator if self.kind == FrameKind::EhFrame => break,
+                Entry::Fde(offset, cie_offset, i) => {
+                    let cie = match cies.entry(cie_offset) {
+                        hash_map::Entry::Occupied(e) => e.get().clone(),
+                        hash_map::Entry::Vacant(e) => {
+                            e.insert(self.parse_cie(cie_offset as usize)?).clone()
+                        }
+                    };
+                    fdes.push(self.parse_fde(offset, cie, i)?);
+                }
+                _ => {}
+            }
+            offset = next;
+        }
+        Ok(fdes)
+    }
+}
+
+/// The binary search table of `.eh_frame_hdr`.
+#[derive(Debug)]
+pub struct EhFrameHdr {
+    pub version: u8,
+    /// Virtual address of the `.eh_frame` section.
+    pub eh_frame_ptr: u64,
+    /// `(initial location, FDE address)` pairs, sorted by location.
+    pub table: Vec<(u64, u64)>,
+}
+
+impl EhFrameHdr {
+    /// Parses `.eh_frame_hdr` loaded at `address`.
+    pub fn parse(data: &[u8], address: u64, address_size: u8) -> Result<Self, Error> {
+        let bases = PointerBases {
+            section: address,
+            text: 0,
+            data: address,
+        };
+        let (r, version) = nom::le_u8(data)?;
+        if version != 1 {
+            return Err(Error::Unsupported(format!(
+                ".eh_frame_hdr version {}",
+                version
+            )));
+        }
+        let (r, eh_frame_ptr_enc) = nom::le_u8(r)?;
+        let (r, fde_count_enc) = nom::le_u8(r)?;
+        let (r, table_enc) = nom::le_u8(r)?;
+        let (r, eh_frame_ptr) = parse_pointer(data, r, eh_frame_ptr_enc, address_size, &bases)?;
+
+        let mut table = Vec::new();
+        if fde_count_enc != DW_EH_PE_OMIT && table_enc != DW_EH_PE_OMIT {
+            let (mut r, count) = parse_pointer(data, r, fde_count_enc, address_size, &bases)?;
+            for _ in 0..count {
+                let (rest, location) = parse_pointer(data, r, table_enc, address_size, &bases)?;
+                let (rest, fde) = parse_pointer(data, rest, table_enc, address_size, &bases)?;
+                table.push((location, fde));
+                r = rest;
+            }
+        }
+
+        Ok(EhFrameHdr {
+            version: version,
+            eh_frame_ptr: eh_frame_ptr,
+            table: table,
+        })
+    }
+
+    /// Returns the address of the FDE that may cover `pc`.
+    pub fn lookup(&self, pc: u64) -> Option<u64> {
+        let idx = match self.table.binary_search_by(|e| e.0.cmp(&pc)) {
+            Ok(idx) => idx,
+            Err(0) => return None,
+            Err(idx) => idx - 1,
+        };
+        Some(self.table[idx].1)
+    }
+}
+
+/// How to recover the Canonical Frame Address.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum CfaRule<'a> {
+    RegisterOffset(u16, i64),
+    Expression(&'a [u8]),
+}
+
+/// How to recover the value a register had in the caller.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum RegisterRule<'a> {
+    Undefined,
+    SameValue,
+    Offset(i64),
+    ValOffset(i64),
+    Register(u16),
+    Expression(&'a [u8]),
+    ValExpression(&'a [u8]),
+}
+
+/// The unwind rules for the addresses `[start, end)`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct UnwindRow<'a> {
+    pub start: u64,
+    pub end: u64,
+    pub cfa: CfaRule<'a>,
+    pub registers: Vec<(u16, RegisterRule<'a>)>,
+    pub return_address_register: u16,
+    /// AArch64 only: whether the return address is signed by pointer
+    /// authentication.
+    pub ra_signed: bool,
+}
+
+impl<'a> UnwindRow<'a> {
+    pub fn register(&self, reg: u16) -> RegisterRule<'a> {
+        self.registers
+            .iter()
+            .find(|r| r.0 == reg)
+            .map(|r| r.1.clone())
+            .unwrap_or(RegisterRule::SameValue)
+    }
+
+    fn set_register(&mut self, reg: u16, rule: RegisterRule<'a>) {
+        match self.registers.iter().position(|r| r.0 == reg) {
+            Some(idx) => self.registers[idx].1 = rule,
+            None => self.registers.push((reg, rule)),
+        }
+    }
+}
+
+fn parse_block(i: &[u8]) -> nom::IResult<&[u8], &[u8]> {
+    let (r, len) = parse_uleb128(i)?;
+    take_unit(r, len)
+}
+
+/// Runs the call frame `instructions` from `row`, stopping before the first
+/// row starting after `pc`. `initial` holds the rules set by the CIE, used by
+/// the restore instructions.
+fn execute<'a>(
+    section: &FrameSection<'a>,
+    cie: &Cie<'a>,
+    mut i: &'a [u8],
+    row: &mut UnwindRow<'a>,
+    initial: Option<&UnwindRow<'a>>,
+    pc: u64,
+) -> Result<bool, Error> {
+    let caf = cie.code_alignment_factor;
+    let daf = cie.data_alignment_factor;
+    let mut stack: Vec<UnwindRow<'a>> = Vec::new();
+
+    let restore = |row: &mut UnwindRow<'a>, reg: u16| {
+        let rule = initial
+            .map(|r| r.register(reg))
+            .unwrap_or(RegisterRule::SameValue);
+        row.set_register(reg, rule);
+    };
+
+    while !i.is_empty() {
+        let (r, opcode) = nom::le_u8(i)?;
+        i = r;
+
+        let mut advance = None;
+        match opcode & 0xc0 {
+            DW_CFA_ADVANCE_LOC => advance = Some(u64::from(opcode & 0x3f) * caf),
+            DW_CFA_OFFSET => {
+                let (r, off) = parse_uleb128(i)?;
+                row.set_register(
+                    u16::from(opcode & 0x3f),
+                    RegisterRule::Offset(off as i64 * daf),
+                );
+                i = r;
+            }
+            DW_CFA_RESTORE => restore(row, u16::from(opcode & 0x3f)),
+            _ => match opcode {
+                DW_CFA_NOP => {}
+                DW_CFA_SET_LOC => {
+                    let (r, loc) = parse_pointer(
+                        section.data,
+                        i,
+                        cie.fde_encoding,
+                        cie.address_size,
+                        &section.bases,
+                    )?;
+                    i = r;
+                    if loc > pc {
+                        row.end = loc;
+                        return Ok(true);
+                    }
+                    row.start = loc;
+                }
+                DW_CFA_ADVANCE_LOC1 => {
+                    let (r, delta) = nom::le_u8(i)?;
+                    advance = Some(u64::from(delta) * caf);
+                    i = r;
+                }
+                DW_CFA_ADVANCE_LOC2 => {
+                    let (r, delta) = nom::le_u16(i)?;
+                    advance = Some(u64::from(delta) * caf);
+                    i = r;
+                }
+                DW_CFA_ADVANCE_LOC4 => {
+                    let (r, delta) = nom::le_u32(i)?;
+                    advance = Some(u64::from(delta) * caf);
+                    i = r;
+                }
+                DW_CFA_OFFSET_EXTENDED => {
+                    let (r, reg) = parse_uleb128(i)?;
+                    let (r, off) = parse_uleb128(r)?;
+                    row.set_register(reg as u16, RegisterRule::Offset(off as i64 * daf));
+                    i = r;
+                }
+                DW_CFA_OFFSET_EXTENDED_SF => {
+                    let (r, reg) = parse_uleb128(i)?;
+                    let (r, off) = parse_sleb128(r)?;
+                    row.set_register(reg as u16, RegisterRule::Offset(off * daf));
+                    i = r;
+                }
+                DW_CFA_GNU_NEGATIVE_OFFSET_EXTENDED => {
+                    let (r, reg) = parse_uleb128(i)?;
+                    let (r, off) = parse_uleb128(r)?;
+                    row.set_register(reg as u16, RegisterRule::Offset(-(off as i64) * daf));
+                    i = r;
+                }
+                DW_CFA_VAL_OFFSET => {
+                    let (r, reg) = parse_uleb128(i)?;
+                    let (r, off) = parse_uleb128(r)?;
+                    row.set_register(reg as u16, RegisterRule::ValOffset(off as i64 * daf));
+                    i = r;
+                }
+                DW_CFA_VAL_OFFSET_SF => {
+                    let (r, reg) = parse_uleb128(i)?;
+                    let (r, off) = parse_sleb128(r)?;
+                    row.set_register(reg as u16, RegisterRule::ValOffset(off * daf));
+                    i = r;
+                }
+                DW_CFA_RESTORE_EXTENDED => {
+                    let (r, reg) = parse_uleb128(i)?;
+                    restore(row, reg as u16);
+                    i = r;
+                }
+                DW_CFA_UNDEFINED => {
+                    let (r, reg) = parse_uleb128(i)?;
+                    row.set_register(reg as u16, RegisterRule::Undefined);
+                    i = r;
+                }
+                DW_CFA_SAME_VALUE => {
+                    let (r, reg) = parse_uleb128(i)?;
+                    row.set_register(reg as u16, RegisterRule::SameValue);
+                    i = r;
+                }
+                DW_CFA_REGISTER => {
+                    let (r, reg) = parse_uleb128(i)?;
+                    let (r, other) = parse_uleb128(r)?;
+                    row.set_register(reg as u16, RegisterRule::Register(other as u16));
+                    i = r;
+                }
+                DW_CFA_REMEMBER_STATE => stack.push(row.clone()),
+                DW_CFA_RESTORE_STATE => {
+                    if let Some(saved) = stack.pop() {
+                        row.cfa = saved.cfa;
+                        row.registers = saved.registers;
+                        row.ra_signed = saved.ra_signed;
+                    }
+                }
+                DW_CFA_DEF_CFA => {
+                    let (r, reg) = parse_uleb128(i)?;
+                    let (r, off) = parse_uleb128(r)?;
+                    row.cfa = CfaRule::RegisterOffset(reg as u16, off as i64);
+                    i = r;
+                }
+                DW_CFA_DEF_CFA_SF => {
+                    let (r, reg) = parse_uleb128(i)?;
+                    let (r, off) = parse_sleb128(r)?;
+                    row.cfa = CfaRule::RegisterOffset(reg as u16, off * daf);
+                    i = r;
+                }
+                DW_CFA_DEF_CFA_REGISTER => {
+                    let (r, reg) = parse_uleb128(i)?;
+                    let off = match row.cfa {
+                        CfaRule::RegisterOffset(_, off) => off,
+                        CfaRule::Expression(_) => 0,
+                    };
+                    row.cfa = CfaRule::RegisterOffset(reg as u16, off);
+                    i = r;
+                }
+                DW_CFA_DEF_CFA_OFFSET => {
+                    let (r, off) = parse_uleb128(i)?;
+                    if let CfaRule::RegisterOffset(reg, _) = row.cfa {
+                        row.cfa = CfaRule::RegisterOffset(reg, off as i64);
+                    }
+                    i = r;
+                }
+                DW_CFA_DEF_CFA_OFFSET_SF => {
+                    let (r, off) = parse_sleb128(i)?;
+                    if let CfaRule::RegisterOffset(reg, _) = row.cfa {
+                        row.cfa = CfaRule::RegisterOffset(reg, off * daf);
+                    }
+                    i = r;
+                }
+                DW_CFA_DEF_CFA_EXPRESSION => {
+                    let (r, expr) = parse_block(i)?;
+                    row.cfa = CfaRule::Expression(expr);
+                    i = r;
+                }
+                DW_CFA_EXPRESSION => {
+                    let (r, reg) = parse_uleb128(i)?;
+                    let (r, expr) = parse_block(r)?;
+                    row.set_register(reg as u16, RegisterRule::Expression(expr));
+                    i = r;
+                }
+                DW_CFA_VAL_EXPRESSION => {
+                    let (r, reg) = parse_uleb128(i)?;
+                    let (r, expr) = parse_block(r)?;
+                    row.set_register(reg as u16, RegisterRule::ValExpression(expr));
+                    i = r;
+                }
+                DW_CFA_GNU_ARGS_SIZE => i = parse_uleb128(i)?.0,
+                DW_CFA_AARCH64_NEGATE_RA_STATE => row.ra_signed = !row.ra_signed,
+                _ => {
+                    return Err(Error::Unsupported(format!(
+                        "call frame instruction {:#x}",
+                        opcode
+                    )))
+                }
+            },
+        }
+
+        if let Some(delta) = advance {
+            let loc = row.start.wrapping_add(delta);
+            if loc > pc {
+                row.end = loc;
+                return Ok(true);
+            }
+            row.start = loc;
+        }
+    }
+    Ok(false)
+}
+
+impl<'a> FrameSection<'a> {
+    /// Computes the unwind rules of `fde` at `pc`.
+    pub fn unwind_row(&self, fde: &Fde<'a>, pc: u64) -> Result<UnwindRow<'a>, Error> {
+        let cie = &fde.cie;
+        let mut row = UnwindRow {
+            start: fde.initial_location,
+            end: fde.initial_location.wrapping_add(fde.address_range),
+            cfa: CfaRule::RegisterOffset(0, 0),
+            registers: Vec::new(),
+            return_address_register: cie.return_address_register,
+            ra_signed: false,
+        };
+        execute(
+            self,
+            cie,
+            cie.initial_instructions,
+            &mut row,
+            None,
+            u64::MAX,
+        )?;
+        row.start = fde.initial_location;
+        row.end = fde.initial_location.wrapping_add(fde.address_range);
+
+        let initial = row.clone();
+        if !execute(self, cie, fde.instructions, &mut row, Some(&initial), pc)? {
+            row.end = fde.initial_location.wrapping_add(fde.address_range);
+        }
+        Ok(row)
+    }
+}
+
+/// The register numbering of a supported architecture.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Arch {
+    X86_64,
+    Aarch64,
+}
+
+impl Arch {
+    pub fn from_machine(e_machine: u16) -> Option<Arch> {
+        match e_machine {
+            62 => Some(Arch::X86_64),
+            183 => Some(Arch::Aarch64),
+            _ => None,
+        }
+    }
+
+    /// DWARF number of the stack pointer.
+    pub fn sp(self) -> u16 {
+        match self {
+            Arch::X86_64 => 7,
+            Arch::Aarch64 => 31,
+        }
+    }
+
+    /// DWARF number of the program counter.
+    pub fn pc(self) -> u16 {
+        match self {
+            Arch::X86_64 => 16,
+            Arch::Aarch64 => 32,
+        }
+    }
+
+    pub fn register_name(self, reg: u16) -> Option<&'static str> {
+        const X86_64: [&str; 17] = [
+            "rax", "rdx", "rcx", "rbx", "rsi", "rdi", "rbp", "rsp", "r8", "r9", "r10", "r11",
+            "r12", "r13", "r14", "r15", "rip",
+        ];
+        const AARCH64: [&str; 33] = [
+            "x0", "x1", "x2", "x3", "x4", "x5", "x6", "x7", "x8", "x9", "x10", "x11", "x12", "x13",
+            "x14", "x15", "x16", "x17", "x18", "x19", "x20", "x21", "x22", "x23", "x24", "x25",
+            "x26", "x27", "x28", "x29", "x30", "sp", "pc",
+        ];
+        match self {
+            Arch::X86_64 => X86_64.get(reg as usize).cloned(),
+            Arch::Aarch64 => AARCH64.get(reg as usize).cloned(),
+        }
+    }
+}
+
+pub type Registers = HashMap<u16, u64>;
+
+/// Operations `evaluate_expression` runs at most, since `DW_OP_skip` and
+/// `DW_OP_bra` may loop.
+pub const MAX_EXPRESSION_STEPS: usize = 10_000;
+
+fn expression_error(what: &str) -> Error {
+    Error::ParseError(format!("DWARF expression: {}", what))
+}
+
+/// Evaluates a DWARF expression with `initial` pushed on the stack.
+/// `read` loads an address-sized value from memory.
+pub fn evaluate_expression<F>(
+    mut i: &[u8],
+    initial: Option<u64>,
+    regs: &Registers,
+    address_size: u8,
+    read: &F,
+) -> Result<u64, Error>
+where
+    F: Fn(u64) -> Option<u64>,
+{
+    let expr = i;
+    let mut stack: Vec<u64> = initial.into_iter().collect();
+    let mut steps = 0;
+
+    macro_rules! pop {
+        () => {
+            stack
+                .pop()
+                .ok_or_else(|| expression_error("stack underflow"))?
+        };
+    }
+    let register = |reg: u16| {
+        regs.get(&reg)
+            .cloned()
+            .ok_or_else(|| expression_error(&format!("register {} is unknown", reg)))
+    };
+    macro_rules! binop {
+        ($f:expr) => {{
+            let b = pop!();
+            let a = pop!();
+            stack.push($f(a, b));
+        }};
+    }
+
+    while !i.is_empty() {
+        steps += 1;
+        if steps > MAX_EXPRESSION_STEPS {
+            return Err(expression_error("too many steps"));
+        }
+        let (r, op) = nom::le_u8(i)?;
+        i = r;
+        match op {
+            DW_OP_ADDR => {
+                let (r, x) = parse_address(i, address_size)?;
+                stack.push(x);
+                i = r;
+            }
+            DW_OP_DEREF => {
+                let addr = pop!();
+                let value = read(addr)
+                    .ok_or_else(|| expression_error(&format!("cannot read {:#x}", addr)))?;
+                stack.push(value);
+            }
+            DW_OP_CONST1U | DW_OP_CONST2U | DW_OP_CONST4U | DW_OP_CONST8U => {
+                let size = 1 << ((op - DW_OP_CONST1U) / 2);
+                let (r, x) = parse_address(i, size)?;
+                stack.push(x);
+                i = r;
+            }
+            DW_OP_CONST1S => {
+                let (r, x) = nom::le_i8(i)?;
+                stack.push(i64::from(x) as u64);
+                i = r;
+            }
+            DW_OP_CONST2S => {
+                let (r, x) = nom::le_i16(i)?;
+                stack.push(i64::from(x) as u64);
+                i = r;
+            }
+            DW_OP_CONST4S => {
+                let (r, x) = nom::le_i32(i)?;
+                stack.push(i64::from(x) as u64);
+                i = r;
+            }
+            DW_OP_CONST8S => {
+                let (r, x) = nom::le_i64(i)?;
+                stack.push(x as u64);
+                i = r;
+            }
+            DW_OP_CONSTU => {
+                let (r, x) = parse_uleb128(i)?;
+                stack.push(x);
+                i = r;
+            }
+            DW_OP_CONSTS => {
+                let (r, x) = parse_sleb128(i)?;
+                stack.push(x as u64);
+                i = r;
+            }
+            DW_OP_DUP => {
+                let x = *stack
+                    .last()
+                    .ok_or_else(|| expression_error("stack underflow"))?;
+                stack.push(x);
+            }
+            DW_OP_DROP => {
+                pop!();
+            }
+            DW_OP_OVER => {
+                let x = *stack
+                    .len()
+                    .checked_sub(2)
+                    .and_then(|idx| stack.get(idx))
+                    .ok_or_else(|| expression_error("stack underflow"))?;
+                stack.push(x);
+            }
+            DW_OP_PICK => {
+                let (r, idx) = nom::le_u8(i)?;
+                let x = *stack
+                    .len()
+                    .checked_sub(1 + idx as usize)
+                    .and_then(|idx| stack.get(idx))
+                    .ok_or_else(|| expression_error("stack underflow"))?;
+                stack.push(x);
+                i = r;
+            }
+            DW_OP_SWAP => {
+                let len = stack.len();
+                if len < 2 {
+                    return Err(expression_error("stack underflow"));
+                }
+                stack.swap(len - 1, len - 2);
+            }
+            DW_OP_ROT => {
+                let len = stack.len();
+                if len < 3 {
+                    return Err(expression_error("stack underflow"));
+                }
+                let top = stack.remove(len - 1);
+                stack.insert(len - 3, top);
+            }
+            DW_OP_ABS => {
+                let x = pop!() as i64;
+                stack.push(x.wrapping_abs() as u64);
+            }
+            DW_OP_NEG => {
+                let x = pop!() as i64;
+                stack.push(x.wrapping_neg() as u64);
+            }
+            DW_OP_NOT => {
+                let x = pop!();
+                stack.push(!x);
+            }
+            DW_OP_AND => binop!(|a, b| a & b),
+            DW_OP_OR => binop!(|a, b| a | b),
+            DW_OP_XOR => binop!(|a, b| a ^ b),
+            DW_OP_PLUS => binop!(|a: u64, b| a.wrapping_add(b)),
+            DW_OP_MINUS => binop!(|a: u64, b| a.wrapping_sub(b)),
+            DW_OP_MUL => binop!(|a: u64, b| a.wrapping_mul(b)),
+            DW_OP_SHL => binop!(|a: u64, b: u64| a.wrapping_shl(b as u32)),
+            DW_OP_SHR => binop!(|a: u64, b: u64| a.wrapping_shr(b as u32)),
+            DW_OP_SHRA => binop!(|a: u64, b: u64| (a as i64).wrapping_shr(b as u32) as u64),
+            DW_OP_DIV | DW_OP_MOD => {
+                let b = pop!() as i64;
+                let a = pop!() as i64;
+                if b == 0 {
+                    return Err(expression_error("division by zero"));
+                }
+                let x = if op == DW_OP_DIV {
+                    a.wrapping_div(b)
+                } else {
+                    a.wrapping_rem(b)
+                };
+                stack.push(x as u64);
+            }
+            DW_OP_EQ => binop!(|a, b| (a == b) as u64),
+            DW_OP_NE => binop!(|a, b| (a != b) as u64),
+            DW_OP_GE => binop!(|a, b| ((a as i64) >= (b as i64)) as u64),
+            DW_OP_GT => binop!(|a, b| ((a as i64) > (b as i64)) as u64),
+            DW_OP_LE => binop!(|a, b| ((a as i64) <= (b as i64)) as u64),
+            DW_OP_LT => binop!(|a, b| ((a as i64) < (b as i64)) as u64),
+            DW_OP_PLUS_UCONST => {
+                let (r, x) = parse_uleb128(i)?;
+                let a = pop!();
+                stack.push(a.wrapping_add(x));
+                i = r;
+            }
+            DW_OP_SKIP | DW_OP_BRA => {
+                let (r, delta) = nom::le_i16(i)?;
+                i = r;
+                if op == DW_OP_SKIP || pop!() != 0 {
+                    let pos = (expr.len() - i.len()) as i64 + i64::from(delta);
+                    if pos < 0 || pos > expr.len() as i64 {
+                        return Err(expression_error("branch out of bounds"));
+                    }
+                    i = &expr[pos as usize..];
+                }
+            }
+            DW_OP_LIT0..=DW_OP_LIT31 => stack.push(u64::from(op - DW_OP_LIT0)),
+            DW_OP_BREG0..=DW_OP_BREG31 => {
+                let (r, off) = parse_sleb128(i)?;
+                let reg = u16::from(op - DW_OP_BREG0);
+                stack.push(register(reg)?.wrapping_add(off as u64));
+                i = r;
+            }
+            DW_OP_BREGX => {
+                let (r, reg) = parse_uleb128(i)?;
+                let (r, off) = parse_sleb128(r)?;
+                stack.push(register(reg as u16)?.wrapping_add(off as u64));
+                i = r;
+            }
+            DW_OP_NOP => {}
+            _ => {
+                return Err(expression_error(&format!(
+                    "unsupported operation {:#x}",
+                    op
+                )))
+            }
+        }
+    }
+    stack.pop().ok_or_else(|| expression_error("empty stack"))
+}
+
+/// Unwinds one frame: computes the caller's registers from the callee's
+/// `regs` with the rules of `row`. The program counter of the caller is the
+/// return address, and its stack pointer the CFA.
+pub fn unwind_frame<F>(
+    row: &UnwindRow,
+    arch: Arch,
+    regs: &Registers,
+    address_size: u8,
+    read: F,
+) -> Option<Registers>
+where
+    F: Fn(u64) -> Option<u64>,
+{
+    let cfa = match row.cfa {
+        CfaRule::RegisterOffset(reg, off) => regs.get(&reg)?.wrapping_add(off as u64),
+        CfaRule::Expression(expr) => {
+            evaluate_expression(expr, None, regs, address_size, &read).ok()?
+        }
+    };
+
+    let mut caller = regs.clone();
+    for &(reg, ref rule) in row.registers.iter() {
+        let value = match *rule {
+            RegisterRule::Undefined => None,
+            RegisterRule::SameValue => regs.get(&reg).cloned(),
+            RegisterRule::Offset(off) => read(cfa.wrapping_add(off as u64)),
+            RegisterRule::ValOffset(off) => Some(cfa.wrapping_add(off as u64)),
+            RegisterRule::Register(other) => regs.get(&other).cloned(),
+            RegisterRule::Expression(expr) => {
+                evaluate_expression(expr, Some(cfa), regs, address_size, &read)
+                    .ok()
+                    .and_then(&read)
+            }
+            RegisterRule::ValExpression(expr) => {
+                evaluate_expression(expr, Some(cfa), regs, address_size, &read).ok()
+            }
+        };
+        match value {
+            Some(v) => caller.insert(reg, v),
+            None => caller.remove(&reg),
+        };
+    }
+
+    let ra = *caller.get(&row.return_address_register)?;
+    caller.insert(arch.pc(), ra);
+    caller.insert(arch.sp(), cfa);
+    Some(caller)
+}
+
+/// All the FDEs of a frame section, indexed by address.
+#[derive(Debug)]
+pub struct FrameTable<'a> {
+    pub section: FrameSection<'a>,
+    pub fdes: Vec<Fde<'a>>,
+}
+
+impl<'a> FrameTable<'a> {
+    pub fn new(section: FrameSection<'a>) -> Result<Self, Error> {
+        let mut fdes = section.fdes()?;
+        fdes.sort_by_key(|f| f.initial_location);
+        Ok(FrameTable {
+            section: section,
+            fdes: fdes,
+        })
+    }
+
+    pub fn fde_for_address(&self, pc: u64) -> Option<&Fde<'a>> {
+        let idx = match self.fdes.binary_search_by(|f| f.initial_location.cmp(&pc)) {
+            Ok(idx) => idx,
+            Err(0) => return None,
+            Err(idx) => idx - 1,
+        };
+        let fde = &self.fdes[idx];
+        if fde.contains(pc) {
+            Some(fde)
+        } else {
+            None
+        }
+    }
+
+    /// Computes the unwind rules at `pc`.
+    pub fn unwind_row(&self, pc: u64) -> Result<Option<UnwindRow<'a>>, Error> {
+        match self.fde_for_address(pc) {
+            Some(fde) => self.section.unwind_row(fde, pc).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the `(start, end)` function ranges whose start address is not
+    /// covered by any FDE.
+    pub fn missing(&self, functions: &[(u64, u64)]) -> Vec<(u64, u64)> {
+        functions
+            .iter()
+            .filter(|&&(start, _)| self.fde_for_address(start).is_none())
+            .cloned()
+            .collect()
+    }
+}
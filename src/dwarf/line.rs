@@ -0,0 +1,323 @@
+use nom;
+
+use dwarf::reader::*;
+use dwarf::*;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LineRow {
+    pub address: u64,
+    pub file: u64,
+    pub line: u32,
+    pub column: u32,
+}
+
+/// A contiguous run of rows ending with `DW_LNE_end_sequence` at `end`.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LineSequence {
+    pub start: u64,
+    pub end: u64,
+    pub rows: Vec<LineRow>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LineProgram {
+    pub version: u16,
+    pub files: Vec<String>,
+    pub sequences: Vec<LineSequence>,
+}
+
+impl LineProgram {
+    /// Returns the path of the file at index `file` of the file table.
+    pub fn file(&self, file: u64) -> Option<&str> {
+        let idx = if self.version >= 5 {
+            file
+        } else {
+            file.checked_sub(1)?
+        };
+        self.files.get(idx as usize).map(|s| s.as_str())
+    }
+}
+
+fn join_path(dir: &str, file: &str) -> String {
+    if file.starts_with('/') || dir.is_empty() {
+        String::from(file)
+    } else if dir.ends_with('/') {
+        format!("{}{}", dir, file)
+    } else {
+        format!("{}/{}", dir, file)
+    }
+}
+
+/// Parses an entry of a DWARF 5 directory or file name table, returning the
+/// path and directory index.
+fn parse_entry<'a>(
+    mut i: &'a [u8],
+    formats: &[(u64, u64)],
+    unit: &UnitHeader,
+    sections: &DwarfSections<'a>,
+) -> nom::IResult<&'a [u8], (String, u64)> {
+    let mut path = String::new();
+    let mut dir = 0;
+
+    for &(content, form) in formats.iter() {
+        let (rest, value) = parse_attr_value(i, form, 0, unit)?;
+        match content {
+            DW_LNCT_PATH => {
+                let s = match value {
+                    AttrValue::String(s) => Some(s),
+                    AttrValue::StrOffset(off) => str_at(sections.debug_str, off),
+                    AttrValue::LineStrOffset(off) => str_at(sections.debug_line_str, off),
+                    _ => None,
+                };
+                if let Some(s) = s {
+                    path = String::from_utf8_lossy(s).into_owned();
+                }
+            }
+            DW_LNCT_DIRECTORY_INDEX => {
+                if let AttrValue::Udata(x) = value {
+                    dir = x;
+                }
+            }
+            _ => {}
+        }
+        i = rest;
+    }
+    Ok((i, (path, dir)))
+}
+
+fn parse_entry_formats(i: &[u8]) -> nom::IResult<&[u8], Vec<(u64, u64)>> {
+    let (mut i, count) = nom::le_u8(i)?;
+    let mut formats = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let (rest, content) = parse_uleb128(i)?;
+        let (rest, form) = parse_uleb128(rest)?;
+        formats.push((content, form));
+        i = rest;
+    }
+    Ok((i, formats))
+}
+
+fn initial_row() -> LineRow {
+    LineRow {
+        address: 0,
+        file: 1,
+        line: 1,
+        column: 0,
+    }
+}
+
+/// Parses the line number program at `offset` in `.debug_line`. `comp_dir`
+/// and `comp_name` come from the owning compilation unit and are used for
+/// directory and file index 0 before DWARF 5.
+pub fn parse_line_program<'a>(
+    sections: &DwarfSections<'a>,
+    offset: u64,
+    comp_dir: &str,
+    comp_name: &str,
+) -> nom::IResult<&'a [u8], LineProgram> {
+    let debug_line = sections.debug_line;
+    if offset >= debug_line.len() as u64 {
+        return Err(nom::Err::Incomplete(nom::Needed::Unknown));
+    }
+    let i = &debug_line[offset as usize..];
+    let (after, (length, format64)) = parse_initial_length(i)?;
+    let (after, program) = take_unit(after, length)?;
+
+    let (r, version) = nom::le_u16(program)?;
+    if !(2..=5).contains(&version) {
+        return Err(nom::Err::Error(error_position!(i, nom::ErrorKind::Verify)));
+    }
+    let (r, address_size) = if version >= 5 {
+        let (r, address_size) = nom::le_u8(r)?;
+        let (r, _segment_selector_size) = nom::le_u8(r)?;
+        (r, address_size)
+    } else {
+        (r, 8)
+    };
+    let (r, header_length) = parse_offset(r, format64)?;
+    let (ops, header) = take_unit(r, header_length)?;
+
+    let (h, minimum_instruction_length) = nom::le_u8(header)?;
+    let (h, _maximum_operations_per_instruction) =
+        if version >= 4 { nom::le_u8(h)? } else { (h, 1) };
+    let (h, _default_is_stmt) = nom::le_u8(h)?;
+    let (h, line_base) = nom::le_i8(h)?;
+    let (h, line_range) = nom::le_u8(h)?;
+    let (h, opcode_base) = nom::le_u8(h)?;
+    if line_range == 0 || opcode_base == 0 {
+        return Err(nom::Err::Error(error_position!(i, nom::ErrorKind::Verify)));
+    }
+    let (mut h, standard_opcode_lengths) = take_unit(h, u64::from(opcode_base - 1))?;
+
+    let mut files = Vec::new();
+    if version >= 5 {
+        // The entries are parsed with a fake unit carrying the program's
+        // offset and address sizes.
+        let unit = UnitHeader {
+            offset: 0,
+            end: 0,
+            entries: 0,
+            format64: format64,
+            version: version,
+            unit_type: DW_UT_COMPILE,
+            address_size: address_size,
+            abbrev_offset: 0,
+        };
+
+        let (rest, formats) = parse_entry_formats(h)?;
+        let (mut rest, count) = parse_uleb128(rest)?;
+        let mut dirs = Vec::new();
+        for _ in 0..count {
+            let (r, (path, _)) = parse_entry(rest, &formats, &unit, sections)?;
+            dirs.push(path);
+            rest = r;
+        }
+
+        let (rest, formats) = parse_entry_formats(rest)?;
+        let (mut rest, count) = parse_uleb128(rest)?;
+        for _ in 0..count {
+            let (r, (path, dir)) = parse_entry(rest, &formats, &unit, sections)?;
+            let dir = dirs.get(dir as usize).map(|s| s.as_str()).unwrap_or("");
+            let dir = join_path(comp_dir, dir);
+            files.push(join_path(&dir, &path));
+            rest = r;
+        }
+    } else {
+        let mut dirs = vec![String::from(comp_dir)];
+        loop {
+            let (rest, dir) = parse_cstr(h)?;
+            h = rest;
+            if dir.is_empty() {
+                break;
+            }
+            let dir = String::from_utf8_lossy(dir);
+            dirs.push(join_path(comp_dir, &dir));
+        }
+        loop {
+            let (rest, name) = parse_cstr(h)?;
+            if name.is_empty() {
+                break;
+            }
+            let (rest, dir) = parse_uleb128(rest)?;
+            let (rest, _mtime) = parse_uleb128(rest)?;
+            let (rest, _length) = parse_uleb128(rest)?;
+            let dir = dirs
+                .get(dir as usize)
+                .map(|s| s.as_str())
+                .unwrap_or(comp_dir);
+            files.push(join_path(dir, &String::from_utf8_lossy(name)));
+            h = rest;
+        }
+        if files.is_empty() && !comp_name.is_empty() {
+            files.push(join_path(comp_dir, comp_name));
+        }
+    }
+
+    let mut sequences = Vec::new();
+    let mut rows = Vec::new();
+    let mut state = initial_row();
+    let mut i = ops;
+    let min_inst = u64::from(minimum_instruction_length);
+
+    while !i.is_empty() {
+        let (rest, opcode) = nom::le_u8(i)?;
+        i = rest;
+
+        if opcode >= opcode_base {
+            let adjusted = opcode - opcode_base;
+            state.address = state
+                .address
+                .wrapping_add(u64::from(adjusted / line_range) * min_inst);
+            let delta = i64::from(line_base) + i64::from(adjusted % line_range);
+            state.line = (i64::from(state.line) + delta) as u32;
+            rows.push(state.clone());
+            continue;
+        }
+
+        match opcode {
+            0 => {
+                let (rest, len) = parse_uleb128(i)?;
+                let (rest, ext) = take_unit(rest, len)?;
+                i = rest;
+                if ext.is_empty() {
+                    continue;
+                }
+                match ext[0] {
+                    DW_LNE_END_SEQUENCE => {
+                        if let Some(first) = rows.first().map(|r: &LineRow| r.address) {
+                            sequences.push(LineSequence {
+                                start: first,
+                                end: state.address,
+                                rows: rows,
+                            });
+                        }
+                        rows = Vec::new();
+                        state = initial_row();
+                    }
+                    DW_LNE_SET_ADDRESS => {
+                        let size = (ext.len() - 1) as u8;
+                        state.address = parse_address(&ext[1..], size)?.1;
+                    }
+                    _ => {}
+                }
+            }
+            DW_LNS_COPY => {
+                rows.push(state.clone());
+            }
+            DW_LNS_ADVANCE_PC => {
+                let (rest, delta) = parse_uleb128(i)?;
+                state.address = state.address.wrapping_add(delta.wrapping_mul(min_inst));
+                i = rest;
+            }
+            DW_LNS_ADVANCE_LINE => {
+                let (rest, delta) = parse_sleb128(i)?;
+                state.line = i64::from(state.line).wrapping_add(delta) as u32;
+                i = rest;
+            }
+            DW_LNS_SET_FILE => {
+                let (rest, file) = parse_uleb128(i)?;
+                state.file = file;
+                i = rest;
+            }
+            DW_LNS_SET_COLUMN => {
+                let (rest, column) = parse_uleb128(i)?;
+                state.column = column as u32;
+                i = rest;
+            }
+            DW_LNS_CONST_ADD_PC => {
+                let adjusted = 255 - opcode_base;
+                state.address = state
+                    .address
+                    .wrapping_add(u64::from(adjusted / line_range) * min_inst);
+            }
+            DW_LNS_FIXED_ADVANCE_PC => {
+                let (rest, delta) = nom::le_u16(i)?;
+                state.address = state.address.wrapping_add(u64::from(delta));
+                i = rest;
+            }
+            DW_LNS_NEGATE_STMT
+            | DW_LNS_SET_BASIC_BLOCK
+            | DW_LNS_SET_PROLOGUE_END
+            | DW_LNS_SET_EPILOGUE_BEGIN => {}
+            _ => {
+                // Unknown standard opcode: skip its ULEB128 operands.
+                let nargs = standard_opcode_lengths[opcode as usize - 1];
+                for _ in 0..nargs {
+                    i = parse_uleb128(i)?.0;
+                }
+            }
+        }
+    }
+
+    Ok((
+        after,
+        LineProgram {
+            version: version,
+            files: files,
+            sequences: sequences,
+        },
+    ))
+}
@@ -0,0 +1,777 @@
+use nom;
+use std::collections::HashMap;
+
+use error::Error;
+use header::SHF_COMPRESSED;
+use {Elf32, Elf64};
+
+pub mod frame;
+pub mod line;
+pub mod reader;
+pub mod unit;
+
+pub use self::frame::*;
+pub use self::line::*;
+use self::reader::*;
+pub use self::unit::*;
+
+pub const DW_UT_COMPILE: u8 = 0x01;
+pub const DW_UT_TYPE: u8 = 0x02;
+pub const DW_UT_PARTIAL: u8 = 0x03;
+pub const DW_UT_SKELETON: u8 = 0x04;
+pub const DW_UT_SPLIT_COMPILE: u8 = 0x05;
+pub const DW_UT_SPLIT_TYPE: u8 = 0x06;
+
+pub const DW_TAG_LEXICAL_BLOCK: u64 = 0x0b;
+pub const DW_TAG_COMPILE_UNIT: u64 = 0x11;
+pub const DW_TAG_INLINED_SUBROUTINE: u64 = 0x1d;
+pub const DW_TAG_SUBPROGRAM: u64 = 0x2e;
+pub const DW_TAG_PARTIAL_UNIT: u64 = 0x3c;
+pub const DW_TAG_SKELETON_UNIT: u64 = 0x4a;
+
+pub const DW_AT_NAME: u64 = 0x03;
+pub const DW_AT_STMT_LIST: u64 = 0x10;
+pub const DW_AT_LOW_PC: u64 = 0x11;
+pub const DW_AT_HIGH_PC: u64 = 0x12;
+pub const DW_AT_COMP_DIR: u64 = 0x1b;
+pub const DW_AT_ABSTRACT_ORIGIN: u64 = 0x31;
+pub const DW_AT_SPECIFICATION: u64 = 0x47;
+pub const DW_AT_RANGES: u64 = 0x55;
+pub const DW_AT_CALL_COLUMN: u64 = 0x57;
+pub const DW_AT_CALL_FILE: u64 = 0x58;
+pub const DW_AT_CALL_LINE: u64 = 0x59;
+pub const DW_AT_LINKAGE_NAME: u64 = 0x6e;
+pub const DW_AT_STR_OFFSETS_BASE: u64 = 0x72;
+pub const DW_AT_ADDR_BASE: u64 = 0x73;
+pub const DW_AT_RNGLISTS_BASE: u64 = 0x74;
+pub const DW_AT_MIPS_LINKAGE_NAME: u64 = 0x2007;
+pub const DW_AT_GNU_ADDR_BASE: u64 = 0x2133;
+pub const DW_AT_GNU_RANGES_BASE: u64 = 0x2132;
+
+pub const DW_FORM_ADDR: u64 = 0x01;
+pub const DW_FORM_BLOCK2: u64 = 0x03;
+pub const DW_FORM_BLOCK4: u64 = 0x04;
+pub const DW_FORM_DATA2: u64 = 0x05;
+pub const DW_FORM_DATA4: u64 = 0x06;
+pub const DW_FORM_DATA8: u64 = 0x07;
+pub const DW_FORM_STRING: u64 = 0x08;
+pub const DW_FORM_BLOCK: u64 = 0x09;
+pub const DW_FORM_BLOCK1: u64 = 0x0a;
+pub const DW_FORM_DATA1: u64 = 0x0b;
+pub const DW_FORM_FLAG: u64 = 0x0c;
+pub const DW_FORM_SDATA: u64 = 0x0d;
+pub const DW_FORM_STRP: u64 = 0x0e;
+pub const DW_FORM_UDATA: u64 = 0x0f;
+pub const DW_FORM_REF_ADDR: u64 = 0x10;
+pub const DW_FORM_REF1: u64 = 0x11;
+pub const DW_FORM_REF2: u64 = 0x12;
+pub const DW_FORM_REF4: u64 = 0x13;
+pub const DW_FORM_REF8: u64 = 0x14;
+pub const DW_FORM_REF_UDATA: u64 = 0x15;
+pub const DW_FORM_INDIRECT: u64 = 0x16;
+pub const DW_FORM_SEC_OFFSET: u64 = 0x17;
+pub const DW_FORM_EXPRLOC: u64 = 0x18;
+pub const DW_FORM_FLAG_PRESENT: u64 = 0x19;
+pub const DW_FORM_STRX: u64 = 0x1a;
+pub const DW_FORM_ADDRX: u64 = 0x1b;
+pub const DW_FORM_REF_SUP4: u64 = 0x1c;
+pub const DW_FORM_STRP_SUP: u64 = 0x1d;
+pub const DW_FORM_DATA16: u64 = 0x1e;
+pub const DW_FORM_LINE_STRP: u64 = 0x1f;
+pub const DW_FORM_REF_SIG8: u64 = 0x20;
+pub const DW_FORM_IMPLICIT_CONST: u64 = 0x21;
+pub const DW_FORM_LOCLISTX: u64 = 0x22;
+pub const DW_FORM_RNGLISTX: u64 = 0x23;
+pub const DW_FORM_REF_SUP8: u64 = 0x24;
+pub const DW_FORM_STRX1: u64 = 0x25;
+pub const DW_FORM_STRX2: u64 = 0x26;
+pub const DW_FORM_STRX3: u64 = 0x27;
+pub const DW_FORM_STRX4: u64 = 0x28;
+pub const DW_FORM_ADDRX1: u64 = 0x29;
+pub const DW_FORM_ADDRX2: u64 = 0x2a;
+pub const DW_FORM_ADDRX3: u64 = 0x2b;
+pub const DW_FORM_ADDRX4: u64 = 0x2c;
+pub const DW_FORM_GNU_ADDR_INDEX: u64 = 0x1f01;
+pub const DW_FORM_GNU_STR_INDEX: u64 = 0x1f02;
+pub const DW_FORM_GNU_REF_ALT: u64 = 0x1f20;
+pub const DW_FORM_GNU_STRP_ALT: u64 = 0x1f21;
+
+pub const DW_LNS_COPY: u8 = 0x01;
+pub const DW_LNS_ADVANCE_PC: u8 = 0x02;
+pub const DW_LNS_ADVANCE_LINE: u8 = 0x03;
+pub const DW_LNS_SET_FILE: u8 = 0x04;
+pub const DW_LNS_SET_COLUMN: u8 = 0x05;
+pub const DW_LNS_NEGATE_STMT: u8 = 0x06;
+pub const DW_LNS_SET_BASIC_BLOCK: u8 = 0x07;
+pub const DW_LNS_CONST_ADD_PC: u8 = 0x08;
+pub const DW_LNS_FIXED_ADVANCE_PC: u8 = 0x09;
+pub const DW_LNS_SET_PROLOGUE_END: u8 = 0x0a;
+pub const DW_LNS_SET_EPILOGUE_BEGIN: u8 = 0x0b;
+
+pub const DW_LNE_END_SEQUENCE: u8 = 0x01;
+pub const DW_LNE_SET_ADDRESS: u8 = 0x02;
+
+pub const DW_LNCT_PATH: u64 = 0x1;
+pub const DW_LNCT_DIRECTORY_INDEX: u64 = 0x2;
+
+pub const DW_RLE_END_OF_LIST: u8 = 0x00;
+pub const DW_RLE_BASE_ADDRESSX: u8 = 0x01;
+pub const DW_RLE_STARTX_ENDX: u8 = 0x02;
+pub const DW_RLE_STARTX_LENGTH: u8 = 0x03;
+pub const DW_RLE_OFFSET_PAIR: u8 = 0x04;
+pub const DW_RLE_BASE_ADDRESS: u8 = 0x05;
+pub const DW_RLE_START_END: u8 = 0x06;
+pub const DW_RLE_START_LENGTH: u8 = 0x07;
+
+/// The DWARF sections used for symbolization. Missing optional sections are
+/// empty.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DwarfSections<'a> {
+    pub debug_info: &'a [u8],
+    pub debug_abbrev: &'a [u8],
+    pub debug_line: &'a [u8],
+    pub debug_line_str: &'a [u8],
+    pub debug_str: &'a [u8],
+    pub debug_str_offsets: &'a [u8],
+    pub debug_addr: &'a [u8],
+    pub debug_ranges: &'a [u8],
+    pub debug_rnglists: &'a [u8],
+}
+
+impl<'a> DwarfSections<'a> {
+    fn load<F>(load: F) -> Result<Self, Error>
+    where
+        F: Fn(&str) -> Result<Option<&'a [u8]>, Error>,
+    {
+        let required = |name: &str| match load(name)? {
+            Some(data) => Ok(data),
+            None => Err(Error::MissingSection(String::from(name))),
+        };
+        let optional = |name: &str| load(name).map(|data| data.unwrap_or(&[]));
+
+        Ok(DwarfSections {
+            debug_info: required(".debug_info")?,
+            debug_abbrev: required(".debug_abbrev")?,
+            debug_line: optional(".debug_line")?,
+            debug_line_str: optional(".debug_line_str")?,
+            debug_str: optional(".debug_str")?,
+            debug_str_offsets: optional(".debug_str_offsets")?,
+            debug_addr: optional(".debug_addr")?,
+            debug_ranges: optional(".debug_ranges")?,
+            debug_rnglists: optional(".debug_rnglists")?,
+        })
+    }
+
+    pub fn from_elf64(elf: &Elf64<'a>) -> Result<Self, Error> {
+        DwarfSections::load(|name| match elf.section_by_name(name) {
+            Some(s) if s.sh_flags & SHF_COMPRESSED != 0 => {
+                Err(Error::Unsupported(format!("compressed section {}", name)))
+            }
+            Some(s) => Ok(elf.section_data(s)),
+            None => Ok(None),
+        })
+    }
+
+    pub fn from_elf32(elf: &Elf32<'a>) -> Result<Self, Error> {
+        DwarfSections::load(|name| match elf.section_by_name(name) {
+            Some(s) if u64::from(s.sh_flags) & SHF_COMPRESSED != 0 => {
+                Err(Error::Unsupported(format!("compressed section {}", name)))
+            }
+            Some(s) => Ok(elf.section_data(s)),
+            None => Ok(None),
+        })
+    }
+}
+
+/// A source location.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Location {
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+}
+
+/// A frame of the (possibly inlined) call stack at an address. The innermost
+/// frame comes first.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Frame {
+    pub function: Option<String>,
+    pub location: Option<Location>,
+}
+
+#[derive(Debug)]
+struct Inlined {
+    depth: usize,
+    ranges: Vec<(u64, u64)>,
+    name: Option<String>,
+    call: Location,
+}
+
+#[derive(Debug)]
+struct Function {
+    name: Option<String>,
+    inlined: Vec<Inlined>,
+}
+
+#[derive(Debug)]
+struct UnitLines {
+    program: Option<LineProgram>,
+}
+
+/// Per-unit values needed to resolve attribute values.
+#[derive(Debug, Clone, Default)]
+struct UnitBases {
+    low_pc: u64,
+    addr_base: u64,
+    str_offsets_base: u64,
+    rnglists_base: u64,
+}
+
+/// Address to source location and function lookups built from the DWARF
+/// `.debug_info` and `.debug_line` sections.
+#[derive(Debug)]
+pub struct Addr2Line<'a> {
+    sections: DwarfSections<'a>,
+    units: Vec<UnitHeader>,
+    abbrevs: HashMap<u64, Abbrevs>,
+    lines: Vec<UnitLines>,
+    functions: Vec<Function>,
+    /// `(begin, end, function index)`, sorted by `begin`.
+    function_ranges: Vec<(u64, u64, usize)>,
+    /// `(start, end, unit index, sequence index)`, sorted by `start`.
+    sequences: Vec<(u64, u64, usize, usize)>,
+}
+
+#[derive(Clone, Copy)]
+struct Scope {
+    function: Option<usize>,
+    depth: usize,
+}
+
+fn contains(ranges: &[(u64, u64)], addr: u64) -> bool {
+    ranges
+        .iter()
+        .any(|&(begin, end)| begin <= addr && addr < end)
+}
+
+/// Returns the index of the last entry whose key is lower or equal to `key`.
+fn last_le<T, F>(entries: &[T], key: u64, f: F) -> Option<usize>
+where
+    F: Fn(&T) -> u64,
+{
+    match entries.binary_search_by(|e| f(e).cmp(&key)) {
+        Ok(mut idx) => {
+            while idx + 1 < entries.len() && f(&entries[idx + 1]) == key {
+                idx += 1;
+            }
+            Some(idx)
+        }
+        Err(0) => None,
+        Err(idx) => Some(idx - 1),
+    }
+}
+
+impl<'a> Addr2Line<'a> {
+    pub fn new(sections: DwarfSections<'a>) -> Result<Self, Error> {
+        let mut units = Vec::new();
+        let mut offset = 0;
+        while offset < sections.debug_info.len() {
+            let (_, unit) = parse_unit_header(sections.debug_info, offset)?;
+            offset = unit.end;
+            units.push(unit);
+        }
+
+        let mut abbrevs = HashMap::new();
+        for unit in units.iter() {
+            if !abbrevs.contains_key(&unit.abbrev_offset) {
+                let start = unit.abbrev_offset as usize;
+                if start > sections.debug_abbrev.len() {
+                    return Err(Error::ParseError(format!(
+                        "abbreviation offset {:#x} out of bounds",
+                        start
+                    )));
+                }
+                let (_, a) = parse_abbrevs(&sections.debug_abbrev[start..])?;
+                abbrevs.insert(unit.abbrev_offset, a);
+            }
+        }
+
+        let mut ctx = Addr2Line {
+            sections: sections,
+            units: units,
+            abbrevs: abbrevs,
+            lines: Vec::new(),
+            functions: Vec::new(),
+            function_ranges: Vec::new(),
+            sequences: Vec::new(),
+        };
+        for idx in 0..ctx.units.len() {
+            ctx.parse_unit(idx)?;
+        }
+        ctx.function_ranges.sort_by_key(|r| r.0);
+        ctx.sequences.sort_by_key(|s| s.0);
+
+        Ok(ctx)
+    }
+
+    fn parse_unit(&mut self, idx: usize) -> Result<(), Error> {
+        let unit = self.units[idx].clone();
+        let debug_info = self.sections.debug_info;
+        let mut bases = UnitBases::default();
+        let mut stack: Vec<Scope> = Vec::new();
+        let mut offset = unit.entries;
+        let mut lines = UnitLines { program: None };
+
+        while offset < unit.end {
+            let (rest, die) = {
+                let abbrevs = &self.abbrevs[&unit.abbrev_offset];
+                parse_die(debug_info, offset, &unit, abbrevs)?
+            };
+            offset = unit.end - rest.len();
+
+            let die = match die {
+                Some(die) => die,
+                None => {
+                    stack.pop();
+                    if stack.is_empty() {
+                        break;
+                    }
+                    continue;
+                }
+            };
+
+            let parent = stack.last().cloned().unwrap_or(Scope {
+                function: None,
+                depth: 0,
+            });
+            let mut scope = parent;
+
+            match die.tag {
+                DW_TAG_COMPILE_UNIT | DW_TAG_PARTIAL_UNIT | DW_TAG_SKELETON_UNIT => {
+                    bases = self.unit_bases(&die, &unit);
+                    lines = self.unit_lines(&die, &unit, &bases);
+                }
+                DW_TAG_SUBPROGRAM => {
+                    let ranges = self.die_ranges(&die, &unit, &bases);
+                    if !ranges.is_empty() {
+                        let function = self.functions.len();
+                        self.functions.push(Function {
+                            name: self.die_name(&die, &unit, &bases, 0),
+                            inlined: Vec::new(),
+                        });
+                        for &(begin, end) in ranges.iter() {
+                            self.function_ranges.push((begin, end, function));
+                        }
+                        scope = Scope {
+                            function: Some(function),
+                            depth: 0,
+                        };
+                    }
+                }
+                DW_TAG_INLINED_SUBROUTINE => {
+                    if let Some(function) = parent.function {
+                        let ranges = self.die_ranges(&die, &unit, &bases);
+                        let name = self.die_name(&die, &unit, &bases, 0);
+                        let call = self.call_location(&die, &lines);
+                        self.functions[function].inlined.push(Inlined {
+                            depth: parent.depth + 1,
+                            ranges: ranges,
+                            name: name,
+                            call: call,
+                        });
+                        scope.depth += 1;
+                    }
+                }
+                _ => {}
+            }
+
+            if die.has_children {
+                stack.push(scope);
+            } else if stack.is_empty() {
+                break;
+            }
+        }
+
+        if let Some(ref program) = lines.program {
+            for (seq, sequence) in program.sequences.iter().enumerate() {
+                self.sequences
+                    .push((sequence.start, sequence.end, idx, seq));
+            }
+        }
+        self.lines.push(lines);
+        Ok(())
+    }
+
+    fn unit_bases(&self, die: &Die<'a>, unit: &UnitHeader) -> UnitBases {
+        let udata = |name| match die.attr(name) {
+            Some(AttrValue::SecOffset(x)) | Some(AttrValue::Udata(x)) => Some(x),
+            _ => None,
+        };
+        let mut bases = UnitBases {
+            low_pc: 0,
+            addr_base: udata(DW_AT_ADDR_BASE)
+                .or_else(|| udata(DW_AT_GNU_ADDR_BASE))
+                .unwrap_or(0),
+            str_offsets_base: udata(DW_AT_STR_OFFSETS_BASE).unwrap_or(0),
+            rnglists_base: udata(DW_AT_RNGLISTS_BASE)
+                .or_else(|| udata(DW_AT_GNU_RANGES_BASE))
+                .unwrap_or(0),
+        };
+        if let Some(low_pc) = die.attr(DW_AT_LOW_PC) {
+            bases.low_pc = self.address(low_pc, unit, &bases).unwrap_or(0);
+        }
+        bases
+    }
+
+    fn unit_lines(&self, die: &Die<'a>, unit: &UnitHeader, bases: &UnitBases) -> UnitLines {
+        let offset = match die.attr(DW_AT_STMT_LIST) {
+            Some(AttrValue::SecOffset(x)) | Some(AttrValue::Udata(x)) => x,
+            _ => return UnitLines { program: None },
+        };
+        let comp_dir = die
+            .attr(DW_AT_COMP_DIR)
+            .and_then(|v| self.string(v, unit, bases))
+            .unwrap_or_default();
+        let comp_name = die
+            .attr(DW_AT_NAME)
+            .and_then(|v| self.string(v, unit, bases))
+            .unwrap_or_default();
+
+        UnitLines {
+            program: parse_line_program(&self.sections, offset, &comp_dir, &comp_name)
+                .ok()
+                .map(|(_, p)| p),
+        }
+    }
+
+    fn call_location(&self, die: &Die<'a>, lines: &UnitLines) -> Location {
+        let udata = |name| match die.attr(name) {
+            Some(AttrValue::Udata(x)) => Some(x),
+            Some(AttrValue::Sdata(x)) => Some(x as u64),
+            _ => None,
+        };
+        let file = match (udata(DW_AT_CALL_FILE), lines.program.as_ref()) {
+            (Some(file), Some(program)) => program.file(file).map(String::from),
+            _ => None,
+        };
+
+        Location {
+            file: file,
+            line: udata(DW_AT_CALL_LINE).filter(|&x| x != 0).map(|x| x as u32),
+            column: udata(DW_AT_CALL_COLUMN)
+                .filter(|&x| x != 0)
+                .map(|x| x as u32),
+        }
+    }
+
+    fn address(&self, value: AttrValue<'a>, unit: &UnitHeader, bases: &UnitBases) -> Option<u64> {
+        match value {
+            AttrValue::Address(x) => Some(x),
+            AttrValue::AddressIndex(idx) => {
+                let off = bases.addr_base + idx * u64::from(unit.address_size);
+                if off > self.sections.debug_addr.len() as u64 {
+                    return None;
+                }
+                parse_address(&self.sections.debug_addr[off as usize..], unit.address_size)
+                    .ok()
+                    .map(|(_, x)| x)
+            }
+            _ => None,
+        }
+    }
+
+    fn string(&self, value: AttrValue<'a>, unit: &UnitHeader, bases: &UnitBases) -> Option<String> {
+        let s = match value {
+            AttrValue::String(s) => Some(s),
+            AttrValue::StrOffset(off) => str_at(self.sections.debug_str, off),
+            AttrValue::LineStrOffset(off) => str_at(self.sections.debug_line_str, off),
+            AttrValue::StrIndex(idx) => {
+                let off = bases.str_offsets_base + idx * u64::from(unit.offset_size());
+                if off > self.sections.debug_str_offsets.len() as u64 {
+                    return None;
+                }
+                let data = &self.sections.debug_str_offsets[off as usize..];
+                let (_, off) = parse_offset(data, unit.format64).ok()?;
+                str_at(self.sections.debug_str, off)
+            }
+            _ => None,
+        };
+        s.map(|s| String::from_utf8_lossy(s).into_owned())
+    }
+
+    /// Returns the linkage name or name of `die`, following
+    /// `DW_AT_abstract_origin` and `DW_AT_specification` references.
+    fn die_name(
+        &self,
+        die: &Die<'a>,
+        unit: &UnitHeader,
+        bases: &UnitBases,
+        depth: usize,
+    ) -> Option<String> {
+        for &name in [DW_AT_LINKAGE_NAME, DW_AT_MIPS_LINKAGE_NAME, DW_AT_NAME].iter() {
+            if let Some(s) = die.attr(name).and_then(|v| self.string(v, unit, bases)) {
+                return Some(s);
+            }
+        }
+        if depth > 16 {
+            return None;
+        }
+
+        for &name in [DW_AT_ABSTRACT_ORIGIN, DW_AT_SPECIFICATION].iter() {
+            let target = match die.attr(name) {
+                Some(AttrValue::UnitRef(off)) => unit.offset as u64 + off,
+                Some(AttrValue::InfoRef(off)) => off,
+                _ => continue,
+            };
+            let uidx = match last_le(&self.units, target, |u| u.offset as u64) {
+                Some(uidx) => uidx,
+                None => continue,
+            };
+            let target_unit = &self.units[uidx];
+            if target >= target_unit.end as u64 {
+                continue;
+            }
+            let abbrevs = &self.abbrevs[&target_unit.abbrev_offset];
+            if let Ok((_, Some(target_die))) = parse_die(
+                self.sections.debug_info,
+                target as usize,
+                target_unit,
+                abbrevs,
+            ) {
+                // The referenced unit shares the bases of the referencing one
+                // in the common case of a reference within the same unit.
+                if let Some(name) = self.die_name(&target_die, target_unit, bases, depth + 1) {
+                    return Some(name);
+                }
+            }
+        }
+        None
+    }
+
+    fn die_ranges(&self, die: &Die<'a>, unit: &UnitHeader, bases: &UnitBases) -> Vec<(u64, u64)> {
+        let mut ranges = Vec::new();
+
+        if let Some(low_pc) = die
+            .attr(DW_AT_LOW_PC)
+            .and_then(|v| self.address(v, unit, bases))
+        {
+            let high_pc = match die.attr(DW_AT_HIGH_PC) {
+                Some(AttrValue::Udata(x)) => Some(low_pc.wrapping_add(x)),
+                Some(AttrValue::Sdata(x)) => Some(low_pc.wrapping_add(x as u64)),
+                Some(v) => self.address(v, unit, bases),
+                None => None,
+            };
+            if let Some(high_pc) = high_pc {
+                ranges.push((low_pc, high_pc));
+            }
+        }
+
+        match die.attr(DW_AT_RANGES) {
+            Some(AttrValue::RangeListIndex(idx)) => {
+                let off = bases.rnglists_base + idx * u64::from(unit.offset_size());
+                if off < self.sections.debug_rnglists.len() as u64 {
+                    let data = &self.sections.debug_rnglists[off as usize..];
+                    if let Ok((_, x)) = parse_offset(data, unit.format64) {
+                        self.rnglist(bases.rnglists_base + x, unit, bases, &mut ranges);
+                    }
+                }
+            }
+            Some(AttrValue::SecOffset(off)) | Some(AttrValue::Udata(off)) => {
+                if unit.version >= 5 {
+                    self.rnglist(off, unit, bases, &mut ranges);
+                } else {
+                    self.ranges(off, unit, bases, &mut ranges);
+                }
+            }
+            _ => {}
+        }
+
+        ranges.retain(|&(begin, end)| begin < end);
+        ranges
+    }
+
+    /// Reads a pre-DWARF 5 range list from `.debug_ranges`.
+    fn ranges(&self, offset: u64, unit: &UnitHeader, bases: &UnitBases, out: &mut Vec<(u64, u64)>) {
+        if offset >= self.sections.debug_ranges.len() as u64 {
+            return;
+        }
+        let mut i = &self.sections.debug_ranges[offset as usize..];
+        let size = unit.address_size;
+        let max = if size == 8 {
+            !0u64
+        } else {
+            (1u64 << (size * 8)) - 1
+        };
+        let mut base = bases.low_pc;
+
+        while let Ok((rest, begin)) = parse_address(i, size) {
+            let (rest, end) = match parse_address(rest, size) {
+                Ok(x) => x,
+                Err(_) => break,
+            };
+            i = rest;
+            if begin == 0 && end == 0 {
+                break;
+            } else if begin == max {
+                base = end;
+            } else {
+                out.push((base.wrapping_add(begin), base.wrapping_add(end)));
+            }
+        }
+    }
+
+    /// Reads a DWARF 5 range list from `.debug_rnglists`.
+    fn rnglist(
+        &self,
+        offset: u64,
+        unit: &UnitHeader,
+        bases: &UnitBases,
+        out: &mut Vec<(u64, u64)>,
+    ) {
+        if offset >= self.sections.debug_rnglists.len() as u64 {
+            return;
+        }
+        let mut i = &self.sections.debug_rnglists[offset as usize..];
+        let size = unit.address_size;
+        let mut base = bases.low_pc;
+        let addrx = |idx| self.address(AttrValue::AddressIndex(idx), unit, bases);
+
+        loop {
+            let (rest, kind) = match nom::le_u8(i) {
+                Ok(x) => x,
+                Err(_) => return,
+            };
+            let result = match kind {
+                DW_RLE_END_OF_LIST => return,
+                DW_RLE_BASE_ADDRESSX => parse_uleb128(rest).map(|(r, idx)| {
+                    base = addrx(idx).unwrap_or(0);
+                    r
+                }),
+                DW_RLE_STARTX_ENDX => parse_uleb128(rest).and_then(|(r, a)| {
+                    let (r, b) = parse_uleb128(r)?;
+                    if let (Some(a), Some(b)) = (addrx(a), addrx(b)) {
+                        out.push((a, b));
+                    }
+                    Ok(r)
+                }),
+                DW_RLE_STARTX_LENGTH => parse_uleb128(rest).and_then(|(r, a)| {
+                    let (r, len) = parse_uleb128(r)?;
+                    if let Some(a) = addrx(a) {
+                        out.push((a, a.wrapping_add(len)));
+                    }
+                    Ok(r)
+                }),
+                DW_RLE_OFFSET_PAIR => parse_uleb128(rest).and_then(|(r, a)| {
+                    let (r, b) = parse_uleb128(r)?;
+                    out.push((base.wrapping_add(a), base.wrapping_add(b)));
+                    Ok(r)
+                }),
+                DW_RLE_BASE_ADDRESS => parse_address(rest, size).map(|(r, a)| {
+                    base = a;
+                    r
+                }),
+                DW_RLE_START_END => parse_address(rest, size).and_then(|(r, a)| {
+                    let (r, b) = parse_address(r, size)?;
+                    out.push((a, b));
+                    Ok(r)
+                }),
+                DW_RLE_START_LENGTH => parse_address(rest, size).and_then(|(r, a)| {
+                    let (r, len) = parse_uleb128(r)?;
+                    out.push((a, a.wrapping_add(len)));
+                    Ok(r)
+                }),
+                _ => return,
+            };
+            i = match result {
+                Ok(r) => r,
+                Err(_) => return,
+            };
+        }
+    }
+
+    /// Returns the source location of the instruction at `addr`.
+    pub fn find_location(&self, addr: u64) -> Option<Location> {
+        let idx = last_le(&self.sequences, addr, |s| s.0)?;
+        let (_, end, unit, seq) = self.sequences[idx];
+        if addr >= end {
+            return None;
+        }
+        let program = self.lines[unit].program.as_ref()?;
+        let rows = &program.sequences[seq].rows;
+        let row = &rows[last_le(rows, addr, |r| r.address)?];
+
+        Some(Location {
+            file: program.file(row.file).map(String::from),
+            line: if row.line != 0 { Some(row.line) } else { None },
+            column: if row.column != 0 {
+                Some(row.column)
+            } else {
+                None
+            },
+        })
+    }
+
+    fn find_function(&self, addr: u64) -> Option<&Function> {
+        let idx = last_le(&self.function_ranges, addr, |r| r.0)?;
+        // Functions do not overlap in practice, but nested or duplicated
+        // ranges may precede the closest one.
+        self.function_ranges[..idx + 1]
+            .iter()
+            .rev()
+            .take(8)
+            .find(|r| r.0 <= addr && addr < r.1)
+            .map(|r| &self.functions[r.2])
+    }
+
+    /// Returns the name of the function containing `addr`, ignoring
+    /// inlining.
+    pub fn find_function_name(&self, addr: u64) -> Option<&str> {
+        self.find_function(addr)
+            .and_then(|f| f.name.as_ref())
+            .map(|s| s.as_str())
+    }
+
+    /// Returns the call stack at `addr`, from the innermost inlined function
+    /// to the function containing it.
+    pub fn find_frames(&self, addr: u64) -> Vec<Frame> {
+        let mut location = self.find_location(addr);
+        let function = match self.find_function(addr) {
+            Some(f) => f,
+            None => {
+                return match location {
+                    Some(l) => vec![Frame {
+                        function: None,
+                        location: Some(l),
+                    }],
+                    None => Vec::new(),
+                };
+            }
+        };
+
+        let mut chain: Vec<&Inlined> = Vec::new();
+        for inlined in function.inlined.iter() {
+            if contains(&inlined.ranges, addr) {
+                while chain.last().map_or(false, |l| l.depth >= inlined.depth) {
+                    chain.pop();
+                }
+                chain.push(inlined);
+            }
+        }
+
+        let mut frames = Vec::with_capacity(chain.len() + 1);
+        for inlined in chain.iter().rev() {
+            frames.push(Frame {
+                function: inlined.name.clone(),
+                location: location,
+            });
+            location = Some(inlined.call.clone());
+        }
+        frames.push(Frame {
+            function: function.name.clone(),
+            location: location,
+        });
+        frames
+    }
+}
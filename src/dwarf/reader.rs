@@ -0,0 +1,99 @@
+use nom;
+
+pub fn parse_uleb128(i: &[u8]) -> nom::IResult<&[u8], u64> {
+    let mut result = 0u64;
+    let mut shift = 0u32;
+
+    for (idx, &byte) in i.iter().enumerate() {
+        if shift < 64 {
+            result |= u64::from(byte & 0x7f) << shift;
+        }
+        shift += 7;
+        if byte & 0x80 == 0 {
+            return Ok((&i[idx + 1..], result));
+        }
+    }
+    Err(nom::Err::Incomplete(nom::Needed::Unknown))
+}
+
+pub fn parse_sleb128(i: &[u8]) -> nom::IResult<&[u8], i64> {
+    let mut result = 0i64;
+    let mut shift = 0u32;
+
+    for (idx, &byte) in i.iter().enumerate() {
+        if shift < 64 {
+            result |= i64::from(byte & 0x7f) << shift;
+        }
+        shift += 7;
+        if byte & 0x80 == 0 {
+            if shift < 64 && byte & 0x40 != 0 {
+                result |= !0i64 << shift;
+            }
+            return Ok((&i[idx + 1..], result));
+        }
+    }
+    Err(nom::Err::Incomplete(nom::Needed::Unknown))
+}
+
+/// Parses a NUL-terminated string, returning it without the terminator.
+pub fn parse_cstr(i: &[u8]) -> nom::IResult<&[u8], &[u8]> {
+    match i.iter().position(|&c| c == 0) {
+        Some(len) => Ok((&i[len + 1..], &i[..len])),
+        None => Err(nom::Err::Incomplete(nom::Needed::Unknown)),
+    }
+}
+
+/// Parses a DWARF initial length, returning the unit length and whether the
+/// unit uses the 64-bit DWARF format.
+pub fn parse_initial_length(i: &[u8]) -> nom::IResult<&[u8], (u64, bool)> {
+    let (rest, length) = nom::le_u32(i)?;
+    if length == 0xffff_ffff {
+        let (rest, length) = nom::le_u64(rest)?;
+        Ok((rest, (length, true)))
+    } else if length >= 0xffff_fff0 {
+        Err(nom::Err::Error(error_position!(i, nom::ErrorKind::Verify)))
+    } else {
+        Ok((rest, (u64::from(length), false)))
+    }
+}
+
+pub fn parse_offset(i: &[u8], format64: bool) -> nom::IResult<&[u8], u64> {
+    if format64 {
+        nom::le_u64(i)
+    } else {
+        nom::le_u32(i).map(|(rest, x)| (rest, u64::from(x)))
+    }
+}
+
+pub fn parse_address(i: &[u8], size: u8) -> nom::IResult<&[u8], u64> {
+    match size {
+        1 => nom::le_u8(i).map(|(rest, x)| (rest, u64::from(x))),
+        2 => nom::le_u16(i).map(|(rest, x)| (rest, u64::from(x))),
+        4 => nom::le_u32(i).map(|(rest, x)| (rest, u64::from(x))),
+        8 => nom::le_u64(i),
+        _ => Err(nom::Err::Error(error_position!(i, nom::ErrorKind::Verify))),
+    }
+}
+
+/// Splits a unit of `length` bytes off the front of `i`.
+pub fn take_unit(i: &[u8], length: u64) -> nom::IResult<&[u8], &[u8]> {
+    if (i.len() as u64) < length {
+        Err(nom::Err::Incomplete(nom::Needed::Size(length as usize)))
+    } else {
+        let length = length as usize;
+        Ok((&i[length..], &i[..length]))
+    }
+}
+
+/// Returns the NUL-terminated string starting at `offset` in `section`.
+pub fn str_at(section: &[u8], offset: u64) -> Option<&[u8]> {
+    if offset > section.len() as u64 {
+        return None;
+    }
+    parse_cstr(&section[offset as usize..]).ok().map(|(_, s)| s)
+}
+
+/// Returns the offset of `i` in `section`, of which it must be a subslice.
+pub fn offset_in(section: &[u8], i: &[u8]) -> usize {
+    i.as_ptr() as usize - section.as_ptr() as usize
+}
@@ -0,0 +1,298 @@
+use nom;
+use std::collections::HashMap;
+
+use dwarf::reader::*;
+use dwarf::*;
+
+#[derive(Debug)]
+pub struct AttrSpec {
+    pub name: u64,
+    pub form: u64,
+    pub implicit_const: i64,
+}
+
+#[derive(Debug)]
+pub struct Abbrev {
+    pub tag: u64,
+    pub has_children: bool,
+    pub attrs: Vec<AttrSpec>,
+}
+
+pub type Abbrevs = HashMap<u64, Abbrev>;
+
+pub fn parse_abbrevs(mut i: &[u8]) -> nom::IResult<&[u8], Abbrevs> {
+    let mut abbrevs = HashMap::new();
+
+    loop {
+        let (rest, code) = parse_uleb128(i)?;
+        if code == 0 {
+            return Ok((rest, abbrevs));
+        }
+        let (rest, tag) = parse_uleb128(rest)?;
+        let (mut rest, has_children) = nom::le_u8(rest)?;
+        let mut attrs = Vec::new();
+        loop {
+            let (r, name) = parse_uleb128(rest)?;
+            let (r, form) = parse_uleb128(r)?;
+            if name == 0 && form == 0 {
+                rest = r;
+                break;
+            }
+            let (r, implicit_const) = if form == DW_FORM_IMPLICIT_CONST {
+                parse_sleb128(r)?
+            } else {
+                (r, 0)
+            };
+            attrs.push(AttrSpec {
+                name: name,
+                form: form,
+                implicit_const: implicit_const,
+            });
+            rest = r;
+        }
+        abbrevs.insert(
+            code,
+            Abbrev {
+                tag: tag,
+                has_children: has_children != 0,
+                attrs: attrs,
+            },
+        );
+        i = rest;
+    }
+}
+
+/// Header of a unit in `.debug_info`. Offsets are relative to the start of
+/// the section.
+#[derive(Debug, Clone)]
+pub struct UnitHeader {
+    pub offset: usize,
+    pub end: usize,
+    pub entries: usize,
+    pub format64: bool,
+    pub version: u16,
+    pub unit_type: u8,
+    pub address_size: u8,
+    pub abbrev_offset: u64,
+}
+
+impl UnitHeader {
+    pub fn offset_size(&self) -> u8 {
+        if self.format64 {
+            8
+        } else {
+            4
+        }
+    }
+}
+
+pub fn parse_unit_header(debug_info: &[u8], offset: usize) -> nom::IResult<&[u8], UnitHeader> {
+    let i = &debug_info[offset..];
+    let (rest, (length, format64)) = parse_initial_length(i)?;
+    let (after, unit) = take_unit(rest, length)?;
+    let (r, version) = nom::le_u16(unit)?;
+    if !(2..=5).contains(&version) {
+        return Err(nom::Err::Error(error_position!(i, nom::ErrorKind::Verify)));
+    }
+
+    let (r, unit_type, address_size, abbrev_offset) = if version >= 5 {
+        let (r, unit_type) = nom::le_u8(r)?;
+        let (r, address_size) = nom::le_u8(r)?;
+        let (r, abbrev_offset) = parse_offset(r, format64)?;
+        let r = match unit_type {
+            DW_UT_SKELETON | DW_UT_SPLIT_COMPILE => nom::le_u64(r)?.0,
+            DW_UT_TYPE | DW_UT_SPLIT_TYPE => {
+                let r = nom::le_u64(r)?.0;
+                parse_offset(r, format64)?.0
+            }
+            _ => r,
+        };
+        (r, unit_type, address_size, abbrev_offset)
+    } else {
+        let (r, abbrev_offset) = parse_offset(r, format64)?;
+        let (r, address_size) = nom::le_u8(r)?;
+        (r, DW_UT_COMPILE, address_size, abbrev_offset)
+    };
+
+    let end = debug_info.len() - after.len();
+    Ok((
+        after,
+        UnitHeader {
+            offset: offset,
+            end: end,
+            entries: end - r.len(),
+            format64: format64,
+            version: version,
+            unit_type: unit_type,
+            address_size: address_size,
+            abbrev_offset: abbrev_offset,
+        },
+    ))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AttrValue<'a> {
+    Address(u64),
+    AddressIndex(u64),
+    Udata(u64),
+    Sdata(i64),
+    Flag(bool),
+    String(&'a [u8]),
+    StrOffset(u64),
+    LineStrOffset(u64),
+    StrIndex(u64),
+    UnitRef(u64),
+    InfoRef(u64),
+    SecOffset(u64),
+    RangeListIndex(u64),
+    Block(&'a [u8]),
+    Other,
+}
+
+fn take_block<'a>(i: &'a [u8], len: u64) -> nom::IResult<&'a [u8], AttrValue<'a>> {
+    take_unit(i, len).map(|(rest, b)| (rest, AttrValue::Block(b)))
+}
+
+pub fn parse_attr_value<'a>(
+    i: &'a [u8],
+    form: u64,
+    implicit_const: i64,
+    unit: &UnitHeader,
+) -> nom::IResult<&'a [u8], AttrValue<'a>> {
+    let format64 = unit.format64;
+    match form {
+        DW_FORM_ADDR => {
+            parse_address(i, unit.address_size).map(|(r, x)| (r, AttrValue::Address(x)))
+        }
+        DW_FORM_BLOCK1 => {
+            let (r, len) = nom::le_u8(i)?;
+            take_block(r, u64::from(len))
+        }
+        DW_FORM_BLOCK2 => {
+            let (r, len) = nom::le_u16(i)?;
+            take_block(r, u64::from(len))
+        }
+        DW_FORM_BLOCK4 => {
+            let (r, len) = nom::le_u32(i)?;
+            take_block(r, u64::from(len))
+        }
+        DW_FORM_BLOCK | DW_FORM_EXPRLOC => {
+            let (r, len) = parse_uleb128(i)?;
+            take_block(r, len)
+        }
+        DW_FORM_DATA1 => parse_address(i, 1).map(|(r, x)| (r, AttrValue::Udata(x))),
+        DW_FORM_DATA2 => parse_address(i, 2).map(|(r, x)| (r, AttrValue::Udata(x))),
+        DW_FORM_DATA4 => parse_address(i, 4).map(|(r, x)| (r, AttrValue::Udata(x))),
+        DW_FORM_DATA8 => parse_address(i, 8).map(|(r, x)| (r, AttrValue::Udata(x))),
+        DW_FORM_DATA16 => take_block(i, 16),
+        DW_FORM_SDATA => parse_sleb128(i).map(|(r, x)| (r, AttrValue::Sdata(x))),
+        DW_FORM_UDATA => parse_uleb128(i).map(|(r, x)| (r, AttrValue::Udata(x))),
+        DW_FORM_IMPLICIT_CONST => Ok((i, AttrValue::Sdata(implicit_const))),
+        DW_FORM_FLAG => nom::le_u8(i).map(|(r, x)| (r, AttrValue::Flag(x != 0))),
+        DW_FORM_FLAG_PRESENT => Ok((i, AttrValue::Flag(true))),
+        DW_FORM_STRING => parse_cstr(i).map(|(r, s)| (r, AttrValue::String(s))),
+        DW_FORM_STRP => parse_offset(i, format64).map(|(r, x)| (r, AttrValue::StrOffset(x))),
+        DW_FORM_LINE_STRP => {
+            parse_offset(i, format64).map(|(r, x)| (r, AttrValue::LineStrOffset(x)))
+        }
+        DW_FORM_STRP_SUP | DW_FORM_GNU_STRP_ALT => {
+            parse_offset(i, format64).map(|(r, _)| (r, AttrValue::Other))
+        }
+        DW_FORM_STRX | DW_FORM_GNU_STR_INDEX => {
+            parse_uleb128(i).map(|(r, x)| (r, AttrValue::StrIndex(x)))
+        }
+        DW_FORM_STRX1 => parse_address(i, 1).map(|(r, x)| (r, AttrValue::StrIndex(x))),
+        DW_FORM_STRX2 => parse_address(i, 2).map(|(r, x)| (r, AttrValue::StrIndex(x))),
+        DW_FORM_STRX3 => parse_u24(i).map(|(r, x)| (r, AttrValue::StrIndex(x))),
+        DW_FORM_STRX4 => parse_address(i, 4).map(|(r, x)| (r, AttrValue::StrIndex(x))),
+        DW_FORM_ADDRX | DW_FORM_GNU_ADDR_INDEX => {
+            parse_uleb128(i).map(|(r, x)| (r, AttrValue::AddressIndex(x)))
+        }
+        DW_FORM_ADDRX1 => parse_address(i, 1).map(|(r, x)| (r, AttrValue::AddressIndex(x))),
+        DW_FORM_ADDRX2 => parse_address(i, 2).map(|(r, x)| (r, AttrValue::AddressIndex(x))),
+        DW_FORM_ADDRX3 => parse_u24(i).map(|(r, x)| (r, AttrValue::AddressIndex(x))),
+        DW_FORM_ADDRX4 => parse_address(i, 4).map(|(r, x)| (r, AttrValue::AddressIndex(x))),
+        DW_FORM_REF1 => parse_address(i, 1).map(|(r, x)| (r, AttrValue::UnitRef(x))),
+        DW_FORM_REF2 => parse_address(i, 2).map(|(r, x)| (r, AttrValue::UnitRef(x))),
+        DW_FORM_REF4 => parse_address(i, 4).map(|(r, x)| (r, AttrValue::UnitRef(x))),
+        DW_FORM_REF8 => parse_address(i, 8).map(|(r, x)| (r, AttrValue::UnitRef(x))),
+        DW_FORM_REF_UDATA => parse_uleb128(i).map(|(r, x)| (r, AttrValue::UnitRef(x))),
+        DW_FORM_REF_ADDR => {
+            let (r, x) = if unit.version <= 2 {
+                parse_address(i, unit.address_size)?
+            } else {
+                parse_offset(i, format64)?
+            };
+            Ok((r, AttrValue::InfoRef(x)))
+        }
+        DW_FORM_REF_SIG8 => nom::le_u64(i).map(|(r, _)| (r, AttrValue::Other)),
+        DW_FORM_REF_SUP4 => nom::le_u32(i).map(|(r, _)| (r, AttrValue::Other)),
+        DW_FORM_REF_SUP8 => nom::le_u64(i).map(|(r, _)| (r, AttrValue::Other)),
+        DW_FORM_GNU_REF_ALT => parse_offset(i, format64).map(|(r, _)| (r, AttrValue::Other)),
+        DW_FORM_SEC_OFFSET => parse_offset(i, format64).map(|(r, x)| (r, AttrValue::SecOffset(x))),
+        DW_FORM_RNGLISTX => parse_uleb128(i).map(|(r, x)| (r, AttrValue::RangeListIndex(x))),
+        DW_FORM_LOCLISTX => parse_uleb128(i).map(|(r, _)| (r, AttrValue::Other)),
+        DW_FORM_INDIRECT => {
+            let (r, form) = parse_uleb128(i)?;
+            parse_attr_value(r, form, implicit_const, unit)
+        }
+        _ => Err(nom::Err::Error(error_position!(i, nom::ErrorKind::Switch))),
+    }
+}
+
+fn parse_u24(i: &[u8]) -> nom::IResult<&[u8], u64> {
+    let (r, lo) = nom::le_u16(i)?;
+    let (r, hi) = nom::le_u8(r)?;
+    Ok((r, u64::from(lo) | (u64::from(hi) << 16)))
+}
+
+/// A debugging information entry with its attributes.
+#[derive(Debug)]
+pub struct Die<'a> {
+    pub offset: usize,
+    pub tag: u64,
+    pub has_children: bool,
+    pub attrs: Vec<(u64, AttrValue<'a>)>,
+}
+
+impl<'a> Die<'a> {
+    pub fn attr(&self, name: u64) -> Option<AttrValue<'a>> {
+        self.attrs.iter().find(|a| a.0 == name).map(|a| a.1)
+    }
+}
+
+/// Parses the entry at `offset` in `debug_info`. A `None` entry is the null
+/// entry terminating a list of siblings.
+pub fn parse_die<'a>(
+    debug_info: &'a [u8],
+    offset: usize,
+    unit: &UnitHeader,
+    abbrevs: &Abbrevs,
+) -> nom::IResult<&'a [u8], Option<Die<'a>>> {
+    let i = &debug_info[offset..unit.end];
+    let (mut rest, code) = parse_uleb128(i)?;
+    if code == 0 {
+        return Ok((rest, None));
+    }
+    let abbrev = match abbrevs.get(&code) {
+        Some(a) => a,
+        None => return Err(nom::Err::Error(error_position!(i, nom::ErrorKind::NoneOf))),
+    };
+
+    let mut attrs = Vec::with_capacity(abbrev.attrs.len());
+    for spec in abbrev.attrs.iter() {
+        let (r, value) = parse_attr_value(rest, spec.form, spec.implicit_const, unit)?;
+        attrs.push((spec.name, value));
+        rest = r;
+    }
+
+    Ok((
+        rest,
+        Some(Die {
+            offset: offset,
+            tag: abbrev.tag,
+            has_children: abbrev.has_children,
+            attrs: attrs,
+        }),
+    ))
+}
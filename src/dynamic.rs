@@ -0,0 +1,23 @@
+use header::*;
+
+/// An entry of the dynamic section, independent of the file class.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Dynamic {
+    pub tag: i64,
+    pub val: u64,
+}
+
+impl Dynamic {
+    pub fn new(tag: i64, val: u64) -> Self {
+        Dynamic { tag: tag, val: val }
+    }
+
+    /// Returns whether the value is an offset in the dynamic string table.
+    pub fn is_string(&self) -> bool {
+        match self.tag {
+            DT_NEEDED | DT_SONAME | DT_RPATH | DT_RUNPATH => true,
+            _ => false,
+        }
+    }
+}
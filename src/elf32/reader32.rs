@@ -0,0 +1,160 @@
+use std::io::{Read, Seek, SeekFrom};
+use std::mem::size_of;
+
+use super::{
+    parse_elf32_header_within, parse_elf32_section, parse_elf32_segment, parse_elf32_symbol, Elf32,
+    Elf32Header, Elf32Section, Elf32Segment, Elf32Symbol,
+};
+use error::Error;
+use header::{PT_NOTE, SHT_NOBITS, SHT_NOTE};
+use notes::{iter_notes, NT_GNU_BUILD_ID};
+
+/// A 32-bit file read from a stream, for inputs that cannot be loaded or
+/// mapped. Only the header, the program and section header tables and the
+/// section names are read up front; contents are read on request.
+pub struct Elf32Reader<R> {
+    reader: R,
+    size: u64,
+    pub header: Elf32Header,
+    pub segments: Vec<Elf32Segment>,
+    pub sections: Vec<Elf32Section>,
+    shstrtab: Vec<u8>,
+}
+
+impl<R: Read + Seek> Elf32Reader<R> {
+    pub fn new(mut reader: R) -> Result<Self, Error> {
+        let size = reader.seek(SeekFrom::End(0))?;
+        reader.seek(SeekFrom::Start(0))?;
+        let mut buf = vec![0u8; size_of::<Elf32Header>()];
+        reader.read_exact(&mut buf)?;
+        let header = parse_elf32_header_within(&buf, size)?.1;
+
+        let mut elf = Elf32Reader {
+            reader: reader,
+            size: size,
+            header: header,
+            segments: Vec::new(),
+            sections: Vec::new(),
+            shstrtab: Vec::new(),
+        };
+        let (phoff, phnum, phentsize) = (
+            elf.header.e_phoff,
+            elf.header.e_phnum as usize,
+            u64::from(elf.header.e_phentsize),
+        );
+        let (shoff, shnum, shentsize) = (
+            elf.header.e_shoff,
+            elf.header.e_shnum as usize,
+            u64::from(elf.header.e_shentsize),
+        );
+        let buf = elf.read_at(u64::from(phoff), phnum as u64 * phentsize)?;
+        elf.segments = count!(&buf[..], parse_elf32_segment, phnum)?.1;
+        let buf = elf.read_at(u64::from(shoff), shnum as u64 * shentsize)?;
+        elf.sections = count!(&buf[..], parse_elf32_section, shnum)?.1;
+        let shstrndx = elf.header.e_shstrndx as usize;
+        if shstrndx < elf.sections.len() {
+            elf.shstrtab = elf.section_data(shstrndx)?;
+        }
+        Ok(elf)
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Reads `len` bytes at `offset`, which must be within the file.
+    pub fn read_at(&mut self, offset: u64, len: u64) -> Result<Vec<u8>, Error> {
+        match offset.checked_add(len) {
+            Some(end) if end <= self.size => {}
+            _ => return Err(Error::ParseError(String::from("read out of bounds"))),
+        }
+        let mut buf = vec![0u8; len as usize];
+        self.reader.seek(SeekFrom::Start(offset))?;
+        self.reader.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// Returns the name of `section` from the section header string table.
+    pub fn section_name(&self, section: &Elf32Section) -> Option<&str> {
+        let s = self.shstrtab.get(section.sh_name as usize..)?;
+        let len = s.iter().position(|&c| c == 0)?;
+        ::std::str::from_utf8(&s[..len]).ok()
+    }
+
+    /// Returns the index of the first section named `name`.
+    pub fn section_index(&self, name: &str) -> Option<usize> {
+        self.sections
+            .iter()
+            .position(|s| self.section_name(s) == Some(name))
+    }
+
+    /// Reads the contents of the section at `idx`, which are empty for
+    /// `SHT_NOBITS` sections.
+    pub fn section_data(&mut self, idx: usize) -> Result<Vec<u8>, Error> {
+        let (sh_type, offset, size) = match self.sections.get(idx) {
+            Some(s) => (s.sh_type, s.sh_offset, s.sh_size),
+            None => return Err(Error::MissingSection(format!("section {}", idx))),
+        };
+        if sh_type == SHT_NOBITS {
+            return Ok(Vec::new());
+        }
+        self.read_at(u64::from(offset), u64::from(size))
+    }
+
+    /// Reads the contents of the segment at `idx` in the file.
+    pub fn segment_data(&mut self, idx: usize) -> Result<Vec<u8>, Error> {
+        let (offset, size) = match self.segments.get(idx) {
+            Some(s) => (s.p_offset, s.p_filesz),
+            None => return Err(Error::ParseError(format!("no segment {}", idx))),
+        };
+        self.read_at(u64::from(offset), u64::from(size))
+    }
+
+    /// Reads and parses the entries of the symbol table at `idx`.
+    pub fn symbol_table(&mut self, idx: usize) -> Result<Vec<Elf32Symbol>, Error> {
+        let entsize = match self.sections.get(idx) {
+            Some(s) => Elf32::symbol_entsize(s)?,
+            None => return Err(Error::MissingSection(format!("section {}", idx))),
+        };
+        let data = self.section_data(idx)?;
+        let mut symbols = Vec::with_capacity(data.len() / entsize);
+        for entry in data.chunks(entsize).filter(|e| e.len() == entsize) {
+            symbols.push(parse_elf32_symbol(entry)?.1);
+        }
+        Ok(symbols)
+    }
+
+    /// Reads the GNU build ID note, from `SHT_NOTE` sections, or from
+    /// `PT_NOTE` segments when section headers are missing.
+    pub fn build_id(&mut self) -> Result<Option<Vec<u8>>, Error> {
+        let notes: Vec<(usize, u32)> = if self.sections.is_empty() {
+            self.segments
+                .iter()
+                .enumerate()
+                .filter(|&(_, s)| s.p_type == PT_NOTE)
+                .map(|(idx, s)| (idx, s.p_align))
+                .collect()
+        } else {
+            self.sections
+                .iter()
+                .enumerate()
+                .filter(|&(_, s)| s.sh_type == SHT_NOTE)
+                .map(|(idx, s)| (idx, s.sh_addralign))
+                .collect()
+        };
+        for (idx, align) in notes {
+            let data = if self.sections.is_empty() {
+                self.segment_data(idx)?
+            } else {
+                self.section_data(idx)?
+            };
+            for note in iter_notes(&data, align as usize) {
+                let note = note?;
+                if note.n_type == NT_GNU_BUILD_ID && note.name == "GNU" {
+                    return Ok(Some(note.desc.to_vec()));
+                }
+            }
+        }
+        Ok(None)
+    }
+}
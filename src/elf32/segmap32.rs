@@ -0,0 +1,119 @@
+use super::{Elf32, Elf32Section, Elf32Segment};
+use header::{
+    PT_DYNAMIC, PT_GNU_EH_FRAME, PT_GNU_RELRO, PT_GNU_STACK, PT_LOAD, PT_NOTE, PT_PHDR, PT_TLS,
+    SHF_ALLOC, SHF_TLS, SHT_NOBITS,
+};
+
+/// Tells whether `segment` contains `section`, with the rules of the
+/// "Section to Segment mapping" of `readelf -l`.
+///
+/// `SHT_NOBITS` sections only need to fit in memory, `.tbss` only belongs to
+/// `PT_TLS` segments, and zero-sized sections at the very start or end of
+/// `PT_DYNAMIC` and `PT_NOTE` segments are left out.
+pub fn section_in_elf32_segment(section: &Elf32Section, segment: &Elf32Segment) -> bool {
+    let (sh_flags, sh_addr, sh_offset, sh_size) = (
+        u64::from(section.sh_flags),
+        u64::from(section.sh_addr),
+        u64::from(section.sh_offset),
+        u64::from(section.sh_size),
+    );
+    let (p_type, p_offset, p_vaddr, p_filesz, p_memsz) = (
+        segment.p_type,
+        u64::from(segment.p_offset),
+        u64::from(segment.p_vaddr),
+        u64::from(segment.p_filesz),
+        u64::from(segment.p_memsz),
+    );
+    let tls = sh_flags & SHF_TLS != 0;
+    let alloc = sh_flags & SHF_ALLOC != 0;
+    let nobits = section.sh_type == SHT_NOBITS;
+
+    // .tbss takes no room outside of the TLS template
+    if tls && nobits && p_type != PT_TLS {
+        return false;
+    }
+
+    // Only PT_LOAD, PT_GNU_RELRO and PT_TLS contain TLS sections, PT_TLS
+    // contains nothing else and PT_PHDR contains no section at all.
+    let tls_ok = if tls {
+        p_type == PT_TLS || p_type == PT_GNU_RELRO || p_type == PT_LOAD
+    } else {
+        p_type != PT_TLS && p_type != PT_PHDR
+    };
+    let alloc_only = matches!(
+        p_type,
+        PT_LOAD | PT_DYNAMIC | PT_GNU_EH_FRAME | PT_GNU_STACK | PT_GNU_RELRO
+    );
+    let in_file = nobits
+        || (sh_offset >= p_offset
+            && sh_offset - p_offset <= p_filesz.wrapping_sub(1)
+            && (sh_offset - p_offset)
+                .checked_add(sh_size)
+                .is_some_and(|end| end <= p_filesz));
+    let in_memory = !alloc
+        || (sh_addr >= p_vaddr
+            && sh_addr - p_vaddr <= p_memsz.wrapping_sub(1)
+            && (sh_addr - p_vaddr)
+                .checked_add(sh_size)
+                .is_some_and(|end| end <= p_memsz));
+    let not_empty_edge = (p_type != PT_DYNAMIC && p_type != PT_NOTE)
+        || sh_size != 0
+        || p_memsz == 0
+        || ((nobits || (sh_offset > p_offset && sh_offset - p_offset < p_filesz))
+            && (!alloc || (sh_addr > p_vaddr && sh_addr - p_vaddr < p_memsz)));
+
+    tls_ok && (alloc || !alloc_only) && in_file && in_memory && not_empty_edge
+}
+
+impl<'a> Elf32<'a> {
+    /// Returns the indexes of the sections contained in the segment at
+    /// `idx`, leaving out the null section.
+    pub fn segment_sections(&self, idx: usize) -> Vec<usize> {
+        match self.segments.get(idx) {
+            Some(segment) => (1..self.sections.len())
+                .filter(|&i| section_in_elf32_segment(&self.sections[i], segment))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns the sections contained in each segment, as listed by
+    /// `readelf -l`.
+    pub fn segment_section_map(&self) -> Vec<Vec<usize>> {
+        (0..self.segments.len())
+            .map(|idx| self.segment_sections(idx))
+            .collect()
+    }
+
+    /// Returns the indexes of the segments containing the section at `idx`.
+    pub fn section_segments(&self, idx: usize) -> Vec<usize> {
+        match self.sections.get(idx) {
+            Some(section) if idx > 0 => self
+                .segments
+                .iter()
+                .enumerate()
+                .filter(|&(_, segment)| section_in_elf32_segment(section, segment))
+                .map(|(i, _)| i)
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the indexes of the `SHF_ALLOC` sections not contained in any
+    /// `PT_LOAD` segment, leaving out `.tbss` which only exists in `PT_TLS`.
+    pub fn unloaded_sections(&self) -> Vec<usize> {
+        (1..self.sections.len())
+            .filter(|&i| {
+                let section = &self.sections[i];
+                let tbss =
+                    u64::from(section.sh_flags) & SHF_TLS != 0 && section.sh_type == SHT_NOBITS;
+                u64::from(section.sh_flags) & SHF_ALLOC != 0
+                    && !tbss
+                    && !self
+                        .segments
+                        .iter()
+                        .any(|s| s.p_type == PT_LOAD && section_in_elf32_segment(section, s))
+            })
+            .collect()
+    }
+}
@@ -1,15 +1,17 @@
 use nom;
 use std::mem::size_of;
 
-use header::{parse_elf_ident, ElfIdent};
+use header::{le_u8, parse_elf_ident, ElfIdent};
 
 type Elf32Half = u16;
 type Elf32Word = u32;
 type Elf32Xword = u64;
 type Elf32Addr = u32;
+type Elf32Sword = i32;
 type Elf32Off = u32;
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Elf32Header {
     pub e_ident: ElfIdent,
     pub e_type: Elf32Half,
@@ -28,6 +30,7 @@ pub struct Elf32Header {
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Elf32Section {
     pub sh_name: Elf32Word,
     pub sh_type: Elf32Word,
@@ -42,6 +45,7 @@ pub struct Elf32Section {
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Elf32Segment {
     pub p_type: Elf32Word,
     pub p_offset: Elf32Off,
@@ -53,6 +57,39 @@ pub struct Elf32Segment {
     pub p_align: Elf32Word,
 }
 
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Elf32Symbol {
+    pub st_name: Elf32Word,
+    pub st_value: Elf32Addr,
+    pub st_size: Elf32Word,
+    pub st_info: u8,
+    pub st_other: u8,
+    pub st_shndx: Elf32Half,
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Elf32Rel {
+    pub r_offset: Elf32Addr,
+    pub r_info: Elf32Word,
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Elf32Rela {
+    pub r_offset: Elf32Addr,
+    pub r_info: Elf32Word,
+    pub r_addend: Elf32Sword,
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Elf32Dyn {
+    pub d_tag: Elf32Sword,
+    pub d_val: Elf32Word,
+}
+
 #[inline(always)]
 pub fn parse_elf32_half(i: &[u8]) -> nom::IResult<&[u8], Elf32Half> {
     nom::le_u16(i)
@@ -92,19 +129,20 @@ named!(
             >> _e_ehsize:
                 verify!(parse_elf32_half, |x: Elf32Half| (x as usize)
                     == size_of::<Elf32Header>())
+            // Relocatable files without program headers may leave e_phentsize unset
             >> _e_phentsize:
-                verify!(parse_elf32_half, |x: Elf32Half| (x as usize) == size_of::<
-                    Elf32Segment,
-                >(
-                ))
+                verify!(parse_elf32_half, |x: Elf32Half| x == 0
+                    || (x as usize) == size_of::<Elf32Segment>())
             >> _e_phnum: parse_elf32_half
+            // Files stripped of their section headers leave all three fields zero
             >> _e_shentsize:
-                verify!(parse_elf32_half, |x: Elf32Half| (x as usize) == size_of::<
-                    Elf32Section,
-                >(
-                ))
-            >> _e_shnum: parse_elf32_half
-            >> _e_shstrndx: verify!(parse_elf32_half, |x: Elf32Half| x < _e_shnum)
+                verify!(parse_elf32_half, |x: Elf32Half| x == 0
+                    || (x as usize) == size_of::<Elf32Section>())
+            >> _e_shnum:
+                verify!(parse_elf32_half, |x: Elf32Half| x == 0 || _e_shentsize != 0)
+            >> _e_shstrndx:
+                verify!(parse_elf32_half, |x: Elf32Half| x < _e_shnum
+                    || (x == 0 && _e_shnum == 0))
             >> (Elf32Header {
                 e_ident: _e_ident,
                 e_type: _e_type,
@@ -125,29 +163,43 @@ named!(
 );
 
 pub fn parse_elf32_header(i: &[u8]) -> nom::IResult<&[u8], Elf32Header> {
+    parse_elf32_header_within(i, i.len() as u64)
+}
+
+/// Parses the header at the start of `i`, checking the program and section
+/// header tables against a file of `size` bytes, of which `i` may hold only
+/// the header.
+pub fn parse_elf32_header_within(i: &[u8], size: u64) -> nom::IResult<&[u8], Elf32Header> {
     match parse_elf32_header_aux(i) {
         Ok((rest, hdr)) => {
-            let ph_start = hdr.e_phoff as usize;
-            let ph_end = ph_start + (hdr.e_phnum as usize) * (hdr.e_phentsize as usize);
-            let sh_start = hdr.e_shoff as usize;
-            let sh_end = sh_start + (hdr.e_shnum as usize) * (hdr.e_shentsize as usize);
-
-            if ph_end > i.len() {
-                Err(nom::Err::Error(error_position!(i, nom::ErrorKind::Verify)))
-            } else if sh_end > i.len() {
-                Err(nom::Err::Error(error_position!(i, nom::ErrorKind::Verify)))
-            } else if ph_start < sh_start && ph_end > sh_start {
-                Err(nom::Err::Error(error_position!(i, nom::ErrorKind::Verify)))
-            } else if sh_start < ph_start && sh_end > ph_start {
-                Err(nom::Err::Error(error_position!(i, nom::ErrorKind::Verify)))
-            } else {
-                Ok((rest, hdr))
+            let ph_start = u64::from(hdr.e_phoff);
+            let ph_end = ph_start.checked_add(u64::from(hdr.e_phnum) * u64::from(hdr.e_phentsize));
+            let sh_start = u64::from(hdr.e_shoff);
+            let sh_end = sh_start.checked_add(u64::from(hdr.e_shnum) * u64::from(hdr.e_shentsize));
+
+            match (ph_end, sh_end) {
+                (Some(ph_end), Some(sh_end))
+                    if ph_end <= size
+                        && sh_end <= size
+                        && (ph_start == ph_end
+                            || sh_start == sh_end
+                            || ph_end <= sh_start
+                            || sh_end <= ph_start) =>
+                {
+                    Ok((rest, hdr))
+                }
+                _ => Err(nom::Err::Error(error_position!(i, nom::ErrorKind::Verify))),
             }
         }
         Err(e) => Err(e),
     }
 }
 
+#[inline(always)]
+pub fn parse_elf32_sword(i: &[u8]) -> nom::IResult<&[u8], Elf32Sword> {
+    nom::le_i32(i)
+}
+
 named!(pub parse_elf32_segment<Elf32Segment>,
     do_parse!(
             _p_type:    parse_elf32_word
@@ -197,3 +249,57 @@ named!(pub parse_elf32_section<Elf32Section>,
         })
     )
 );
+
+named!(pub parse_elf32_symbol<Elf32Symbol>,
+    do_parse!(
+            _st_name:   parse_elf32_word
+        >>  _st_value:  parse_elf32_addr
+        >>  _st_size:   parse_elf32_word
+        >>  _st_info:   le_u8
+        >>  _st_other:  le_u8
+        >>  _st_shndx:  parse_elf32_half
+        >>  ( Elf32Symbol {
+            st_name:    _st_name,
+            st_value:   _st_value,
+            st_size:    _st_size,
+            st_info:    _st_info,
+            st_other:   _st_other,
+            st_shndx:   _st_shndx,
+        })
+    )
+);
+
+named!(pub parse_elf32_dyn<Elf32Dyn>,
+    do_parse!(
+            _d_tag: parse_elf32_sword
+        >>  _d_val: parse_elf32_word
+        >>  ( Elf32Dyn {
+            d_tag:  _d_tag,
+            d_val:  _d_val,
+        })
+    )
+);
+
+named!(pub parse_elf32_rel<Elf32Rel>,
+    do_parse!(
+            _r_offset:  parse_elf32_addr
+        >>  _r_info:    parse_elf32_word
+        >>  ( Elf32Rel {
+            r_offset:   _r_offset,
+            r_info:     _r_info,
+        })
+    )
+);
+
+named!(pub parse_elf32_rela<Elf32Rela>,
+    do_parse!(
+            _r_offset:  parse_elf32_addr
+        >>  _r_info:    parse_elf32_word
+        >>  _r_addend:  parse_elf32_sword
+        >>  ( Elf32Rela {
+            r_offset:   _r_offset,
+            r_info:     _r_info,
+            r_addend:   _r_addend,
+        })
+    )
+);
@@ -0,0 +1,114 @@
+use nom;
+
+use super::{
+    parse_elf32, parse_elf32_header, parse_elf32_section, parse_elf32_segment, Elf32, Elf32Header,
+    Elf32Section, Elf32Segment,
+};
+use error::Error;
+use header::{PT_NOTE, SHT_NOBITS, SHT_NOTE};
+use notes::{iter_notes, Note, NT_GNU_BUILD_ID};
+use table::Table;
+
+/// A 32-bit file of which only the header is parsed up front. Program and
+/// section headers are read from `data` on access, without allocating.
+#[derive(Debug)]
+pub struct Elf32View<'a> {
+    pub data: &'a [u8],
+    pub header: Elf32Header,
+}
+
+pub fn parse_elf32_view<'a>(i: &'a [u8]) -> nom::IResult<&'a [u8], Elf32View<'a>> {
+    let (rest, header) = parse_elf32_header(i)?;
+    Ok((
+        rest,
+        Elf32View {
+            data: i,
+            header: header,
+        },
+    ))
+}
+
+impl<'a> Elf32View<'a> {
+    pub fn segments(&self) -> Table<'a, Elf32Segment> {
+        Table::new(
+            self.data,
+            self.header.e_phoff as usize,
+            self.header.e_phentsize as usize,
+            self.header.e_phnum as usize,
+            parse_elf32_segment,
+        )
+    }
+
+    pub fn sections(&self) -> Table<'a, Elf32Section> {
+        Table::new(
+            self.data,
+            self.header.e_shoff as usize,
+            self.header.e_shentsize as usize,
+            self.header.e_shnum as usize,
+            parse_elf32_section,
+        )
+    }
+
+    /// Returns the contents of `section` in the file, or an empty slice for
+    /// `SHT_NOBITS` sections.
+    pub fn section_data(&self, section: &Elf32Section) -> Option<&'a [u8]> {
+        if section.sh_type == SHT_NOBITS {
+            return Some(&[]);
+        }
+        let start = section.sh_offset as usize;
+        let end = start.checked_add(section.sh_size as usize)?;
+        self.data.get(start..end)
+    }
+
+    /// Returns the contents of `segment` in the file.
+    pub fn segment_data(&self, segment: &Elf32Segment) -> Option<&'a [u8]> {
+        let start = segment.p_offset as usize;
+        let end = start.checked_add(segment.p_filesz as usize)?;
+        self.data.get(start..end)
+    }
+
+    /// Returns the name of `section` from the section header string table.
+    pub fn section_name(&self, section: &Elf32Section) -> Option<&'a str> {
+        let strtab = self.sections().get(self.header.e_shstrndx as usize)?;
+        let s = self
+            .section_data(&strtab)?
+            .get(section.sh_name as usize..)?;
+        let len = s.iter().position(|&c| c == 0)?;
+        ::std::str::from_utf8(&s[..len]).ok()
+    }
+
+    /// Returns the first well-formed note of type `n_type` owned by `name`,
+    /// looking at `SHT_NOTE` sections, or at `PT_NOTE` segments when section
+    /// headers are missing.
+    pub fn find_note(&self, name: &str, n_type: u32) -> Option<Note<'a>> {
+        let matches = |data: &'a [u8], align: u32| {
+            iter_notes(data, align as usize)
+                .filter_map(|n| n.ok())
+                .find(|n| n.n_type == n_type && n.name == name)
+        };
+        let sections = self.sections();
+        if sections.is_empty() {
+            self.segments()
+                .iter()
+                .filter(|s| s.p_type == PT_NOTE)
+                .filter_map(|s| matches(self.segment_data(&s)?, s.p_align))
+                .next()
+        } else {
+            sections
+                .iter()
+                .filter(|s| s.sh_type == SHT_NOTE)
+                .filter_map(|s| matches(self.section_data(&s)?, s.sh_addralign))
+                .next()
+        }
+    }
+
+    /// Returns the contents of the GNU build ID note.
+    pub fn build_id(&self) -> Option<&'a [u8]> {
+        self.find_note("GNU", NT_GNU_BUILD_ID).map(|n| n.desc)
+    }
+
+    /// Parses the program and section header tables.
+    pub fn to_elf32(&self) -> Result<Elf32<'a>, Error> {
+        Ok(parse_elf32(self.data)?.1)
+    }
+}
@@ -0,0 +1,291 @@
+use libc::{c_char, size_t};
+use std::borrow::Cow;
+
+use super::*;
+use capi::*;
+use dynamic::Dynamic;
+use error::Error;
+use header::{SHT_DYNSYM, SHT_SYMTAB};
+use notes::Note;
+use symbols::Symbol;
+
+impl<'a> Elf64<'a> {
+    fn c_symbol_section(&self, table: RsElfSymbolTable) -> Result<&Elf64Section, Error> {
+        let (sh_type, name) = match table {
+            RsElfSymbolTable::Symtab => (SHT_SYMTAB, ".symtab"),
+            RsElfSymbolTable::Dynsym => (SHT_DYNSYM, ".dynsym"),
+        };
+        self.sections
+            .iter()
+            .find(|s| s.sh_type == sh_type)
+            .ok_or_else(|| Error::MissingSection(String::from(name)))
+    }
+}
+
+impl<'a> CApi<'a> for Elf64<'a> {
+    fn c_header(&self) -> RsElfHeader {
+        let h = &self.header;
+        RsElfHeader {
+            ei_class: h.e_ident.class as u8,
+            ei_data: h.e_ident.data as u8,
+            ei_osabi: h.e_ident.osabi as u8,
+            ei_abiversion: h.e_ident.abiversion,
+            e_type: h.e_type,
+            e_machine: h.e_machine,
+            e_version: h.e_version,
+            e_entry: h.e_entry,
+            e_phoff: h.e_phoff,
+            e_shoff: h.e_shoff,
+            e_flags: h.e_flags,
+            e_ehsize: h.e_ehsize,
+            e_phentsize: h.e_phentsize,
+            e_phnum: h.e_phnum,
+            e_shentsize: h.e_shentsize,
+            e_shnum: h.e_shnum,
+            e_shstrndx: h.e_shstrndx,
+        }
+    }
+
+    fn c_number_of_segments(&self) -> usize {
+        self.segments.len()
+    }
+
+    fn c_segment(&self, idx: usize) -> Option<RsElfSegment> {
+        self.segments.get(idx).map(|s| RsElfSegment {
+            p_type: s.p_type,
+            p_flags: s.p_flags,
+            p_offset: s.p_offset,
+            p_vaddr: s.p_vaddr,
+            p_paddr: s.p_paddr,
+            p_filesz: s.p_filesz,
+            p_memsz: s.p_memsz,
+            p_align: s.p_align,
+        })
+    }
+
+    fn c_number_of_sections(&self) -> usize {
+        self.sections.len()
+    }
+
+    fn c_section(&self, idx: usize) -> Option<RsElfSectionHeader> {
+        self.sections.get(idx).map(|s| RsElfSectionHeader {
+            name: self.section_name(s).map_or(::std::ptr::null(), file_str),
+            sh_name: s.sh_name,
+            sh_type: s.sh_type,
+            sh_flags: s.sh_flags,
+            sh_addr: s.sh_addr,
+            sh_offset: s.sh_offset,
+            sh_size: s.sh_size,
+            sh_link: s.sh_link,
+            sh_info: s.sh_info,
+            sh_addralign: s.sh_addralign,
+            sh_entsize: s.sh_entsize,
+        })
+    }
+
+    fn c_find_section(&self, name: &str) -> Option<usize> {
+        self.section_index(name)
+    }
+
+    fn c_number_of_symbols(&self, table: RsElfSymbolTable) -> Result<usize, Error> {
+        self.symbol_count(self.c_symbol_section(table)?)
+    }
+
+    fn c_symbol(&self, table: RsElfSymbolTable, idx: usize) -> Result<Symbol<'a>, Error> {
+        self.symbol_at(self.c_symbol_section(table)?, idx)
+    }
+
+    fn c_dynamic(&self) -> Result<Cow<[Dynamic]>, Error> {
+        self.dynamic().map(Cow::Owned)
+    }
+
+    fn c_dynamic_string(&self, offset: u64) -> Option<&'a str> {
+        self.dynamic_string(offset)
+    }
+
+    fn c_notes(&self) -> Result<Cow<[Note<'a>]>, Error> {
+        self.notes().map(Cow::Owned)
+    }
+}
+
+/// Fills `out` with the file header.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle of `rs_elf64_parse` not released with
+/// `rs_elf64_free_exe` yet, and `out` NULL or valid for writing an
+/// `RsElfHeader`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf64_get_header<'a>(
+    e: *const Elf64<'a>,
+    out: *mut RsElfHeader,
+) -> RsElfError {
+    get_header(e, out)
+}
+
+/// Returns the number of program headers, 0 for a NULL handle.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle of `rs_elf64_parse` not released with
+/// `rs_elf64_free_exe` yet.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf64_get_number_of_segments<'a>(e: *const Elf64<'a>) -> size_t {
+    get_number_of_segments(e)
+}
+
+/// Fills `out` with the program header `idx`.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle of `rs_elf64_parse` not released with
+/// `rs_elf64_free_exe` yet, and `out` NULL or valid for writing an
+/// `RsElfSegment`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf64_get_segment<'a>(
+    e: *const Elf64<'a>,
+    idx: size_t,
+    out: *mut RsElfSegment,
+) -> RsElfError {
+    get_segment(e, idx, out)
+}
+
+/// Returns the number of section headers, 0 for a NULL handle.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle of `rs_elf64_parse` not released with
+/// `rs_elf64_free_exe` yet.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf64_get_number_of_section_headers<'a>(e: *const Elf64<'a>) -> size_t {
+    get_number_of_section_headers(e)
+}
+
+/// Fills `out` with the section header `idx` and its name.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle of `rs_elf64_parse` not released with
+/// `rs_elf64_free_exe` yet, and `out` NULL or valid for writing an
+/// `RsElfSectionHeader`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf64_get_section_header<'a>(
+    e: *const Elf64<'a>,
+    idx: size_t,
+    out: *mut RsElfSectionHeader,
+) -> RsElfError {
+    get_section_header(e, idx, out)
+}
+
+/// Stores in `idx` the index of the first section named `name`.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle of `rs_elf64_parse` not released with
+/// `rs_elf64_free_exe` yet, `name` NULL or a NUL-terminated string and
+/// `idx` NULL or valid for writing a `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf64_find_section<'a>(
+    e: *const Elf64<'a>,
+    name: *const c_char,
+    idx: *mut size_t,
+) -> RsElfError {
+    find_section(e, name, idx)
+}
+
+/// Stores in `count` the number of entries of a symbol table.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle of `rs_elf64_parse` not released with
+/// `rs_elf64_free_exe` yet, and `count` NULL or valid for writing a
+/// `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf64_get_number_of_symbols<'a>(
+    e: *const Elf64<'a>,
+    table: RsElfSymbolTable,
+    count: *mut size_t,
+) -> RsElfError {
+    get_number_of_symbols(e, table, count)
+}
+
+/// Fills `out` with the entry `idx` of a symbol table.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle of `rs_elf64_parse` not released with
+/// `rs_elf64_free_exe` yet, and `out` NULL or valid for writing an
+/// `RsElfSymbol`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf64_get_symbol<'a>(
+    e: *const Elf64<'a>,
+    table: RsElfSymbolTable,
+    idx: size_t,
+    out: *mut RsElfSymbol,
+) -> RsElfError {
+    get_symbol(e, table, idx, out)
+}
+
+/// Stores in `count` the number of dynamic entries before `DT_NULL`.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle of `rs_elf64_parse` not released with
+/// `rs_elf64_free_exe` yet, and `count` NULL or valid for writing a
+/// `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf64_get_number_of_dynamic_entries<'a>(
+    e: *const Elf64<'a>,
+    count: *mut size_t,
+) -> RsElfError {
+    get_number_of_dynamic_entries(e, count)
+}
+
+/// Fills `out` with the dynamic entry `idx`. Unlike
+/// `rs_elf_get_dynamic_entry`, the entries are parsed again on each call.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle of `rs_elf64_parse` not released with
+/// `rs_elf64_free_exe` yet, and `out` NULL or valid for writing an
+/// `RsElfDynamic`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf64_get_dynamic_entry<'a>(
+    e: *const Elf64<'a>,
+    idx: size_t,
+    out: *mut RsElfDynamic,
+) -> RsElfError {
+    get_dynamic_entry(e, idx, out)
+}
+
+/// Stores in `count` the number of notes.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle of `rs_elf64_parse` not released with
+/// `rs_elf64_free_exe` yet, and `count` NULL or valid for writing a
+/// `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf64_get_number_of_notes<'a>(
+    e: *const Elf64<'a>,
+    count: *mut size_t,
+) -> RsElfError {
+    get_number_of_notes(e, count)
+}
+
+/// Fills `out` with the note `idx`. Unlike `rs_elf_get_note`, the notes are
+/// parsed again on each call.
+///
+/// # Safety
+///
+/// `e` must be NULL or a handle of `rs_elf64_parse` not released with
+/// `rs_elf64_free_exe` yet, and `out` NULL or valid for writing an
+/// `RsElfNote`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_elf64_get_note<'a>(
+    e: *const Elf64<'a>,
+    idx: size_t,
+    out: *mut RsElfNote,
+) -> RsElfError {
+    get_note(e, idx, out)
+}
@@ -4,7 +4,40 @@ use nom;
 pub mod types64;
 pub use types64::*;
 
-use libc::{c_void, size_t, uint8_t};
+pub mod capi64;
+pub use capi64::*;
+
+pub mod view64;
+pub use view64::*;
+
+pub mod reader64;
+pub use reader64::*;
+
+pub mod segmap64;
+pub use segmap64::*;
+
+use libc::size_t;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use capi;
+use dwarf::{
+    Addr2Line, DwarfSections, EhFrameHdr, FrameKind, FrameSection, FrameTable, PointerBases,
+};
+use dynamic::Dynamic;
+use error::Error;
+use exe_ext::ExeExt;
+use header::{
+    arch_name, DT_JMPREL, DT_NEEDED, DT_NULL, DT_PLTREL, DT_PLTRELSZ, DT_REL, DT_RELA, DT_RELAENT,
+    DT_RELASZ, DT_RELENT, DT_RELR, DT_RELRSZ, DT_RELSZ, DT_RPATH, DT_RUNPATH, DT_SONAME, DT_STRTAB,
+    DT_SYMENT, DT_SYMTAB, PF_R, PF_W, PF_X, PT_DYNAMIC, PT_GNU_EH_FRAME, PT_LOAD, PT_NOTE,
+    SHN_LORESERVE, SHT_DYNAMIC, SHT_DYNSYM, SHT_GNU_VERDEF, SHT_GNU_VERNEED, SHT_GNU_VERSYM,
+    SHT_NOBITS, SHT_NOTE, SHT_NULL, SHT_REL, SHT_RELA, SHT_SYMTAB,
+};
+use notes::{os_name, parse_notes, Note};
+use relocations::{parse_relr, Relocation};
+use symbols::Symbol;
+use versions::{parse_version_definitions, parse_version_needs, parse_version_symbols, Versions};
 
 #[derive(Debug)]
 pub struct Elf64<'a> {
@@ -12,6 +45,8 @@ pub struct Elf64<'a> {
     pub header: Elf64Header,
     pub segments: Vec<Elf64Segment>,
     pub sections: Vec<Elf64Section>,
+    /// Index of the first section of each name, built on the first lookup.
+    section_names: OnceLock<HashMap<&'a str, usize>>,
 }
 
 pub fn parse_elf64<'a>(i: &'a [u8]) -> nom::IResult<&'a [u8], Elf64<'a>> {
@@ -33,10 +68,546 @@ pub fn parse_elf64<'a>(i: &'a [u8]) -> nom::IResult<&'a [u8], Elf64<'a>> {
             header: header,
             segments: segments.1,
             sections: sections.1,
+            section_names: OnceLock::new(),
         },
     ))
 }
 
+impl<'a> Elf64<'a> {
+    /// Returns the name of `section` from the section header string table.
+    pub fn section_name(&self, section: &Elf64Section) -> Option<&'a str> {
+        let strtab = self.sections.get(self.header.e_shstrndx as usize)?;
+        self.string_at(strtab, section.sh_name as usize)
+    }
+
+    fn index_section_names(&self) -> HashMap<&'a str, usize> {
+        let mut map = HashMap::with_capacity(self.sections.len());
+        for (idx, section) in self.sections.iter().enumerate() {
+            if let Some(name) = self.section_name(section) {
+                map.entry(name).or_insert(idx);
+            }
+        }
+        map
+    }
+
+    /// Returns the index of the first section named `name`. Names are
+    /// indexed on the first lookup, so sections added or renamed afterwards
+    /// are not found.
+    pub fn section_index(&self, name: &str) -> Option<usize> {
+        let names = self
+            .section_names
+            .get_or_init(|| self.index_section_names());
+        let idx = *names.get(name)?;
+        match self.sections.get(idx) {
+            Some(section) if self.section_name(section) == Some(name) => Some(idx),
+            _ => None,
+        }
+    }
+
+    /// Returns the first section named `name`.
+    pub fn section_by_name(&self, name: &str) -> Option<&Elf64Section> {
+        self.section_index(name)
+            .and_then(|idx| self.sections.get(idx))
+    }
+
+    /// Returns the sections of type `sh_type`, in file order.
+    pub fn sections_by_type(&self, sh_type: u32) -> Vec<&Elf64Section> {
+        self.sections
+            .iter()
+            .filter(|s| s.sh_type == sh_type)
+            .collect()
+    }
+
+    /// Iterates over the sections with their index, name and contents. A
+    /// missing name is empty, and so are the contents of `SHT_NOBITS` or
+    /// out of bounds sections.
+    pub fn iter_sections<'s>(
+        &'s self,
+    ) -> impl Iterator<Item = (usize, &'a str, &'s Elf64Section, &'a [u8])> + 's {
+        self.sections.iter().enumerate().map(move |(idx, section)| {
+            (
+                idx,
+                self.section_name(section).unwrap_or(""),
+                section,
+                self.section_data(section).unwrap_or(&[]),
+            )
+        })
+    }
+
+    /// Returns the contents of `section` in the file, or an empty slice for
+    /// `SHT_NOBITS` sections.
+    pub fn section_data(&self, section: &Elf64Section) -> Option<&'a [u8]> {
+        if section.sh_type == SHT_NOBITS {
+            return Some(&[]);
+        }
+        let start = section.sh_offset as usize;
+        let end = start.checked_add(section.sh_size as usize)?;
+        self.data.get(start..end)
+    }
+
+    /// Returns the NUL-terminated string at `offset` in the string table
+    /// `strtab`.
+    pub fn string_at(&self, strtab: &Elf64Section, offset: usize) -> Option<&'a str> {
+        let data = self.section_data(strtab)?;
+        let s = data.get(offset..)?;
+        let len = s.iter().position(|&c| c == 0)?;
+        ::std::str::from_utf8(&s[..len]).ok()
+    }
+
+    pub(crate) fn symbol_entsize(section: &Elf64Section) -> Result<usize, Error> {
+        let entsize = match section.sh_entsize {
+            0 => 24,
+            n => n as usize,
+        };
+        if entsize < 24 {
+            return Err(Error::ParseError(format!(
+                "bad symbol entry size {}",
+                entsize
+            )));
+        }
+        Ok(entsize)
+    }
+
+    fn symbol_table_data(&self, section: &Elf64Section) -> Result<&'a [u8], Error> {
+        self.section_data(section)
+            .ok_or_else(|| Error::ParseError(String::from("symbol table is out of bounds")))
+    }
+
+    /// Parses the entries of the symbol table `section`.
+    pub fn symbol_table(&self, section: &Elf64Section) -> Result<Vec<Elf64Symbol>, Error> {
+        let data = self.symbol_table_data(section)?;
+        let entsize = Self::symbol_entsize(section)?;
+        let mut symbols = Vec::with_capacity(data.len() / entsize);
+        for entry in data.chunks(entsize).filter(|e| e.len() == entsize) {
+            symbols.push(parse_elf64_symbol(entry)?.1);
+        }
+        Ok(symbols)
+    }
+
+    fn resolve_symbol(&self, strtab: Option<&Elf64Section>, s: &Elf64Symbol) -> Symbol<'a> {
+        let name = strtab
+            .and_then(|t| self.string_at(t, s.st_name as usize))
+            .unwrap_or("");
+        Symbol::new(
+            name, s.st_value, s.st_size, s.st_info, s.st_other, s.st_shndx,
+        )
+    }
+
+    /// Parses the symbol table `section` and resolves the symbol names.
+    pub fn symbols(&self, section: &Elf64Section) -> Result<Vec<Symbol<'a>>, Error> {
+        let strtab = self.sections.get(section.sh_link as usize);
+        Ok(self
+            .symbol_table(section)?
+            .iter()
+            .map(|s| self.resolve_symbol(strtab, s))
+            .collect())
+    }
+
+    /// Returns the number of entries of the symbol table `section`.
+    pub fn symbol_count(&self, section: &Elf64Section) -> Result<usize, Error> {
+        Ok(self.symbol_table_data(section)?.len() / Self::symbol_entsize(section)?)
+    }
+
+    /// Parses the entry `idx` of the symbol table `section`, without parsing
+    /// the whole table.
+    pub fn symbol_at(&self, section: &Elf64Section, idx: usize) -> Result<Symbol<'a>, Error> {
+        let data = self.symbol_table_data(section)?;
+        let entsize = Self::symbol_entsize(section)?;
+        let entry = idx
+            .checked_mul(entsize)
+            .and_then(|start| data.get(start..start + entsize))
+            .ok_or_else(|| Error::ParseError(format!("no symbol at index {}", idx)))?;
+        let symbol = parse_elf64_symbol(entry)?.1;
+        Ok(self.resolve_symbol(self.sections.get(section.sh_link as usize), &symbol))
+    }
+
+    /// Returns the symbols of `.symtab`, or nothing if the file is stripped.
+    pub fn symtab(&self) -> Result<Vec<Symbol<'a>>, Error> {
+        match self.sections.iter().find(|s| s.sh_type == SHT_SYMTAB) {
+            Some(s) => self.symbols(s),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Returns the symbols of `.dynsym`, or nothing for static files.
+    pub fn dynsym(&self) -> Result<Vec<Symbol<'a>>, Error> {
+        match self.sections.iter().find(|s| s.sh_type == SHT_DYNSYM) {
+            Some(s) => self.symbols(s),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Returns the letter `nm` displays for `symbol`, a symbol of this file.
+    pub fn symbol_type_letter(&self, symbol: &Symbol) -> char {
+        match self.sections.get(symbol.shndx as usize) {
+            Some(s) if symbol.shndx < SHN_LORESERVE => {
+                symbol.type_letter(s.sh_type, s.sh_flags, self.section_name(s).unwrap_or(""))
+            }
+            _ => symbol.type_letter(SHT_NULL, 0, ""),
+        }
+    }
+
+    /// Returns the file offset of the virtual address `addr`, if it is
+    /// backed by the file in a `PT_LOAD` segment.
+    pub fn address_to_offset(&self, addr: u64) -> Option<usize> {
+        self.segments
+            .iter()
+            .filter(|s| s.p_type == PT_LOAD)
+            .find(|s| addr >= s.p_vaddr && addr - s.p_vaddr < s.p_filesz)
+            .map(|s| (s.p_offset + addr - s.p_vaddr) as usize)
+    }
+
+    fn dynamic_data(&self) -> Option<&'a [u8]> {
+        match self.sections.iter().find(|s| s.sh_type == SHT_DYNAMIC) {
+            Some(s) => self.section_data(s),
+            None => self
+                .segments
+                .iter()
+                .find(|s| s.p_type == PT_DYNAMIC)
+                .and_then(|s| self.segment_data(s)),
+        }
+    }
+
+    /// Parses the entries of the `SHT_REL` or `SHT_RELA` section `section`.
+    pub fn relocations(&self, section: &Elf64Section) -> Result<Vec<Relocation>, Error> {
+        let rela = match section.sh_type {
+            SHT_RELA => true,
+            SHT_REL => false,
+            _ => return Err(Error::ParseError(String::from("not a relocation section"))),
+        };
+        let data = self.section_data(section).ok_or_else(|| {
+            Error::ParseError(String::from("relocation section is out of bounds"))
+        })?;
+        Self::parse_relocations(data, rela, section.sh_entsize as usize)
+    }
+
+    /// Parses the `SHT_RELA` or `SHT_REL` entries of `data`, `entsize`
+    /// bytes each, or the size of the structure when 0.
+    fn parse_relocations(
+        data: &[u8],
+        rela: bool,
+        entsize: usize,
+    ) -> Result<Vec<Relocation>, Error> {
+        let min_entsize = if rela { 24 } else { 16 };
+        let entsize = match entsize {
+            0 => min_entsize,
+            n => n,
+        };
+        if entsize < min_entsize {
+            return Err(Error::ParseError(format!(
+                "bad relocation entry size {}",
+                entsize
+            )));
+        }
+        let mut relocations = Vec::with_capacity(data.len() / entsize);
+        for entry in data.chunks(entsize).filter(|e| e.len() == entsize) {
+            relocations.push(if rela {
+                let r = parse_elf64_rela(entry)?.1;
+                Relocation::new(
+                    r.r_offset,
+                    r.r_info,
+                    (r.r_info >> 32) as u32,
+                    r.r_info as u32,
+                    Some(r.r_addend),
+                )
+            } else {
+                let r = parse_elf64_rel(entry)?.1;
+                Relocation::new(
+                    r.r_offset,
+                    r.r_info,
+                    (r.r_info >> 32) as u32,
+                    r.r_info as u32,
+                    None,
+                )
+            });
+        }
+        Ok(relocations)
+    }
+
+    /// Parses the symbol versioning sections, which are empty for files
+    /// without versioning.
+    pub fn versions(&self) -> Result<Versions<'a>, Error> {
+        let mut versions = Versions::default();
+        for section in &self.sections {
+            let data = || {
+                self.section_data(section).ok_or_else(|| {
+                    Error::ParseError(String::from("version section is out of bounds"))
+                })
+            };
+            let strtab = self.sections.get(section.sh_link as usize);
+            let strings = |offset: u32| strtab.and_then(|t| self.string_at(t, offset as usize));
+            match section.sh_type {
+                SHT_GNU_VERSYM => versions.symbols = parse_version_symbols(data()?)?,
+                SHT_GNU_VERDEF => {
+                    versions.definitions =
+                        parse_version_definitions(data()?, section.sh_info as usize, strings)?
+                }
+                SHT_GNU_VERNEED => {
+                    versions.needs =
+                        parse_version_needs(data()?, section.sh_info as usize, strings)?
+                }
+                _ => {}
+            }
+        }
+        Ok(versions)
+    }
+
+    /// Parses the entries of the dynamic section, up to `DT_NULL`. Static
+    /// files have none.
+    pub fn dynamic(&self) -> Result<Vec<Dynamic>, Error> {
+        let data = match self.dynamic_data() {
+            Some(data) => data,
+            None => return Ok(Vec::new()),
+        };
+        let mut entries = Vec::new();
+        for entry in data.chunks(16).filter(|e| e.len() == 16) {
+            let d = parse_elf64_dyn(entry)?.1;
+            if d.d_tag == DT_NULL {
+                break;
+            }
+            entries.push(Dynamic::new(d.d_tag, d.d_val));
+        }
+        Ok(entries)
+    }
+
+    /// Returns the string at `offset` in the dynamic string table, found
+    /// through the dynamic section link or `DT_STRTAB`.
+    pub fn dynamic_string(&self, offset: u64) -> Option<&'a str> {
+        let linked = self
+            .sections
+            .iter()
+            .find(|s| s.sh_type == SHT_DYNAMIC)
+            .and_then(|s| self.sections.get(s.sh_link as usize));
+        if let Some(strtab) = linked {
+            return self.string_at(strtab, offset as usize);
+        }
+        let strtab = self
+            .dynamic()
+            .ok()?
+            .into_iter()
+            .find(|d| d.tag == DT_STRTAB)?;
+        let start = self
+            .address_to_offset(strtab.val)?
+            .checked_add(offset as usize)?;
+        let s = self.data.get(start..)?;
+        let len = s.iter().position(|&c| c == 0)?;
+        ::std::str::from_utf8(&s[..len]).ok()
+    }
+
+    /// Returns the value of the first dynamic entry `tag`.
+    fn dynamic_value(dynamic: &[Dynamic], tag: i64) -> Option<u64> {
+        dynamic.iter().find(|d| d.tag == tag).map(|d| d.val)
+    }
+
+    /// Returns the `size` bytes at the address `addr` of a table of the
+    /// dynamic section, named `tag` in errors.
+    fn dynamic_table(&self, addr: u64, size: u64, tag: &str) -> Result<&'a [u8], Error> {
+        self.address_to_offset(addr)
+            .and_then(|start| self.data.get(start..start.checked_add(size as usize)?))
+            .ok_or_else(|| Error::ParseError(format!("{} is out of bounds", tag)))
+    }
+
+    /// Parses the relocation tables of the dynamic section, `DT_RELA`,
+    /// `DT_REL` and `DT_JMPREL` in this order, as ld.so applies them. A
+    /// `DT_JMPREL` table inside the `DT_RELA` or `DT_REL` one is only read
+    /// once.
+    pub fn dynamic_relocations(&self) -> Result<Vec<Relocation>, Error> {
+        let dynamic = self.dynamic()?;
+        let value = |tag| Self::dynamic_value(&dynamic, tag);
+        let mut relocations = Vec::new();
+        let mut ranges = Vec::new();
+        let tables = [
+            (DT_RELA, DT_RELASZ, DT_RELAENT, "DT_RELA"),
+            (DT_REL, DT_RELSZ, DT_RELENT, "DT_REL"),
+        ];
+        for &(tag, size_tag, entsize_tag, name) in &tables {
+            if let (Some(addr), Some(size)) = (value(tag), value(size_tag)) {
+                let data = self.dynamic_table(addr, size, name)?;
+                let entsize = value(entsize_tag).unwrap_or(0) as usize;
+                relocations.extend(Self::parse_relocations(data, tag == DT_RELA, entsize)?);
+                ranges.push((addr, addr.saturating_add(size)));
+            }
+        }
+        if let (Some(addr), Some(size)) = (value(DT_JMPREL), value(DT_PLTRELSZ)) {
+            let end = addr.saturating_add(size);
+            if !ranges
+                .iter()
+                .any(|&(start, stop)| start <= addr && end <= stop)
+            {
+                let data = self.dynamic_table(addr, size, "DT_JMPREL")?;
+                let rela = value(DT_PLTREL) == Some(DT_RELA as u64);
+                relocations.extend(Self::parse_relocations(data, rela, 0)?);
+            }
+        }
+        Ok(relocations)
+    }
+
+    /// Returns the offsets of the relative relocations of `DT_RELR`.
+    pub fn dynamic_relr(&self) -> Result<Vec<u64>, Error> {
+        let dynamic = self.dynamic()?;
+        let value = |tag| Self::dynamic_value(&dynamic, tag);
+        match (value(DT_RELR), value(DT_RELRSZ)) {
+            (Some(addr), Some(size)) => parse_relr(self.dynamic_table(addr, size, "DT_RELR")?, 8),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Parses the entry `idx` of the dynamic symbol table found through
+    /// `DT_SYMTAB`, which relocations of the dynamic section refer to.
+    pub fn dynamic_symbol(&self, idx: usize) -> Result<Symbol<'a>, Error> {
+        let dynamic = self.dynamic()?;
+        let value = |tag| Self::dynamic_value(&dynamic, tag);
+        let addr =
+            value(DT_SYMTAB).ok_or_else(|| Error::MissingSection(String::from("DT_SYMTAB")))?;
+        let entsize = match value(DT_SYMENT).unwrap_or(0) {
+            0 => 24,
+            n if n < 24 => return Err(Error::ParseError(format!("bad symbol entry size {}", n))),
+            n => n,
+        };
+        let entry = (idx as u64)
+            .checked_mul(entsize)
+            .and_then(|offset| addr.checked_add(offset))
+            .ok_or_else(|| Error::ParseError(format!("no symbol at index {}", idx)))?;
+        let symbol = parse_elf64_symbol(self.dynamic_table(entry, entsize, "DT_SYMTAB")?)?.1;
+        let name = self.dynamic_string(u64::from(symbol.st_name)).unwrap_or("");
+        Ok(Symbol::new(
+            name,
+            symbol.st_value,
+            symbol.st_size,
+            symbol.st_info,
+            symbol.st_other,
+            symbol.st_shndx,
+        ))
+    }
+
+    /// Returns the `DT_NEEDED` library names.
+    pub fn needed(&self) -> Result<Vec<&'a str>, Error> {
+        Ok(self
+            .dynamic()?
+            .iter()
+            .filter(|d| d.tag == DT_NEEDED)
+            .filter_map(|d| self.dynamic_string(d.val))
+            .collect())
+    }
+
+    /// Returns the `DT_SONAME` of a shared library.
+    pub fn soname(&self) -> Result<Option<&'a str>, Error> {
+        Ok(self
+            .dynamic()?
+            .iter()
+            .find(|d| d.tag == DT_SONAME)
+            .and_then(|d| self.dynamic_string(d.val)))
+    }
+
+    /// Returns the `DT_RPATH` search path, which ld.so ignores when
+    /// `DT_RUNPATH` is present.
+    pub fn rpath(&self) -> Result<Option<&'a str>, Error> {
+        Ok(self
+            .dynamic()?
+            .iter()
+            .find(|d| d.tag == DT_RPATH)
+            .and_then(|d| self.dynamic_string(d.val)))
+    }
+
+    /// Returns the `DT_RUNPATH` search path.
+    pub fn runpath(&self) -> Result<Option<&'a str>, Error> {
+        Ok(self
+            .dynamic()?
+            .iter()
+            .find(|d| d.tag == DT_RUNPATH)
+            .and_then(|d| self.dynamic_string(d.val)))
+    }
+
+    /// Returns the symbols used for dynamic linking, or those of `.symtab`
+    /// for static and relocatable files.
+    fn linkage_symbols(&self) -> Result<Vec<Symbol<'a>>, Error> {
+        if self.sections.iter().any(|s| s.sh_type == SHT_DYNSYM) {
+            self.dynsym()
+        } else {
+            self.symtab()
+        }
+    }
+
+    /// Returns the contents of `segment` in the file.
+    pub fn segment_data(&self, segment: &Elf64Segment) -> Option<&'a [u8]> {
+        let start = segment.p_offset as usize;
+        let end = start.checked_add(segment.p_filesz as usize)?;
+        self.data.get(start..end)
+    }
+
+    /// Parses the notes of all `SHT_NOTE` sections, or of the `PT_NOTE`
+    /// segments when section headers are missing.
+    pub fn notes(&self) -> Result<Vec<Note<'a>>, Error> {
+        let mut notes = Vec::new();
+        if self.sections.is_empty() {
+            for segment in self.segments.iter().filter(|s| s.p_type == PT_NOTE) {
+                let data = self
+                    .segment_data(segment)
+                    .ok_or_else(|| Error::ParseError(String::from("PT_NOTE is out of bounds")))?;
+                notes.extend(parse_notes(data, segment.p_align as usize)?);
+            }
+        } else {
+            for section in self.sections.iter().filter(|s| s.sh_type == SHT_NOTE) {
+                let data = self
+                    .section_data(section)
+                    .ok_or_else(|| Error::ParseError(String::from("SHT_NOTE is out of bounds")))?;
+                notes.extend(parse_notes(data, section.sh_addralign as usize)?);
+            }
+        }
+        Ok(notes)
+    }
+
+    fn frame_table(&self, name: &str, kind: FrameKind) -> Result<FrameTable<'a>, Error> {
+        let section = self
+            .section_by_name(name)
+            .ok_or_else(|| Error::MissingSection(String::from(name)))?;
+        let data = self
+            .section_data(section)
+            .ok_or_else(|| Error::ParseError(format!("{} is out of bounds", name)))?;
+        let bases = PointerBases {
+            section: section.sh_addr,
+            text: self.section_by_name(".text").map_or(0, |s| s.sh_addr),
+            data: self.section_by_name(".got").map_or(0, |s| s.sh_addr),
+        };
+        FrameTable::new(FrameSection::new(data, kind, 8, bases))
+    }
+
+    /// Parses the call frame information of `.eh_frame`.
+    pub fn eh_frame(&self) -> Result<FrameTable<'a>, Error> {
+        self.frame_table(".eh_frame", FrameKind::EhFrame)
+    }
+
+    /// Parses the call frame information of `.debug_frame`.
+    pub fn debug_frame(&self) -> Result<FrameTable<'a>, Error> {
+        self.frame_table(".debug_frame", FrameKind::DebugFrame)
+    }
+
+    /// Parses `.eh_frame_hdr`, falling back to the `PT_GNU_EH_FRAME` segment
+    /// when section headers are missing.
+    pub fn eh_frame_hdr(&self) -> Result<EhFrameHdr, Error> {
+        let found = match self.section_by_name(".eh_frame_hdr") {
+            Some(s) => Some((self.section_data(s), s.sh_addr)),
+            None => self
+                .segments
+                .iter()
+                .find(|s| s.p_type == PT_GNU_EH_FRAME)
+                .map(|s| (self.segment_data(s), s.p_vaddr)),
+        };
+        match found {
+            Some((Some(data), address)) => EhFrameHdr::parse(data, address, 8),
+            Some((None, _)) => Err(Error::ParseError(String::from(
+                ".eh_frame_hdr is out of bounds",
+            ))),
+            None => Err(Error::MissingSection(String::from(".eh_frame_hdr"))),
+        }
+    }
+
+    /// Builds the DWARF address to line and function lookup tables.
+    pub fn addr2line(&self) -> Result<Addr2Line<'a>, Error> {
+        Addr2Line::new(DwarfSections::from_elf64(self)?)
+    }
+}
+
 impl exe::Section for Elf64Section {
     fn get_flags(&self) -> u32 {
         // Always readable
@@ -59,7 +630,27 @@ impl exe::Section for Elf64Section {
     }
 
     fn get_size(&self) -> usize {
-        self.sh_size as usize
+        // SHT_NOBITS sections occupy no space in the file
+        if self.sh_type == SHT_NOBITS {
+            0
+        } else {
+            self.sh_size as usize
+        }
+    }
+}
+
+impl exe::Section for Elf64Segment {
+    fn get_flags(&self) -> u32 {
+        // PF_R, PF_W and PF_X already match the exe bitmask
+        self.p_flags & (PF_R | PF_W | PF_X)
+    }
+
+    fn get_offset(&self) -> usize {
+        self.p_offset as usize
+    }
+
+    fn get_size(&self) -> usize {
+        self.p_filesz as usize
     }
 }
 
@@ -75,30 +666,7 @@ impl<'a> exe::Exe<'a> for Elf64<'a> {
     }
 
     fn get_section_name_at(&self, idx: usize) -> Option<&str> {
-        let strndx = match self.sections.iter().nth(self.header.e_shstrndx as usize) {
-            Some(s) => s,
-            None => {
-                return None;
-            }
-        };
-        let s = match self.sections.iter().nth(idx) {
-            Some(x) => x,
-            None => {
-                return None;
-            }
-        };
-
-        let off = s.sh_name as usize + strndx.sh_offset as usize;
-        match self.data[off..]
-            .iter()
-            .enumerate()
-            .filter(|(_, &c)| c == 0)
-            .map(|(i, _)| i)
-            .nth(0)
-        {
-            Some(size) => ::std::str::from_utf8(&self.data[off..off + size]).ok(),
-            None => None,
-        }
+        self.sections.get(idx).and_then(|s| self.section_name(s))
     }
 
     fn parse(i: &'a [u8]) -> Option<Self> {
@@ -109,25 +677,69 @@ impl<'a> exe::Exe<'a> for Elf64<'a> {
     }
 
     fn get_info(&self) -> exe::Info {
+        let ident = &self.header.e_ident;
+        let notes = self.notes().unwrap_or_default();
         exe::Info {
-            os: String::from("linux"),
-            arch: String::from("x86"),
+            os: String::from(os_name(&ident.osabi, &notes)),
+            arch: arch_name(self.header.e_machine),
             bits: 64usize,
         }
     }
 
     fn get_data(&self, start: usize, len: usize) -> &[u8] {
-        &self.data[start..(start + len)]
+        start
+            .checked_add(len)
+            .and_then(|end| self.data.get(start..end))
+            .unwrap_or(&[])
+    }
+}
+
+impl<'a> ExeExt<'a> for Elf64<'a> {
+    type Segment = Elf64Segment;
+
+    fn get_entry_point(&self) -> u64 {
+        self.header.e_entry
+    }
+
+    fn get_load_segments(&self) -> Vec<&Elf64Segment> {
+        self.segments
+            .iter()
+            .filter(|s| s.p_type == PT_LOAD)
+            .collect()
+    }
+
+    fn get_imported_symbols(&self) -> Result<Vec<Symbol<'a>>, Error> {
+        let mut symbols = self.linkage_symbols()?;
+        symbols.retain(|s| s.is_imported());
+        Ok(symbols)
+    }
+
+    fn get_exported_symbols(&self) -> Result<Vec<Symbol<'a>>, Error> {
+        let mut symbols = self.linkage_symbols()?;
+        symbols.retain(|s| s.is_exported());
+        Ok(symbols)
     }
 }
 
+/// Parses a 64-bit file, returning NULL on failure. The handle borrows `i`.
+///
+/// # Safety
+///
+/// `i` must be NULL or valid for reading `len` bytes, and stay valid and
+/// unmodified until the handle is released with `rs_elf64_free_exe`.
 #[no_mangle]
-pub extern "C" fn rs_elf64_parse<'a>(i: *const uint8_t, len: size_t) -> *const c_void {
-    let buf = unsafe { ::std::slice::from_raw_parts(i as *const u8, len) };
+pub unsafe extern "C" fn rs_elf64_parse<'a>(i: *const u8, len: size_t) -> *mut Elf64<'a> {
+    let buf = match capi::input_slice(i, len) {
+        Some(buf) => buf,
+        None => return ::std::ptr::null_mut(),
+    };
 
     match parse_elf64(buf) {
-        Ok((_, e64)) => Box::into_raw(Box::new(e64)) as *const c_void,
-        Err(_) => ::std::ptr::null::<c_void>(),
+        Ok((_, e64)) => Box::into_raw(Box::new(e64)),
+        Err(e) => {
+            capi::fail_with(&Error::from(e));
+            ::std::ptr::null_mut()
+        }
     }
 }
 
@@ -1,15 +1,17 @@
 use nom;
 use std::mem::size_of;
 
-use header::{parse_elf_ident, ElfIdent};
+use header::{le_u8, parse_elf_ident, ElfIdent};
 
 type Elf64Half = u16;
 type Elf64Word = u32;
 type Elf64Xword = u64;
 type Elf64Addr = u64;
+type Elf64Sxword = i64;
 type Elf64Off = u64;
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Elf64Header {
     pub e_ident: ElfIdent,
     pub e_type: Elf64Half,
@@ -28,6 +30,7 @@ pub struct Elf64Header {
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Elf64Section {
     pub sh_name: Elf64Word,
     pub sh_type: Elf64Word,
@@ -42,6 +45,7 @@ pub struct Elf64Section {
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Elf64Segment {
     pub p_type: Elf64Word,
     pub p_flags: Elf64Word,
@@ -53,6 +57,39 @@ pub struct Elf64Segment {
     pub p_align: Elf64Xword,
 }
 
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Elf64Symbol {
+    pub st_name: Elf64Word,
+    pub st_info: u8,
+    pub st_other: u8,
+    pub st_shndx: Elf64Half,
+    pub st_value: Elf64Addr,
+    pub st_size: Elf64Xword,
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Elf64Rel {
+    pub r_offset: Elf64Addr,
+    pub r_info: Elf64Xword,
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Elf64Rela {
+    pub r_offset: Elf64Addr,
+    pub r_info: Elf64Xword,
+    pub r_addend: Elf64Sxword,
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Elf64Dyn {
+    pub d_tag: Elf64Sxword,
+    pub d_val: Elf64Xword,
+}
+
 #[inline(always)]
 pub fn parse_elf64_half(i: &[u8]) -> nom::IResult<&[u8], Elf64Half> {
     nom::le_u16(i)
@@ -92,19 +129,20 @@ named!(
             >> _e_ehsize:
                 verify!(parse_elf64_half, |x: Elf64Half| (x as usize)
                     == size_of::<Elf64Header>())
+            // Relocatable files without program headers may leave e_phentsize unset
             >> _e_phentsize:
-                verify!(parse_elf64_half, |x: Elf64Half| (x as usize) == size_of::<
-                    Elf64Segment,
-                >(
-                ))
+                verify!(parse_elf64_half, |x: Elf64Half| x == 0
+                    || (x as usize) == size_of::<Elf64Segment>())
             >> _e_phnum: parse_elf64_half
+            // Files stripped of their section headers leave all three fields zero
             >> _e_shentsize:
-                verify!(parse_elf64_half, |x: Elf64Half| (x as usize) == size_of::<
-                    Elf64Section,
-                >(
-                ))
-            >> _e_shnum: parse_elf64_half
-            >> _e_shstrndx: verify!(parse_elf64_half, |x: Elf64Half| x < _e_shnum)
+                verify!(parse_elf64_half, |x: Elf64Half| x == 0
+                    || (x as usize) == size_of::<Elf64Section>())
+            >> _e_shnum:
+                verify!(parse_elf64_half, |x: Elf64Half| x == 0 || _e_shentsize != 0)
+            >> _e_shstrndx:
+                verify!(parse_elf64_half, |x: Elf64Half| x < _e_shnum
+                    || (x == 0 && _e_shnum == 0))
             >> (Elf64Header {
                 e_ident: _e_ident,
                 e_type: _e_type,
@@ -125,29 +163,43 @@ named!(
 );
 
 pub fn parse_elf64_header(i: &[u8]) -> nom::IResult<&[u8], Elf64Header> {
+    parse_elf64_header_within(i, i.len() as u64)
+}
+
+/// Parses the header at the start of `i`, checking the program and section
+/// header tables against a file of `size` bytes, of which `i` may hold only
+/// the header.
+pub fn parse_elf64_header_within(i: &[u8], size: u64) -> nom::IResult<&[u8], Elf64Header> {
     match parse_elf64_header_aux(i) {
         Ok((rest, hdr)) => {
-            let ph_start = hdr.e_phoff as usize;
-            let ph_end = ph_start + (hdr.e_phnum as usize) * (hdr.e_phentsize as usize);
-            let sh_start = hdr.e_shoff as usize;
-            let sh_end = sh_start + (hdr.e_shnum as usize) * (hdr.e_shentsize as usize);
-
-            if ph_end > i.len() {
-                Err(nom::Err::Error(error_position!(i, nom::ErrorKind::Verify)))
-            } else if sh_end > i.len() {
-                Err(nom::Err::Error(error_position!(i, nom::ErrorKind::Verify)))
-            } else if ph_start < sh_start && ph_end > sh_start {
-                Err(nom::Err::Error(error_position!(i, nom::ErrorKind::Verify)))
-            } else if sh_start < ph_start && sh_end > ph_start {
-                Err(nom::Err::Error(error_position!(i, nom::ErrorKind::Verify)))
-            } else {
-                Ok((rest, hdr))
+            let ph_start = hdr.e_phoff;
+            let ph_end = ph_start.checked_add(u64::from(hdr.e_phnum) * u64::from(hdr.e_phentsize));
+            let sh_start = hdr.e_shoff;
+            let sh_end = sh_start.checked_add(u64::from(hdr.e_shnum) * u64::from(hdr.e_shentsize));
+
+            match (ph_end, sh_end) {
+                (Some(ph_end), Some(sh_end))
+                    if ph_end <= size
+                        && sh_end <= size
+                        && (ph_start == ph_end
+                            || sh_start == sh_end
+                            || ph_end <= sh_start
+                            || sh_end <= ph_start) =>
+                {
+                    Ok((rest, hdr))
+                }
+                _ => Err(nom::Err::Error(error_position!(i, nom::ErrorKind::Verify))),
             }
         }
         Err(e) => Err(e),
     }
 }
 
+#[inline(always)]
+pub fn parse_elf64_sxword(i: &[u8]) -> nom::IResult<&[u8], Elf64Sxword> {
+    nom::le_i64(i)
+}
+
 named!(pub parse_elf64_segment<Elf64Segment>,
     do_parse!(
             _p_type:    parse_elf64_word
@@ -197,3 +249,57 @@ named!(pub parse_elf64_section<Elf64Section>,
         })
     )
 );
+
+named!(pub parse_elf64_symbol<Elf64Symbol>,
+    do_parse!(
+            _st_name:   parse_elf64_word
+        >>  _st_info:   le_u8
+        >>  _st_other:  le_u8
+        >>  _st_shndx:  parse_elf64_half
+        >>  _st_value:  parse_elf64_addr
+        >>  _st_size:   parse_elf64_xword
+        >>  ( Elf64Symbol {
+            st_name:    _st_name,
+            st_info:    _st_info,
+            st_other:   _st_other,
+            st_shndx:   _st_shndx,
+            st_value:   _st_value,
+            st_size:    _st_size,
+        })
+    )
+);
+
+named!(pub parse_elf64_dyn<Elf64Dyn>,
+    do_parse!(
+            _d_tag: parse_elf64_sxword
+        >>  _d_val: parse_elf64_xword
+        >>  ( Elf64Dyn {
+            d_tag:  _d_tag,
+            d_val:  _d_val,
+        })
+    )
+);
+
+named!(pub parse_elf64_rel<Elf64Rel>,
+    do_parse!(
+            _r_offset:  parse_elf64_addr
+        >>  _r_info:    parse_elf64_xword
+        >>  ( Elf64Rel {
+            r_offset:   _r_offset,
+            r_info:     _r_info,
+        })
+    )
+);
+
+named!(pub parse_elf64_rela<Elf64Rela>,
+    do_parse!(
+            _r_offset:  parse_elf64_addr
+        >>  _r_info:    parse_elf64_xword
+        >>  _r_addend:  parse_elf64_sxword
+        >>  ( Elf64Rela {
+            r_offset:   _r_offset,
+            r_info:     _r_info,
+            r_addend:   _r_addend,
+        })
+    )
+);
@@ -0,0 +1,36 @@
+use nom;
+use std::io;
+
+#[derive(Fail, Debug)]
+pub enum Error {
+    #[fail(display = "Parse error : {}", _0)]
+    ParseError(String),
+
+    #[fail(display = "Missing section : {}", _0)]
+    MissingSection(String),
+
+    #[fail(display = "Unsupported : {}", _0)]
+    Unsupported(String),
+
+    #[fail(display = "IO error : {}", _0)]
+    IOError(#[cause] io::Error),
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        Error::IOError(e)
+    }
+}
+
+impl<'a> From<nom::Err<&'a [u8]>> for Error {
+    fn from(e: nom::Err<&'a [u8]>) -> Error {
+        match e {
+            nom::Err::Incomplete(needed) => {
+                Error::ParseError(format!("truncated input ({:?})", needed))
+            }
+            nom::Err::Error(ctx) | nom::Err::Failure(ctx) => {
+                Error::ParseError(format!("{:?}", ctx.into_error_kind()))
+            }
+        }
+    }
+}
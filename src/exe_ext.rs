@@ -0,0 +1,19 @@
+use exe;
+
+use error::Error;
+use symbols::Symbol;
+
+/// Information the `exe::Exe` abstraction has no room for: the entry point,
+/// the loadable segments and the dynamic symbols.
+pub trait ExeExt<'a>: exe::Exe<'a> {
+    /// Segments are exposed as `exe::Section`s, with `p_flags` mapped to the
+    /// same R/W/X bitmask as `exe::Section::get_flags`.
+    type Segment: exe::Section;
+
+    fn get_entry_point(&self) -> u64;
+    fn get_load_segments(&self) -> Vec<&Self::Segment>;
+    /// Returns the undefined symbols that must be provided by other objects.
+    fn get_imported_symbols(&self) -> Result<Vec<Symbol<'a>>, Error>;
+    /// Returns the defined symbols visible to other objects.
+    fn get_exported_symbols(&self) -> Result<Vec<Symbol<'a>>, Error>;
+}
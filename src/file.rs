@@ -0,0 +1,293 @@
+use memmap2::Mmap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use dwarf::Addr2Line;
+use dynamic::Dynamic;
+use error::Error;
+use header::{parse_elf_ident, ElfClass, ElfData};
+use loader::{LoadOptions, LoadedImage};
+use notes::Note;
+use size::SizeReport;
+use symbols::Symbol;
+use versions::{SymbolVersion, Versions};
+use {
+    parse_elf32, parse_elf32_view, parse_elf64, parse_elf64_view, Elf32, Elf32Reader, Elf32View,
+    Elf64, Elf64Reader, Elf64View,
+};
+
+/// An ELF file of either class, detected from its identification.
+#[derive(Debug)]
+pub enum Elf<'a> {
+    Elf32(Elf32<'a>),
+    Elf64(Elf64<'a>),
+}
+
+/// Evaluates `$body` with `$e` bound to the file of either class.
+macro_rules! dispatch {
+    ($self:ident, $e:ident => $body:expr) => {
+        match *$self {
+            Elf::Elf32(ref $e) => $body,
+            Elf::Elf64(ref $e) => $body,
+        }
+    };
+}
+
+impl<'a> Elf<'a> {
+    /// Parses `i` as a 32 or 64-bit file. Big-endian files are detected but
+    /// not supported.
+    pub fn parse(i: &'a [u8]) -> Result<Self, Error> {
+        let ident = parse_elf_ident(i)?.1;
+        if ident.data != ElfData::DataLSB {
+            return Err(Error::Unsupported(String::from("big-endian file")));
+        }
+        match ident.class {
+            ElfClass::Class32 => Ok(Elf::Elf32(parse_elf32(i)?.1)),
+            ElfClass::Class64 => Ok(Elf::Elf64(parse_elf64(i)?.1)),
+        }
+    }
+
+    pub fn data(&self) -> &'a [u8] {
+        dispatch!(self, e => e.data)
+    }
+
+    pub fn class(&self) -> ElfClass {
+        dispatch!(self, e => e.header.e_ident.class)
+    }
+
+    pub fn endianness(&self) -> ElfData {
+        dispatch!(self, e => e.header.e_ident.data)
+    }
+
+    pub fn machine(&self) -> u16 {
+        dispatch!(self, e => e.header.e_machine)
+    }
+
+    pub fn symtab(&self) -> Result<Vec<Symbol<'a>>, Error> {
+        dispatch!(self, e => e.symtab())
+    }
+
+    pub fn dynsym(&self) -> Result<Vec<Symbol<'a>>, Error> {
+        dispatch!(self, e => e.dynsym())
+    }
+
+    pub fn symbol_type_letter(&self, symbol: &Symbol) -> char {
+        dispatch!(self, e => e.symbol_type_letter(symbol))
+    }
+
+    pub fn versions(&self) -> Result<Versions<'a>, Error> {
+        dispatch!(self, e => e.versions())
+    }
+
+    pub fn dynamic(&self) -> Result<Vec<Dynamic>, Error> {
+        dispatch!(self, e => e.dynamic())
+    }
+
+    pub fn dynamic_string(&self, offset: u64) -> Option<&'a str> {
+        dispatch!(self, e => e.dynamic_string(offset))
+    }
+
+    pub fn needed(&self) -> Result<Vec<&'a str>, Error> {
+        dispatch!(self, e => e.needed())
+    }
+
+    pub fn soname(&self) -> Result<Option<&'a str>, Error> {
+        dispatch!(self, e => e.soname())
+    }
+
+    pub fn rpath(&self) -> Result<Option<&'a str>, Error> {
+        dispatch!(self, e => e.rpath())
+    }
+
+    pub fn runpath(&self) -> Result<Option<&'a str>, Error> {
+        dispatch!(self, e => e.runpath())
+    }
+
+    pub fn notes(&self) -> Result<Vec<Note<'a>>, Error> {
+        dispatch!(self, e => e.notes())
+    }
+
+    pub fn addr2line(&self) -> Result<Addr2Line<'a>, Error> {
+        dispatch!(self, e => e.addr2line())
+    }
+
+    pub fn size_report(&self) -> Result<SizeReport, Error> {
+        match *self {
+            Elf::Elf32(ref e) => SizeReport::from_elf32(e),
+            Elf::Elf64(ref e) => SizeReport::from_elf64(e),
+        }
+    }
+
+    /// Loads the file as `options` says, see `LoadedImage::from_elf64`.
+    pub fn load_image<F>(&self, options: &LoadOptions, resolve: F) -> Result<LoadedImage, Error>
+    where
+        F: FnMut(&Symbol<'a>, Option<&SymbolVersion<'a>>) -> Option<u64>,
+    {
+        match *self {
+            Elf::Elf32(ref e) => LoadedImage::from_elf32(e, options, resolve),
+            Elf::Elf64(ref e) => LoadedImage::from_elf64(e, options, resolve),
+        }
+    }
+}
+
+/// A file of either class of which only the header is parsed, see
+/// `Elf64View`.
+#[derive(Debug)]
+pub enum ElfView<'a> {
+    Elf32(Elf32View<'a>),
+    Elf64(Elf64View<'a>),
+}
+
+impl<'a> ElfView<'a> {
+    /// Parses the header of `i` as a 32 or 64-bit file. Big-endian files are
+    /// detected but not supported.
+    pub fn parse(i: &'a [u8]) -> Result<Self, Error> {
+        let ident = parse_elf_ident(i)?.1;
+        if ident.data != ElfData::DataLSB {
+            return Err(Error::Unsupported(String::from("big-endian file")));
+        }
+        match ident.class {
+            ElfClass::Class32 => Ok(ElfView::Elf32(parse_elf32_view(i)?.1)),
+            ElfClass::Class64 => Ok(ElfView::Elf64(parse_elf64_view(i)?.1)),
+        }
+    }
+
+    pub fn class(&self) -> ElfClass {
+        match *self {
+            ElfView::Elf32(ref e) => e.header.e_ident.class,
+            ElfView::Elf64(ref e) => e.header.e_ident.class,
+        }
+    }
+
+    pub fn machine(&self) -> u16 {
+        match *self {
+            ElfView::Elf32(ref e) => e.header.e_machine,
+            ElfView::Elf64(ref e) => e.header.e_machine,
+        }
+    }
+
+    pub fn build_id(&self) -> Option<&'a [u8]> {
+        match *self {
+            ElfView::Elf32(ref e) => e.build_id(),
+            ElfView::Elf64(ref e) => e.build_id(),
+        }
+    }
+
+    /// Parses the program and section header tables.
+    pub fn to_elf(&self) -> Result<Elf<'a>, Error> {
+        match *self {
+            ElfView::Elf32(ref e) => Ok(Elf::Elf32(e.to_elf32()?)),
+            ElfView::Elf64(ref e) => Ok(Elf::Elf64(e.to_elf64()?)),
+        }
+    }
+}
+
+/// A file of either class read from a stream, see `Elf64Reader`.
+pub enum ElfReader<R> {
+    Elf32(Elf32Reader<R>),
+    Elf64(Elf64Reader<R>),
+}
+
+impl<R: Read + Seek> ElfReader<R> {
+    /// Reads the header and tables of a 32 or 64-bit file from `reader`.
+    /// Big-endian files are detected but not supported.
+    pub fn new(mut reader: R) -> Result<Self, Error> {
+        let mut buf = [0u8; 16];
+        reader.seek(SeekFrom::Start(0))?;
+        reader.read_exact(&mut buf)?;
+        let ident = parse_elf_ident(&buf)?.1;
+        if ident.data != ElfData::DataLSB {
+            return Err(Error::Unsupported(String::from("big-endian file")));
+        }
+        match ident.class {
+            ElfClass::Class32 => Ok(ElfReader::Elf32(Elf32Reader::new(reader)?)),
+            ElfClass::Class64 => Ok(ElfReader::Elf64(Elf64Reader::new(reader)?)),
+        }
+    }
+
+    pub fn class(&self) -> ElfClass {
+        match *self {
+            ElfReader::Elf32(ref e) => e.header.e_ident.class,
+            ElfReader::Elf64(ref e) => e.header.e_ident.class,
+        }
+    }
+
+    pub fn machine(&self) -> u16 {
+        match *self {
+            ElfReader::Elf32(ref e) => e.header.e_machine,
+            ElfReader::Elf64(ref e) => e.header.e_machine,
+        }
+    }
+
+    pub fn build_id(&mut self) -> Result<Option<Vec<u8>>, Error> {
+        match *self {
+            ElfReader::Elf32(ref mut e) => e.build_id(),
+            ElfReader::Elf64(ref mut e) => e.build_id(),
+        }
+    }
+}
+
+enum Storage {
+    Mapped(Mmap),
+    Buffered(Vec<u8>),
+}
+
+/// An ELF file loaded from disk, memory-mapped when possible. Parsing it
+/// gives zero-copy views borrowing the file contents.
+pub struct ElfFile {
+    storage: Storage,
+}
+
+impl ElfFile {
+    /// Maps the file at `path` read-only, or reads it into memory when it
+    /// cannot be mapped, such as pipes and character devices.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let mut file = File::open(path)?;
+        let metadata = file.metadata()?;
+        if metadata.is_file() && metadata.len() > 0 {
+            if let Ok(map) = unsafe { Mmap::map(&file) } {
+                return Ok(ElfFile {
+                    storage: Storage::Mapped(map),
+                });
+            }
+        }
+        ElfFile::from_reader(&mut file)
+    }
+
+    /// Reads all of `reader` into memory.
+    pub fn from_reader<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+        Ok(ElfFile::from_vec(buf))
+    }
+
+    pub fn from_vec(buf: Vec<u8>) -> Self {
+        ElfFile {
+            storage: Storage::Buffered(buf),
+        }
+    }
+
+    pub fn data(&self) -> &[u8] {
+        match self.storage {
+            Storage::Mapped(ref map) => map,
+            Storage::Buffered(ref buf) => buf,
+        }
+    }
+
+    pub fn is_mapped(&self) -> bool {
+        match self.storage {
+            Storage::Mapped(_) => true,
+            Storage::Buffered(_) => false,
+        }
+    }
+
+    pub fn parse(&self) -> Result<Elf<'_>, Error> {
+        Elf::parse(self.data())
+    }
+
+    /// Parses only the header, see `ElfView`.
+    pub fn view(&self) -> Result<ElfView<'_>, Error> {
+        ElfView::parse(self.data())
+    }
+}
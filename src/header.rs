@@ -1,8 +1,35 @@
 use enum_primitive::FromPrimitive;
 use nom;
 
+/// Serializes the enum as its numeric value, as the fields it is read from,
+/// and deserializes only the values it has a variant for.
+macro_rules! serde_as_number {
+    ($enum:ident, $ty:ident, $from:ident) => {
+        #[cfg(feature = "serde")]
+        impl ::serde::Serialize for $enum {
+            fn serialize<S: ::serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                ::serde::Serialize::serialize(&(*self as $ty), s)
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'de> ::serde::Deserialize<'de> for $enum {
+            fn deserialize<D: ::serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                let n = <$ty as ::serde::Deserialize>::deserialize(d)?;
+                $enum::$from(n).ok_or_else(|| {
+                    <D::Error as ::serde::de::Error>::custom(format!(
+                        "invalid {} {}",
+                        stringify!($enum),
+                        n
+                    ))
+                })
+            }
+        }
+    };
+}
+
 enum_from_primitive! {
-#[derive(Debug,PartialEq)]
+#[derive(Debug,PartialEq,Clone,Copy)]
 #[repr(u8)]
 pub enum ElfClass {
     Class32 = 1,
@@ -10,8 +37,10 @@ pub enum ElfClass {
 }
 }
 
+serde_as_number!(ElfClass, u8, from_u8);
+
 enum_from_primitive! {
-#[derive(Debug,PartialEq)]
+#[derive(Debug,PartialEq,Clone,Copy)]
 #[repr(u8)]
 pub enum ElfData {
     DataLSB = 1,
@@ -19,8 +48,10 @@ pub enum ElfData {
 }
 }
 
+serde_as_number!(ElfData, u8, from_u8);
+
 enum_from_primitive! {
-#[derive(Debug,PartialEq)]
+#[derive(Debug,PartialEq,Clone,Copy)]
 #[repr(u8)]
 pub enum ElfOSAbi {
     OSAbiSysv= 0,
@@ -40,8 +71,10 @@ pub enum ElfOSAbi {
 }
 }
 
+serde_as_number!(ElfOSAbi, u8, from_u8);
+
 enum_from_primitive! {
-#[derive(Debug,PartialEq)]
+#[derive(Debug,PartialEq,Clone,Copy)]
 #[repr(u16)]
 pub enum ElfType {
     None= 0,
@@ -57,8 +90,10 @@ pub enum ElfType {
 }
 }
 
+serde_as_number!(ElfType, u16, from_u16);
+
 enum_from_primitive! {
-#[derive(Debug,PartialEq)]
+#[derive(Debug,PartialEq,Clone,Copy)]
 #[repr(u16)]
 pub enum ElfMachine {
     MachineNone = 0,
@@ -244,21 +279,80 @@ pub enum ElfMachine {
 }
 }
 
+serde_as_number!(ElfMachine, u16, from_u16);
+
+impl ElfData {
+    pub fn is_little_endian(&self) -> bool {
+        *self == ElfData::DataLSB
+    }
+}
+
+impl ElfMachine {
+    /// Returns the architecture family name. x86-64, AArch64 and ARM are told
+    /// apart, the word size telling apart the variants of the other families.
+    pub fn arch_name(&self) -> &'static str {
+        match *self {
+            ElfMachine::Machine386 | ElfMachine::MachineIamcu => "x86",
+            ElfMachine::MachineX8664 => "x86_64",
+            ElfMachine::MachineArm => "arm",
+            ElfMachine::MachineAarch64 => "aarch64",
+            ElfMachine::MachineMips | ElfMachine::MachineMipsRs3Le | ElfMachine::MachineMipsX => {
+                "mips"
+            }
+            ElfMachine::MachinePpc | ElfMachine::MachinePpc64 => "powerpc",
+            ElfMachine::MachineRiscv => "riscv",
+            ElfMachine::MachineSparc
+            | ElfMachine::MachineSparc32plus
+            | ElfMachine::MachineSparcv9 => "sparc",
+            ElfMachine::MachineS390 => "s390",
+            ElfMachine::MachineIa64 => "ia64",
+            ElfMachine::MachineSh => "sh",
+            ElfMachine::Machine68k => "m68k",
+            ElfMachine::MachineParisc => "parisc",
+            ElfMachine::MachineAlpha => "alpha",
+            ElfMachine::MachineAvr => "avr",
+            ElfMachine::MachineMsp430 => "msp430",
+            ElfMachine::MachineXtensa => "xtensa",
+            ElfMachine::MachineMicroblaze => "microblaze",
+            ElfMachine::MachineAlteraNios2 => "nios2",
+            ElfMachine::MachineOpenrisc => "openrisc",
+            ElfMachine::MachineArc
+            | ElfMachine::MachineArcCompact
+            | ElfMachine::MachineArcCompact2 => "arc",
+            ElfMachine::MachineBpf => "bpf",
+            ElfMachine::MachineAmdgpu => "amdgpu",
+            ElfMachine::MachineCuda => "cuda",
+            _ => "unknown",
+        }
+    }
+}
+
+/// Returns the architecture name of `e_machine`. Files are parsed as
+/// little-endian only, so there is no big-endian variant of the names.
+pub fn arch_name(e_machine: u16) -> String {
+    let name = ElfMachine::from_u16(e_machine).map_or("unknown", |m| m.arch_name());
+    String::from(name)
+}
+
 enum_from_primitive! {
-#[derive(Debug,PartialEq)]
-#[repr(u16)]
+#[derive(Debug,PartialEq,Clone,Copy)]
+#[repr(u8)]
 pub enum ElfVersion {
     Current = 1,
 }
 }
 
+serde_as_number!(ElfVersion, u8, from_u8);
+
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ElfIdent {
     pub tag: [u8; 4],
     pub class: ElfClass,
     pub data: ElfData,
     pub version: ElfVersion,
     pub osabi: ElfOSAbi,
+    pub abiversion: u8,
     pub padding: [u8; 7],
 }
 
@@ -315,7 +409,7 @@ macro_rules! parse_u32_enum {
 parse_u8_enum!(parse_elf_class, ElfClass);
 parse_u8_enum!(parse_elf_data, ElfData);
 parse_u8_enum!(parse_elf_version, ElfVersion);
-parse_u16_enum!(parse_elf_osabi, ElfOSAbi);
+parse_u8_enum!(parse_elf_osabi, ElfOSAbi);
 
 named!(pub parse_elf_ident<ElfIdent>,
     do_parse!(
@@ -324,6 +418,7 @@ named!(pub parse_elf_ident<ElfIdent>,
         >>  _data:      parse_elf_data
         >>  _version:   parse_elf_version
         >>  _osabi:     parse_elf_osabi
+        >>  _abiversion: le_u8
         >>  _padding:   count_fixed!(u8, le_u8, 7)
         >>  (ElfIdent {
             tag:        [_tag[0], _tag[1], _tag[2], _tag[3]],
@@ -331,7 +426,148 @@ named!(pub parse_elf_ident<ElfIdent>,
             data:       _data,
             version:    _version,
             osabi:      _osabi,
+            abiversion: _abiversion,
             padding:    _padding
         })
     )
 );
+
+pub const SHT_NULL: u32 = 0;
+pub const SHT_PROGBITS: u32 = 1;
+pub const SHT_SYMTAB: u32 = 2;
+pub const SHT_STRTAB: u32 = 3;
+pub const SHT_RELA: u32 = 4;
+pub const SHT_HASH: u32 = 5;
+pub const SHT_DYNAMIC: u32 = 6;
+pub const SHT_NOTE: u32 = 7;
+pub const SHT_NOBITS: u32 = 8;
+pub const SHT_REL: u32 = 9;
+pub const SHT_SHLIB: u32 = 10;
+pub const SHT_DYNSYM: u32 = 11;
+pub const SHT_INIT_ARRAY: u32 = 14;
+pub const SHT_FINI_ARRAY: u32 = 15;
+pub const SHT_PREINIT_ARRAY: u32 = 16;
+pub const SHT_GROUP: u32 = 17;
+pub const SHT_SYMTAB_SHNDX: u32 = 18;
+pub const SHT_RELR: u32 = 19;
+pub const SHT_GNU_ATTRIBUTES: u32 = 0x6fff_fff5;
+pub const SHT_GNU_HASH: u32 = 0x6fff_fff6;
+pub const SHT_GNU_LIBLIST: u32 = 0x6fff_fff7;
+pub const SHT_GNU_VERDEF: u32 = 0x6fff_fffd;
+pub const SHT_GNU_VERNEED: u32 = 0x6fff_fffe;
+pub const SHT_GNU_VERSYM: u32 = 0x6fff_ffff;
+
+pub const SHF_WRITE: u64 = 0x1;
+pub const SHF_ALLOC: u64 = 0x2;
+pub const SHF_EXECINSTR: u64 = 0x4;
+pub const SHF_MERGE: u64 = 0x10;
+pub const SHF_STRINGS: u64 = 0x20;
+pub const SHF_INFO_LINK: u64 = 0x40;
+pub const SHF_LINK_ORDER: u64 = 0x80;
+pub const SHF_OS_NONCONFORMING: u64 = 0x100;
+pub const SHF_GROUP: u64 = 0x200;
+pub const SHF_TLS: u64 = 0x400;
+pub const SHF_COMPRESSED: u64 = 0x800;
+
+pub const GRP_COMDAT: u32 = 0x1;
+
+pub const EF_ARM_ABI_FLOAT_SOFT: u32 = 0x200;
+pub const EF_ARM_ABI_FLOAT_HARD: u32 = 0x400;
+pub const EF_ARM_EABIMASK: u32 = 0xff00_0000;
+
+pub const EF_RISCV_RVC: u32 = 0x1;
+pub const EF_RISCV_FLOAT_ABI: u32 = 0x6;
+pub const EF_RISCV_RVE: u32 = 0x8;
+pub const EF_RISCV_TSO: u32 = 0x10;
+
+pub const PT_NULL: u32 = 0;
+pub const PT_LOAD: u32 = 1;
+pub const PT_DYNAMIC: u32 = 2;
+pub const PT_INTERP: u32 = 3;
+pub const PT_NOTE: u32 = 4;
+pub const PT_SHLIB: u32 = 5;
+pub const PT_PHDR: u32 = 6;
+pub const PT_TLS: u32 = 7;
+pub const PT_GNU_EH_FRAME: u32 = 0x6474_e550;
+pub const PT_GNU_STACK: u32 = 0x6474_e551;
+pub const PT_GNU_RELRO: u32 = 0x6474_e552;
+pub const PT_GNU_PROPERTY: u32 = 0x6474_e553;
+
+pub const PF_X: u32 = 0x1;
+pub const PF_W: u32 = 0x2;
+pub const PF_R: u32 = 0x4;
+
+pub const SHN_UNDEF: u16 = 0;
+pub const SHN_LORESERVE: u16 = 0xff00;
+pub const SHN_ABS: u16 = 0xfff1;
+pub const SHN_COMMON: u16 = 0xfff2;
+pub const SHN_XINDEX: u16 = 0xffff;
+
+pub const STB_LOCAL: u8 = 0;
+pub const STB_GLOBAL: u8 = 1;
+pub const STB_WEAK: u8 = 2;
+pub const STB_GNU_UNIQUE: u8 = 10;
+
+pub const STT_NOTYPE: u8 = 0;
+pub const STT_OBJECT: u8 = 1;
+pub const STT_FUNC: u8 = 2;
+pub const STT_SECTION: u8 = 3;
+pub const STT_FILE: u8 = 4;
+pub const STT_COMMON: u8 = 5;
+pub const STT_TLS: u8 = 6;
+pub const STT_GNU_IFUNC: u8 = 10;
+
+pub const STV_DEFAULT: u8 = 0;
+pub const STV_INTERNAL: u8 = 1;
+pub const STV_HIDDEN: u8 = 2;
+pub const STV_PROTECTED: u8 = 3;
+
+pub const DT_NULL: i64 = 0;
+pub const DT_NEEDED: i64 = 1;
+pub const DT_PLTRELSZ: i64 = 2;
+pub const DT_PLTGOT: i64 = 3;
+pub const DT_HASH: i64 = 4;
+pub const DT_STRTAB: i64 = 5;
+pub const DT_SYMTAB: i64 = 6;
+pub const DT_RELA: i64 = 7;
+pub const DT_RELASZ: i64 = 8;
+pub const DT_RELAENT: i64 = 9;
+pub const DT_STRSZ: i64 = 10;
+pub const DT_SYMENT: i64 = 11;
+pub const DT_INIT: i64 = 12;
+pub const DT_FINI: i64 = 13;
+pub const DT_SONAME: i64 = 14;
+pub const DT_RPATH: i64 = 15;
+pub const DT_SYMBOLIC: i64 = 16;
+pub const DT_REL: i64 = 17;
+pub const DT_RELSZ: i64 = 18;
+pub const DT_RELENT: i64 = 19;
+pub const DT_PLTREL: i64 = 20;
+pub const DT_DEBUG: i64 = 21;
+pub const DT_TEXTREL: i64 = 22;
+pub const DT_JMPREL: i64 = 23;
+pub const DT_BIND_NOW: i64 = 24;
+pub const DT_INIT_ARRAY: i64 = 25;
+pub const DT_FINI_ARRAY: i64 = 26;
+pub const DT_INIT_ARRAYSZ: i64 = 27;
+pub const DT_FINI_ARRAYSZ: i64 = 28;
+pub const DT_RUNPATH: i64 = 29;
+pub const DT_FLAGS: i64 = 30;
+pub const DT_PREINIT_ARRAY: i64 = 32;
+pub const DT_PREINIT_ARRAYSZ: i64 = 33;
+pub const DT_SYMTAB_SHNDX: i64 = 34;
+pub const DT_RELRSZ: i64 = 35;
+pub const DT_RELR: i64 = 36;
+pub const DT_RELRENT: i64 = 37;
+pub const DT_GNU_HASH: i64 = 0x6fff_fef5;
+pub const DT_VERSYM: i64 = 0x6fff_fff0;
+pub const DT_RELACOUNT: i64 = 0x6fff_fff9;
+pub const DT_RELCOUNT: i64 = 0x6fff_fffa;
+pub const DT_FLAGS_1: i64 = 0x6fff_fffb;
+pub const DT_VERDEF: i64 = 0x6fff_fffc;
+pub const DT_VERDEFNUM: i64 = 0x6fff_fffd;
+pub const DT_VERNEED: i64 = 0x6fff_fffe;
+pub const DT_VERNEEDNUM: i64 = 0x6fff_ffff;
+
+pub const DF_1_NOW: u64 = 0x1;
+pub const DF_1_NODEFLIB: u64 = 0x800;
@@ -0,0 +1,250 @@
+use nom;
+use std::str;
+
+use error::Error;
+use file::ElfView;
+use header::{ElfClass, ElfMachine};
+
+/// Magic of the format of libc5 and glibc before 2.2, also written ahead
+/// of the new format by `ldconfig -c compat`.
+pub const LD_CACHE_MAGIC_OLD: &[u8] = b"ld.so-1.7.0";
+/// Magic and version of the format written by glibc 2.2 and later.
+pub const LD_CACHE_MAGIC_NEW: &[u8] = b"glibc-ld.so.cache1.1";
+
+/// Size of the header of the old format, up to the first entry.
+const OLD_HEADER_SIZE: usize = 16;
+const OLD_ENTRY_SIZE: usize = 12;
+/// Size of the header of the new format, up to the first entry.
+const NEW_HEADER_SIZE: usize = 48;
+const NEW_ENTRY_SIZE: usize = 24;
+/// Alignment of the new format following the old one.
+const NEW_ALIGN: usize = 8;
+
+pub const FLAG_TYPE_MASK: i32 = 0x00ff;
+pub const FLAG_LIBC4: i32 = 0x0000;
+pub const FLAG_ELF: i32 = 0x0001;
+pub const FLAG_ELF_LIBC5: i32 = 0x0002;
+pub const FLAG_ELF_LIBC6: i32 = 0x0003;
+pub const FLAG_REQUIRED_MASK: i32 = 0xff00;
+pub const FLAG_SPARC_LIB64: i32 = 0x0100;
+pub const FLAG_IA64_LIB64: i32 = 0x0200;
+pub const FLAG_X8664_LIB64: i32 = 0x0300;
+pub const FLAG_S390_LIB64: i32 = 0x0400;
+pub const FLAG_POWERPC_LIB64: i32 = 0x0500;
+pub const FLAG_MIPS64_LIBN32: i32 = 0x0600;
+pub const FLAG_MIPS64_LIBN64: i32 = 0x0700;
+pub const FLAG_X8664_LIBX32: i32 = 0x0800;
+pub const FLAG_ARM_LIBHF: i32 = 0x0900;
+pub const FLAG_AARCH64_LIB64: i32 = 0x0a00;
+pub const FLAG_ARM_LIBSF: i32 = 0x0b00;
+pub const FLAG_MIPS_LIB32_NAN2008: i32 = 0x0c00;
+pub const FLAG_MIPS64_LIBN32_NAN2008: i32 = 0x0d00;
+pub const FLAG_MIPS64_LIBN64_NAN2008: i32 = 0x0e00;
+pub const FLAG_RISCV_FLOAT_ABI_SOFT: i32 = 0x0f00;
+pub const FLAG_RISCV_FLOAT_ABI_DOUBLE: i32 = 0x1000;
+pub const FLAG_LARCH_FLOAT_ABI_SOFT: i32 = 0x1100;
+pub const FLAG_LARCH_FLOAT_ABI_DOUBLE: i32 = 0x1200;
+
+/// Set in `hwcap` when the low 32 bits index the `glibc-hwcaps`
+/// subdirectories of the cache extensions instead of being hardware
+/// capability bits.
+pub const DL_CACHE_HWCAP_EXTENSION: u64 = 1 << 62;
+
+/// A library indexed by `ldconfig` in `/etc/ld.so.cache`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LdCacheEntry<'a> {
+    /// Library type and ABI, such as `FLAG_X8664_LIB64 | FLAG_ELF_LIBC6`.
+    pub flags: i32,
+    pub soname: &'a str,
+    pub path: &'a str,
+    /// Minimum kernel version required by the library, 0 if any. Always 0
+    /// in the old format.
+    pub os_version: u32,
+    /// Hardware capabilities the library needs. Always 0 in the old format.
+    pub hwcap: u64,
+}
+
+/// The entries of `/etc/ld.so.cache`, sorted by `ldconfig` in the order
+/// ld.so prefers them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LdCache<'a> {
+    pub entries: Vec<LdCacheEntry<'a>>,
+}
+
+fn cache_string(data: &[u8], offset: u32) -> Result<&str, Error> {
+    let s = data
+        .get(offset as usize..)
+        .ok_or_else(|| Error::ParseError(String::from("cache string is out of bounds")))?;
+    let len = s
+        .iter()
+        .position(|&c| c == 0)
+        .ok_or_else(|| Error::ParseError(String::from("unterminated cache string")))?;
+    str::from_utf8(&s[..len]).map_err(|_| Error::ParseError(String::from("bad cache string")))
+}
+
+/// Returns the `nlibs` entries of `size` bytes starting at `start`.
+fn entry_table(data: &[u8], start: usize, nlibs: u32, size: usize) -> Result<&[u8], Error> {
+    data.get(start..)
+        .and_then(|t| t.get(..(nlibs as usize).checked_mul(size)?))
+        .ok_or_else(|| Error::ParseError(String::from("cache entries are out of bounds")))
+}
+
+/// Returns the number of entries of the old format, after the magic
+/// padded to 4 bytes.
+fn old_nlibs(data: &[u8]) -> Result<u32, Error> {
+    let header = data
+        .get(OLD_HEADER_SIZE - 4..)
+        .ok_or_else(|| Error::ParseError(String::from("truncated ld.so.cache")))?;
+    Ok(nom::le_u32(header)?.1)
+}
+
+/// Parses the entries of the old format in `data`, which starts with its
+/// header. String offsets are relative to the end of the entries.
+fn parse_old_entries<'a>(data: &'a [u8]) -> Result<Vec<LdCacheEntry<'a>>, Error> {
+    let nlibs = old_nlibs(data)?;
+    let table = entry_table(data, OLD_HEADER_SIZE, nlibs, OLD_ENTRY_SIZE)?;
+    let strings = &data[OLD_HEADER_SIZE + table.len()..];
+    let mut entries = Vec::with_capacity(nlibs as usize);
+    for entry in table.chunks(OLD_ENTRY_SIZE) {
+        let (rest, flags) = nom::le_i32(entry)?;
+        let (rest, key) = nom::le_u32(rest)?;
+        let (_, value) = nom::le_u32(rest)?;
+        entries.push(LdCacheEntry {
+            flags: flags,
+            soname: cache_string(strings, key)?,
+            path: cache_string(strings, value)?,
+            os_version: 0,
+            hwcap: 0,
+        });
+    }
+    Ok(entries)
+}
+
+/// Parses the entries of the new format in `data`, which starts with its
+/// header. String offsets are relative to the header.
+fn parse_new_entries<'a>(data: &'a [u8]) -> Result<Vec<LdCacheEntry<'a>>, Error> {
+    let (_, nlibs) = nom::le_u32(&data[LD_CACHE_MAGIC_NEW.len()..])?;
+    let table = entry_table(data, NEW_HEADER_SIZE, nlibs, NEW_ENTRY_SIZE)?;
+    let mut entries = Vec::with_capacity(nlibs as usize);
+    for entry in table.chunks(NEW_ENTRY_SIZE) {
+        let (rest, flags) = nom::le_i32(entry)?;
+        let (rest, key) = nom::le_u32(rest)?;
+        let (rest, value) = nom::le_u32(rest)?;
+        let (rest, os_version) = nom::le_u32(rest)?;
+        let (_, hwcap) = nom::le_u64(rest)?;
+        entries.push(LdCacheEntry {
+            flags: flags,
+            soname: cache_string(data, key)?,
+            path: cache_string(data, value)?,
+            os_version: os_version,
+            hwcap: hwcap,
+        });
+    }
+    Ok(entries)
+}
+
+impl<'a> LdCacheEntry<'a> {
+    /// Returns the class required by the flags, if they tell.
+    pub fn class(&self) -> Option<ElfClass> {
+        match self.flags & FLAG_REQUIRED_MASK {
+            FLAG_SPARC_LIB64
+            | FLAG_IA64_LIB64
+            | FLAG_X8664_LIB64
+            | FLAG_S390_LIB64
+            | FLAG_POWERPC_LIB64
+            | FLAG_MIPS64_LIBN64
+            | FLAG_AARCH64_LIB64
+            | FLAG_MIPS64_LIBN64_NAN2008
+            | FLAG_LARCH_FLOAT_ABI_SOFT
+            | FLAG_LARCH_FLOAT_ABI_DOUBLE => Some(ElfClass::Class64),
+            // RISC-V flags give the float ABI of either class.
+            FLAG_RISCV_FLOAT_ABI_SOFT | FLAG_RISCV_FLOAT_ABI_DOUBLE => None,
+            _ => Some(ElfClass::Class32),
+        }
+    }
+
+    /// Returns the machine required by the flags, if they tell. Libraries
+    /// without required flags are 32-bit ones of any machine.
+    pub fn machine(&self) -> Option<u16> {
+        let machine = match self.flags & FLAG_REQUIRED_MASK {
+            FLAG_SPARC_LIB64 => ElfMachine::MachineSparcv9,
+            FLAG_IA64_LIB64 => ElfMachine::MachineIa64,
+            FLAG_X8664_LIB64 | FLAG_X8664_LIBX32 => ElfMachine::MachineX8664,
+            FLAG_S390_LIB64 => ElfMachine::MachineS390,
+            FLAG_POWERPC_LIB64 => ElfMachine::MachinePpc64,
+            FLAG_MIPS64_LIBN32
+            | FLAG_MIPS64_LIBN64
+            | FLAG_MIPS_LIB32_NAN2008
+            | FLAG_MIPS64_LIBN32_NAN2008
+            | FLAG_MIPS64_LIBN64_NAN2008 => ElfMachine::MachineMips,
+            FLAG_ARM_LIBHF | FLAG_ARM_LIBSF => ElfMachine::MachineArm,
+            FLAG_AARCH64_LIB64 => ElfMachine::MachineAarch64,
+            FLAG_RISCV_FLOAT_ABI_SOFT | FLAG_RISCV_FLOAT_ABI_DOUBLE => ElfMachine::MachineRiscv,
+            _ => return None,
+        };
+        Some(machine as u16)
+    }
+
+    /// Whether the entry is an ELF library, as opposed to a libc4 a.out one.
+    pub fn is_elf(&self) -> bool {
+        self.flags & FLAG_TYPE_MASK != FLAG_LIBC4
+    }
+
+    /// Returns the index of the `glibc-hwcaps` subdirectory of the library,
+    /// if it is in one.
+    pub fn hwcaps_index(&self) -> Option<u32> {
+        if self.hwcap & DL_CACHE_HWCAP_EXTENSION != 0 {
+            Some(self.hwcap as u32)
+        } else {
+            None
+        }
+    }
+
+    /// Whether a library of this class and machine matches the flags.
+    pub fn matches(&self, class: ElfClass, machine: u16) -> bool {
+        self.is_elf()
+            && self.class().map_or(true, |c| c == class)
+            && self.machine().map_or(true, |m| m == machine)
+    }
+
+    /// Cross-checks the flags against the header of the file at `path`,
+    /// which `ldconfig` read to build the cache. A mismatch means the cache
+    /// is stale or the file was replaced.
+    pub fn check(&self, elf: &ElfView) -> bool {
+        self.matches(elf.class(), elf.machine())
+    }
+}
+
+impl<'a> LdCache<'a> {
+    /// Parses a cache in the old format, the new one, or the new one
+    /// following the old one, in which case only the new entries are
+    /// returned as ld.so does. Only little-endian caches are supported.
+    pub fn parse(data: &'a [u8]) -> Result<Self, Error> {
+        if data.starts_with(LD_CACHE_MAGIC_NEW) {
+            return Ok(LdCache {
+                entries: parse_new_entries(data)?,
+            });
+        }
+        if !data.starts_with(LD_CACHE_MAGIC_OLD) {
+            return Err(Error::Unsupported(String::from(
+                "unknown ld.so.cache format",
+            )));
+        }
+        let nlibs = old_nlibs(data)?;
+        let end = OLD_HEADER_SIZE + nlibs as usize * OLD_ENTRY_SIZE;
+        let new_start = (end + NEW_ALIGN - 1) & !(NEW_ALIGN - 1);
+        let entries = match data.get(new_start..) {
+            Some(new) if new.starts_with(LD_CACHE_MAGIC_NEW) => parse_new_entries(new)?,
+            _ => parse_old_entries(data)?,
+        };
+        Ok(LdCache { entries: entries })
+    }
+
+    /// Iterates over the entries for `soname`, most preferred first.
+    pub fn lookup<'s>(
+        &'s self,
+        soname: &'s str,
+    ) -> impl Iterator<Item = &'s LdCacheEntry<'a>> + 's {
+        self.entries.iter().filter(move |e| e.soname == soname)
+    }
+}
@@ -7,7 +7,20 @@ extern crate nom;
 #[macro_use]
 extern crate exe;
 
+#[macro_use]
+extern crate failure;
+
 extern crate libc;
+extern crate memmap2;
+
+#[cfg(feature = "demangle")]
+extern crate cpp_demangle;
+#[cfg(feature = "demangle")]
+extern crate rustc_demangle;
+
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
 
 #[allow(dead_code)]
 #[allow(unused_macros)]
@@ -19,3 +32,66 @@ pub use elf32::*;
 
 pub mod elf64;
 pub use elf64::*;
+
+pub mod error;
+pub use error::*;
+
+pub mod table;
+pub use table::*;
+
+pub mod dwarf;
+
+pub mod demangle;
+pub use demangle::*;
+
+pub mod symbols;
+pub use symbols::*;
+
+pub mod symbolizer;
+pub use symbolizer::*;
+
+pub mod archive;
+pub use archive::*;
+
+pub mod notes;
+pub use notes::*;
+
+pub mod exe_ext;
+pub use exe_ext::*;
+
+pub mod dynamic;
+pub use dynamic::*;
+
+pub mod relocations;
+pub use relocations::*;
+
+pub mod versions;
+pub use versions::*;
+
+pub mod size;
+pub use size::*;
+
+#[macro_use]
+pub mod file;
+pub use file::*;
+
+pub mod diff;
+pub use diff::*;
+
+pub mod ldcache;
+pub use ldcache::*;
+
+pub mod resolve;
+pub use resolve::*;
+
+pub mod symcheck;
+pub use symcheck::*;
+
+pub mod loader;
+pub use loader::*;
+
+pub mod link;
+pub use link::*;
+
+pub mod capi;
+pub use capi::*;
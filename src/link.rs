@@ -0,0 +1,1664 @@
+use enum_primitive::FromPrimitive;
+use nom;
+use std::borrow::Cow;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use error::Error;
+use file::Elf;
+use header::*;
+use relocations::*;
+use symbols::Symbol;
+use {Elf32, Elf64};
+
+/// Alignment of the `PT_LOAD` segments of executables. Sections sharing a
+/// page go in the same segment.
+const PAGE_SIZE: u64 = 0x1000;
+
+/// Largest output section with contents, which alignment padding could
+/// otherwise blow up.
+const MAX_SECTION_SIZE: u64 = 1 << 30;
+
+/// An output section of a `LinkConfig`, like an output section description
+/// of a linker script.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputSection {
+    pub name: String,
+    /// Start address, following the previous section when `None`.
+    pub address: Option<u64>,
+    /// Minimum alignment, raised to that of the input sections.
+    pub align: u64,
+    /// Patterns of the input sections, placed pattern by pattern in object
+    /// order: a section name, a prefix followed by `*`, or `COMMON` for the
+    /// common symbols.
+    pub inputs: Vec<String>,
+}
+
+impl OutputSection {
+    pub fn new(name: &str, inputs: &[&str]) -> Self {
+        OutputSection {
+            name: String::from(name),
+            address: None,
+            align: 1,
+            inputs: inputs.iter().map(|&i| String::from(i)).collect(),
+        }
+    }
+}
+
+/// The layout of a static link, a small subset of a GNU ld linker script.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkConfig {
+    /// Address of the first output section, when it has none of its own.
+    pub base: u64,
+    /// Name of the entry point symbol.
+    pub entry: String,
+    pub sections: Vec<OutputSection>,
+    /// Patterns of the input sections to drop, like `/DISCARD/`.
+    pub discard: Vec<String>,
+}
+
+impl LinkConfig {
+    /// Lays out `.text`, `.rodata`, `.data` and `.bss` one after the other
+    /// from `base`, and drops unwinding tables and notes.
+    pub fn new(base: u64) -> Self {
+        LinkConfig {
+            base: base,
+            entry: String::from("_start"),
+            sections: vec![
+                OutputSection::new(".text", &[".text", ".text.*"]),
+                OutputSection::new(
+                    ".rodata",
+                    &[".rodata", ".rodata.*", ".srodata", ".srodata.*"],
+                ),
+                OutputSection::new(".data", &[".data", ".data.*", ".sdata", ".sdata.*"]),
+                OutputSection::new(".bss", &[".sbss", ".sbss.*", ".bss", ".bss.*", "COMMON"]),
+            ],
+            discard: [".eh_frame", ".ARM.exidx*", ".ARM.extab*", ".note.*"]
+                .iter()
+                .map(|&d| String::from(d))
+                .collect(),
+        }
+    }
+}
+
+/// A section of a `LinkedImage`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkedSection {
+    pub name: String,
+    /// `SHT_NOBITS` when all its input sections are, `SHT_PROGBITS`
+    /// otherwise.
+    pub sh_type: u32,
+    /// Union of the flags of its input sections.
+    pub flags: u64,
+    pub addr: u64,
+    pub size: u64,
+    pub align: u64,
+    /// Empty for `SHT_NOBITS` sections.
+    pub data: Vec<u8>,
+}
+
+/// Relocatable files statically linked in memory: their allocated sections
+/// merged by name and laid out as a `LinkConfig` tells, global symbols
+/// resolved and relocations applied.
+///
+/// Only what freestanding code needs is supported: there is no dynamic
+/// linking, GOT, PLT, TLS or range extension thunk, and no relaxation but
+/// that of x86-64 `GOTPCRELX` loads and calls. The padding RISC-V
+/// `R_RISCV_ALIGN` relocations mark is trimmed to what alignment needs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkedImage {
+    pub machine: u16,
+    pub class: ElfClass,
+    /// `e_flags` of the objects, merged as in `merge_flags`.
+    pub flags: u32,
+    /// Address of the entry point symbol, if defined.
+    pub entry: Option<u64>,
+    /// The non-empty output sections, in the order of the config.
+    pub sections: Vec<LinkedSection>,
+    /// Addresses of the global symbols, and of the `__start_` and `__stop_`
+    /// symbols of output sections named like C identifiers and `_end`, as
+    /// GNU ld defines them.
+    pub symbols: BTreeMap<String, u64>,
+}
+
+/// Sections of a `LinkedImage` that share pages, which `executable` writes
+/// as one `PT_LOAD` segment.
+struct Segment<'s> {
+    start: u64,
+    /// End of the last section that is not `SHT_NOBITS`.
+    file_end: u64,
+    end: u64,
+    flags: u32,
+    sections: Vec<&'s LinkedSection>,
+}
+
+/// An allocated section of a relocatable file.
+struct InputSection<'a> {
+    name: &'a str,
+    sh_type: u32,
+    flags: u64,
+    size: u64,
+    align: u64,
+    data: Cow<'a, [u8]>,
+    relocations: Vec<Relocation>,
+}
+
+/// A relocatable file, independent of its class.
+struct Object<'a> {
+    machine: u16,
+    class: ElfClass,
+    flags: u32,
+    /// By section index.
+    sections: Vec<InputSection<'a>>,
+    /// By symbol index.
+    symbols: Vec<Symbol<'a>>,
+    /// Signatures and member sections of the COMDAT groups.
+    groups: Vec<(&'a str, Vec<usize>)>,
+}
+
+/// Parses the `SHT_GROUP` section `data` whose signature is symbol `info`,
+/// if it is a COMDAT group.
+fn comdat_group<'a>(
+    data: &[u8],
+    symbols: &[Symbol<'a>],
+    info: u32,
+) -> Result<Option<(&'a str, Vec<usize>)>, Error> {
+    let mut words = Vec::with_capacity(data.len() / 4);
+    for word in data.chunks(4).filter(|w| w.len() == 4) {
+        words.push(nom::le_u32(word)?.1);
+    }
+    match words.split_first() {
+        Some((&flags, members)) if flags & GRP_COMDAT != 0 => {
+            let signature = symbols
+                .get(info as usize)
+                .ok_or_else(|| Error::ParseError(String::from("bad group signature")))?;
+            Ok(Some((
+                signature.name,
+                members.iter().map(|&m| m as usize).collect(),
+            )))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Appends the `size` low bytes of `value` to `out`, in little-endian order.
+fn push(out: &mut Vec<u8>, value: u64, size: usize) {
+    out.extend_from_slice(&value.to_le_bytes()[..size]);
+}
+
+fn not_relocatable() -> Error {
+    Error::Unsupported(String::from("only relocatable files can be linked"))
+}
+
+fn out_of_bounds(name: Option<&str>) -> Error {
+    Error::ParseError(format!(
+        "section {} is out of bounds",
+        name.unwrap_or("<unnamed>")
+    ))
+}
+
+fn overflow(what: &str) -> Error {
+    Error::ParseError(format!("{} overflows", what))
+}
+
+fn object_from_elf64<'a>(elf: &Elf64<'a>) -> Result<Object<'a>, Error> {
+    if elf.header.e_type != ElfType::Rel as u16 {
+        return Err(not_relocatable());
+    }
+    let symbols = elf.symtab()?;
+    let mut sections = Vec::with_capacity(elf.sections.len());
+    for s in &elf.sections {
+        let flags = s.sh_flags;
+        let data = match flags & SHF_ALLOC {
+            0 => &[],
+            _ => elf
+                .section_data(s)
+                .ok_or_else(|| out_of_bounds(elf.section_name(s)))?,
+        };
+        sections.push(InputSection {
+            name: elf.section_name(s).unwrap_or(""),
+            sh_type: s.sh_type,
+            flags: flags,
+            size: s.sh_size,
+            align: s.sh_addralign.max(1),
+            data: Cow::Borrowed(data),
+            relocations: Vec::new(),
+        });
+    }
+    let mut groups = Vec::new();
+    for section in &elf.sections {
+        match section.sh_type {
+            SHT_REL | SHT_RELA => {
+                let relocations = elf.relocations(section)?;
+                if let Some(target) = sections.get_mut(section.sh_info as usize) {
+                    target.relocations.extend(relocations);
+                }
+            }
+            SHT_GROUP => {
+                let data = elf
+                    .section_data(section)
+                    .ok_or_else(|| out_of_bounds(elf.section_name(section)))?;
+                groups.extend(comdat_group(data, &symbols, section.sh_info)?);
+            }
+            _ => {}
+        }
+    }
+    Ok(Object {
+        machine: elf.header.e_machine,
+        class: ElfClass::Class64,
+        flags: elf.header.e_flags,
+        sections: sections,
+        symbols: symbols,
+        groups: groups,
+    })
+}
+
+fn object_from_elf32<'a>(elf: &Elf32<'a>) -> Result<Object<'a>, Error> {
+    if elf.header.e_type != ElfType::Rel as u16 {
+        return Err(not_relocatable());
+    }
+    let symbols = elf.symtab()?;
+    let mut sections = Vec::with_capacity(elf.sections.len());
+    for s in &elf.sections {
+        let flags = u64::from(s.sh_flags);
+        let data = match flags & SHF_ALLOC {
+            0 => &[],
+            _ => elf
+                .section_data(s)
+                .ok_or_else(|| out_of_bounds(elf.section_name(s)))?,
+        };
+        sections.push(InputSection {
+            name: elf.section_name(s).unwrap_or(""),
+            sh_type: s.sh_type,
+            flags: flags,
+            size: u64::from(s.sh_size),
+            align: u64::from(s.sh_addralign).max(1),
+            data: Cow::Borrowed(data),
+            relocations: Vec::new(),
+        });
+    }
+    let mut groups = Vec::new();
+    for section in &elf.sections {
+        match section.sh_type {
+            SHT_REL | SHT_RELA => {
+                let relocations = elf.relocations(section)?;
+                if let Some(target) = sections.get_mut(section.sh_info as usize) {
+                    target.relocations.extend(relocations);
+                }
+            }
+            SHT_GROUP => {
+                let data = elf
+                    .section_data(section)
+                    .ok_or_else(|| out_of_bounds(elf.section_name(section)))?;
+                groups.extend(comdat_group(data, &symbols, section.sh_info)?);
+            }
+            _ => {}
+        }
+    }
+    Ok(Object {
+        machine: elf.header.e_machine,
+        class: ElfClass::Class32,
+        flags: elf.header.e_flags,
+        sections: sections,
+        symbols: symbols,
+        groups: groups,
+    })
+}
+
+/// Returns where `offset` of a section moves to once the `(start, len)`
+/// byte ranges `deleted` are removed from it.
+fn moved(deleted: &[(u64, u64)], offset: u64) -> u64 {
+    offset
+        - deleted
+            .iter()
+            .filter(|&&(start, _)| start < offset)
+            .map(|&(start, len)| len.min(offset - start))
+            .sum::<u64>()
+}
+
+/// Returns the `e_flags` of an image linked from objects with `flags`, or
+/// an error if they were built for different ABIs: another EABI version or
+/// float ABI on ARM, another float ABI or RVE on RISC-V. The float ABI of
+/// ARM objects that leave it unspecified is compatible with both, and
+/// RISC-V images use compressed instructions or TSO if any object does.
+fn merge_flags(machine: u16, flags: &[u32]) -> Result<u32, Error> {
+    let arm_float = EF_ARM_ABI_FLOAT_SOFT | EF_ARM_ABI_FLOAT_HARD;
+    // The bits all objects must share, and those any of them may set
+    let (fixed, merged) = match ElfMachine::from_u16(machine) {
+        Some(ElfMachine::MachineArm) => (EF_ARM_EABIMASK, arm_float),
+        Some(ElfMachine::MachineRiscv) => (
+            EF_RISCV_FLOAT_ABI | EF_RISCV_RVE,
+            EF_RISCV_RVC | EF_RISCV_TSO,
+        ),
+        _ => (!0, 0),
+    };
+    let mut result = flags[0];
+    for &f in &flags[1..] {
+        if f & fixed != flags[0] & fixed {
+            return Err(Error::Unsupported(format!(
+                "objects with e_flags {:#x} and {:#x}",
+                flags[0], f
+            )));
+        }
+        result |= f & merged;
+    }
+    if machine == ElfMachine::MachineArm as u16 && result & arm_float == arm_float {
+        return Err(Error::Unsupported(String::from(
+            "objects with soft-float and hard-float ABIs",
+        )));
+    }
+    Ok(result)
+}
+
+/// Deletes the part of the padding of `R_RISCV_ALIGN` relocations that
+/// alignment does not need, as linkers must even when they do not relax,
+/// and moves the relocations and symbols past it. The padding left is
+/// rewritten as `nop`s.
+fn riscv_align(object: &mut Object) -> Result<(), Error> {
+    for idx in 0..object.sections.len() {
+        let mut aligns: Vec<(u64, u64)> = object.sections[idx]
+            .relocations
+            .iter()
+            .filter(|r| r.kind == R_RISCV_ALIGN)
+            .map(|r| (r.offset, r.addend.unwrap_or(0) as u64))
+            .collect();
+        if aligns.is_empty() {
+            continue;
+        }
+        aligns.sort();
+
+        let section = &mut object.sections[idx];
+        let mut data = section.data.to_vec();
+        let mut deleted = Vec::new();
+        let mut removed = 0;
+        for (offset, padding) in aligns {
+            let align = (padding + 2).next_power_of_two();
+            if align > section.align {
+                return Err(Error::Unsupported(format!(
+                    "R_RISCV_ALIGN to {} in {} aligned to {}",
+                    align, section.name, section.align
+                )));
+            }
+            let end = offset
+                .checked_add(padding)
+                .filter(|&end| end <= data.len() as u64)
+                .ok_or_else(|| {
+                    Error::ParseError(format!(
+                        "R_RISCV_ALIGN at {:#x} is out of {}",
+                        offset, section.name
+                    ))
+                })?;
+            let start = offset - removed;
+            let needed = align_up(start, align)? - start;
+            if needed > padding {
+                return Err(Error::ParseError(format!(
+                    "R_RISCV_ALIGN at {:#x} of {} lacks padding",
+                    offset, section.name
+                )));
+            }
+            let mut at = offset as usize;
+            while at + 4 <= (offset + needed) as usize {
+                data[at..at + 4].copy_from_slice(&[0x13, 0, 0, 0]);
+                at += 4;
+            }
+            if at + 2 <= (offset + needed) as usize {
+                data[at..at + 2].copy_from_slice(&[0x01, 0]);
+            }
+            deleted.push((offset + needed, end - offset - needed));
+            removed += padding - needed;
+        }
+
+        let mut kept = Vec::with_capacity(data.len() - removed as usize);
+        let mut from = 0;
+        for &(start, len) in &deleted {
+            kept.extend_from_slice(&data[from..start as usize]);
+            from = (start + len) as usize;
+        }
+        kept.extend_from_slice(&data[from..]);
+        section.data = Cow::Owned(kept);
+        section.size -= removed;
+        section.relocations.retain(|r| r.kind != R_RISCV_ALIGN);
+        for r in &mut section.relocations {
+            r.offset = moved(&deleted, r.offset);
+        }
+
+        let section_symbols: HashSet<u32> = object
+            .symbols
+            .iter()
+            .enumerate()
+            .filter(|&(_, s)| s.kind == STT_SECTION && s.shndx as usize == idx)
+            .map(|(i, _)| i as u32)
+            .collect();
+        for r in object
+            .sections
+            .iter_mut()
+            .flat_map(|s| s.relocations.iter_mut())
+            .filter(|r| section_symbols.contains(&r.sym))
+        {
+            r.addend = r.addend.map(|a| moved(&deleted, a as u64) as i64);
+        }
+        for symbol in object
+            .symbols
+            .iter_mut()
+            .filter(|s| s.kind != STT_SECTION && s.shndx as usize == idx)
+        {
+            let end = moved(&deleted, symbol.value.saturating_add(symbol.size));
+            symbol.value = moved(&deleted, symbol.value);
+            symbol.size = end - symbol.value;
+        }
+    }
+    Ok(())
+}
+
+fn matches(pattern: &str, name: &str) -> bool {
+    if pattern.ends_with('*') {
+        name.starts_with(&pattern[..pattern.len() - 1])
+    } else {
+        name == pattern
+    }
+}
+
+fn align_up(value: u64, align: u64) -> Result<u64, Error> {
+    match value % align {
+        0 => Some(value),
+        r => value.checked_add(align - r),
+    }
+    .ok_or_else(|| overflow(&format!("aligning {:#x} to {}", value, align)))
+}
+
+fn is_c_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// The definition of a global symbol that takes precedence.
+struct Definition {
+    object: usize,
+    symbol: usize,
+    bind: u8,
+    /// Size and alignment of common symbols.
+    common: Option<(u64, u64)>,
+}
+
+impl Definition {
+    /// Defined symbols take precedence over common ones, which take
+    /// precedence over weak ones.
+    fn rank(&self) -> u8 {
+        match (self.common, self.bind) {
+            (Some(_), _) => 1,
+            (None, STB_WEAK) => 0,
+            _ => 2,
+        }
+    }
+}
+
+/// A relocation to apply to an output section.
+struct Fixup<'a> {
+    section: usize,
+    /// Offset of the place in the output section.
+    offset: u64,
+    place: u64,
+    /// Symbol address, without the Thumb bit.
+    symbol: u64,
+    thumb: bool,
+    addend: Option<i64>,
+    kind: u32,
+    name: &'a str,
+}
+
+/// Why a relocation could not be applied.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Fault {
+    Unsupported,
+    OutOfRange,
+    Misaligned,
+    OutOfBounds,
+}
+
+struct Linker<'a, 'c> {
+    objects: Vec<Object<'a>>,
+    config: &'c LinkConfig,
+    machine: u16,
+    class: ElfClass,
+    discarded: Vec<Vec<bool>>,
+    definitions: HashMap<&'a str, Definition>,
+    /// Common symbols to allocate, in object and symbol table order.
+    commons: Vec<&'a str>,
+    /// Output section and offset of each input section.
+    placements: Vec<Vec<Option<(usize, u64)>>>,
+    common_placements: HashMap<&'a str, (usize, u64)>,
+    sections: Vec<LinkedSection>,
+}
+
+impl<'a, 'c> Linker<'a, 'c> {
+    /// Drops the sections matching the discard patterns and the repeated
+    /// COMDAT groups.
+    fn discard(&mut self) {
+        let mut signatures = HashSet::new();
+        for (idx, object) in self.objects.iter().enumerate() {
+            for section in 0..object.sections.len() {
+                let name = object.sections[section].name;
+                if self.config.discard.iter().any(|d| matches(d, name)) {
+                    self.discarded[idx][section] = true;
+                }
+            }
+            for &(signature, ref members) in &object.groups {
+                if signatures.insert(signature) {
+                    continue;
+                }
+                for &member in members {
+                    if let Some(discarded) = self.discarded[idx].get_mut(member) {
+                        *discarded = true;
+                    }
+                }
+            }
+        }
+    }
+
+    fn is_discarded(&self, object: usize, shndx: u16) -> bool {
+        shndx < SHN_LORESERVE
+            && self.discarded[object]
+                .get(shndx as usize)
+                .cloned()
+                .unwrap_or(true)
+    }
+
+    /// Picks the definition of each global symbol.
+    fn resolve(&mut self) -> Result<(), Error> {
+        for (idx, object) in self.objects.iter().enumerate() {
+            for (sym_idx, symbol) in object.symbols.iter().enumerate() {
+                if symbol.bind == STB_LOCAL || symbol.shndx == SHN_UNDEF {
+                    continue;
+                }
+                if self.is_discarded(idx, symbol.shndx) {
+                    continue;
+                }
+                let definition = Definition {
+                    object: idx,
+                    symbol: sym_idx,
+                    bind: symbol.bind,
+                    common: match symbol.shndx {
+                        SHN_COMMON => Some((symbol.size, symbol.value.max(1))),
+                        _ => None,
+                    },
+                };
+                match self.definitions.entry(symbol.name) {
+                    Entry::Vacant(entry) => {
+                        if definition.common.is_some() {
+                            self.commons.push(symbol.name);
+                        }
+                        entry.insert(definition);
+                    }
+                    Entry::Occupied(mut entry) => {
+                        let current = entry.get_mut();
+                        match (current.rank(), definition.rank()) {
+                            (2, 2) => {
+                                return Err(Error::ParseError(format!(
+                                    "multiple definition of `{}`",
+                                    symbol.name
+                                )))
+                            }
+                            (1, 1) => {
+                                let (size, align) = current.common.unwrap_or((0, 1));
+                                let (new_size, new_align) = definition.common.unwrap_or((0, 1));
+                                current.common = Some((size.max(new_size), align.max(new_align)));
+                            }
+                            (current_rank, rank) if rank > current_rank => {
+                                if definition.common.is_some() {
+                                    self.commons.push(symbol.name);
+                                }
+                                *current = definition;
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+        let definitions = &self.definitions;
+        self.commons
+            .retain(|name| definitions.get(name).map_or(false, |d| d.common.is_some()));
+        Ok(())
+    }
+
+    /// Assigns the input sections and common symbols to output sections,
+    /// and the output sections to addresses.
+    fn layout(&mut self) -> Result<(), Error> {
+        let mut location = self.config.base;
+        for (out_idx, output) in self.config.sections.iter().enumerate() {
+            let mut inputs = Vec::new();
+            let mut commons = Vec::new();
+            for pattern in &output.inputs {
+                if pattern == "COMMON" {
+                    for &name in &self.commons {
+                        if !self.common_placements.contains_key(name) {
+                            self.common_placements.insert(name, (out_idx, 0));
+                            commons.push(name);
+                        }
+                    }
+                    continue;
+                }
+                for (idx, object) in self.objects.iter().enumerate() {
+                    for (section_idx, section) in object.sections.iter().enumerate() {
+                        if section.flags & SHF_ALLOC == 0
+                            || self.discarded[idx][section_idx]
+                            || self.placements[idx][section_idx].is_some()
+                            || !matches(pattern, section.name)
+                        {
+                            continue;
+                        }
+                        self.placements[idx][section_idx] = Some((out_idx, 0));
+                        inputs.push((idx, section_idx));
+                    }
+                }
+            }
+
+            let mut section = LinkedSection {
+                name: output.name.clone(),
+                sh_type: SHT_NOBITS,
+                flags: 0,
+                addr: 0,
+                size: 0,
+                align: output.align.max(1),
+                data: Vec::new(),
+            };
+            let mut offset = 0;
+            for &(idx, section_idx) in &inputs {
+                let input = &self.objects[idx].sections[section_idx];
+                offset = align_up(offset, input.align)?;
+                self.placements[idx][section_idx] = Some((out_idx, offset));
+                offset = offset
+                    .checked_add(input.size)
+                    .ok_or_else(|| overflow(&output.name))?;
+                section.align = section.align.max(input.align);
+                section.flags |= input.flags;
+                if input.sh_type != SHT_NOBITS {
+                    section.sh_type = SHT_PROGBITS;
+                }
+            }
+            for &name in &commons {
+                let (size, align) = self.definitions[name].common.unwrap_or((0, 1));
+                offset = align_up(offset, align)?;
+                self.common_placements.insert(name, (out_idx, offset));
+                offset = offset
+                    .checked_add(size)
+                    .ok_or_else(|| overflow(&output.name))?;
+                section.align = section.align.max(align);
+                section.flags |= SHF_ALLOC | SHF_WRITE;
+            }
+            section.size = offset;
+            section.addr = align_up(output.address.unwrap_or(location), section.align)?;
+            location = section
+                .addr
+                .checked_add(section.size)
+                .ok_or_else(|| overflow(&output.name))?;
+            if self.class == ElfClass::Class32 && location > u64::from(u32::max_value()) + 1 {
+                return Err(Error::Unsupported(format!(
+                    "{} ends beyond 4 GiB",
+                    section.name
+                )));
+            }
+            if section.sh_type == SHT_PROGBITS {
+                if section.size > MAX_SECTION_SIZE {
+                    return Err(Error::Unsupported(format!(
+                        "{} is {:#x} bytes large",
+                        section.name, section.size
+                    )));
+                }
+                section.data = vec![0; section.size as usize];
+                for &(idx, section_idx) in &inputs {
+                    let input = &self.objects[idx].sections[section_idx];
+                    let start = self.placements[idx][section_idx].map_or(0, |p| p.1) as usize;
+                    let len = input.data.len().min(input.size as usize);
+                    section.data[start..start + len].copy_from_slice(&input.data[..len]);
+                }
+            }
+            self.sections.push(section);
+        }
+
+        for (idx, object) in self.objects.iter().enumerate() {
+            for (section_idx, section) in object.sections.iter().enumerate() {
+                if section.flags & SHF_ALLOC != 0
+                    && section.size != 0
+                    && !self.discarded[idx][section_idx]
+                    && self.placements[idx][section_idx].is_none()
+                {
+                    return Err(Error::Unsupported(format!(
+                        "section {} of object {} matches no output section",
+                        section.name, idx
+                    )));
+                }
+            }
+        }
+        if let Some(name) = self
+            .commons
+            .iter()
+            .find(|&name| !self.common_placements.contains_key(name))
+        {
+            return Err(Error::Unsupported(format!(
+                "common symbol `{}` matches no output section",
+                name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Returns the address of the symbol `symbol` of object `idx`, unless it
+    /// is undefined or in a dropped section.
+    fn address(&self, idx: usize, symbol: &Symbol) -> Option<u64> {
+        let (out, offset) = match symbol.shndx {
+            SHN_UNDEF => return None,
+            SHN_ABS => return Some(symbol.value),
+            // The value of common symbols is their alignment.
+            SHN_COMMON => {
+                return self
+                    .common_placements
+                    .get(symbol.name)
+                    .map(|&(out, offset)| self.sections[out].addr + offset)
+            }
+            shndx => self.placements[idx].get(shndx as usize).and_then(|&p| p)?,
+        };
+        Some(
+            self.sections[out]
+                .addr
+                .wrapping_add(offset)
+                .wrapping_add(symbol.value),
+        )
+    }
+
+    fn symbols(&self) -> BTreeMap<String, u64> {
+        let mut symbols: BTreeMap<String, u64> = self
+            .definitions
+            .iter()
+            .filter_map(|(&name, def)| {
+                let symbol = &self.objects[def.object].symbols[def.symbol];
+                self.address(def.object, symbol)
+                    .map(|address| (String::from(name), address))
+            })
+            .collect();
+        let mut end = self.config.base;
+        for section in &self.sections {
+            end = end.max(section.addr + section.size);
+            if !is_c_identifier(&section.name) {
+                continue;
+            }
+            symbols
+                .entry(format!("__start_{}", section.name))
+                .or_insert(section.addr);
+            symbols
+                .entry(format!("__stop_{}", section.name))
+                .or_insert(section.addr + section.size);
+        }
+        symbols.entry(String::from("_end")).or_insert(end);
+        symbols
+    }
+
+    /// Collects the relocations of the placed sections with the addresses
+    /// of their symbols.
+    fn fixups(&self, symbols: &BTreeMap<String, u64>) -> Result<Vec<Fixup<'a>>, Error> {
+        let mut fixups = Vec::new();
+        let mut undefined = Vec::new();
+        for (idx, object) in self.objects.iter().enumerate() {
+            for (section_idx, section) in object.sections.iter().enumerate() {
+                let (out, offset) = match self.placements[idx][section_idx] {
+                    Some(placement) => placement,
+                    None => continue,
+                };
+                for r in &section.relocations {
+                    if r.offset >= section.size {
+                        return Err(Error::ParseError(format!(
+                            "relocation at {:#x} is out of {} of object {}",
+                            r.offset, section.name, idx
+                        )));
+                    }
+                    let symbol = object.symbols.get(r.sym as usize).ok_or_else(|| {
+                        Error::ParseError(String::from("relocation symbol is out of bounds"))
+                    })?;
+                    let address = if r.sym == 0 {
+                        Some(0)
+                    } else if symbol.bind == STB_LOCAL {
+                        Some(self.address(idx, symbol).ok_or_else(|| {
+                            Error::ParseError(format!(
+                                "relocation in {} of object {} against a dropped section",
+                                section.name, idx
+                            ))
+                        })?)
+                    } else {
+                        symbols.get(symbol.name).cloned()
+                    };
+                    let address = match address {
+                        Some(address) => address,
+                        None if symbol.bind == STB_WEAK => 0,
+                        None => {
+                            if !undefined.contains(&symbol.name) {
+                                undefined.push(symbol.name);
+                            }
+                            continue;
+                        }
+                    };
+                    let thumb = self.machine == ElfMachine::MachineArm as u16 && address & 1 != 0;
+                    fixups.push(Fixup {
+                        section: out,
+                        offset: offset + r.offset,
+                        place: self.sections[out].addr + offset + r.offset,
+                        symbol: if thumb { address & !1 } else { address },
+                        thumb: thumb,
+                        addend: r.addend,
+                        kind: r.kind,
+                        name: symbol.name,
+                    });
+                }
+            }
+        }
+        if !undefined.is_empty() {
+            return Err(Error::ParseError(format!(
+                "undefined symbols: {}",
+                undefined.join(", ")
+            )));
+        }
+        Ok(fixups)
+    }
+
+    fn relocate(&mut self, fixups: &[Fixup]) -> Result<(), Error> {
+        // RISC-V `PCREL_LO12` relocations point to the instruction of their
+        // `PCREL_HI20` relocation.
+        let mut pcrel_hi = HashMap::new();
+        if self.machine == ElfMachine::MachineRiscv as u16 {
+            for f in fixups.iter().filter(|f| f.kind == R_RISCV_PCREL_HI20) {
+                let addend = f.addend.unwrap_or(0) as u64;
+                let value = f.symbol.wrapping_add(addend).wrapping_sub(f.place) as i64;
+                pcrel_hi.insert(f.place, value);
+            }
+        }
+        let (machine, class) = (self.machine, self.class);
+        for f in fixups {
+            let data = &mut self.sections[f.section].data;
+            let result = match f.addend {
+                Some(addend) => Ok(addend),
+                None if machine == ElfMachine::MachineArm as u16 => {
+                    arm_addend(data, f.offset, f.kind)
+                }
+                None => Err(Fault::Unsupported),
+            };
+            let result = result.and_then(|addend| {
+                let mut value = f.symbol.wrapping_add(addend as u64);
+                let mut pc = value.wrapping_sub(f.place) as i64;
+                if class == ElfClass::Class32 {
+                    value &= 0xffff_ffff;
+                    pc = pc as i32 as i64;
+                }
+                match ElfMachine::from_u16(machine) {
+                    Some(ElfMachine::MachineX8664) => {
+                        relocate_x86_64(data, f.offset, f.kind, value, pc)
+                    }
+                    Some(ElfMachine::MachineAarch64) => {
+                        relocate_aarch64(data, f.offset, f.kind, value, pc, f.place)
+                    }
+                    Some(ElfMachine::MachineRiscv) => {
+                        let hi = pcrel_hi.get(&value).cloned();
+                        let wide = class == ElfClass::Class64;
+                        relocate_riscv(data, f.offset, f.kind, value, pc, wide, hi)
+                    }
+                    Some(ElfMachine::MachineArm) => {
+                        relocate_arm(data, f.offset, f.kind, value, f.thumb, f.place)
+                    }
+                    _ => Err(Fault::Unsupported),
+                }
+            });
+            if let Err(fault) = result {
+                let kind = relocation_type_name(self.machine, f.kind)
+                    .map(String::from)
+                    .unwrap_or_else(|| format!("type {}", f.kind));
+                let what = format!("relocation {} against `{}`", kind, f.name);
+                return Err(match fault {
+                    Fault::Unsupported => Error::Unsupported(what),
+                    Fault::OutOfRange => Error::ParseError(format!("{} is out of range", what)),
+                    Fault::Misaligned => Error::ParseError(format!("{} is misaligned", what)),
+                    Fault::OutOfBounds => {
+                        Error::ParseError(format!("{} is out of its section", what))
+                    }
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+fn read(data: &[u8], offset: u64, size: usize) -> Result<u64, Fault> {
+    let start = offset as usize;
+    let bytes = data
+        .get(start..start.checked_add(size).ok_or(Fault::OutOfBounds)?)
+        .ok_or(Fault::OutOfBounds)?;
+    let mut word = [0; 8];
+    word[..size].copy_from_slice(bytes);
+    Ok(u64::from_le_bytes(word))
+}
+
+fn write(data: &mut [u8], offset: u64, size: usize, value: u64) -> Result<(), Fault> {
+    let start = offset as usize;
+    let bytes = data
+        .get_mut(start..start.checked_add(size).ok_or(Fault::OutOfBounds)?)
+        .ok_or(Fault::OutOfBounds)?;
+    bytes.copy_from_slice(&value.to_le_bytes()[..size]);
+    Ok(())
+}
+
+/// Replaces the bits `mask` of the `size` bytes at `offset` with `bits`.
+fn patch(data: &mut [u8], offset: u64, size: usize, mask: u64, bits: u64) -> Result<(), Fault> {
+    let value = read(data, offset, size)?;
+    write(data, offset, size, (value & !mask) | (bits & mask))
+}
+
+fn sign_extend(value: u64, bits: u32) -> i64 {
+    ((value << (64 - bits)) as i64) >> (64 - bits)
+}
+
+fn signed(value: i64, bits: u32) -> Result<(), Fault> {
+    if value >= -(1 << (bits - 1)) && value < 1 << (bits - 1) {
+        Ok(())
+    } else {
+        Err(Fault::OutOfRange)
+    }
+}
+
+fn unsigned(value: u64, bits: u32) -> Result<(), Fault> {
+    if bits >= 64 || value >> bits == 0 {
+        Ok(())
+    } else {
+        Err(Fault::OutOfRange)
+    }
+}
+
+/// Checks that `value` fits in `bits` as a signed or unsigned integer.
+fn either(value: u64, bits: u32) -> Result<(), Fault> {
+    signed(value as i64, bits).or_else(|_| unsigned(value, bits))
+}
+
+fn aligned(value: i64, align: i64) -> Result<(), Fault> {
+    if value % align == 0 {
+        Ok(())
+    } else {
+        Err(Fault::Misaligned)
+    }
+}
+
+/// Applies an x86-64 relocation, `value` being S + A and `pc` S + A - P.
+fn relocate_x86_64(
+    data: &mut [u8],
+    offset: u64,
+    kind: u32,
+    value: u64,
+    pc: i64,
+) -> Result<(), Fault> {
+    match kind {
+        R_X86_64_NONE => Ok(()),
+        R_X86_64_64 => write(data, offset, 8, value),
+        R_X86_64_PC64 => write(data, offset, 8, pc as u64),
+        R_X86_64_PC32 | R_X86_64_PLT32 => {
+            signed(pc, 32)?;
+            write(data, offset, 4, pc as u64)
+        }
+        R_X86_64_32 => {
+            unsigned(value, 32)?;
+            write(data, offset, 4, value)
+        }
+        R_X86_64_32S => {
+            signed(value as i64, 32)?;
+            write(data, offset, 4, value)
+        }
+        R_X86_64_16 => {
+            either(value, 16)?;
+            write(data, offset, 2, value)
+        }
+        R_X86_64_PC16 => {
+            signed(pc, 16)?;
+            write(data, offset, 2, pc as u64)
+        }
+        R_X86_64_8 => {
+            either(value, 8)?;
+            write(data, offset, 1, value)
+        }
+        R_X86_64_PC8 => {
+            signed(pc, 8)?;
+            write(data, offset, 1, pc as u64)
+        }
+        R_X86_64_GOTPCRELX | R_X86_64_REX_GOTPCRELX => {
+            // Without a GOT, `mov foo@GOTPCREL(%rip), %reg` becomes `lea
+            // foo(%rip), %reg` and `call *foo@GOTPCREL(%rip)` becomes
+            // `addr32 call foo`, as ld relaxes them.
+            let opcode = offset.checked_sub(2).ok_or(Fault::OutOfBounds)?;
+            let replacement = match read(data, opcode, 2)? {
+                op if op & 0xff == 0x8b => (op & !0xff) | 0x8d,
+                0x15ff => 0xe867,
+                _ => return Err(Fault::Unsupported),
+            };
+            signed(pc, 32)?;
+            write(data, opcode, 2, replacement)?;
+            write(data, offset, 4, pc as u64)
+        }
+        _ => Err(Fault::Unsupported),
+    }
+}
+
+/// Writes the immediate of an AArch64 `adr` or `adrp` instruction.
+fn patch_adr(data: &mut [u8], offset: u64, imm: i64) -> Result<(), Fault> {
+    let imm = imm as u64;
+    let bits = (imm & 3) << 29 | ((imm >> 2) & 0x7ffff) << 5;
+    patch(data, offset, 4, 0x6000_0000 | 0x7ffff << 5, bits)
+}
+
+/// Applies an AArch64 relocation, `value` being S + A and `pc` S + A - P.
+fn relocate_aarch64(
+    data: &mut [u8],
+    offset: u64,
+    kind: u32,
+    value: u64,
+    pc: i64,
+    place: u64,
+) -> Result<(), Fault> {
+    match kind {
+        R_AARCH64_NONE => Ok(()),
+        R_AARCH64_ABS64 => write(data, offset, 8, value),
+        R_AARCH64_ABS32 => {
+            either(value, 32)?;
+            write(data, offset, 4, value)
+        }
+        R_AARCH64_ABS16 => {
+            either(value, 16)?;
+            write(data, offset, 2, value)
+        }
+        R_AARCH64_PREL64 => write(data, offset, 8, pc as u64),
+        R_AARCH64_PREL32 => {
+            either(pc as u64, 32)?;
+            write(data, offset, 4, pc as u64)
+        }
+        R_AARCH64_PREL16 => {
+            either(pc as u64, 16)?;
+            write(data, offset, 2, pc as u64)
+        }
+        R_AARCH64_MOVW_UABS_G0
+        | R_AARCH64_MOVW_UABS_G0_NC
+        | R_AARCH64_MOVW_UABS_G1
+        | R_AARCH64_MOVW_UABS_G1_NC
+        | R_AARCH64_MOVW_UABS_G2
+        | R_AARCH64_MOVW_UABS_G2_NC
+        | R_AARCH64_MOVW_UABS_G3 => {
+            let group = (kind - R_AARCH64_MOVW_UABS_G0) / 2;
+            if kind != R_AARCH64_MOVW_UABS_G3 && (kind - R_AARCH64_MOVW_UABS_G0) % 2 == 0 {
+                unsigned(value, 16 * (group + 1))?;
+            }
+            let imm = (value >> (16 * group)) & 0xffff;
+            patch(data, offset, 4, 0xffff << 5, imm << 5)
+        }
+        R_AARCH64_LD_PREL_LO19 | R_AARCH64_CONDBR19 => {
+            signed(pc, 21)?;
+            aligned(pc, 4)?;
+            patch(data, offset, 4, 0x7ffff << 5, (pc as u64 >> 2) << 5)
+        }
+        R_AARCH64_TSTBR14 => {
+            signed(pc, 16)?;
+            aligned(pc, 4)?;
+            patch(data, offset, 4, 0x3fff << 5, (pc as u64 >> 2) << 5)
+        }
+        R_AARCH64_ADR_PREL_LO21 => {
+            signed(pc, 21)?;
+            patch_adr(data, offset, pc)
+        }
+        R_AARCH64_ADR_PREL_PG_HI21 | R_AARCH64_ADR_PREL_PG_HI21_NC => {
+            let pages = (value & !0xfff).wrapping_sub(place & !0xfff) as i64;
+            if kind == R_AARCH64_ADR_PREL_PG_HI21 {
+                signed(pages, 33)?;
+            }
+            patch_adr(data, offset, pages >> 12)
+        }
+        R_AARCH64_ADD_ABS_LO12_NC => patch(data, offset, 4, 0xfff << 10, (value & 0xfff) << 10),
+        R_AARCH64_LDST8_ABS_LO12_NC
+        | R_AARCH64_LDST16_ABS_LO12_NC
+        | R_AARCH64_LDST32_ABS_LO12_NC
+        | R_AARCH64_LDST64_ABS_LO12_NC
+        | R_AARCH64_LDST128_ABS_LO12_NC => {
+            let shift = match kind {
+                R_AARCH64_LDST8_ABS_LO12_NC => 0,
+                R_AARCH64_LDST16_ABS_LO12_NC => 1,
+                R_AARCH64_LDST32_ABS_LO12_NC => 2,
+                R_AARCH64_LDST64_ABS_LO12_NC => 3,
+                _ => 4,
+            };
+            let imm = (value & 0xfff) >> shift;
+            patch(data, offset, 4, 0xfff << 10, imm << 10)
+        }
+        R_AARCH64_JUMP26 | R_AARCH64_CALL26 => {
+            signed(pc, 28)?;
+            aligned(pc, 4)?;
+            patch(data, offset, 4, 0x3ff_ffff, pc as u64 >> 2)
+        }
+        _ => Err(Fault::Unsupported),
+    }
+}
+
+/// Returns the bits of an immediate in a RISC-V `B` type instruction.
+fn riscv_b_type(imm: u64) -> u64 {
+    (imm >> 12 & 1) << 31 | (imm >> 5 & 0x3f) << 25 | (imm >> 1 & 0xf) << 8 | (imm >> 11 & 1) << 7
+}
+
+/// Returns the bits of an immediate in a RISC-V `J` type instruction.
+fn riscv_j_type(imm: u64) -> u64 {
+    (imm >> 20 & 1) << 31
+        | (imm >> 1 & 0x3ff) << 21
+        | (imm >> 11 & 1) << 20
+        | (imm >> 12 & 0xff) << 12
+}
+
+/// Returns the upper 20 bits of `imm` for a RISC-V `lui` or `auipc`, rounded
+/// for the sign extension of the lower 12 bits.
+fn riscv_hi20(imm: u64) -> u64 {
+    imm.wrapping_add(0x800) & 0xffff_f000
+}
+
+fn riscv_i_type(imm: u64) -> u64 {
+    (imm & 0xfff) << 20
+}
+
+fn riscv_s_type(imm: u64) -> u64 {
+    (imm >> 5 & 0x7f) << 25 | (imm & 0x1f) << 7
+}
+
+/// Applies a RISC-V relocation, `value` being S + A and `pc` S + A - P.
+/// `pcrel_hi` is the value of the `PCREL_HI20` relocation at S for
+/// `PCREL_LO12` ones. Alignment and relaxation hints are ignored since
+/// nothing is relaxed.
+fn relocate_riscv(
+    data: &mut [u8],
+    offset: u64,
+    kind: u32,
+    value: u64,
+    pc: i64,
+    wide: bool,
+    pcrel_hi: Option<i64>,
+) -> Result<(), Fault> {
+    const B_TYPE: u64 = 0xfe00_0f80;
+    const U_TYPE: u64 = 0xffff_f000;
+    const I_TYPE: u64 = 0xfff0_0000;
+    const S_TYPE: u64 = 0xfe00_0f80;
+    let hi_range = |imm: i64| signed(imm.wrapping_add(0x800), 32);
+    let pc_bits = pc as u64;
+    match kind {
+        R_RISCV_NONE | R_RISCV_ALIGN | R_RISCV_RELAX => Ok(()),
+        R_RISCV_32 => {
+            either(value, 32)?;
+            write(data, offset, 4, value)
+        }
+        R_RISCV_64 => write(data, offset, 8, value),
+        R_RISCV_32_PCREL => {
+            signed(pc, 32)?;
+            write(data, offset, 4, pc_bits)
+        }
+        R_RISCV_BRANCH => {
+            signed(pc, 13)?;
+            aligned(pc, 2)?;
+            patch(data, offset, 4, B_TYPE, riscv_b_type(pc_bits))
+        }
+        R_RISCV_JAL => {
+            signed(pc, 21)?;
+            aligned(pc, 2)?;
+            patch(data, offset, 4, U_TYPE, riscv_j_type(pc_bits))
+        }
+        R_RISCV_CALL | R_RISCV_CALL_PLT => {
+            hi_range(pc)?;
+            patch(data, offset, 4, U_TYPE, riscv_hi20(pc_bits))?;
+            patch(data, offset + 4, 4, I_TYPE, riscv_i_type(pc_bits))
+        }
+        R_RISCV_PCREL_HI20 => {
+            hi_range(pc)?;
+            patch(data, offset, 4, U_TYPE, riscv_hi20(pc_bits))
+        }
+        R_RISCV_PCREL_LO12_I | R_RISCV_PCREL_LO12_S => {
+            let hi = pcrel_hi.ok_or(Fault::Unsupported)? as u64;
+            match kind {
+                R_RISCV_PCREL_LO12_I => patch(data, offset, 4, I_TYPE, riscv_i_type(hi)),
+                _ => patch(data, offset, 4, S_TYPE, riscv_s_type(hi)),
+            }
+        }
+        R_RISCV_HI20 => {
+            if wide {
+                hi_range(value as i64)?;
+            }
+            patch(data, offset, 4, U_TYPE, riscv_hi20(value))
+        }
+        R_RISCV_LO12_I => patch(data, offset, 4, I_TYPE, riscv_i_type(value)),
+        R_RISCV_LO12_S => patch(data, offset, 4, S_TYPE, riscv_s_type(value)),
+        R_RISCV_ADD8 | R_RISCV_ADD16 | R_RISCV_ADD32 | R_RISCV_ADD64 => {
+            let size = 1 << (kind - R_RISCV_ADD8);
+            let old = read(data, offset, size)?;
+            write(data, offset, size, old.wrapping_add(value))
+        }
+        R_RISCV_SUB8 | R_RISCV_SUB16 | R_RISCV_SUB32 | R_RISCV_SUB64 => {
+            let size = 1 << (kind - R_RISCV_SUB8);
+            let old = read(data, offset, size)?;
+            write(data, offset, size, old.wrapping_sub(value))
+        }
+        R_RISCV_SET6 => patch(data, offset, 1, 0x3f, value),
+        R_RISCV_SUB6 => {
+            let old = read(data, offset, 1)?;
+            patch(data, offset, 1, 0x3f, old.wrapping_sub(value))
+        }
+        R_RISCV_SET8 => write(data, offset, 1, value),
+        R_RISCV_SET16 => write(data, offset, 2, value),
+        R_RISCV_SET32 => write(data, offset, 4, value),
+        R_RISCV_RVC_BRANCH => {
+            signed(pc, 9)?;
+            aligned(pc, 2)?;
+            let bits = (pc_bits >> 8 & 1) << 12
+                | (pc_bits >> 3 & 3) << 10
+                | (pc_bits >> 6 & 3) << 5
+                | (pc_bits >> 1 & 3) << 3
+                | (pc_bits >> 5 & 1) << 2;
+            patch(data, offset, 2, 0x1c7c, bits)
+        }
+        R_RISCV_RVC_JUMP => {
+            signed(pc, 12)?;
+            aligned(pc, 2)?;
+            let bits = (pc_bits >> 11 & 1) << 12
+                | (pc_bits >> 4 & 1) << 11
+                | (pc_bits >> 8 & 3) << 9
+                | (pc_bits >> 10 & 1) << 8
+                | (pc_bits >> 6 & 1) << 7
+                | (pc_bits >> 7 & 1) << 6
+                | (pc_bits >> 1 & 7) << 3
+                | (pc_bits >> 5 & 1) << 2;
+            patch(data, offset, 2, 0x1ffc, bits)
+        }
+        _ => Err(Fault::Unsupported),
+    }
+}
+
+/// Reads the two halfwords of a 32-bit Thumb instruction.
+fn read_thumb(data: &[u8], offset: u64) -> Result<(u64, u64), Fault> {
+    Ok((read(data, offset, 2)?, read(data, offset + 2, 2)?))
+}
+
+fn write_thumb(data: &mut [u8], offset: u64, (hi, lo): (u64, u64)) -> Result<(), Fault> {
+    write(data, offset, 2, hi)?;
+    write(data, offset + 2, 2, lo)
+}
+
+/// Returns the immediate of the Thumb `bl`, `blx` or `b.w` at `offset`.
+fn thumb_branch(data: &[u8], offset: u64) -> Result<i64, Fault> {
+    let (hi, lo) = read_thumb(data, offset)?;
+    let s = hi >> 10 & 1;
+    let i1 = !(lo >> 13 ^ s) & 1;
+    let i2 = !(lo >> 11 ^ s) & 1;
+    let imm = s << 24 | i1 << 23 | i2 << 22 | (hi & 0x3ff) << 12 | (lo & 0x7ff) << 1;
+    Ok(sign_extend(imm, 25))
+}
+
+/// Writes the immediate of the Thumb `bl`, `blx` or `b.w` at `offset`, and
+/// bit 12 of the second halfword that tells `bl` from `blx`.
+fn patch_thumb_branch(data: &mut [u8], offset: u64, imm: i64, bit12: u64) -> Result<(), Fault> {
+    let (hi, lo) = read_thumb(data, offset)?;
+    let imm = imm as u64;
+    let s = imm >> 24 & 1;
+    let j1 = (!(imm >> 23) ^ s) & 1;
+    let j2 = (!(imm >> 22) ^ s) & 1;
+    let hi = (hi & 0xf800) | s << 10 | (imm >> 12 & 0x3ff);
+    let lo = (lo & 0xc000) | j1 << 13 | bit12 << 12 | j2 << 11 | (imm >> 1 & 0x7ff);
+    write_thumb(data, offset, (hi, lo))
+}
+
+/// Returns the 16-bit immediate of the ARM `movw` or `movt` at `offset`.
+fn arm_mov(data: &[u8], offset: u64) -> Result<u64, Fault> {
+    let insn = read(data, offset, 4)?;
+    Ok((insn >> 4 & 0xf000) | (insn & 0xfff))
+}
+
+/// Returns the 16-bit immediate of the Thumb `movw` or `movt` at `offset`.
+fn thumb_mov(data: &[u8], offset: u64) -> Result<u64, Fault> {
+    let (hi, lo) = read_thumb(data, offset)?;
+    Ok((hi & 0xf) << 12 | (hi >> 10 & 1) << 11 | (lo >> 12 & 7) << 8 | (lo & 0xff))
+}
+
+fn patch_thumb_mov(data: &mut [u8], offset: u64, imm: u64) -> Result<(), Fault> {
+    let (hi, lo) = read_thumb(data, offset)?;
+    let hi = (hi & 0xfbf0) | (imm >> 12 & 0xf) | (imm >> 11 & 1) << 10;
+    let lo = (lo & 0x8f00) | (imm >> 8 & 7) << 12 | (imm & 0xff);
+    write_thumb(data, offset, (hi, lo))
+}
+
+/// Returns the addend stored in the place of an ARM `SHT_REL` relocation.
+fn arm_addend(data: &[u8], offset: u64, kind: u32) -> Result<i64, Fault> {
+    Ok(match kind {
+        R_ARM_NONE | R_ARM_V4BX => 0,
+        R_ARM_ABS32 | R_ARM_REL32 => read(data, offset, 4)? as i32 as i64,
+        R_ARM_PREL31 => sign_extend(read(data, offset, 4)?, 31),
+        R_ARM_CALL | R_ARM_JUMP24 => {
+            let insn = read(data, offset, 4)?;
+            // The H bit of `blx`.
+            let half = if insn >> 28 == 0xf { insn >> 23 & 2 } else { 0 };
+            sign_extend((insn & 0xff_ffff) << 2 | half, 26)
+        }
+        R_ARM_THM_CALL | R_ARM_THM_JUMP24 => thumb_branch(data, offset)?,
+        R_ARM_THM_JUMP19 => {
+            let (hi, lo) = read_thumb(data, offset)?;
+            let imm = (hi >> 10 & 1) << 20
+                | (lo >> 11 & 1) << 19
+                | (lo >> 13 & 1) << 18
+                | (hi & 0x3f) << 12
+                | (lo & 0x7ff) << 1;
+            sign_extend(imm, 21)
+        }
+        R_ARM_THM_JUMP11 => sign_extend((read(data, offset, 2)? & 0x7ff) << 1, 12),
+        R_ARM_THM_JUMP8 => sign_extend((read(data, offset, 2)? & 0xff) << 1, 9),
+        R_ARM_MOVW_ABS_NC | R_ARM_MOVT_ABS => sign_extend(arm_mov(data, offset)?, 16),
+        R_ARM_THM_MOVW_ABS_NC | R_ARM_THM_MOVT_ABS => sign_extend(thumb_mov(data, offset)?, 16),
+        _ => return Err(Fault::Unsupported),
+    })
+}
+
+/// Applies an ARM relocation, `value` being S + A, and `thumb` telling
+/// whether S is a Thumb function. Calls switch between `bl` and `blx` as
+/// the target needs, other branches cannot change state without a veneer.
+fn relocate_arm(
+    data: &mut [u8],
+    offset: u64,
+    kind: u32,
+    value: u64,
+    thumb: bool,
+    place: u64,
+) -> Result<(), Fault> {
+    let t = if thumb { 1 } else { 0 };
+    let pc = value.wrapping_sub(place) as i32 as i64;
+    match kind {
+        R_ARM_NONE | R_ARM_V4BX => Ok(()),
+        R_ARM_ABS32 => write(data, offset, 4, value | t),
+        R_ARM_REL32 => write(data, offset, 4, (value | t).wrapping_sub(place)),
+        R_ARM_PREL31 => {
+            let pc = (value | t).wrapping_sub(place) as i32 as i64;
+            signed(pc, 31)?;
+            patch(data, offset, 4, 0x7fff_ffff, pc as u64)
+        }
+        R_ARM_CALL | R_ARM_JUMP24 => {
+            signed(pc, 26)?;
+            let insn = read(data, offset, 4)?;
+            let imm = pc as u64 >> 2 & 0xff_ffff;
+            let insn = if thumb {
+                if kind == R_ARM_JUMP24 || (insn >> 28 != 0xe && insn >> 28 != 0xf) {
+                    return Err(Fault::Unsupported);
+                }
+                0xfa00_0000 | (pc as u64 >> 1 & 1) << 24 | imm
+            } else {
+                aligned(pc, 4)?;
+                match insn >> 28 {
+                    0xf => 0xeb00_0000 | imm,
+                    _ => (insn & 0xff00_0000) | imm,
+                }
+            };
+            write(data, offset, 4, insn)
+        }
+        R_ARM_THM_CALL => {
+            if thumb {
+                signed(pc, 25)?;
+                patch_thumb_branch(data, offset, pc, 1)
+            } else {
+                // `blx` is relative to the word-aligned address.
+                let pc = value.wrapping_sub(place & !3) as i32 as i64;
+                signed(pc, 25)?;
+                aligned(pc, 4)?;
+                patch_thumb_branch(data, offset, pc, 0)
+            }
+        }
+        R_ARM_THM_JUMP24 => {
+            if !thumb {
+                return Err(Fault::Unsupported);
+            }
+            signed(pc, 25)?;
+            patch_thumb_branch(data, offset, pc, 1)
+        }
+        R_ARM_THM_JUMP19 => {
+            signed(pc, 21)?;
+            let (hi, lo) = read_thumb(data, offset)?;
+            let imm = pc as u64;
+            let hi = (hi & 0xfbc0) | (imm >> 20 & 1) << 10 | (imm >> 12 & 0x3f);
+            let lo =
+                (lo & 0xd000) | (imm >> 18 & 1) << 13 | (imm >> 19 & 1) << 11 | (imm >> 1 & 0x7ff);
+            write_thumb(data, offset, (hi, lo))
+        }
+        R_ARM_THM_JUMP11 => {
+            signed(pc, 12)?;
+            patch(data, offset, 2, 0x7ff, pc as u64 >> 1)
+        }
+        R_ARM_THM_JUMP8 => {
+            signed(pc, 9)?;
+            patch(data, offset, 2, 0xff, pc as u64 >> 1)
+        }
+        R_ARM_MOVW_ABS_NC | R_ARM_MOVT_ABS => {
+            let imm = match kind {
+                R_ARM_MOVW_ABS_NC => (value | t) & 0xffff,
+                _ => value >> 16 & 0xffff,
+            };
+            patch(
+                data,
+                offset,
+                4,
+                0xf_0fff,
+                (imm & 0xf000) << 4 | (imm & 0xfff),
+            )
+        }
+        R_ARM_THM_MOVW_ABS_NC | R_ARM_THM_MOVT_ABS => {
+            let imm = match kind {
+                R_ARM_THM_MOVW_ABS_NC => (value | t) & 0xffff,
+                _ => value >> 16 & 0xffff,
+            };
+            patch_thumb_mov(data, offset, imm)
+        }
+        _ => Err(Fault::Unsupported),
+    }
+}
+
+impl LinkedImage {
+    /// Links the relocatable files `objects`, all of the same machine among
+    /// x86-64, AArch64, RISC-V and ARM, as `config` lays them out.
+    ///
+    /// Like GNU ld, a defined global symbol takes precedence over common
+    /// and weak ones, repeated COMDAT groups are dropped, and weak
+    /// references to undefined symbols resolve to 0. Allocated sections
+    /// matching no output section are an error rather than orphans.
+    pub fn new(objects: &[Elf], config: &LinkConfig) -> Result<Self, Error> {
+        let mut objects = objects
+            .iter()
+            .map(|elf| match *elf {
+                Elf::Elf32(ref e) => object_from_elf32(e),
+                Elf::Elf64(ref e) => object_from_elf64(e),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let (machine, class) = match objects.first() {
+            Some(object) => (object.machine, object.class),
+            None => return Err(Error::Unsupported(String::from("nothing to link"))),
+        };
+        if objects
+            .iter()
+            .any(|o| o.machine != machine || o.class != class)
+        {
+            return Err(Error::Unsupported(String::from(
+                "objects are for different machines",
+            )));
+        }
+        match ElfMachine::from_u16(machine) {
+            Some(ElfMachine::MachineX8664)
+            | Some(ElfMachine::MachineAarch64)
+            | Some(ElfMachine::MachineRiscv)
+            | Some(ElfMachine::MachineArm) => {}
+            _ => {
+                return Err(Error::Unsupported(format!(
+                    "linking for machine {}",
+                    machine
+                )))
+            }
+        }
+        let flags: Vec<u32> = objects.iter().map(|o| o.flags).collect();
+        let flags = merge_flags(machine, &flags)?;
+        if machine == ElfMachine::MachineRiscv as u16 {
+            for object in &mut objects {
+                riscv_align(object)?;
+            }
+        }
+
+        let mut linker = Linker {
+            discarded: objects
+                .iter()
+                .map(|o| vec![false; o.sections.len()])
+                .collect(),
+            placements: objects
+                .iter()
+                .map(|o| vec![None; o.sections.len()])
+                .collect(),
+            objects: objects,
+            config: config,
+            machine: machine,
+            class: class,
+            definitions: HashMap::new(),
+            commons: Vec::new(),
+            common_placements: HashMap::new(),
+            sections: Vec::new(),
+        };
+        linker.discard();
+        linker.resolve()?;
+        linker.layout()?;
+        let symbols = linker.symbols();
+        let fixups = linker.fixups(&symbols)?;
+        linker.relocate(&fixups)?;
+
+        Ok(LinkedImage {
+            machine: machine,
+            class: class,
+            flags: flags,
+            entry: symbols.get(&config.entry).cloned(),
+            sections: linker
+                .sections
+                .into_iter()
+                .filter(|s| s.size != 0)
+                .collect(),
+            symbols: symbols,
+        })
+    }
+
+    /// Returns the output section named `name`.
+    pub fn section(&self, name: &str) -> Option<&LinkedSection> {
+        self.sections.iter().find(|s| s.name == name)
+    }
+
+    /// Returns the contents of the sections that are not `SHT_NOBITS`, from
+    /// the lowest address to the end of the highest one with the gaps
+    /// zero-filled, as `objcopy -O binary` writes them.
+    pub fn flat(&self) -> Vec<u8> {
+        let sections: Vec<&LinkedSection> = self
+            .sections
+            .iter()
+            .filter(|s| s.sh_type != SHT_NOBITS)
+            .collect();
+        let start = sections.iter().map(|s| s.addr).min().unwrap_or(0);
+        let end = sections.iter().map(|s| s.addr + s.size).max().unwrap_or(0);
+        let mut data = vec![0; (end - start) as usize];
+        for section in sections {
+            let offset = (section.addr - start) as usize;
+            data[offset..offset + section.data.len()].copy_from_slice(&section.data);
+        }
+        data
+    }
+
+    /// Returns a statically linked executable of the image: an ELF header
+    /// with `e_entry` the entry point, or 0 if it is undefined, and one
+    /// `PT_LOAD` segment for each run of sections sharing pages, with the
+    /// union of their permissions. There are no section headers.
+    pub fn executable(&self) -> Vec<u8> {
+        let mut sections: Vec<&LinkedSection> = self.sections.iter().collect();
+        sections.sort_by_key(|s| s.addr);
+        let mut segments: Vec<Segment> = Vec::new();
+        for section in sections {
+            let mut flags = PF_R;
+            if section.flags & SHF_WRITE != 0 {
+                flags |= PF_W;
+            }
+            if section.flags & SHF_EXECINSTR != 0 {
+                flags |= PF_X;
+            }
+            let end = section.addr + section.size;
+            let file_end = match section.sh_type {
+                SHT_NOBITS => None,
+                _ => Some(end),
+            };
+            match segments.last_mut() {
+                Some(ref mut segment)
+                    if section.addr / PAGE_SIZE <= (segment.end - 1) / PAGE_SIZE =>
+                {
+                    if let Some(file_end) = file_end {
+                        segment.file_end = file_end;
+                    }
+                    segment.end = segment.end.max(end);
+                    segment.flags |= flags;
+                    segment.sections.push(section);
+                    continue;
+                }
+                _ => {}
+            }
+            segments.push(Segment {
+                start: section.addr,
+                file_end: file_end.unwrap_or(section.addr),
+                end: end,
+                flags: flags,
+                sections: vec![section],
+            });
+        }
+
+        let (word, ehsize, phentsize) = match self.class {
+            ElfClass::Class32 => (4, 52, 32),
+            ElfClass::Class64 => (8, 64, 56),
+        };
+        let phoff = ehsize as u64;
+        let mut out = Vec::new();
+        out.extend_from_slice(&[0x7f, b'E', b'L', b'F', self.class as u8, 1, 1]);
+        out.resize(16, 0);
+        push(&mut out, ElfType::Exec as u64, 2);
+        push(&mut out, u64::from(self.machine), 2);
+        push(&mut out, 1, 4);
+        push(&mut out, self.entry.unwrap_or(0), word);
+        push(&mut out, phoff, word);
+        push(&mut out, 0, word);
+        push(&mut out, u64::from(self.flags), 4);
+        push(&mut out, ehsize as u64, 2);
+        push(&mut out, phentsize as u64, 2);
+        push(&mut out, segments.len() as u64, 2);
+        push(&mut out, 0, 6);
+
+        // File offsets are congruent to addresses modulo the page size.
+        let mut offset = phoff + (phentsize * segments.len()) as u64;
+        let mut offsets = Vec::with_capacity(segments.len());
+        for segment in &segments {
+            offset += segment.start.wrapping_sub(offset) % PAGE_SIZE;
+            offsets.push(offset);
+            let filesz = segment.file_end - segment.start;
+            let memsz = segment.end - segment.start;
+            push(&mut out, u64::from(PT_LOAD), 4);
+            if self.class == ElfClass::Class64 {
+                push(&mut out, u64::from(segment.flags), 4);
+            }
+            for &value in &[offset, segment.start, segment.start, filesz, memsz] {
+                push(&mut out, value, word);
+            }
+            if self.class == ElfClass::Class32 {
+                push(&mut out, u64::from(segment.flags), 4);
+            }
+            push(&mut out, PAGE_SIZE, word);
+            offset += filesz;
+        }
+
+        out.resize(offset as usize, 0);
+        for (segment, &offset) in segments.iter().zip(&offsets) {
+            for section in segment.sections.iter().filter(|s| s.sh_type != SHT_NOBITS) {
+                let start = (offset + section.addr - segment.start) as usize;
+                out[start..start + section.data.len()].copy_from_slice(&section.data);
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,358 @@
+use error::Error;
+use header::*;
+use relocations::*;
+use symbols::Symbol;
+use versions::SymbolVersion;
+use {Elf32, Elf64};
+
+/// Granularity of the mappings, to which the start of the image is rounded
+/// down.
+const PAGE_SIZE: u64 = 0x1000;
+
+/// What a relocation type does, for the types the loader applies.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Action {
+    /// Base plus addend.
+    Relative,
+    /// Symbol address, plus the explicit addend on some processors.
+    Symbol { addend: bool },
+    /// Symbol plus addend, over `size` bytes.
+    Absolute { size: usize },
+}
+
+fn action(machine: u16, kind: u32, word_size: usize) -> Option<Action> {
+    let word = Action::Absolute { size: word_size };
+    let half = Action::Absolute { size: 4 };
+    let action = match machine {
+        m if m == ElfMachine::MachineX8664 as u16 => match kind {
+            R_X86_64_RELATIVE => Action::Relative,
+            R_X86_64_GLOB_DAT | R_X86_64_JUMP_SLOT => Action::Symbol { addend: false },
+            R_X86_64_64 => word,
+            R_X86_64_32 => half,
+            _ => return None,
+        },
+        m if m == ElfMachine::Machine386 as u16 => match kind {
+            R_386_RELATIVE => Action::Relative,
+            R_386_GLOB_DAT | R_386_JMP_SLOT => Action::Symbol { addend: false },
+            R_386_32 => word,
+            _ => return None,
+        },
+        m if m == ElfMachine::MachineAarch64 as u16 => match kind {
+            R_AARCH64_RELATIVE => Action::Relative,
+            R_AARCH64_GLOB_DAT | R_AARCH64_JUMP_SLOT => Action::Symbol { addend: true },
+            R_AARCH64_ABS64 => word,
+            R_AARCH64_ABS32 => half,
+            _ => return None,
+        },
+        m if m == ElfMachine::MachineArm as u16 => match kind {
+            R_ARM_RELATIVE => Action::Relative,
+            R_ARM_GLOB_DAT | R_ARM_JUMP_SLOT => Action::Symbol { addend: false },
+            R_ARM_ABS32 => word,
+            _ => return None,
+        },
+        m if m == ElfMachine::MachineRiscv as u16 => match kind {
+            R_RISCV_RELATIVE => Action::Relative,
+            R_RISCV_JUMP_SLOT => Action::Symbol { addend: true },
+            R_RISCV_64 => word,
+            R_RISCV_32 => half,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(action)
+}
+
+/// The memory image of a file loaded at a chosen address, with its
+/// relocations applied, as ld.so would map it before running any code.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadedImage {
+    /// Address of the first byte of `data`.
+    pub base: u64,
+    /// Difference between run-time and link-time addresses, `l_addr` in
+    /// glibc.
+    pub bias: u64,
+    /// From the first page of the first `PT_LOAD` segment to the end of the
+    /// last one. Gaps between segments are zero-filled.
+    pub data: Vec<u8>,
+    /// Relocations whose symbol the resolver did not find or that cannot be
+    /// read, left untouched. Weak references resolve to 0 instead.
+    pub unresolved: Vec<Relocation>,
+    /// Relocations of other types, such as TLS and `IRELATIVE` ones, left
+    /// untouched.
+    pub skipped: Vec<Relocation>,
+}
+
+/// Where and how a file is loaded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadOptions {
+    /// Address of the first byte of the image.
+    pub base: u64,
+    /// Largest span of the `PT_LOAD` segments an image is allocated for.
+    pub max_size: u64,
+}
+
+impl LoadOptions {
+    /// Loads at `base` images of up to 1 GiB.
+    pub fn new(base: u64) -> Self {
+        LoadOptions {
+            base: base,
+            max_size: 1 << 30,
+        }
+    }
+}
+
+/// A `PT_LOAD` segment and its bytes in the file.
+struct Segment<'a> {
+    vaddr: u64,
+    memsz: u64,
+    data: &'a [u8],
+}
+
+/// A relocation with its symbol, if any, or the error reading it.
+struct Reloc<'a> {
+    relocation: Relocation,
+    symbol: Option<Result<Symbol<'a>, Error>>,
+    version: Option<SymbolVersion<'a>>,
+}
+
+struct LoadInput<'a> {
+    machine: u16,
+    word_size: usize,
+    segments: Vec<Segment<'a>>,
+    /// Offsets of the relative relocations of `DT_RELR`.
+    relr: Vec<u64>,
+    relocations: Vec<Reloc<'a>>,
+}
+
+fn out_of_bounds(offset: u64) -> Error {
+    Error::ParseError(format!("relocation at {:#x} is out of the image", offset))
+}
+
+impl<'a> LoadInput<'a> {
+    fn load<F>(self, options: &LoadOptions, mut resolve: F) -> Result<LoadedImage, Error>
+    where
+        F: FnMut(&Symbol<'a>, Option<&SymbolVersion<'a>>) -> Option<u64>,
+    {
+        let start = self
+            .segments
+            .iter()
+            .map(|s| s.vaddr)
+            .min()
+            .ok_or_else(|| Error::MissingSection(String::from("PT_LOAD")))?
+            & !(PAGE_SIZE - 1);
+        let mut end = start;
+        for segment in &self.segments {
+            let segment_end = segment
+                .vaddr
+                .checked_add(segment.memsz)
+                .ok_or_else(|| Error::ParseError(String::from("segment end overflows")))?;
+            end = end.max(segment_end);
+        }
+        if end - start > options.max_size {
+            return Err(Error::ParseError(format!(
+                "image of {:#x} bytes is too large",
+                end - start
+            )));
+        }
+        let mut image = LoadedImage {
+            base: options.base,
+            bias: options.base.wrapping_sub(start),
+            data: vec![0; (end - start) as usize],
+            unresolved: Vec::new(),
+            skipped: Vec::new(),
+        };
+        for segment in &self.segments {
+            let offset = (segment.vaddr - start) as usize;
+            let len = segment.data.len().min(segment.memsz as usize);
+            image.data[offset..offset + len].copy_from_slice(&segment.data[..len]);
+        }
+
+        let word_size = self.word_size;
+        for &offset in &self.relr {
+            let addend = image.read(offset, word_size)?;
+            image.write(offset, word_size, addend.wrapping_add(image.bias))?;
+        }
+        for reloc in self.relocations {
+            let r = reloc.relocation;
+            let action = match action(self.machine, r.kind, word_size) {
+                Some(action) => action,
+                None => {
+                    image.skipped.push(r);
+                    continue;
+                }
+            };
+            let size = match action {
+                Action::Absolute { size } => size,
+                _ => word_size,
+            };
+            let addend = match r.addend {
+                Some(addend) => addend as u64,
+                None => image.read(r.offset, size)?,
+            };
+            let value = if action == Action::Relative {
+                image.bias.wrapping_add(addend)
+            } else {
+                let address = match reloc.symbol {
+                    None => 0,
+                    Some(Err(_)) => {
+                        image.unresolved.push(r);
+                        continue;
+                    }
+                    Some(Ok(ref symbol)) => match resolve(symbol, reloc.version.as_ref()) {
+                        Some(address) => address,
+                        None if symbol.bind == STB_WEAK => 0,
+                        None => {
+                            image.unresolved.push(r);
+                            continue;
+                        }
+                    },
+                };
+                match action {
+                    Action::Symbol { addend: false } => address,
+                    _ => address.wrapping_add(addend),
+                }
+            };
+            image.write(r.offset, size, value)?;
+        }
+        Ok(image)
+    }
+}
+
+impl LoadedImage {
+    /// Returns the run-time address of the link-time address `vaddr`.
+    pub fn address(&self, vaddr: u64) -> u64 {
+        vaddr.wrapping_add(self.bias)
+    }
+
+    /// Returns the bytes of `data` at the link-time address `offset`.
+    fn range(&self, offset: u64, size: usize) -> Result<(usize, usize), Error> {
+        let begin = self
+            .address(offset)
+            .checked_sub(self.base)
+            .map(|o| o as usize)
+            .filter(|&o| {
+                o.checked_add(size)
+                    .map_or(false, |end| end <= self.data.len())
+            })
+            .ok_or_else(|| out_of_bounds(offset))?;
+        Ok((begin, begin + size))
+    }
+
+    fn read(&self, offset: u64, size: usize) -> Result<u64, Error> {
+        let (begin, end) = self.range(offset, size)?;
+        let mut bytes = [0; 8];
+        bytes[..size].copy_from_slice(&self.data[begin..end]);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn write(&mut self, offset: u64, size: usize, value: u64) -> Result<(), Error> {
+        let (begin, end) = self.range(offset, size)?;
+        self.data[begin..end].copy_from_slice(&value.to_le_bytes()[..size]);
+        Ok(())
+    }
+
+    /// Loads the `PT_LOAD` segments of `elf` at `options.base` and applies
+    /// its `R_*_RELATIVE`, `GLOB_DAT`, `JUMP_SLOT` and absolute relocations,
+    /// those of `DT_RELR` included. Relocations and their symbols are found
+    /// through `PT_DYNAMIC`, as ld.so does, and files without it have none.
+    ///
+    /// `resolve` returns the run-time address of a symbol, which may be
+    /// defined in the file itself: ld.so looks up all symbols in the global
+    /// scope. Executables that are not position independent must be loaded
+    /// at their link address.
+    pub fn from_elf64<'a, F>(
+        elf: &Elf64<'a>,
+        options: &LoadOptions,
+        resolve: F,
+    ) -> Result<Self, Error>
+    where
+        F: FnMut(&Symbol<'a>, Option<&SymbolVersion<'a>>) -> Option<u64>,
+    {
+        let versions = elf.versions()?;
+        let mut relocations = Vec::new();
+        for relocation in elf.dynamic_relocations()? {
+            let (symbol, version) = match relocation.sym as usize {
+                0 => (None, None),
+                idx => {
+                    let symbol = elf.dynamic_symbol(idx);
+                    let version = match symbol {
+                        Ok(ref symbol) => versions.symbol_version(idx, symbol),
+                        Err(_) => None,
+                    };
+                    (Some(symbol), version)
+                }
+            };
+            relocations.push(Reloc {
+                relocation: relocation,
+                symbol: symbol,
+                version: version,
+            });
+        }
+        let mut segments = Vec::new();
+        for p in elf.segments.iter().filter(|p| p.p_type == PT_LOAD) {
+            segments.push(Segment {
+                vaddr: p.p_vaddr,
+                memsz: p.p_memsz,
+                data: elf
+                    .segment_data(p)
+                    .ok_or_else(|| Error::ParseError(String::from("segment is out of bounds")))?,
+            });
+        }
+        let input = LoadInput {
+            machine: elf.header.e_machine,
+            word_size: 8,
+            segments: segments,
+            relr: elf.dynamic_relr()?,
+            relocations: relocations,
+        };
+        input.load(options, resolve)
+    }
+
+    pub fn from_elf32<'a, F>(
+        elf: &Elf32<'a>,
+        options: &LoadOptions,
+        resolve: F,
+    ) -> Result<Self, Error>
+    where
+        F: FnMut(&Symbol<'a>, Option<&SymbolVersion<'a>>) -> Option<u64>,
+    {
+        let versions = elf.versions()?;
+        let mut relocations = Vec::new();
+        for relocation in elf.dynamic_relocations()? {
+            let (symbol, version) = match relocation.sym as usize {
+                0 => (None, None),
+                idx => {
+                    let symbol = elf.dynamic_symbol(idx);
+                    let version = match symbol {
+                        Ok(ref symbol) => versions.symbol_version(idx, symbol),
+                        Err(_) => None,
+                    };
+                    (Some(symbol), version)
+                }
+            };
+            relocations.push(Reloc {
+                relocation: relocation,
+                symbol: symbol,
+                version: version,
+            });
+        }
+        let mut segments = Vec::new();
+        for p in elf.segments.iter().filter(|p| p.p_type == PT_LOAD) {
+            segments.push(Segment {
+                vaddr: u64::from(p.p_vaddr),
+                memsz: u64::from(p.p_memsz),
+                data: elf
+                    .segment_data(p)
+                    .ok_or_else(|| Error::ParseError(String::from("segment is out of bounds")))?,
+            });
+        }
+        let input = LoadInput {
+            machine: elf.header.e_machine,
+            word_size: 4,
+            segments: segments,
+            relr: elf.dynamic_relr()?,
+            relocations: relocations,
+        };
+        input.load(options, resolve)
+    }
+}
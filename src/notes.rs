@@ -0,0 +1,157 @@
+use nom;
+use std::str;
+
+use error::Error;
+use header::ElfOSAbi;
+
+pub const NT_GNU_ABI_TAG: u32 = 1;
+pub const NT_GNU_HWCAP: u32 = 2;
+pub const NT_GNU_BUILD_ID: u32 = 3;
+pub const NT_GNU_GOLD_VERSION: u32 = 4;
+pub const NT_GNU_PROPERTY_TYPE_0: u32 = 5;
+
+/// `NT_FREEBSD_ABI_TAG`, `NT_NETBSD_IDENT`, `NT_OPENBSD_IDENT` and
+/// `NT_ANDROID_TYPE_IDENT` share this value.
+pub const NT_OS_IDENT: u32 = 1;
+
+pub const GNU_ABI_TAG_LINUX: u32 = 0;
+pub const GNU_ABI_TAG_HURD: u32 = 1;
+pub const GNU_ABI_TAG_SOLARIS: u32 = 2;
+pub const GNU_ABI_TAG_FREEBSD: u32 = 3;
+pub const GNU_ABI_TAG_NETBSD: u32 = 4;
+pub const GNU_ABI_TAG_SYLLABLE: u32 = 5;
+pub const GNU_ABI_TAG_NACL: u32 = 6;
+
+/// An entry of a `SHT_NOTE` section or `PT_NOTE` segment.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Note<'a> {
+    pub n_type: u32,
+    /// Owner name, without its terminating NUL.
+    pub name: &'a str,
+    pub desc: &'a [u8],
+}
+
+fn align_up(x: usize, align: usize) -> usize {
+    (x + align - 1) & !(align - 1)
+}
+
+/// Iterator over the notes of a `SHT_NOTE` section or `PT_NOTE` segment,
+/// stopping after the first malformed entry.
+pub struct NoteIter<'a> {
+    data: &'a [u8],
+    align: usize,
+}
+
+/// Iterates over the notes of `data`, whose entries are padded to `align`
+/// bytes (4, or 8 for some 64-bit notes such as GNU properties).
+pub fn iter_notes<'a>(data: &'a [u8], align: usize) -> NoteIter<'a> {
+    NoteIter {
+        data: data,
+        align: if align == 8 { 8 } else { 4 },
+    }
+}
+
+impl<'a> NoteIter<'a> {
+    fn parse_note(&mut self) -> Result<Note<'a>, Error> {
+        let align = self.align;
+        let (rest, namesz) = nom::le_u32(self.data)?;
+        let (rest, descsz) = nom::le_u32(rest)?;
+        let (rest, n_type) = nom::le_u32(rest)?;
+        let (namesz, descsz) = (namesz as usize, descsz as usize);
+        // Padding is relative to the start of the 12 bytes note header.
+        let name_end = align_up(12 + namesz, align) - 12;
+        let desc_end = name_end
+            .checked_add(descsz)
+            .map(|end| align_up(12 + end, align) - 12)
+            .ok_or_else(|| Error::ParseError(String::from("note is too large")))?;
+        if name_end > rest.len() || name_end + descsz > rest.len() {
+            return Err(Error::ParseError(String::from("note is out of bounds")));
+        }
+
+        let name = &rest[..namesz];
+        let name = match name.iter().position(|&c| c == 0) {
+            Some(len) => &name[..len],
+            None => name,
+        };
+        let note = Note {
+            n_type: n_type,
+            name: str::from_utf8(name)
+                .map_err(|_| Error::ParseError(String::from("bad note name")))?,
+            desc: &rest[name_end..name_end + descsz],
+        };
+        self.data = &rest[desc_end.min(rest.len())..];
+        Ok(note)
+    }
+}
+
+impl<'a> Iterator for NoteIter<'a> {
+    type Item = Result<Note<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.len() < 12 {
+            return None;
+        }
+        let note = self.parse_note();
+        if note.is_err() {
+            self.data = &[];
+        }
+        Some(note)
+    }
+}
+
+/// Parses the notes of `data`, whose entries are padded to `align` bytes (4,
+/// or 8 for some 64-bit notes such as GNU properties).
+pub fn parse_notes<'a>(data: &'a [u8], align: usize) -> Result<Vec<Note<'a>>, Error> {
+    iter_notes(data, align).collect()
+}
+
+/// Returns the operating system named by an ABI tag note, if any.
+pub fn abi_tag_os(notes: &[Note]) -> Option<&'static str> {
+    for note in notes.iter().filter(|n| n.n_type == NT_OS_IDENT) {
+        match note.name {
+            "GNU" => {
+                if note.desc.len() < 4 {
+                    continue;
+                }
+                return match nom::le_u32(note.desc).ok()?.1 {
+                    GNU_ABI_TAG_LINUX => Some("linux"),
+                    GNU_ABI_TAG_HURD => Some("hurd"),
+                    GNU_ABI_TAG_SOLARIS => Some("solaris"),
+                    GNU_ABI_TAG_FREEBSD => Some("freebsd"),
+                    GNU_ABI_TAG_NETBSD => Some("netbsd"),
+                    GNU_ABI_TAG_SYLLABLE => Some("syllable"),
+                    GNU_ABI_TAG_NACL => Some("nacl"),
+                    _ => None,
+                };
+            }
+            "FreeBSD" => return Some("freebsd"),
+            "NetBSD" => return Some("netbsd"),
+            "OpenBSD" => return Some("openbsd"),
+            "Android" => return Some("android"),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Returns the operating system of a file from its OS/ABI identification,
+/// looking at the ABI tag notes for the generic System V ABI.
+pub fn os_name(osabi: &ElfOSAbi, notes: &[Note]) -> &'static str {
+    match *osabi {
+        // Linux toolchains emit System V files, usually without an ABI tag
+        // in shared libraries.
+        ElfOSAbi::OSAbiSysv | ElfOSAbi::OSAbiArmAeabi => abi_tag_os(notes).unwrap_or("linux"),
+        ElfOSAbi::OSAbiGnu => "linux",
+        ElfOSAbi::OSAbiHpux => "hpux",
+        ElfOSAbi::OSAbiNetbsd => "netbsd",
+        ElfOSAbi::OSAbiSolaris => "solaris",
+        ElfOSAbi::OSAbiAix => "aix",
+        ElfOSAbi::OSAbiIrix => "irix",
+        ElfOSAbi::OSAbiFreebsd => "freebsd",
+        ElfOSAbi::OSAbiTru64 => "tru64",
+        ElfOSAbi::OSAbiModesto => "modesto",
+        ElfOSAbi::OSAbiOpenbsd => "openbsd",
+        ElfOSAbi::OSAbiArm | ElfOSAbi::OSAbiStandalone => "none",
+    }
+}
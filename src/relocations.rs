@@ -0,0 +1,464 @@
+use enum_primitive::FromPrimitive;
+use nom;
+
+use error::Error;
+use header::ElfMachine;
+
+/// A `SHT_REL` or `SHT_RELA` entry, independent of the file class.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Relocation {
+    pub offset: u64,
+    /// Raw `r_info`, as 32 or 64 bits depending on the file class.
+    pub info: u64,
+    /// Index of the symbol in the linked symbol table.
+    pub sym: u32,
+    /// Processor-specific relocation type.
+    pub kind: u32,
+    /// Explicit addend of `SHT_RELA` entries.
+    pub addend: Option<i64>,
+}
+
+impl Relocation {
+    pub fn new(offset: u64, info: u64, sym: u32, kind: u32, addend: Option<i64>) -> Self {
+        Relocation {
+            offset: offset,
+            info: info,
+            sym: sym,
+            kind: kind,
+            addend: addend,
+        }
+    }
+}
+
+pub const R_X86_64_NONE: u32 = 0;
+pub const R_X86_64_64: u32 = 1;
+pub const R_X86_64_PC32: u32 = 2;
+pub const R_X86_64_PLT32: u32 = 4;
+pub const R_X86_64_COPY: u32 = 5;
+pub const R_X86_64_GLOB_DAT: u32 = 6;
+pub const R_X86_64_JUMP_SLOT: u32 = 7;
+pub const R_X86_64_RELATIVE: u32 = 8;
+pub const R_X86_64_GOTPCREL: u32 = 9;
+pub const R_X86_64_32: u32 = 10;
+pub const R_X86_64_32S: u32 = 11;
+pub const R_X86_64_16: u32 = 12;
+pub const R_X86_64_PC16: u32 = 13;
+pub const R_X86_64_8: u32 = 14;
+pub const R_X86_64_PC8: u32 = 15;
+pub const R_X86_64_PC64: u32 = 24;
+pub const R_X86_64_GOTPCRELX: u32 = 41;
+pub const R_X86_64_REX_GOTPCRELX: u32 = 42;
+
+pub const R_386_32: u32 = 1;
+pub const R_386_COPY: u32 = 5;
+pub const R_386_GLOB_DAT: u32 = 6;
+pub const R_386_JMP_SLOT: u32 = 7;
+pub const R_386_RELATIVE: u32 = 8;
+
+pub const R_AARCH64_NONE: u32 = 0;
+pub const R_AARCH64_ABS64: u32 = 257;
+pub const R_AARCH64_ABS32: u32 = 258;
+pub const R_AARCH64_ABS16: u32 = 259;
+pub const R_AARCH64_PREL64: u32 = 260;
+pub const R_AARCH64_PREL32: u32 = 261;
+pub const R_AARCH64_PREL16: u32 = 262;
+pub const R_AARCH64_MOVW_UABS_G0: u32 = 263;
+pub const R_AARCH64_MOVW_UABS_G0_NC: u32 = 264;
+pub const R_AARCH64_MOVW_UABS_G1: u32 = 265;
+pub const R_AARCH64_MOVW_UABS_G1_NC: u32 = 266;
+pub const R_AARCH64_MOVW_UABS_G2: u32 = 267;
+pub const R_AARCH64_MOVW_UABS_G2_NC: u32 = 268;
+pub const R_AARCH64_MOVW_UABS_G3: u32 = 269;
+pub const R_AARCH64_LD_PREL_LO19: u32 = 273;
+pub const R_AARCH64_ADR_PREL_LO21: u32 = 274;
+pub const R_AARCH64_ADR_PREL_PG_HI21: u32 = 275;
+pub const R_AARCH64_ADR_PREL_PG_HI21_NC: u32 = 276;
+pub const R_AARCH64_ADD_ABS_LO12_NC: u32 = 277;
+pub const R_AARCH64_LDST8_ABS_LO12_NC: u32 = 278;
+pub const R_AARCH64_TSTBR14: u32 = 279;
+pub const R_AARCH64_CONDBR19: u32 = 280;
+pub const R_AARCH64_JUMP26: u32 = 282;
+pub const R_AARCH64_CALL26: u32 = 283;
+pub const R_AARCH64_LDST16_ABS_LO12_NC: u32 = 284;
+pub const R_AARCH64_LDST32_ABS_LO12_NC: u32 = 285;
+pub const R_AARCH64_LDST64_ABS_LO12_NC: u32 = 286;
+pub const R_AARCH64_LDST128_ABS_LO12_NC: u32 = 299;
+pub const R_AARCH64_COPY: u32 = 1024;
+pub const R_AARCH64_GLOB_DAT: u32 = 1025;
+pub const R_AARCH64_JUMP_SLOT: u32 = 1026;
+pub const R_AARCH64_RELATIVE: u32 = 1027;
+
+pub const R_ARM_NONE: u32 = 0;
+pub const R_ARM_ABS32: u32 = 2;
+pub const R_ARM_REL32: u32 = 3;
+pub const R_ARM_THM_CALL: u32 = 10;
+pub const R_ARM_COPY: u32 = 20;
+pub const R_ARM_GLOB_DAT: u32 = 21;
+pub const R_ARM_JUMP_SLOT: u32 = 22;
+pub const R_ARM_RELATIVE: u32 = 23;
+pub const R_ARM_CALL: u32 = 28;
+pub const R_ARM_JUMP24: u32 = 29;
+pub const R_ARM_THM_JUMP24: u32 = 30;
+pub const R_ARM_V4BX: u32 = 40;
+pub const R_ARM_PREL31: u32 = 42;
+pub const R_ARM_MOVW_ABS_NC: u32 = 43;
+pub const R_ARM_MOVT_ABS: u32 = 44;
+pub const R_ARM_THM_MOVW_ABS_NC: u32 = 47;
+pub const R_ARM_THM_MOVT_ABS: u32 = 48;
+pub const R_ARM_THM_JUMP19: u32 = 51;
+pub const R_ARM_THM_JUMP11: u32 = 102;
+pub const R_ARM_THM_JUMP8: u32 = 103;
+
+pub const R_RISCV_NONE: u32 = 0;
+pub const R_RISCV_32: u32 = 1;
+pub const R_RISCV_64: u32 = 2;
+pub const R_RISCV_RELATIVE: u32 = 3;
+pub const R_RISCV_COPY: u32 = 4;
+pub const R_RISCV_JUMP_SLOT: u32 = 5;
+pub const R_RISCV_BRANCH: u32 = 16;
+pub const R_RISCV_JAL: u32 = 17;
+pub const R_RISCV_CALL: u32 = 18;
+pub const R_RISCV_CALL_PLT: u32 = 19;
+pub const R_RISCV_PCREL_HI20: u32 = 23;
+pub const R_RISCV_PCREL_LO12_I: u32 = 24;
+pub const R_RISCV_PCREL_LO12_S: u32 = 25;
+pub const R_RISCV_HI20: u32 = 26;
+pub const R_RISCV_LO12_I: u32 = 27;
+pub const R_RISCV_LO12_S: u32 = 28;
+pub const R_RISCV_ADD8: u32 = 33;
+pub const R_RISCV_ADD16: u32 = 34;
+pub const R_RISCV_ADD32: u32 = 35;
+pub const R_RISCV_ADD64: u32 = 36;
+pub const R_RISCV_SUB8: u32 = 37;
+pub const R_RISCV_SUB16: u32 = 38;
+pub const R_RISCV_SUB32: u32 = 39;
+pub const R_RISCV_SUB64: u32 = 40;
+pub const R_RISCV_ALIGN: u32 = 43;
+pub const R_RISCV_RVC_BRANCH: u32 = 44;
+pub const R_RISCV_RVC_JUMP: u32 = 45;
+pub const R_RISCV_RELAX: u32 = 51;
+pub const R_RISCV_SUB6: u32 = 52;
+pub const R_RISCV_SET6: u32 = 53;
+pub const R_RISCV_SET8: u32 = 54;
+pub const R_RISCV_SET16: u32 = 55;
+pub const R_RISCV_SET32: u32 = 56;
+pub const R_RISCV_32_PCREL: u32 = 57;
+
+static R_X86_64: [&str; 44] = [
+    "R_X86_64_NONE",
+    "R_X86_64_64",
+    "R_X86_64_PC32",
+    "R_X86_64_GOT32",
+    "R_X86_64_PLT32",
+    "R_X86_64_COPY",
+    "R_X86_64_GLOB_DAT",
+    "R_X86_64_JUMP_SLOT",
+    "R_X86_64_RELATIVE",
+    "R_X86_64_GOTPCREL",
+    "R_X86_64_32",
+    "R_X86_64_32S",
+    "R_X86_64_16",
+    "R_X86_64_PC16",
+    "R_X86_64_8",
+    "R_X86_64_PC8",
+    "R_X86_64_DTPMOD64",
+    "R_X86_64_DTPOFF64",
+    "R_X86_64_TPOFF64",
+    "R_X86_64_TLSGD",
+    "R_X86_64_TLSLD",
+    "R_X86_64_DTPOFF32",
+    "R_X86_64_GOTTPOFF",
+    "R_X86_64_TPOFF32",
+    "R_X86_64_PC64",
+    "R_X86_64_GOTOFF64",
+    "R_X86_64_GOTPC32",
+    "R_X86_64_GOT64",
+    "R_X86_64_GOTPCREL64",
+    "R_X86_64_GOTPC64",
+    "R_X86_64_GOTPLT64",
+    "R_X86_64_PLTOFF64",
+    "R_X86_64_SIZE32",
+    "R_X86_64_SIZE64",
+    "R_X86_64_GOTPC32_TLSDESC",
+    "R_X86_64_TLSDESC_CALL",
+    "R_X86_64_TLSDESC",
+    "R_X86_64_IRELATIVE",
+    "R_X86_64_RELATIVE64",
+    "R_X86_64_PC32_BND",
+    "R_X86_64_PLT32_BND",
+    "R_X86_64_GOTPCRELX",
+    "R_X86_64_REX_GOTPCRELX",
+    "R_X86_64_CODE_4_GOTPCRELX",
+];
+
+static R_386: [&str; 44] = [
+    "R_386_NONE",
+    "R_386_32",
+    "R_386_PC32",
+    "R_386_GOT32",
+    "R_386_PLT32",
+    "R_386_COPY",
+    "R_386_GLOB_DAT",
+    "R_386_JUMP_SLOT",
+    "R_386_RELATIVE",
+    "R_386_GOTOFF",
+    "R_386_GOTPC",
+    "R_386_32PLT",
+    "",
+    "",
+    "R_386_TLS_TPOFF",
+    "R_386_TLS_IE",
+    "R_386_TLS_GOTIE",
+    "R_386_TLS_LE",
+    "R_386_TLS_GD",
+    "R_386_TLS_LDM",
+    "R_386_16",
+    "R_386_PC16",
+    "R_386_8",
+    "R_386_PC8",
+    "R_386_TLS_GD_32",
+    "R_386_TLS_GD_PUSH",
+    "R_386_TLS_GD_CALL",
+    "R_386_TLS_GD_POP",
+    "R_386_TLS_LDM_32",
+    "R_386_TLS_LDM_PUSH",
+    "R_386_TLS_LDM_CALL",
+    "R_386_TLS_LDM_POP",
+    "R_386_TLS_LDO_32",
+    "R_386_TLS_IE_32",
+    "R_386_TLS_LE_32",
+    "R_386_TLS_DTPMOD32",
+    "R_386_TLS_DTPOFF32",
+    "R_386_TLS_TPOFF32",
+    "R_386_SIZE32",
+    "R_386_TLS_GOTDESC",
+    "R_386_TLS_DESC_CALL",
+    "R_386_TLS_DESC",
+    "R_386_IRELATIVE",
+    "R_386_GOT32X",
+];
+
+fn aarch64_name(kind: u32) -> Option<&'static str> {
+    Some(match kind {
+        0 => "R_AARCH64_NONE",
+        257 => "R_AARCH64_ABS64",
+        258 => "R_AARCH64_ABS32",
+        259 => "R_AARCH64_ABS16",
+        260 => "R_AARCH64_PREL64",
+        261 => "R_AARCH64_PREL32",
+        262 => "R_AARCH64_PREL16",
+        263 => "R_AARCH64_MOVW_UABS_G0",
+        264 => "R_AARCH64_MOVW_UABS_G0_NC",
+        265 => "R_AARCH64_MOVW_UABS_G1",
+        266 => "R_AARCH64_MOVW_UABS_G1_NC",
+        267 => "R_AARCH64_MOVW_UABS_G2",
+        268 => "R_AARCH64_MOVW_UABS_G2_NC",
+        269 => "R_AARCH64_MOVW_UABS_G3",
+        273 => "R_AARCH64_LD_PREL_LO19",
+        274 => "R_AARCH64_ADR_PREL_LO21",
+        275 => "R_AARCH64_ADR_PREL_PG_HI21",
+        276 => "R_AARCH64_ADR_PREL_PG_HI21_NC",
+        277 => "R_AARCH64_ADD_ABS_LO12_NC",
+        278 => "R_AARCH64_LDST8_ABS_LO12_NC",
+        279 => "R_AARCH64_TSTBR14",
+        280 => "R_AARCH64_CONDBR19",
+        282 => "R_AARCH64_JUMP26",
+        283 => "R_AARCH64_CALL26",
+        284 => "R_AARCH64_LDST16_ABS_LO12_NC",
+        285 => "R_AARCH64_LDST32_ABS_LO12_NC",
+        286 => "R_AARCH64_LDST64_ABS_LO12_NC",
+        299 => "R_AARCH64_LDST128_ABS_LO12_NC",
+        311 => "R_AARCH64_ADR_GOT_PAGE",
+        312 => "R_AARCH64_LD64_GOT_LO12_NC",
+        1024 => "R_AARCH64_COPY",
+        1025 => "R_AARCH64_GLOB_DAT",
+        1026 => "R_AARCH64_JUMP_SLOT",
+        1027 => "R_AARCH64_RELATIVE",
+        1028 => "R_AARCH64_TLS_DTPMOD",
+        1029 => "R_AARCH64_TLS_DTPREL",
+        1030 => "R_AARCH64_TLS_TPREL",
+        1031 => "R_AARCH64_TLSDESC",
+        1032 => "R_AARCH64_IRELATIVE",
+        _ => return None,
+    })
+}
+
+fn arm_name(kind: u32) -> Option<&'static str> {
+    Some(match kind {
+        0 => "R_ARM_NONE",
+        1 => "R_ARM_PC24",
+        2 => "R_ARM_ABS32",
+        3 => "R_ARM_REL32",
+        4 => "R_ARM_LDR_PC_G0",
+        5 => "R_ARM_ABS16",
+        6 => "R_ARM_ABS12",
+        7 => "R_ARM_THM_ABS5",
+        8 => "R_ARM_ABS8",
+        9 => "R_ARM_SBREL32",
+        10 => "R_ARM_THM_CALL",
+        11 => "R_ARM_THM_PC8",
+        12 => "R_ARM_BREL_ADJ",
+        13 => "R_ARM_TLS_DESC",
+        17 => "R_ARM_TLS_DTPMOD32",
+        18 => "R_ARM_TLS_DTPOFF32",
+        19 => "R_ARM_TLS_TPOFF32",
+        20 => "R_ARM_COPY",
+        21 => "R_ARM_GLOB_DAT",
+        22 => "R_ARM_JUMP_SLOT",
+        23 => "R_ARM_RELATIVE",
+        24 => "R_ARM_GOTOFF32",
+        25 => "R_ARM_BASE_PREL",
+        26 => "R_ARM_GOT_BREL",
+        27 => "R_ARM_PLT32",
+        28 => "R_ARM_CALL",
+        29 => "R_ARM_JUMP24",
+        30 => "R_ARM_THM_JUMP24",
+        31 => "R_ARM_BASE_ABS",
+        38 => "R_ARM_TARGET1",
+        39 => "R_ARM_SBREL31",
+        40 => "R_ARM_V4BX",
+        41 => "R_ARM_TARGET2",
+        42 => "R_ARM_PREL31",
+        43 => "R_ARM_MOVW_ABS_NC",
+        44 => "R_ARM_MOVT_ABS",
+        45 => "R_ARM_MOVW_PREL_NC",
+        46 => "R_ARM_MOVT_PREL",
+        47 => "R_ARM_THM_MOVW_ABS_NC",
+        48 => "R_ARM_THM_MOVT_ABS",
+        49 => "R_ARM_THM_MOVW_PREL_NC",
+        50 => "R_ARM_THM_MOVT_PREL",
+        51 => "R_ARM_THM_JUMP19",
+        52 => "R_ARM_THM_JUMP6",
+        96 => "R_ARM_GOT_PREL",
+        102 => "R_ARM_THM_JUMP11",
+        103 => "R_ARM_THM_JUMP8",
+        104 => "R_ARM_TLS_GD32",
+        105 => "R_ARM_TLS_LDM32",
+        106 => "R_ARM_TLS_LDO32",
+        107 => "R_ARM_TLS_IE32",
+        108 => "R_ARM_TLS_LE32",
+        160 => "R_ARM_IRELATIVE",
+        _ => return None,
+    })
+}
+
+fn riscv_name(kind: u32) -> Option<&'static str> {
+    Some(match kind {
+        0 => "R_RISCV_NONE",
+        1 => "R_RISCV_32",
+        2 => "R_RISCV_64",
+        3 => "R_RISCV_RELATIVE",
+        4 => "R_RISCV_COPY",
+        5 => "R_RISCV_JUMP_SLOT",
+        6 => "R_RISCV_TLS_DTPMOD32",
+        7 => "R_RISCV_TLS_DTPMOD64",
+        8 => "R_RISCV_TLS_DTPREL32",
+        9 => "R_RISCV_TLS_DTPREL64",
+        10 => "R_RISCV_TLS_TPREL32",
+        11 => "R_RISCV_TLS_TPREL64",
+        12 => "R_RISCV_TLSDESC",
+        16 => "R_RISCV_BRANCH",
+        17 => "R_RISCV_JAL",
+        18 => "R_RISCV_CALL",
+        19 => "R_RISCV_CALL_PLT",
+        20 => "R_RISCV_GOT_HI20",
+        21 => "R_RISCV_TLS_GOT_HI20",
+        22 => "R_RISCV_TLS_GD_HI20",
+        23 => "R_RISCV_PCREL_HI20",
+        24 => "R_RISCV_PCREL_LO12_I",
+        25 => "R_RISCV_PCREL_LO12_S",
+        26 => "R_RISCV_HI20",
+        27 => "R_RISCV_LO12_I",
+        28 => "R_RISCV_LO12_S",
+        29 => "R_RISCV_TPREL_HI20",
+        30 => "R_RISCV_TPREL_LO12_I",
+        31 => "R_RISCV_TPREL_LO12_S",
+        32 => "R_RISCV_TPREL_ADD",
+        33 => "R_RISCV_ADD8",
+        34 => "R_RISCV_ADD16",
+        35 => "R_RISCV_ADD32",
+        36 => "R_RISCV_ADD64",
+        37 => "R_RISCV_SUB8",
+        38 => "R_RISCV_SUB16",
+        39 => "R_RISCV_SUB32",
+        40 => "R_RISCV_SUB64",
+        41 => "R_RISCV_GOT32_PCREL",
+        43 => "R_RISCV_ALIGN",
+        44 => "R_RISCV_RVC_BRANCH",
+        45 => "R_RISCV_RVC_JUMP",
+        51 => "R_RISCV_RELAX",
+        52 => "R_RISCV_SUB6",
+        53 => "R_RISCV_SET6",
+        54 => "R_RISCV_SET8",
+        55 => "R_RISCV_SET16",
+        56 => "R_RISCV_SET32",
+        57 => "R_RISCV_32_PCREL",
+        58 => "R_RISCV_IRELATIVE",
+        59 => "R_RISCV_PLT32",
+        60 => "R_RISCV_SET_ULEB128",
+        61 => "R_RISCV_SUB_ULEB128",
+        _ => return None,
+    })
+}
+
+/// Decodes the entries of a `SHT_RELR` section into the offsets of the
+/// relative relocations they encode. `word_size` is 4 or 8 depending on the
+/// file class.
+pub fn parse_relr(data: &[u8], word_size: usize) -> Result<Vec<u64>, Error> {
+    let mut offsets = Vec::new();
+    let mut base = 0u64;
+    let step = word_size as u64;
+    for entry in data.chunks(word_size).filter(|e| e.len() == word_size) {
+        let entry = if word_size == 8 {
+            nom::le_u64(entry)?.1
+        } else {
+            u64::from(nom::le_u32(entry)?.1)
+        };
+        if entry & 1 == 0 {
+            // An address, followed by bitmaps of the next words.
+            offsets.push(entry);
+            base = entry.wrapping_add(step);
+        } else {
+            let bits = word_size as u64 * 8 - 1;
+            for i in 0..bits {
+                if entry >> (i + 1) & 1 != 0 {
+                    offsets.push(base.wrapping_add(i * step));
+                }
+            }
+            base = base.wrapping_add(bits * step);
+        }
+    }
+    Ok(offsets)
+}
+
+/// Returns the name of the relocation type `kind` for the processor
+/// `e_machine`, when known.
+pub fn relocation_type_name(e_machine: u16, kind: u32) -> Option<&'static str> {
+    let table: &[&'static str] = match ElfMachine::from_u16(e_machine) {
+        Some(ElfMachine::MachineX8664) => &R_X86_64,
+        Some(ElfMachine::Machine386) | Some(ElfMachine::MachineIamcu) => &R_386,
+        Some(ElfMachine::MachineAarch64) => return aarch64_name(kind),
+        Some(ElfMachine::MachineArm) => return arm_name(kind),
+        Some(ElfMachine::MachineRiscv) => return riscv_name(kind),
+        _ => return None,
+    };
+    table
+        .get(kind as usize)
+        .cloned()
+        .filter(|name| !name.is_empty())
+}
+
+/// Returns whether `kind` is the `R_*_COPY` type of the processor
+/// `e_machine`, which copies a variable of a shared object into the
+/// executable.
+pub fn is_copy_relocation(e_machine: u16, kind: u32) -> bool {
+    match ElfMachine::from_u16(e_machine) {
+        Some(ElfMachine::MachineX8664) => kind == R_X86_64_COPY,
+        Some(ElfMachine::Machine386) | Some(ElfMachine::MachineIamcu) => kind == R_386_COPY,
+        Some(ElfMachine::MachineAarch64) => kind == R_AARCH64_COPY,
+        Some(ElfMachine::MachineArm) => kind == R_ARM_COPY,
+        Some(ElfMachine::MachineRiscv) => kind == R_RISCV_COPY,
+        _ => false,
+    }
+}
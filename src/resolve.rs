@@ -0,0 +1,522 @@
+use std::collections::VecDeque;
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use error::Error;
+use file::ElfFile;
+use header::*;
+use ldcache::LdCache;
+
+/// Number of symbolic links followed when mapping a path into the root,
+/// as `MAXSYMLINKS` on Linux.
+const MAX_SYMLINKS: usize = 40;
+
+/// Where the loader found an object, in the order it searches.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SearchSource {
+    /// The executable the tree was resolved for.
+    Executable,
+    /// The needed name contains a slash and is used as a path.
+    Path,
+    Rpath,
+    LdLibraryPath,
+    Runpath,
+    Cache,
+    DefaultDirs,
+}
+
+/// A `DT_NEEDED` entry and the object it resolved to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dependency {
+    pub name: String,
+    /// Index of the object in `DependencyTree::objects`, `None` if the
+    /// library was not found.
+    pub object: Option<usize>,
+}
+
+/// An object of the tree, with the path the loader opens it with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadedObject {
+    /// Absolute path within the root.
+    pub path: PathBuf,
+    pub soname: Option<String>,
+    pub source: SearchSource,
+    pub needed: Vec<Dependency>,
+}
+
+/// The objects an executable loads, in load order starting with the
+/// executable.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DependencyTree {
+    pub objects: Vec<LoadedObject>,
+}
+
+impl DependencyTree {
+    /// Returns the needed names that could not be resolved, once each.
+    pub fn missing(&self) -> Vec<&str> {
+        let mut missing: Vec<&str> = Vec::new();
+        for dep in self.objects.iter().flat_map(|o| o.needed.iter()) {
+            if dep.object.is_none() && !missing.contains(&dep.name.as_str()) {
+                missing.push(&dep.name);
+            }
+        }
+        missing
+    }
+}
+
+/// Resolves the `DT_NEEDED` tree of an executable as glibc's ld.so does,
+/// without running anything, like `ldd` for untrusted files.
+///
+/// Libraries are searched in the `DT_RPATH` of the requesting object and
+/// of the objects that loaded it, unless it has a `DT_RUNPATH`, then in
+/// `LD_LIBRARY_PATH`, the `DT_RUNPATH` of the requesting object,
+/// `/etc/ld.so.cache` and the default directories. Files whose class or
+/// machine differ from those of the executable are skipped, as ld.so
+/// does.
+///
+/// All paths are relative to `root`, symbolic links included, so that a
+/// sysroot is resolved as if it was `/`. Relative search directories are
+/// relative to the root as well, since there is no working directory.
+#[derive(Debug, Clone)]
+pub struct LibraryResolver {
+    /// Directory standing for `/`.
+    pub root: PathBuf,
+    /// Directories of `LD_LIBRARY_PATH`, see `split_library_path`.
+    pub library_path: Vec<String>,
+    /// Expansion of `$LIB`, by default `lib64` for 64-bit executables and
+    /// `lib` otherwise.
+    pub lib: Option<String>,
+    /// Expansion of `$PLATFORM`, by default derived from the machine.
+    /// Search directories using it are dropped when it is unknown.
+    pub platform: Option<String>,
+    /// Directories searched last, by default those of glibc for the class
+    /// of the executable. Distributions with another layout, such as
+    /// Debian's multiarch directories, rely on the cache instead.
+    pub default_dirs: Option<Vec<String>>,
+    /// Whether to search `/etc/ld.so.cache`.
+    pub use_cache: bool,
+    /// Whether to use the cache entries of libraries built for hardware
+    /// capabilities or `glibc-hwcaps` subdirectories. ld.so only loads them
+    /// on processors that have these capabilities, which cannot be told
+    /// from the files, so they are skipped by default.
+    pub use_hwcap_entries: bool,
+}
+
+/// Splits `LD_LIBRARY_PATH` on colons and semicolons. Empty entries stand
+/// for the current directory.
+pub fn split_library_path(value: &str) -> Vec<String> {
+    value
+        .split(|c| c == ':' || c == ';')
+        .map(|dir| if dir.is_empty() { "." } else { dir })
+        .map(String::from)
+        .collect()
+}
+
+fn default_platform(machine: u16) -> Option<&'static str> {
+    match machine {
+        m if m == ElfMachine::MachineX8664 as u16 => Some("x86_64"),
+        m if m == ElfMachine::Machine386 as u16 => Some("i686"),
+        m if m == ElfMachine::MachineAarch64 as u16 => Some("aarch64"),
+        m if m == ElfMachine::MachineArm as u16 => Some("v7l"),
+        _ => None,
+    }
+}
+
+/// A loaded object with what the search needs to know about it.
+struct Node {
+    host_path: PathBuf,
+    /// Names the object may be requested with: the needed names that
+    /// resolved to it, its soname and its path.
+    names: Vec<String>,
+    /// Index of the object that loaded it first.
+    loader: Option<usize>,
+    rpath: Option<String>,
+    runpath: Option<String>,
+    nodeflib: bool,
+}
+
+/// The state of one resolution.
+struct Search<'r> {
+    resolver: &'r LibraryResolver,
+    cache: Option<LdCache<'r>>,
+    class: ElfClass,
+    machine: u16,
+    lib: String,
+    platform: Option<String>,
+    default_dirs: Vec<String>,
+    nodes: Vec<Node>,
+    objects: Vec<LoadedObject>,
+}
+
+/// What is read from a candidate file.
+struct Candidate {
+    host_path: PathBuf,
+    soname: Option<String>,
+    needed: Vec<String>,
+    rpath: Option<String>,
+    runpath: Option<String>,
+    nodeflib: bool,
+}
+
+fn read_candidate(
+    host_path: PathBuf,
+    expected: Option<(ElfClass, u16)>,
+) -> Result<(Candidate, ElfClass, u16), Error> {
+    let file = ElfFile::open(&host_path)?;
+    let elf = file.parse()?;
+    if let Some((class, machine)) = expected {
+        if elf.class() != class || elf.machine() != machine {
+            return Err(Error::Unsupported(String::from("wrong class or machine")));
+        }
+    }
+    let nodeflib = elf
+        .dynamic()?
+        .iter()
+        .any(|d| d.tag == DT_FLAGS_1 && d.val & DF_1_NODEFLIB != 0);
+    let candidate = Candidate {
+        host_path: host_path,
+        soname: elf.soname()?.map(String::from),
+        needed: elf.needed()?.into_iter().map(String::from).collect(),
+        rpath: elf.rpath()?.map(String::from),
+        runpath: elf.runpath()?.map(String::from),
+        nodeflib: nodeflib,
+    };
+    Ok((candidate, elf.class(), elf.machine()))
+}
+
+/// Returns the directory of `path`, as `$ORIGIN` expands to.
+fn origin(path: &Path) -> String {
+    path.parent().map_or_else(
+        || String::from("/"),
+        |dir| dir.to_string_lossy().into_owned(),
+    )
+}
+
+impl LibraryResolver {
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        LibraryResolver {
+            root: root.as_ref().to_path_buf(),
+            library_path: Vec::new(),
+            lib: None,
+            platform: None,
+            default_dirs: None,
+            use_cache: true,
+            use_hwcap_entries: false,
+        }
+    }
+
+    /// Maps `path`, absolute within the root, to a path of the host.
+    /// Symbolic links are resolved within the root, so that absolute
+    /// targets do not escape it. Returns `None` if the path does not exist.
+    pub fn host_path(&self, path: &Path) -> Option<PathBuf> {
+        // Components still to walk, last first; `None` is the root.
+        let mut pending: Vec<Option<OsString>> = Vec::new();
+        let push = |pending: &mut Vec<Option<OsString>>, path: &Path| {
+            for component in path.components().rev() {
+                match component {
+                    Component::Normal(c) => pending.push(Some(c.to_os_string())),
+                    Component::ParentDir => pending.push(Some(OsString::from(".."))),
+                    Component::RootDir | Component::Prefix(_) => pending.push(None),
+                    Component::CurDir => (),
+                }
+            }
+        };
+        push(&mut pending, path);
+
+        let mut resolved = PathBuf::new();
+        let mut links = 0;
+        while let Some(component) = pending.pop() {
+            let component = match component {
+                Some(c) => c,
+                None => {
+                    resolved = PathBuf::new();
+                    continue;
+                }
+            };
+            if component == ".." {
+                resolved.pop();
+                continue;
+            }
+            let next = resolved.join(&component);
+            let host = self.root.join(&next);
+            let metadata = fs::symlink_metadata(&host).ok()?;
+            if metadata.file_type().is_symlink() {
+                links += 1;
+                if links > MAX_SYMLINKS {
+                    return None;
+                }
+                let target = fs::read_link(&host).ok()?;
+                push(&mut pending, &target);
+            } else {
+                resolved = next;
+            }
+        }
+        Some(self.root.join(resolved))
+    }
+
+    /// Resolves the dependencies of `executable`, an absolute path within
+    /// the root.
+    pub fn resolve<P: AsRef<Path>>(&self, executable: P) -> Result<DependencyTree, Error> {
+        let path = Path::new("/").join(executable.as_ref());
+        let host_path = self.host_path(&path).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{}: no such file in the root", path.display()),
+            )
+        })?;
+        let (executable, class, machine) = read_candidate(host_path, None)?;
+
+        let cache_data = if self.use_cache {
+            self.host_path(Path::new("/etc/ld.so.cache"))
+                .and_then(|p| fs::read(p).ok())
+        } else {
+            None
+        };
+        let mut search = Search {
+            resolver: self,
+            cache: None,
+            class: class,
+            machine: machine,
+            lib: self.lib.clone().unwrap_or_else(|| {
+                String::from(if class == ElfClass::Class64 {
+                    "lib64"
+                } else {
+                    "lib"
+                })
+            }),
+            platform: self
+                .platform
+                .clone()
+                .or_else(|| default_platform(machine).map(String::from)),
+            default_dirs: self.default_dirs.clone().unwrap_or_else(|| {
+                let dirs: &[&str] = if class == ElfClass::Class64 {
+                    &["/lib64", "/usr/lib64"]
+                } else {
+                    &["/lib", "/usr/lib"]
+                };
+                dirs.iter().map(|&d| String::from(d)).collect()
+            }),
+            nodes: Vec::new(),
+            objects: Vec::new(),
+        };
+        // An unreadable cache is ignored, as ld.so does.
+        search.cache = cache_data
+            .as_ref()
+            .and_then(|data| LdCache::parse(data).ok());
+        search.add(path, executable, SearchSource::Executable, None, None);
+        search.run();
+        Ok(DependencyTree {
+            objects: search.objects,
+        })
+    }
+}
+
+impl<'r> Search<'r> {
+    /// Records a new object and returns its index.
+    fn add(
+        &mut self,
+        path: PathBuf,
+        candidate: Candidate,
+        source: SearchSource,
+        name: Option<&str>,
+        loader: Option<usize>,
+    ) -> usize {
+        let mut names: Vec<String> = name.into_iter().map(String::from).collect();
+        names.extend(candidate.soname.iter().cloned());
+        names.push(path.to_string_lossy().into_owned());
+        self.nodes.push(Node {
+            host_path: candidate.host_path,
+            names: names,
+            loader: loader,
+            rpath: candidate.rpath,
+            runpath: candidate.runpath,
+            nodeflib: candidate.nodeflib,
+        });
+        self.objects.push(LoadedObject {
+            path: path,
+            soname: candidate.soname,
+            source: source,
+            needed: candidate
+                .needed
+                .into_iter()
+                .map(|name| Dependency {
+                    name: name,
+                    object: None,
+                })
+                .collect(),
+        });
+        self.objects.len() - 1
+    }
+
+    /// Loads the needed objects breadth first, as ld.so does.
+    fn run(&mut self) {
+        let mut queue = VecDeque::new();
+        queue.push_back(0);
+        while let Some(idx) = queue.pop_front() {
+            for dep in 0..self.objects[idx].needed.len() {
+                let name = self.objects[idx].needed[dep].name.clone();
+                let loaded = self
+                    .nodes
+                    .iter()
+                    .position(|n| n.names.iter().any(|n| *n == name));
+                let object = match loaded {
+                    Some(object) => Some(object),
+                    None => self.load(idx, &name).map(|object| {
+                        queue.push_back(object);
+                        object
+                    }),
+                };
+                self.objects[idx].needed[dep].object = object;
+            }
+        }
+    }
+
+    /// Searches `name` for the object `requester` and records it, unless
+    /// the file found is already loaded under another name.
+    fn load(&mut self, requester: usize, name: &str) -> Option<usize> {
+        let (path, candidate, source) = self.search(requester, name)?;
+        if let Some(idx) = self
+            .nodes
+            .iter()
+            .position(|n| n.host_path == candidate.host_path)
+        {
+            self.nodes[idx].names.push(String::from(name));
+            return Some(idx);
+        }
+        Some(self.add(path, candidate, source, Some(name), Some(requester)))
+    }
+
+    fn search(&self, requester: usize, name: &str) -> Option<(PathBuf, Candidate, SearchSource)> {
+        let try_dirs = |dirs: &[String], source: SearchSource| {
+            dirs.iter()
+                .filter_map(|dir| {
+                    let path = Path::new("/").join(dir).join(name);
+                    self.open(path).map(|(p, c)| (p, c, source))
+                })
+                .next()
+        };
+
+        if name.contains('/') {
+            let origin = origin(&self.objects[requester].path);
+            let path = self.expand(name, &origin)?;
+            return self
+                .open(Path::new("/").join(path))
+                .map(|(p, c)| (p, c, SearchSource::Path));
+        }
+
+        let node = &self.nodes[requester];
+        if node.runpath.is_none() {
+            let mut loader = Some(requester);
+            while let Some(idx) = loader {
+                // Objects with a `DT_RUNPATH` ignore their `DT_RPATH`.
+                let rpath = match self.nodes[idx].runpath {
+                    Some(_) => None,
+                    None => self.nodes[idx].rpath.as_ref(),
+                };
+                let dirs = self.search_dirs(idx, rpath);
+                if let Some(found) = try_dirs(&dirs, SearchSource::Rpath) {
+                    return Some(found);
+                }
+                loader = self.nodes[idx].loader;
+            }
+        }
+
+        let origin = origin(&self.objects[0].path);
+        let library_path: Vec<String> = self
+            .resolver
+            .library_path
+            .iter()
+            .filter_map(|dir| self.expand(dir, &origin))
+            .collect();
+        if let Some(found) = try_dirs(&library_path, SearchSource::LdLibraryPath) {
+            return Some(found);
+        }
+
+        let dirs = self.search_dirs(requester, node.runpath.as_ref());
+        if let Some(found) = try_dirs(&dirs, SearchSource::Runpath) {
+            return Some(found);
+        }
+
+        if node.nodeflib {
+            return None;
+        }
+        if let Some(ref cache) = self.cache {
+            let compatible = cache
+                .lookup(name)
+                .filter(|e| e.hwcap == 0 || self.resolver.use_hwcap_entries)
+                .filter(|e| e.matches(self.class, self.machine));
+            for entry in compatible {
+                if let Some((path, candidate)) = self.open(PathBuf::from(entry.path)) {
+                    return Some((path, candidate, SearchSource::Cache));
+                }
+            }
+        }
+        try_dirs(&self.default_dirs, SearchSource::DefaultDirs)
+    }
+
+    /// Returns the directories of the search path `value` of the object
+    /// `idx`, a `DT_RPATH` or `DT_RUNPATH`, with substitutions expanded.
+    fn search_dirs(&self, idx: usize, value: Option<&String>) -> Vec<String> {
+        let origin = origin(&self.objects[idx].path);
+        value
+            .map(|v| {
+                v.split(':')
+                    .map(|dir| if dir.is_empty() { "." } else { dir })
+                    .filter_map(|dir| self.expand(dir, &origin))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Expands `$ORIGIN`, `$LIB` and `$PLATFORM`, braced or not. Returns
+    /// `None` if a substitution is unknown, so that ld.so would drop the
+    /// directory.
+    fn expand(&self, s: &str, origin: &str) -> Option<String> {
+        let mut expanded = String::new();
+        let mut rest = s;
+        while let Some(pos) = rest.find('$') {
+            expanded.push_str(&rest[..pos]);
+            rest = &rest[pos + 1..];
+            let substitutions = [
+                ("ORIGIN", Some(origin)),
+                ("LIB", Some(self.lib.as_str())),
+                ("PLATFORM", self.platform.as_ref().map(|p| p.as_str())),
+            ];
+            let found = substitutions.iter().find_map(|&(name, value)| {
+                let braced = format!("{{{}}}", name);
+                if rest.starts_with(&braced) {
+                    Some((braced.len(), value))
+                } else if rest.starts_with(name)
+                    && !rest[name.len()..].starts_with(|c: char| c.is_alphanumeric() || c == '_')
+                {
+                    Some((name.len(), value))
+                } else {
+                    None
+                }
+            });
+            match found {
+                Some((len, value)) => {
+                    expanded.push_str(value?);
+                    rest = &rest[len..];
+                }
+                None => expanded.push('$'),
+            }
+        }
+        expanded.push_str(rest);
+        Some(expanded)
+    }
+
+    /// Opens `path`, absolute within the root, if it is an object the
+    /// executable can load.
+    fn open(&self, path: PathBuf) -> Option<(PathBuf, Candidate)> {
+        let host_path = self.resolver.host_path(&path)?;
+        if !host_path.is_file() {
+            return None;
+        }
+        read_candidate(host_path, Some((self.class, self.machine)))
+            .ok()
+            .map(|(candidate, _, _)| (path, candidate))
+    }
+}
@@ -0,0 +1,483 @@
+use std::collections::{BTreeMap, HashMap};
+
+use error::Error;
+use header::*;
+use symbols::Symbol;
+use {Elf32, Elf64};
+
+pub const SIZE_HEADERS: &str = "[ELF Headers]";
+pub const SIZE_UNMAPPED: &str = "[Unmapped]";
+
+/// Bytes attributed to a section, segment or symbol, in the file and in
+/// memory once loaded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SizeEntry {
+    pub name: String,
+    pub file_size: u64,
+    pub vm_size: u64,
+}
+
+/// Breakdown of the file and memory size of a file, like the `sections`,
+/// `segments` and `symbols` views of Bloaty.
+///
+/// In each view every byte of the file and of the `PT_LOAD` segments is
+/// attributed exactly once, so the entries add up to the whole file. Bytes
+/// claimed by nothing else are attributed to `SIZE_HEADERS` when they hold
+/// the ELF, program or section headers and to `SIZE_UNMAPPED` otherwise. In
+/// the symbols view, bytes of a section not covered by any symbol are
+/// attributed to the section name in brackets, such as `[.text]`.
+///
+/// Entries are sorted by decreasing size.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SizeReport {
+    pub file_size: u64,
+    pub vm_size: u64,
+    pub sections: Vec<SizeEntry>,
+    pub segments: Vec<SizeEntry>,
+    pub symbols: Vec<SizeEntry>,
+}
+
+/// The sizes of an entry in two reports, see `diff_sizes`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SizeDelta {
+    pub name: String,
+    pub old_file_size: u64,
+    pub old_vm_size: u64,
+    pub new_file_size: u64,
+    pub new_vm_size: u64,
+}
+
+impl SizeDelta {
+    fn new(name: &str) -> Self {
+        SizeDelta {
+            name: String::from(name),
+            old_file_size: 0,
+            old_vm_size: 0,
+            new_file_size: 0,
+            new_vm_size: 0,
+        }
+    }
+
+    pub fn file_delta(&self) -> i64 {
+        self.new_file_size as i64 - self.old_file_size as i64
+    }
+
+    pub fn vm_delta(&self) -> i64 {
+        self.new_vm_size as i64 - self.old_vm_size as i64
+    }
+}
+
+/// Compares the entries of the same view of two reports, returning the
+/// entries whose size changed sorted by decreasing change.
+pub fn diff_sizes(old: &[SizeEntry], new: &[SizeEntry]) -> Vec<SizeDelta> {
+    let mut deltas: BTreeMap<&str, SizeDelta> = BTreeMap::new();
+    for e in old {
+        let delta = deltas
+            .entry(&e.name)
+            .or_insert_with(|| SizeDelta::new(&e.name));
+        delta.old_file_size += e.file_size;
+        delta.old_vm_size += e.vm_size;
+    }
+    for e in new {
+        let delta = deltas
+            .entry(&e.name)
+            .or_insert_with(|| SizeDelta::new(&e.name));
+        delta.new_file_size += e.file_size;
+        delta.new_vm_size += e.vm_size;
+    }
+    let mut deltas: Vec<SizeDelta> = deltas
+        .into_iter()
+        .map(|(_, d)| d)
+        .filter(|d| d.file_delta() != 0 || d.vm_delta() != 0)
+        .collect();
+    deltas.sort_by(|a, b| {
+        let change = |d: &SizeDelta| d.file_delta().abs().max(d.vm_delta().abs());
+        change(b).cmp(&change(a)).then(a.name.cmp(&b.name))
+    });
+    deltas
+}
+
+/// Disjoint ranges, each attributed to a label. A range claimed twice keeps
+/// its first label.
+#[derive(Default)]
+struct RangeMap {
+    ranges: BTreeMap<u64, (u64, usize)>,
+}
+
+impl RangeMap {
+    fn claim(&mut self, start: u64, size: u64, label: usize) {
+        let end = start.saturating_add(size);
+        if start >= end {
+            return;
+        }
+        let mut overlapping: Vec<(u64, u64)> = self
+            .ranges
+            .range(..end)
+            .rev()
+            .take_while(|&(_, &(e, _))| e > start)
+            .map(|(&s, &(e, _))| (s, e))
+            .collect();
+        overlapping.reverse();
+        let mut pos = start;
+        for (s, e) in overlapping {
+            if s > pos {
+                self.ranges.insert(pos, (s, label));
+            }
+            pos = pos.max(e);
+        }
+        if pos < end {
+            self.ranges.insert(pos, (end, label));
+        }
+    }
+}
+
+/// The labels of a view and the bytes claimed by each of them.
+#[derive(Default)]
+struct View {
+    labels: Vec<String>,
+    index: HashMap<String, usize>,
+    file: RangeMap,
+    vm: RangeMap,
+}
+
+impl View {
+    fn label(&mut self, name: &str) -> usize {
+        if let Some(&idx) = self.index.get(name) {
+            return idx;
+        }
+        self.labels.push(String::from(name));
+        self.index.insert(String::from(name), self.labels.len() - 1);
+        self.labels.len() - 1
+    }
+
+    fn claim_file(&mut self, name: &str, offset: u64, size: u64) {
+        let label = self.label(name);
+        self.file.claim(offset, size, label);
+    }
+
+    fn claim_vm(&mut self, name: &str, addr: u64, size: u64) {
+        let label = self.label(name);
+        self.vm.claim(addr, size, label);
+    }
+
+    fn entries(self) -> Vec<SizeEntry> {
+        let mut entries: Vec<SizeEntry> = self
+            .labels
+            .into_iter()
+            .map(|name| SizeEntry {
+                name: name,
+                file_size: 0,
+                vm_size: 0,
+            })
+            .collect();
+        for (start, &(end, label)) in &self.file.ranges {
+            entries[label].file_size += end - start;
+        }
+        for (start, &(end, label)) in &self.vm.ranges {
+            entries[label].vm_size += end - start;
+        }
+        entries.retain(|e| e.file_size != 0 || e.vm_size != 0);
+        entries.sort_by(|a, b| {
+            b.file_size
+                .max(b.vm_size)
+                .cmp(&a.file_size.max(a.vm_size))
+                .then(a.name.cmp(&b.name))
+        });
+        entries
+    }
+}
+
+/// A section header of either class.
+struct Region<'a> {
+    name: &'a str,
+    sh_type: u32,
+    flags: u64,
+    addr: u64,
+    offset: u64,
+    size: u64,
+}
+
+/// A `PT_LOAD` program header of either class.
+struct Load {
+    index: usize,
+    flags: u32,
+    offset: u64,
+    vaddr: u64,
+    filesz: u64,
+    memsz: u64,
+}
+
+/// What a report is built from, independent of the file class.
+struct SizeInput<'a> {
+    file_size: u64,
+    relocatable: bool,
+    headers: Vec<(u64, u64)>,
+    sections: Vec<Region<'a>>,
+    loads: Vec<Load>,
+    symbols: Vec<Symbol<'a>>,
+}
+
+impl<'a> SizeInput<'a> {
+    /// Returns the address of the section `idx`. Sections of relocatable
+    /// files are all at address 0, so they are given distinct
+    /// pseudo-addresses.
+    fn section_addr(&self, idx: usize) -> u64 {
+        if self.relocatable {
+            (idx as u64) << 40
+        } else {
+            self.sections[idx].addr
+        }
+    }
+
+    /// Returns whether `section` takes room in memory. `.tbss` only takes
+    /// room in the TLS template of each thread.
+    fn in_memory(section: &Region) -> bool {
+        section.flags & SHF_ALLOC != 0
+            && !(section.flags & SHF_TLS != 0 && section.sh_type == SHT_NOBITS)
+    }
+
+    /// Claims the headers in the file and, when loaded, in memory.
+    fn claim_headers(&self, view: &mut View) {
+        for &(offset, size) in &self.headers {
+            view.claim_file(SIZE_HEADERS, offset, size);
+            let end = offset.saturating_add(size);
+            for p in &self.loads {
+                let start = offset.max(p.offset);
+                let stop = end.min(p.offset.saturating_add(p.filesz));
+                if start >= stop {
+                    continue;
+                }
+                if let Some(addr) = p.vaddr.checked_add(start - p.offset) {
+                    view.claim_vm(SIZE_HEADERS, addr, stop - start);
+                }
+            }
+        }
+    }
+
+    /// Claims what is left of the file and of the loaded segments.
+    fn claim_unmapped(&self, view: &mut View) {
+        view.claim_file(SIZE_UNMAPPED, 0, self.file_size);
+        for p in &self.loads {
+            view.claim_vm(SIZE_UNMAPPED, p.vaddr, p.memsz);
+        }
+    }
+
+    fn section_name(&self, idx: usize) -> String {
+        match self.sections[idx].name {
+            "" => format!("[section {}]", idx),
+            name => String::from(name),
+        }
+    }
+
+    fn claim_sections(&self, view: &mut View, brackets: bool) {
+        for (idx, s) in self.sections.iter().enumerate().skip(1) {
+            let name = if brackets {
+                format!("[{}]", self.section_name(idx))
+            } else {
+                self.section_name(idx)
+            };
+            if s.sh_type != SHT_NOBITS {
+                view.claim_file(&name, s.offset, s.size);
+            }
+            if Self::in_memory(s) {
+                view.claim_vm(&name, self.section_addr(idx), s.size);
+            }
+        }
+    }
+
+    fn sections_view(&self) -> View {
+        let mut view = View::default();
+        self.claim_headers(&mut view);
+        self.claim_sections(&mut view, false);
+        self.claim_unmapped(&mut view);
+        view
+    }
+
+    fn segments_view(&self) -> View {
+        let mut view = View::default();
+        self.claim_headers(&mut view);
+        for p in &self.loads {
+            let flags: String = [(PF_R, 'R'), (PF_W, 'W'), (PF_X, 'X')]
+                .iter()
+                .filter(|&&(flag, _)| p.flags & flag != 0)
+                .map(|&(_, c)| c)
+                .collect();
+            let name = format!("LOAD #{} [{}]", p.index, flags);
+            view.claim_file(&name, p.offset, p.filesz);
+            view.claim_vm(&name, p.vaddr, p.memsz);
+        }
+        self.claim_unmapped(&mut view);
+        view
+    }
+
+    fn symbols_view(&self) -> View {
+        let mut view = View::default();
+        self.claim_headers(&mut view);
+
+        // Among aliases, the global symbol comes first and gets the bytes.
+        let mut symbols: Vec<&Symbol> = self
+            .symbols
+            .iter()
+            .filter(|s| {
+                !s.is_undefined()
+                    && s.shndx < SHN_LORESERVE
+                    && (s.shndx as usize) < self.sections.len()
+                    && s.size != 0
+                    && !s.name.is_empty()
+                    && s.kind != STT_SECTION
+                    && s.kind != STT_FILE
+                    && s.kind != STT_TLS
+            })
+            .collect();
+        symbols.sort_by(|a, b| {
+            (a.shndx, a.value, a.bind == STB_LOCAL, a.name).cmp(&(
+                b.shndx,
+                b.value,
+                b.bind == STB_LOCAL,
+                b.name,
+            ))
+        });
+        for sym in symbols {
+            let idx = sym.shndx as usize;
+            let section = &self.sections[idx];
+            let start = if self.relocatable {
+                sym.value
+            } else if sym.value >= section.addr {
+                sym.value - section.addr
+            } else {
+                continue;
+            };
+            if start >= section.size {
+                continue;
+            }
+            let size = sym.size.min(section.size - start);
+            if section.sh_type != SHT_NOBITS {
+                if let Some(offset) = section.offset.checked_add(start) {
+                    view.claim_file(sym.name, offset, size);
+                }
+            }
+            if Self::in_memory(section) {
+                if let Some(addr) = self.section_addr(idx).checked_add(start) {
+                    view.claim_vm(sym.name, addr, size);
+                }
+            }
+        }
+
+        self.claim_sections(&mut view, true);
+        self.claim_unmapped(&mut view);
+        view
+    }
+
+    fn report(&self) -> SizeReport {
+        let sections = self.sections_view().entries();
+        SizeReport {
+            file_size: self.file_size,
+            vm_size: sections.iter().map(|e| e.vm_size).sum(),
+            sections: sections,
+            segments: self.segments_view().entries(),
+            symbols: self.symbols_view().entries(),
+        }
+    }
+}
+
+/// Returns the symbols of `.symtab`, or of `.dynsym` for stripped files.
+fn report_symbols<'a>(
+    symtab: Vec<Symbol<'a>>,
+    dynsym: Result<Vec<Symbol<'a>>, Error>,
+) -> Result<Vec<Symbol<'a>>, Error> {
+    if symtab.is_empty() {
+        dynsym
+    } else {
+        Ok(symtab)
+    }
+}
+
+impl SizeReport {
+    pub fn from_elf64(elf: &Elf64) -> Result<Self, Error> {
+        let h = &elf.header;
+        let input = SizeInput {
+            file_size: elf.data.len() as u64,
+            relocatable: h.e_type == ElfType::Rel as u16,
+            headers: vec![
+                (0, u64::from(h.e_ehsize)),
+                (h.e_phoff, u64::from(h.e_phnum) * u64::from(h.e_phentsize)),
+                (h.e_shoff, u64::from(h.e_shnum) * u64::from(h.e_shentsize)),
+            ],
+            sections: elf
+                .sections
+                .iter()
+                .map(|s| Region {
+                    name: elf.section_name(s).unwrap_or(""),
+                    sh_type: s.sh_type,
+                    flags: s.sh_flags,
+                    addr: s.sh_addr,
+                    offset: s.sh_offset,
+                    size: s.sh_size,
+                })
+                .collect(),
+            loads: elf
+                .segments
+                .iter()
+                .enumerate()
+                .filter(|&(_, p)| p.p_type == PT_LOAD)
+                .map(|(idx, p)| Load {
+                    index: idx,
+                    flags: p.p_flags,
+                    offset: p.p_offset,
+                    vaddr: p.p_vaddr,
+                    filesz: p.p_filesz,
+                    memsz: p.p_memsz,
+                })
+                .collect(),
+            symbols: report_symbols(elf.symtab()?, elf.dynsym())?,
+        };
+        Ok(input.report())
+    }
+
+    pub fn from_elf32(elf: &Elf32) -> Result<Self, Error> {
+        let h = &elf.header;
+        let input = SizeInput {
+            file_size: elf.data.len() as u64,
+            relocatable: h.e_type == ElfType::Rel as u16,
+            headers: vec![
+                (0, u64::from(h.e_ehsize)),
+                (
+                    u64::from(h.e_phoff),
+                    u64::from(h.e_phnum) * u64::from(h.e_phentsize),
+                ),
+                (
+                    u64::from(h.e_shoff),
+                    u64::from(h.e_shnum) * u64::from(h.e_shentsize),
+                ),
+            ],
+            sections: elf
+                .sections
+                .iter()
+                .map(|s| Region {
+                    name: elf.section_name(s).unwrap_or(""),
+                    sh_type: s.sh_type,
+                    flags: u64::from(s.sh_flags),
+                    addr: u64::from(s.sh_addr),
+                    offset: u64::from(s.sh_offset),
+                    size: u64::from(s.sh_size),
+                })
+                .collect(),
+            loads: elf
+                .segments
+                .iter()
+                .enumerate()
+                .filter(|&(_, p)| p.p_type == PT_LOAD)
+                .map(|(idx, p)| Load {
+                    index: idx,
+                    flags: p.p_flags,
+                    offset: u64::from(p.p_offset),
+                    vaddr: u64::from(p.p_vaddr),
+                    filesz: u64::from(p.p_filesz),
+                    memsz: u64::from(p.p_memsz),
+                })
+                .collect(),
+            symbols: report_symbols(elf.symtab()?, elf.dynsym())?,
+        };
+        Ok(input.report())
+    }
+}
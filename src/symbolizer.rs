@@ -0,0 +1,135 @@
+use std::cmp;
+
+use error::Error;
+use header::*;
+use symbols::Symbol;
+use {Elf32, Elf64};
+
+#[derive(Debug, Clone)]
+struct Entry<'a> {
+    start: u64,
+    end: u64,
+    /// Highest end of this entry and the ones before it.
+    reach: u64,
+    name: &'a str,
+}
+
+/// Resolves addresses to the function or object symbol containing them.
+///
+/// Symbols of `.symtab` and `.dynsym` are merged and sorted by address, so
+/// that lookups are a binary search. Addresses in a symbol nested in another
+/// resolve to the inner one.
+#[derive(Debug)]
+pub struct Symbolizer<'a> {
+    entries: Vec<Entry<'a>>,
+    load_bias: u64,
+}
+
+impl<'a> Symbolizer<'a> {
+    /// Builds the index from symbol tables. On ARM, `thumb` clears the low
+    /// bit that marks Thumb functions.
+    pub fn new(tables: &[Vec<Symbol<'a>>], thumb: bool) -> Self {
+        let mut entries: Vec<(Entry<'a>, bool)> = Vec::new();
+
+        for symbol in tables.iter().flat_map(|t| t.iter()) {
+            if symbol.is_undefined() || symbol.name.is_empty() {
+                continue;
+            }
+            if !symbol.is_function() && symbol.kind != STT_OBJECT {
+                continue;
+            }
+            let mut start = symbol.value;
+            if thumb && symbol.is_function() {
+                start &= !1;
+            }
+            entries.push((
+                Entry {
+                    start: start,
+                    end: start.wrapping_add(symbol.size),
+                    reach: 0,
+                    name: symbol.name,
+                },
+                symbol.bind != STB_LOCAL,
+            ));
+        }
+
+        // Among aliases, keep the global symbol with the largest extent.
+        entries.sort_by(|a, b| {
+            a.0.start
+                .cmp(&b.0.start)
+                .then(b.1.cmp(&a.1))
+                .then(b.0.end.cmp(&a.0.end))
+        });
+        entries.dedup_by_key(|e| e.0.start);
+        let mut entries: Vec<Entry<'a>> = entries.into_iter().map(|e| e.0).collect();
+
+        // Symbols without a size extend up to the next symbol.
+        let mut reach = 0;
+        for idx in 0..entries.len() {
+            if entries[idx].end == entries[idx].start {
+                entries[idx].end = match entries.get(idx + 1) {
+                    Some(next) => next.start,
+                    None => entries[idx].start.wrapping_add(1),
+                };
+            }
+            reach = cmp::max(reach, entries[idx].end);
+            entries[idx].reach = reach;
+        }
+
+        Symbolizer {
+            entries: entries,
+            load_bias: 0,
+        }
+    }
+
+    pub fn from_elf64(elf: &Elf64<'a>) -> Result<Self, Error> {
+        let thumb = elf.header.e_machine == ElfMachine::MachineArm as u16;
+        Ok(Symbolizer::new(&[elf.symtab()?, elf.dynsym()?], thumb))
+    }
+
+    pub fn from_elf32(elf: &Elf32<'a>) -> Result<Self, Error> {
+        let thumb = elf.header.e_machine == ElfMachine::MachineArm as u16;
+        Ok(Symbolizer::new(&[elf.symtab()?, elf.dynsym()?], thumb))
+    }
+
+    /// Sets the difference between the runtime and link-time addresses, for
+    /// position independent files loaded at an arbitrary base.
+    pub fn set_load_bias(&mut self, load_bias: u64) {
+        self.load_bias = load_bias;
+    }
+
+    pub fn load_bias(&self) -> u64 {
+        self.load_bias
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the name of the symbol containing the runtime address `addr`
+    /// and the offset of `addr` in it.
+    pub fn symbolize(&self, addr: u64) -> Option<(&'a str, u64)> {
+        let addr = addr.wrapping_sub(self.load_bias);
+        let mut idx = match self.entries.binary_search_by(|e| e.start.cmp(&addr)) {
+            Ok(idx) => idx,
+            Err(0) => return None,
+            Err(idx) => idx - 1,
+        };
+        // Past the end of the closest symbol, `addr` may still be in one
+        // that starts earlier and encloses it.
+        loop {
+            let entry = &self.entries[idx];
+            if addr < entry.end {
+                return Some((entry.name, addr - entry.start));
+            }
+            if idx == 0 || self.entries[idx - 1].reach <= addr {
+                return None;
+            }
+            idx -= 1;
+        }
+    }
+}
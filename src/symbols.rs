@@ -0,0 +1,138 @@
+use std::borrow::Cow;
+
+use demangle::demangle;
+use header::*;
+
+/// A symbol table entry with its name resolved, independent of the file
+/// class.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Symbol<'a> {
+    pub name: &'a str,
+    pub value: u64,
+    pub size: u64,
+    pub bind: u8,
+    pub kind: u8,
+    pub visibility: u8,
+    pub shndx: u16,
+}
+
+impl<'a> Symbol<'a> {
+    pub fn new(name: &'a str, value: u64, size: u64, info: u8, other: u8, shndx: u16) -> Self {
+        Symbol {
+            name: name,
+            value: value,
+            size: size,
+            bind: info >> 4,
+            kind: info & 0xf,
+            visibility: other & 0x3,
+            shndx: shndx,
+        }
+    }
+
+    /// Returns the demangled name, or the raw name when `raw` is set or the
+    /// name cannot be demangled.
+    pub fn display_name(&self, raw: bool) -> Cow<'a, str> {
+        if raw {
+            return Cow::Borrowed(self.name);
+        }
+        match demangle(self.name) {
+            Some(name) => Cow::Owned(name),
+            None => Cow::Borrowed(self.name),
+        }
+    }
+
+    pub fn is_undefined(&self) -> bool {
+        self.shndx == SHN_UNDEF
+    }
+
+    pub fn is_function(&self) -> bool {
+        self.kind == STT_FUNC || self.kind == STT_GNU_IFUNC
+    }
+
+    /// Returns whether the symbol is a reference resolved from another
+    /// object at link or load time.
+    pub fn is_imported(&self) -> bool {
+        self.is_undefined() && self.bind != STB_LOCAL && !self.name.is_empty()
+    }
+
+    /// Returns the letter `nm` displays for the symbol, given the type,
+    /// flags and name of the section it is defined in. The section is
+    /// ignored for undefined, absolute and common symbols.
+    pub fn type_letter(&self, sh_type: u32, sh_flags: u64, section_name: &str) -> char {
+        if self.shndx == SHN_COMMON {
+            return 'C';
+        }
+        if self.is_undefined() {
+            return match (self.bind, self.kind) {
+                (STB_WEAK, STT_OBJECT) => 'v',
+                (STB_WEAK, _) => 'w',
+                _ => 'U',
+            };
+        }
+        if self.kind == STT_GNU_IFUNC {
+            return 'i';
+        }
+        match (self.bind, self.kind) {
+            (STB_WEAK, STT_OBJECT) => return 'V',
+            (STB_WEAK, _) => return 'W',
+            (STB_GNU_UNIQUE, _) => return 'u',
+            (STB_LOCAL, _) | (STB_GLOBAL, _) => {}
+            _ => return '?',
+        }
+        let letter = if self.shndx == SHN_ABS {
+            'a'
+        } else if self.shndx >= SHN_LORESERVE {
+            '?'
+        } else {
+            section_letter(sh_type, sh_flags, section_name)
+        };
+        if self.bind == STB_GLOBAL {
+            letter.to_ascii_uppercase()
+        } else {
+            letter
+        }
+    }
+
+    /// Returns whether the symbol is defined and visible to other objects.
+    pub fn is_exported(&self) -> bool {
+        !self.is_undefined()
+            && !self.name.is_empty()
+            && (self.bind == STB_GLOBAL || self.bind == STB_WEAK || self.bind == STB_GNU_UNIQUE)
+            && (self.visibility == STV_DEFAULT || self.visibility == STV_PROTECTED)
+    }
+}
+
+const DEBUG_SECTION_PREFIXES: &[&str] = &[
+    ".debug",
+    ".zdebug",
+    ".gnu.debuglto_.debug_",
+    ".gnu.linkonce.wi.",
+    ".line",
+    ".stab",
+];
+
+/// Returns the lowercase `nm` letter of the symbols defined in a section,
+/// following the section flags BFD derives from the ELF ones.
+fn section_letter(sh_type: u32, sh_flags: u64, name: &str) -> char {
+    let contents = sh_type != SHT_NOBITS;
+    let load = contents && sh_flags & SHF_ALLOC != 0;
+    let readonly = sh_flags & SHF_WRITE == 0;
+    if sh_flags & SHF_EXECINSTR != 0 {
+        't'
+    } else if load {
+        if readonly {
+            'r'
+        } else {
+            'd'
+        }
+    } else if !contents {
+        'b'
+    } else if DEBUG_SECTION_PREFIXES.iter().any(|p| name.starts_with(p)) {
+        'N'
+    } else if readonly {
+        'n'
+    } else {
+        '?'
+    }
+}
@@ -0,0 +1,180 @@
+use std::collections::BTreeMap;
+
+use error::Error;
+use file::Elf;
+use header::*;
+use relocations::is_copy_relocation;
+use versions::{exported_symbols, ExportedSymbol};
+
+/// An undefined dynamic symbol that no object of the set defines.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnresolvedSymbol<'a> {
+    /// Index of the referencing object.
+    pub object: usize,
+    pub name: &'a str,
+    pub version: Option<&'a str>,
+    /// File the version is needed from.
+    pub file: Option<&'a str>,
+}
+
+/// A symbol defined by several objects of the set with versions a same
+/// reference binds to. ld.so binds the references to the first one in load
+/// order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterposedSymbol<'a> {
+    pub name: &'a str,
+    /// Indexes of the defining objects, in load order.
+    pub objects: Vec<usize>,
+    /// Version of the definition of each object.
+    pub versions: Vec<Option<&'a str>>,
+}
+
+/// Symbol binding problems across a set of objects, typically an
+/// executable and the libraries it loads in load order, as listed by
+/// `LibraryResolver`.
+///
+/// References are matched to definitions as ld.so does: a versioned
+/// reference needs a definition of that version or an unversioned one,
+/// while an unversioned reference only binds to default versions. Weak
+/// references may stay unresolved. The variables an executable copies with
+/// `R_*_COPY` relocations are references to the definitions of the
+/// libraries rather than definitions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolCheck<'a> {
+    /// In object order, then in symbol table order.
+    pub unresolved: Vec<UnresolvedSymbol<'a>>,
+    /// Sorted by name.
+    pub interposed: Vec<InterposedSymbol<'a>>,
+}
+
+/// A definition found while indexing the set.
+struct Definition<'a> {
+    object: usize,
+    version: Option<&'a str>,
+    hidden: bool,
+}
+
+/// Whether a reference with version `version` binds to `def`.
+fn binds(version: Option<&str>, def: &Definition) -> bool {
+    match version {
+        Some(version) => def.version.map_or(!def.hidden, |v| v == version),
+        None => !def.hidden,
+    }
+}
+
+/// Whether a reference binds to either of `a` and `b`, depending on which
+/// comes first.
+fn overlap(a: &Definition, b: &Definition) -> bool {
+    [None, a.version, b.version]
+        .iter()
+        .any(|&version| binds(version, a) && binds(version, b))
+}
+
+/// Returns the names of the dynamic symbols of `R_*_COPY` relocations.
+fn copied_symbols<'a>(elf: &Elf<'a>) -> Result<Vec<&'a str>, Error> {
+    let mut names = Vec::new();
+    dispatch!(elf, e => {
+        for section in &e.sections {
+            if section.sh_type != SHT_REL && section.sh_type != SHT_RELA {
+                continue;
+            }
+            let table = match e.sections.get(section.sh_link as usize) {
+                Some(table) if table.sh_type == SHT_DYNSYM => table,
+                _ => continue,
+            };
+            for r in e.relocations(section)? {
+                if r.sym != 0 && is_copy_relocation(e.header.e_machine, r.kind) {
+                    names.push(e.symbol_at(table, r.sym as usize)?.name);
+                }
+            }
+        }
+    });
+    Ok(names)
+}
+
+impl<'a> SymbolCheck<'a> {
+    pub fn new(objects: &[Elf<'a>]) -> Result<Self, Error> {
+        let copied = objects
+            .iter()
+            .map(copied_symbols)
+            .collect::<Result<Vec<_>, Error>>()?;
+        let mut definitions: BTreeMap<&'a str, Vec<Definition<'a>>> = BTreeMap::new();
+        for (idx, elf) in objects.iter().enumerate() {
+            let versions = elf.versions()?;
+            let version_names: Vec<&str> = versions
+                .definitions
+                .iter()
+                .filter_map(|d| d.name())
+                .collect();
+            for ExportedSymbol {
+                name,
+                version,
+                hidden,
+                ..
+            } in exported_symbols(elf)?
+            {
+                // Version definitions also appear as absolute symbols.
+                if version_names.contains(&name) || copied[idx].contains(&name) {
+                    continue;
+                }
+                definitions
+                    .entry(name)
+                    .or_insert_with(Vec::new)
+                    .push(Definition {
+                        object: idx,
+                        version: version,
+                        hidden: hidden,
+                    });
+            }
+        }
+
+        let mut unresolved = Vec::new();
+        for (idx, elf) in objects.iter().enumerate() {
+            let versions = elf.versions()?;
+            for (sym_idx, sym) in elf.dynsym()?.iter().enumerate() {
+                let reference =
+                    sym.is_imported() || !sym.is_undefined() && copied[idx].contains(&sym.name);
+                if !reference || sym.bind == STB_WEAK {
+                    continue;
+                }
+                let version = versions.symbol_version(sym_idx, sym);
+                let version_name = version.as_ref().map(|v| v.name);
+                let defined = definitions
+                    .get(sym.name)
+                    .map_or(false, |defs| defs.iter().any(|d| binds(version_name, d)));
+                if !defined {
+                    unresolved.push(UnresolvedSymbol {
+                        object: idx,
+                        name: sym.name,
+                        version: version_name,
+                        file: version.and_then(|v| v.file),
+                    });
+                }
+            }
+        }
+
+        let mut interposed = Vec::new();
+        for (&name, defs) in &definitions {
+            let clashing: Vec<&Definition> = defs
+                .iter()
+                .filter(|a| defs.iter().any(|b| b.object != a.object && overlap(a, b)))
+                .collect();
+            if !clashing.is_empty() {
+                interposed.push(InterposedSymbol {
+                    name: name,
+                    objects: clashing.iter().map(|d| d.object).collect(),
+                    versions: clashing.iter().map(|d| d.version).collect(),
+                });
+            }
+        }
+
+        Ok(SymbolCheck {
+            unresolved: unresolved,
+            interposed: interposed,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.unresolved.is_empty() && self.interposed.is_empty()
+    }
+}
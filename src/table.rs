@@ -0,0 +1,91 @@
+use nom;
+
+/// A table of fixed-size entries in a file, such as program or section
+/// headers, whose entries are parsed on access.
+pub struct Table<'a, T> {
+    data: &'a [u8],
+    entsize: usize,
+    count: usize,
+    parse: fn(&'a [u8]) -> nom::IResult<&'a [u8], T>,
+}
+
+impl<'a, T> Table<'a, T> {
+    /// Describes `count` entries of `entsize` bytes at `offset` in `data`.
+    /// A table that does not fit in `data` is empty.
+    pub fn new(
+        data: &'a [u8],
+        offset: usize,
+        entsize: usize,
+        count: usize,
+        parse: fn(&'a [u8]) -> nom::IResult<&'a [u8], T>,
+    ) -> Self {
+        let end = entsize
+            .checked_mul(count)
+            .and_then(|size| offset.checked_add(size));
+        let (data, count) = match end {
+            Some(end) if entsize > 0 && end <= data.len() => (&data[offset..end], count),
+            _ => (&[][..], 0),
+        };
+        Table {
+            data: data,
+            entsize: entsize,
+            count: count,
+            parse: parse,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Parses the entry at `idx`.
+    pub fn get(&self, idx: usize) -> Option<T> {
+        if idx >= self.count {
+            return None;
+        }
+        let start = idx * self.entsize;
+        (self.parse)(&self.data[start..start + self.entsize])
+            .ok()
+            .map(|(_, entry)| entry)
+    }
+
+    pub fn iter(&self) -> TableIter<'a, T> {
+        TableIter {
+            table: *self,
+            idx: 0,
+        }
+    }
+}
+
+impl<'a, T> Clone for Table<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for Table<'a, T> {}
+
+/// Iterator over the entries of a `Table`, stopping at the first entry
+/// that does not parse.
+pub struct TableIter<'a, T> {
+    table: Table<'a, T>,
+    idx: usize,
+}
+
+impl<'a, T> Iterator for TableIter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let entry = self.table.get(self.idx)?;
+        self.idx += 1;
+        Some(entry)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.table.count - self.idx.min(self.table.count)))
+    }
+}
@@ -0,0 +1,288 @@
+use nom;
+
+use error::Error;
+use file::Elf;
+use header::SHN_UNDEF;
+use symbols::Symbol;
+
+pub const VER_FLG_BASE: u16 = 0x1;
+pub const VER_FLG_WEAK: u16 = 0x2;
+pub const VER_FLG_INFO: u16 = 0x4;
+
+pub const VER_NDX_LOCAL: u16 = 0;
+pub const VER_NDX_GLOBAL: u16 = 1;
+
+pub const VERSYM_HIDDEN: u16 = 0x8000;
+pub const VERSYM_VERSION: u16 = 0x7fff;
+
+/// An entry of a `SHT_GNU_verdef` section, defining a version and its
+/// parents. The layout is the same for both file classes.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct VersionDefinition<'a> {
+    /// Offset of the entry in the section.
+    pub offset: usize,
+    pub version: u16,
+    pub flags: u16,
+    /// Version index, as found in `SHT_GNU_versym`.
+    pub index: u16,
+    pub hash: u32,
+    /// The version name first, then its parents, with the offset of each
+    /// auxiliary entry in the section.
+    pub names: Vec<(usize, &'a str)>,
+}
+
+impl<'a> VersionDefinition<'a> {
+    pub fn name(&self) -> Option<&'a str> {
+        self.names.first().map(|&(_, name)| name)
+    }
+}
+
+/// A version needed from a file, an auxiliary entry of `VersionNeed`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct VersionNeedAux<'a> {
+    /// Offset of the entry in the section.
+    pub offset: usize,
+    pub hash: u32,
+    pub flags: u16,
+    /// Version index, as found in `SHT_GNU_versym`.
+    pub index: u16,
+    pub name: &'a str,
+}
+
+/// An entry of a `SHT_GNU_verneed` section, listing the versions needed
+/// from a file.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct VersionNeed<'a> {
+    /// Offset of the entry in the section.
+    pub offset: usize,
+    pub version: u16,
+    pub file: &'a str,
+    pub versions: Vec<VersionNeedAux<'a>>,
+}
+
+/// The version of a dynamic symbol.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct SymbolVersion<'a> {
+    pub name: &'a str,
+    pub index: u16,
+    /// Set for non-default versions, displayed as `name@version` instead of
+    /// `name@@version`.
+    pub hidden: bool,
+    /// File the version is needed from, for references.
+    pub file: Option<&'a str>,
+}
+
+fn u16_at(data: &[u8], offset: usize) -> Result<u16, Error> {
+    let i = data
+        .get(offset..)
+        .ok_or_else(|| Error::ParseError(String::from("version entry is out of bounds")))?;
+    Ok(nom::le_u16(i)?.1)
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Result<u32, Error> {
+    let i = data
+        .get(offset..)
+        .ok_or_else(|| Error::ParseError(String::from("version entry is out of bounds")))?;
+    Ok(nom::le_u32(i)?.1)
+}
+
+fn string<'a, F>(strings: &F, offset: u32) -> Result<&'a str, Error>
+where
+    F: Fn(u32) -> Option<&'a str>,
+{
+    strings(offset).ok_or_else(|| Error::ParseError(format!("bad version string {}", offset)))
+}
+
+/// Parses the `count` entries of a `SHT_GNU_verdef` section, resolving
+/// names with `strings`.
+pub fn parse_version_definitions<'a, F>(
+    data: &[u8],
+    count: usize,
+    strings: F,
+) -> Result<Vec<VersionDefinition<'a>>, Error>
+where
+    F: Fn(u32) -> Option<&'a str>,
+{
+    let mut definitions = Vec::with_capacity(count);
+    let mut offset = 0usize;
+    for _ in 0..count {
+        let aux_count = u16_at(data, offset + 6)?;
+        let mut names = Vec::with_capacity(aux_count as usize);
+        let mut aux = offset + u32_at(data, offset + 12)? as usize;
+        for _ in 0..aux_count {
+            names.push((aux, string(&strings, u32_at(data, aux)?)?));
+            match u32_at(data, aux + 4)? {
+                0 => break,
+                next => aux += next as usize,
+            }
+        }
+        definitions.push(VersionDefinition {
+            offset: offset,
+            version: u16_at(data, offset)?,
+            flags: u16_at(data, offset + 2)?,
+            index: u16_at(data, offset + 4)?,
+            hash: u32_at(data, offset + 8)?,
+            names: names,
+        });
+        match u32_at(data, offset + 16)? {
+            0 => break,
+            next => offset += next as usize,
+        }
+    }
+    Ok(definitions)
+}
+
+/// Parses the `count` entries of a `SHT_GNU_verneed` section, resolving
+/// names with `strings`.
+pub fn parse_version_needs<'a, F>(
+    data: &[u8],
+    count: usize,
+    strings: F,
+) -> Result<Vec<VersionNeed<'a>>, Error>
+where
+    F: Fn(u32) -> Option<&'a str>,
+{
+    let mut needs = Vec::with_capacity(count);
+    let mut offset = 0usize;
+    for _ in 0..count {
+        let aux_count = u16_at(data, offset + 2)?;
+        let mut versions = Vec::with_capacity(aux_count as usize);
+        let mut aux = offset + u32_at(data, offset + 8)? as usize;
+        for _ in 0..aux_count {
+            versions.push(VersionNeedAux {
+                offset: aux,
+                hash: u32_at(data, aux)?,
+                flags: u16_at(data, aux + 4)?,
+                index: u16_at(data, aux + 6)?,
+                name: string(&strings, u32_at(data, aux + 8)?)?,
+            });
+            match u32_at(data, aux + 12)? {
+                0 => break,
+                next => aux += next as usize,
+            }
+        }
+        needs.push(VersionNeed {
+            offset: offset,
+            version: u16_at(data, offset)?,
+            file: string(&strings, u32_at(data, offset + 4)?)?,
+            versions: versions,
+        });
+        match u32_at(data, offset + 12)? {
+            0 => break,
+            next => offset += next as usize,
+        }
+    }
+    Ok(needs)
+}
+
+/// Parses the entries of a `SHT_GNU_versym` section.
+pub fn parse_version_symbols(data: &[u8]) -> Result<Vec<u16>, Error> {
+    let mut versions = Vec::with_capacity(data.len() / 2);
+    for entry in data.chunks(2).filter(|e| e.len() == 2) {
+        versions.push(nom::le_u16(entry)?.1);
+    }
+    Ok(versions)
+}
+
+/// The symbol versioning sections of a file.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Versions<'a> {
+    /// Version of each dynamic symbol.
+    pub symbols: Vec<u16>,
+    pub definitions: Vec<VersionDefinition<'a>>,
+    pub needs: Vec<VersionNeed<'a>>,
+}
+
+impl<'a> Versions<'a> {
+    /// Returns the name of the version `index`, defined or needed.
+    pub fn version_name(&self, index: u16) -> Option<&'a str> {
+        let index = index & VERSYM_VERSION;
+        self.definitions
+            .iter()
+            .find(|d| d.index == index)
+            .and_then(|d| d.name())
+            .or_else(|| {
+                self.needs
+                    .iter()
+                    .flat_map(|n| n.versions.iter())
+                    .find(|v| v.index == index)
+                    .map(|v| v.name)
+            })
+    }
+
+    /// Returns the version of the dynamic symbol `sym` at `idx`. Local and
+    /// global symbols, and the symbols naming a version definition, have
+    /// none.
+    pub fn symbol_version(&self, idx: usize, sym: &Symbol) -> Option<SymbolVersion<'a>> {
+        let versym = *self.symbols.get(idx)?;
+        let index = versym & VERSYM_VERSION;
+        let hidden = versym & VERSYM_HIDDEN != 0;
+        if index == VER_NDX_LOCAL || index == VER_NDX_GLOBAL {
+            return None;
+        }
+        // Copy-relocated variables are defined, but with a needed version
+        if sym.shndx != SHN_UNDEF {
+            if let Some(def) = self.definitions.iter().find(|d| d.index == index) {
+                let name = def.name()?;
+                if name == sym.name {
+                    return None;
+                }
+                return Some(SymbolVersion {
+                    name: name,
+                    index: index,
+                    hidden: hidden,
+                    file: None,
+                });
+            }
+        }
+        self.needs.iter().find_map(|need| {
+            need.versions
+                .iter()
+                .find(|v| v.index == index)
+                .map(|v| SymbolVersion {
+                    name: v.name,
+                    index: index,
+                    hidden: hidden,
+                    file: Some(need.file),
+                })
+        })
+    }
+}
+
+/// A symbol exported by a shared object, with its version.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportedSymbol<'a> {
+    pub name: &'a str,
+    pub version: Option<&'a str>,
+    /// Set for non-default versions, `name@version` instead of
+    /// `name@@version`.
+    pub hidden: bool,
+    pub kind: u8,
+    pub size: u64,
+}
+
+/// Returns the symbols of `.dynsym` the file exports, with their version.
+pub fn exported_symbols<'a>(elf: &Elf<'a>) -> Result<Vec<ExportedSymbol<'a>>, Error> {
+    let versions = elf.versions()?;
+    Ok(elf
+        .dynsym()?
+        .iter()
+        .enumerate()
+        .filter(|&(_, s)| s.is_exported())
+        .map(|(idx, s)| {
+            let version = versions.symbol_version(idx, s);
+            ExportedSymbol {
+                name: s.name,
+                version: version.as_ref().map(|v| v.name),
+                hidden: version.map_or(false, |v| v.hidden),
+                kind: s.kind,
+                size: s.size,
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,65 @@
+//! Parses `tests/fixtures/libhello.a`, whose members and symbol map are
+//! listed by `ar t` and `nm -s`.
+
+extern crate elf;
+
+mod common;
+
+use common::fixture;
+use elf::*;
+use std::fs;
+
+#[test]
+fn members_and_symbol_map() {
+    let data = fs::read(fixture("libhello.a")).unwrap();
+    let (_, archive) = parse_archive(&data).unwrap();
+    assert!(!archive.thin);
+
+    let names: Vec<&str> = archive.members.iter().map(|m| m.name).collect();
+    assert_eq!(names, vec!["hello.o", "bar32.o"]);
+    for member in &archive.members {
+        // `ar rcD` stores the objects as is, with zero dates and ids.
+        let object = fs::read(fixture(member.name)).unwrap();
+        assert_eq!(member.data, Some(&object[..]));
+        assert_eq!(member.header.ar_size, object.len() as u64);
+        assert_eq!((member.header.ar_date, member.header.ar_uid), (0, 0));
+        assert_eq!(archive.member_at(member.offset), Some(member));
+    }
+    assert!(archive.members[0].parse_elf64().is_some());
+    assert!(archive.members[1].parse_elf32().is_some());
+
+    let symbols: Vec<&str> = archive.symbols.iter().map(|s| s.0).collect();
+    assert_eq!(
+        symbols,
+        vec![
+            "main",
+            "counter",
+            "bar_get",
+            "__x86.get_pc_thunk.dx",
+            "bar_data",
+            "bar_call",
+            "__x86.get_pc_thunk.bx",
+        ]
+    );
+    assert_eq!(
+        archive.member_for_symbol("counter").unwrap().name,
+        "hello.o"
+    );
+    assert_eq!(
+        archive.member_for_symbol("bar_call").unwrap().name,
+        "bar32.o"
+    );
+    assert!(archive.member_for_symbol("foo_add").is_none());
+}
+
+#[test]
+fn malformed_archives() {
+    let data = fs::read(fixture("libhello.a")).unwrap();
+    assert!(parse_archive(&data[1..]).is_err());
+    // A member running past the end of the archive.
+    assert!(parse_archive(&data[..data.len() - 1]).is_err());
+    // A member header missing its "`\n" terminator.
+    let mut bad = data.clone();
+    bad[8 + 58] = b'x';
+    assert!(parse_archive(&bad).is_err());
+}
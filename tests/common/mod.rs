@@ -0,0 +1,122 @@
+//! Helpers shared by the integration tests: the fixtures of `tests/fixtures`,
+//! the golden files of `tests/golden`, the binaries of the crate and
+//! `ld.so.cache` files.
+
+#![allow(dead_code)]
+
+use elf::ElfFile;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+pub fn tests_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests")
+}
+
+pub fn fixture(name: &str) -> PathBuf {
+    tests_dir().join("fixtures").join(name)
+}
+
+pub fn open(name: &str) -> ElfFile {
+    ElfFile::open(&fixture(name)).expect("cannot open fixture")
+}
+
+/// Runs the binary `bin` of the crate from `tests/fixtures` and returns its
+/// standard output, failing the test if it does not succeed.
+pub fn run(bin: &str, args: &[&str], files: &[&str]) -> String {
+    let name = Path::new(bin).file_name().unwrap().to_string_lossy();
+    let output = Command::new(bin)
+        .current_dir(tests_dir().join("fixtures"))
+        .args(args)
+        .args(files)
+        .output()
+        .unwrap_or_else(|e| panic!("failed to run {}: {}", name, e));
+    assert!(
+        output.status.success(),
+        "{} {:?} {:?} failed: {}",
+        name,
+        args,
+        files,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap_or_else(|_| panic!("{} output is not UTF-8", name))
+}
+
+/// Compares `actual` with the golden file `name`, reporting the first line
+/// that differs.
+pub fn check_golden(name: &str, actual: &str) {
+    let path = tests_dir().join("golden").join(name);
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("cannot read {}: {}", path.display(), e));
+    if actual != expected {
+        for (line, (a, e)) in actual.lines().zip(expected.lines()).enumerate() {
+            assert_eq!(a, e, "{}:{} differs", name, line + 1);
+        }
+        assert_eq!(
+            actual.lines().count(),
+            expected.lines().count(),
+            "{} has a different number of lines",
+            name
+        );
+        panic!("{} differs in line endings", name);
+    }
+}
+
+/// A library of the `ld.so.cache` files built by `old_cache` and
+/// `new_cache`.
+pub struct CacheEntry {
+    pub flags: i32,
+    pub soname: &'static str,
+    pub path: String,
+    pub hwcap: u64,
+}
+
+fn push_u32(data: &mut Vec<u8>, value: u32) {
+    data.extend_from_slice(&value.to_le_bytes());
+}
+
+/// Appends the strings of `entries` to `strings`, returning the offsets of
+/// each soname and path from `base`.
+fn add_strings(entries: &[CacheEntry], strings: &mut Vec<u8>, base: usize) -> Vec<(u32, u32)> {
+    let mut add = |s: &str| {
+        let offset = base + strings.len();
+        strings.extend_from_slice(s.as_bytes());
+        strings.push(0);
+        offset as u32
+    };
+    entries
+        .iter()
+        .map(|e| (add(e.soname), add(&e.path)))
+        .collect()
+}
+
+pub fn old_cache(entries: &[CacheEntry]) -> Vec<u8> {
+    let mut data = b"ld.so-1.7.0\0".to_vec();
+    push_u32(&mut data, entries.len() as u32);
+    let mut strings = Vec::new();
+    for (e, (key, value)) in entries.iter().zip(add_strings(entries, &mut strings, 0)) {
+        push_u32(&mut data, e.flags as u32);
+        push_u32(&mut data, key);
+        push_u32(&mut data, value);
+    }
+    data.extend(strings);
+    data
+}
+
+pub fn new_cache(entries: &[CacheEntry]) -> Vec<u8> {
+    let mut strings = Vec::new();
+    let offsets = add_strings(entries, &mut strings, 48 + 24 * entries.len());
+    let mut data = b"glibc-ld.so.cache1.1".to_vec();
+    push_u32(&mut data, entries.len() as u32);
+    push_u32(&mut data, strings.len() as u32);
+    data.extend_from_slice(&[0; 20]);
+    for (e, (key, value)) in entries.iter().zip(offsets) {
+        push_u32(&mut data, e.flags as u32);
+        push_u32(&mut data, key);
+        push_u32(&mut data, value);
+        push_u32(&mut data, 0);
+        data.extend_from_slice(&e.hwcap.to_le_bytes());
+    }
+    data.extend(strings);
+    data
+}
@@ -0,0 +1,37 @@
+//! Demangles C++ and Rust names, checked against `c++filt` from binutils
+//! 2.40 where the demanglers agree.
+
+#![cfg(feature = "demangle")]
+
+extern crate elf;
+
+use elf::*;
+
+#[test]
+fn cpp() {
+    assert_eq!(demangle("_ZN3foo3barEv").unwrap(), "foo::bar()");
+    assert_eq!(demangle("_ZZ4mainE5count").unwrap(), "main::count");
+    assert_eq!(
+        demangle("_ZNSt6vectorIiSaIiEE9push_backERKi").unwrap(),
+        "std::vector<int, std::allocator<int> >::push_back(int const&)"
+    );
+}
+
+#[test]
+fn rust() {
+    // The hash of legacy names is left out, like `{:#}` does.
+    assert_eq!(
+        demangle("_ZN4core3fmt5write17h0123456789abcdefE").unwrap(),
+        "core::fmt::write"
+    );
+    assert_eq!(demangle("_RNvCs1234_7mycrate3foo").unwrap(), "mycrate::foo");
+}
+
+#[test]
+fn not_mangled() {
+    assert_eq!(demangle("main"), None);
+    assert_eq!(demangle("_Zbogus"), None);
+    let symbol = Symbol::new("_ZN3foo3barEv", 0, 0, 0x12, 0, 1);
+    assert_eq!(symbol.display_name(false), "foo::bar()");
+    assert_eq!(symbol.display_name(true), "_ZN3foo3barEv");
+}
@@ -0,0 +1,74 @@
+//! Checks the differences between the files of `tests/fixtures`.
+
+extern crate elf;
+
+mod common;
+
+use common::open;
+use elf::*;
+
+#[test]
+fn identical_files() {
+    for fixture in &["hello", "hello.o", "libfoo.so", "libbar32.so", "bar32.o"] {
+        let file = open(fixture);
+        let elf = file.parse().expect("cannot parse fixture");
+        let diff = ElfDiff::new(&elf, &elf).expect("cannot diff");
+        assert!(diff.is_empty(), "{}: {:?}", fixture, diff);
+        assert!(!diff.breaks_abi(), "{}", fixture);
+    }
+}
+
+#[test]
+fn object_to_executable() {
+    let (old, new) = (open("hello.o"), open("hello"));
+    let diff = ElfDiff::new(&old.parse().unwrap(), &new.parse().unwrap()).unwrap();
+    let e_type = diff.header.iter().find(|h| h.field == "e_type").unwrap();
+    assert_eq!((e_type.old, e_type.new), (1, 3));
+    assert!(diff
+        .sections
+        .iter()
+        .any(|c| c.new_item().is_none() && c.old_item().unwrap().name == ".rela.text.startup"));
+    assert!(diff
+        .sections
+        .iter()
+        .any(|c| c.old_item().is_none() && c.new_item().unwrap().name == ".dynamic"));
+    assert!(diff.segments.iter().all(|c| c.old_item().is_none()));
+    assert!(!diff.needed.is_empty());
+    assert!(diff
+        .symbols
+        .iter()
+        .any(|c| c.old_item().is_none() && c.new_item().unwrap().name == "_start"));
+}
+
+#[test]
+fn exported_abi() {
+    let (old, new) = (open("libfoo.so"), open("libbar32.so"));
+    let diff = ElfDiff::new(&old.parse().unwrap(), &new.parse().unwrap()).unwrap();
+    assert!(diff.breaks_abi());
+    assert!(diff.header.iter().any(|h| h.field == "class"));
+    assert_eq!(diff.soname, Some(Change::Added("libbar.so.1")));
+    assert_eq!(diff.needed, vec![Change::Removed("ld-linux-x86-64.so.2")]);
+    assert_eq!(
+        diff.version_definitions,
+        vec![
+            Change::Added("BAR_1.0"),
+            Change::Removed("VERS_1"),
+            Change::Removed("VERS_2"),
+        ]
+    );
+    let hidden_foo = ExportedSymbol {
+        name: "foo",
+        version: Some("VERS_1"),
+        hidden: true,
+        kind: STT_FUNC,
+        size: 6,
+    };
+    assert!(diff.exports.contains(&Change::Removed(hidden_foo)));
+    assert!(diff
+        .abi_breaks()
+        .all(|c| c.new_item().is_none() || c.old_item().is_some()));
+    assert!(diff
+        .exports
+        .iter()
+        .any(|c| c.new_item().map_or(false, |e| e.name == "bar_get")));
+}
@@ -0,0 +1,96 @@
+//! Compares the output of `dumpelf` on the files of `tests/fixtures` with the
+//! golden files of `tests/golden`, which match `readelf -W` from binutils
+//! 2.40 with the same options.
+//!
+//! The golden files are generated by readelf, see `make golden` in
+//! `tests/fixtures`.
+
+extern crate elf;
+#[cfg(feature = "serde")]
+extern crate serde_json;
+
+mod common;
+
+use common::check_golden;
+
+const FIXTURES: &[&str] = &["hello", "hello.o", "libfoo.so", "libbar32.so", "bar32.o"];
+
+fn dumpelf(args: &[&str], files: &[&str]) -> String {
+    common::run(env!("CARGO_BIN_EXE_dumpelf"), args, files)
+}
+
+fn check_option(option: &str, suffix: &str) {
+    for fixture in FIXTURES {
+        let actual = dumpelf(&[option], &[fixture]);
+        check_golden(&format!("{}.{}", fixture, suffix), &actual);
+    }
+}
+
+#[test]
+fn file_header() {
+    check_option("-h", "h");
+}
+
+#[test]
+fn program_headers() {
+    check_option("-l", "l");
+}
+
+#[test]
+fn section_headers() {
+    check_option("-S", "S");
+}
+
+#[test]
+fn symbols() {
+    check_option("-s", "s");
+}
+
+#[test]
+fn relocations() {
+    check_option("-r", "r");
+}
+
+#[test]
+fn dynamic() {
+    check_option("-d", "d");
+}
+
+#[test]
+fn notes() {
+    check_option("-n", "n");
+}
+
+#[test]
+fn versions() {
+    check_option("-V", "V");
+}
+
+#[test]
+fn all_files() {
+    let actual = dumpelf(&["-h", "-l", "-S", "-s", "-r", "-d", "-n", "-V"], FIXTURES);
+    check_golden("all", &actual);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn json_documents() {
+    let actual = dumpelf(&["--json"], FIXTURES);
+    let documents: Vec<&str> = actual.lines().collect();
+    assert_eq!(documents.len(), FIXTURES.len());
+    for (document, fixture) in documents.iter().zip(FIXTURES) {
+        let prefix = format!("{{\"schema\":1,\"file\":\"{}\",", fixture);
+        assert!(document.starts_with(&prefix), "{}", document);
+        assert!(document.ends_with('}'), "{}", document);
+
+        // Identification fields are numbers, as e_type and e_machine.
+        let value: serde_json::Value = serde_json::from_str(document).unwrap();
+        let file = common::open(fixture);
+        let elf = file.parse().unwrap();
+        let header = &value["header"];
+        assert_eq!(header["e_ident"]["class"], elf.class() as u8);
+        assert_eq!(header["e_ident"]["data"], elf.endianness() as u8);
+        assert_eq!(header["e_ident"]["osabi"], 0);
+        assert_eq!(header["e_machine"], elf.machine());
+    }
+}
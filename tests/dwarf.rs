@@ -0,0 +1,255 @@
+//! Checks the line programs, call frame information and inlined frames of
+//! `tests/fixtures/debug` against `readelf --debug-dump`, see `make golden`
+//! in `tests/fixtures`.
+
+extern crate elf;
+#[cfg(feature = "serde")]
+extern crate serde_json;
+
+mod common;
+
+use common::{fixture, tests_dir};
+use elf::dwarf::*;
+use elf::*;
+use std::fs;
+
+fn golden(name: &str) -> String {
+    fs::read_to_string(tests_dir().join("golden").join(name)).expect("cannot read golden file")
+}
+
+fn hex(s: &str) -> u64 {
+    u64::from_str_radix(s.trim_start_matches("0x"), 16).unwrap()
+}
+
+/// Returns the contents of `tests/fixtures/debug`, a 64-bit executable.
+fn debug() -> Vec<u8> {
+    fs::read(fixture("debug")).expect("cannot read fixture")
+}
+
+#[test]
+fn line_rows() {
+    let data = debug();
+    let elf = parse_elf64(&data).unwrap().1;
+    let sections = DwarfSections::from_elf64(&elf).unwrap();
+    let (_, program) = parse_line_program(&sections, 0, ".", "debug.c").unwrap();
+    assert_eq!(program.version, 5);
+
+    // readelf lists the rows of each sequence, then its end with no line.
+    let mut expected = Vec::new();
+    for line in golden("debug.decodedline").lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 3 || !fields[2].starts_with("0x") {
+            continue;
+        }
+        expected.push((
+            fields[0].to_string(),
+            fields[1].parse().ok(),
+            hex(fields[2]),
+        ));
+    }
+    let mut actual = Vec::new();
+    for sequence in &program.sequences {
+        for row in &sequence.rows {
+            let name = program.file(row.file).unwrap().rsplit('/').next().unwrap();
+            actual.push((name.to_string(), Some(row.line), row.address));
+        }
+        actual.push((String::from("debug.c"), None, sequence.end));
+    }
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn inlined_frames() {
+    let data = debug();
+    let elf = parse_elf64(&data).unwrap().1;
+    let addr2line = elf.addr2line().unwrap();
+    let frames = addr2line.find_frames(0x11a0);
+    let names: Vec<Option<&str>> = frames.iter().map(|f| f.function.as_deref()).collect();
+    assert_eq!(names, vec![Some("square"), Some("sum_squares")]);
+    let lines: Vec<Option<u32>> = frames
+        .iter()
+        .map(|f| f.location.as_ref().and_then(|l| l.line))
+        .collect();
+    assert_eq!(lines, vec![Some(11), Some(18)]);
+
+    let location = addr2line.find_location(0x11c4).unwrap();
+    assert!(location.file.unwrap().ends_with("debug.c"));
+    assert_eq!(location.line, Some(25));
+    assert_eq!(addr2line.find_function_name(0x11c4), Some("buffer_length"));
+    assert_eq!(addr2line.find_function_name(0x10a0), None);
+}
+
+fn register_rule(arch: Arch, rule: &RegisterRule) -> String {
+    match *rule {
+        RegisterRule::Undefined => String::from("u"),
+        RegisterRule::SameValue => String::from("s"),
+        RegisterRule::Offset(n) => format!("c{:+}", n),
+        RegisterRule::ValOffset(n) => format!("v{:+}", n),
+        RegisterRule::Register(r) => arch.register_name(r).unwrap().to_string(),
+        RegisterRule::Expression(_) => String::from("exp"),
+        RegisterRule::ValExpression(_) => String::from("vexp"),
+    }
+}
+
+/// Formats the row at `pc` like `readelf --debug-dump=frames-interp`, for the
+/// registers of `columns`.
+fn interp_row(table: &FrameTable, pc: u64, columns: &[&str]) -> Vec<String> {
+    let arch = Arch::X86_64;
+    let row = table
+        .unwind_row(pc)
+        .unwrap()
+        .expect("no FDE covers the row");
+    let mut fields = vec![match row.cfa {
+        CfaRule::RegisterOffset(reg, offset) => {
+            format!("{}{:+}", arch.register_name(reg).unwrap(), offset)
+        }
+        CfaRule::Expression(_) => String::from("exp"),
+    }];
+    for column in columns {
+        let reg = if *column == "ra" {
+            row.return_address_register
+        } else {
+            (0..17)
+                .find(|&r| arch.register_name(r) == Some(column))
+                .unwrap()
+        };
+        fields.push(match row.registers.iter().find(|r| r.0 == reg) {
+            Some(r) => register_rule(arch, &r.1),
+            None => String::from("u"),
+        });
+    }
+    fields
+}
+
+#[test]
+fn cfa_rows() {
+    let data = debug();
+    let elf = parse_elf64(&data).unwrap().1;
+    let table = elf.eh_frame().unwrap();
+    let mut in_fde = false;
+    let mut columns: Vec<String> = Vec::new();
+    let mut rows = 0;
+    for line in golden("debug.frames-interp").lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() >= 4 && (fields[3] == "FDE" || fields[3] == "CIE") {
+            in_fde = fields[3] == "FDE";
+            columns.clear();
+        } else if fields.first() == Some(&"LOC") {
+            columns = fields[2..].iter().map(|s| s.to_string()).collect();
+        } else if in_fde && !columns.is_empty() && fields.len() == columns.len() + 2 {
+            let columns: Vec<&str> = columns.iter().map(|s| s.as_str()).collect();
+            let actual = interp_row(&table, hex(fields[0]), &columns);
+            assert_eq!(actual, &fields[1..], "row at {}", fields[0]);
+            rows += 1;
+        }
+    }
+    assert!(rows > 10);
+}
+
+#[test]
+fn eh_frame_hdr_matches_eh_frame() {
+    let data = debug();
+    let elf = parse_elf64(&data).unwrap().1;
+    let table = elf.eh_frame().unwrap();
+    let hdr = elf.eh_frame_hdr().unwrap();
+    let eh_frame = elf.section_by_name(".eh_frame").unwrap().sh_addr;
+    assert_eq!(hdr.eh_frame_ptr, eh_frame);
+    assert_eq!(hdr.table.len(), table.fdes.len());
+    for fde in &table.fdes {
+        let address = hdr.lookup(fde.initial_location).unwrap();
+        assert_eq!(address - eh_frame, fde.offset as u64);
+    }
+    assert_eq!(
+        table.missing(&[(0x1190, 0x11bd), (0x1200, 0x1210)]),
+        vec![(0x1200, 0x1210)]
+    );
+}
+
+#[test]
+fn eh_frame_hdr_without_sections() {
+    // The table is read from the PT_GNU_EH_FRAME segment instead.
+    let data = debug();
+    let expected = parse_elf64(&data).unwrap().1.eh_frame_hdr().unwrap();
+    let stripped = fs::read(fixture("debug.nosections")).unwrap();
+    let hdr = parse_elf64(&stripped).unwrap().1.eh_frame_hdr().unwrap();
+    assert_eq!(hdr.eh_frame_ptr, expected.eh_frame_ptr);
+    assert_eq!(hdr.table, expected.table);
+}
+
+#[test]
+fn expressions() {
+    let mut regs = Registers::new();
+    regs.insert(7, 0x7fff_0000);
+    let read = |addr: u64| if addr == 0x7fff_0008 { Some(42) } else { None };
+    // DW_OP_breg7 8, DW_OP_deref, DW_OP_lit2, DW_OP_mul
+    let expr = [0x77, 0x08, 0x06, 0x32, 0x1e];
+    assert_eq!(
+        evaluate_expression(&expr, None, &regs, 8, &read).unwrap(),
+        84
+    );
+    // DW_OP_lit1, DW_OP_bra 1, DW_OP_lit5: the branch skips DW_OP_lit5.
+    let expr = [0x31, 0x28, 0x01, 0x00, 0x35];
+    assert_eq!(
+        evaluate_expression(&expr, Some(3), &regs, 8, &read).unwrap(),
+        3
+    );
+
+    // DW_OP_skip -3 jumps back to itself forever.
+    let expr = [0x2f, 0xfd, 0xff];
+    assert!(evaluate_expression(&expr, None, &regs, 8, &read).is_err());
+    // DW_OP_drop on an empty stack, and an unknown register.
+    assert!(evaluate_expression(&[0x13], None, &regs, 8, &read).is_err());
+    assert!(evaluate_expression(&[0x70, 0x00], None, &regs, 8, &read).is_err());
+}
+
+#[test]
+fn indirect_pointers() {
+    // A CIE with augmentation "zPR": an indirect pc-relative personality
+    // pointer at offset 18 and pc-relative FDE addresses.
+    let mut cie = vec![20, 0, 0, 0, 0, 0, 0, 0, 1];
+    cie.extend_from_slice(b"zPR\0");
+    cie.extend_from_slice(&[1, 0x78, 16, 6, 0x9b, 0, 1, 0, 0, 0x1b, 0]);
+    let bases = PointerBases::default();
+    let section = FrameSection::new(&cie, FrameKind::EhFrame, 8, bases);
+    let parsed = section.parse_cie(0).unwrap();
+    assert_eq!(parsed.personality, Some(18 + 0x100));
+    assert!(parsed.personality_indirect);
+    assert_eq!(parsed.fde_encoding, 0x1b);
+
+    assert!(parse_pointer(&cie[18..], &cie[18..], 0x9b, 8, &bases).is_err());
+    assert_eq!(
+        parse_pointer(&cie[18..], &cie[18..], 0x1b, 8, &bases)
+            .unwrap()
+            .1,
+        0x100
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialized_rows() {
+    let file = common::open("debug");
+    let elf = match file.parse().unwrap() {
+        Elf::Elf64(e) => e,
+        _ => panic!("debug is a 64-bit file"),
+    };
+    let sections = DwarfSections::from_elf64(&elf).unwrap();
+    let (_, program) = parse_line_program(&sections, 0, ".", "debug.c").unwrap();
+    let value = serde_json::to_value(&program).unwrap();
+    let row = &program.sequences[0].rows[0];
+    assert_eq!(value["version"], 5);
+    assert_eq!(value["sequences"][0]["rows"][0]["address"], row.address);
+    assert_eq!(value["sequences"][0]["rows"][0]["line"], row.line);
+
+    let table = elf.eh_frame().unwrap();
+    let row = table.unwind_row(0x1190).unwrap().unwrap();
+    let value = serde_json::to_value(&row).unwrap();
+    assert_eq!(value["start"], row.start);
+    assert_eq!(value["cfa"]["RegisterOffset"], serde_json::json!([7, 8]));
+
+    let addr2line = file.parse().unwrap().addr2line().unwrap();
+    let frames = addr2line.find_frames(0x11a0);
+    let value = serde_json::to_value(&frames).unwrap();
+    assert_eq!(value[0]["function"], "square");
+    assert_eq!(value[1]["location"]["line"], 18);
+}
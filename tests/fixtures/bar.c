@@ -0,0 +1,5 @@
+int bar_data = 7;
+static int bar_hidden;
+int bar_get(void) { return bar_data + bar_hidden++; }
+extern int ext_fn(int);
+int bar_call(int x) { return ext_fn(x) + 1; }
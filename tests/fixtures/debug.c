@@ -0,0 +1,35 @@
+/* Debug information fixture for the DWARF line, frame and symbolizer
+   tests. */
+
+#include <stdio.h>
+#include <string.h>
+
+int total;
+
+static inline int square(int x)
+{
+	return x * x;
+}
+
+__attribute__((noinline)) int sum_squares(int n)
+{
+	int sum = 0;
+	for (int i = 0; i < n; i++)
+		sum += square(i);
+	return sum;
+}
+
+__attribute__((noinline)) int buffer_length(int n)
+{
+	char buffer[n + 1];
+	memset(buffer, 'x', n);
+	buffer[n] = 0;
+	return strlen(buffer);
+}
+
+int main(int argc, char **argv)
+{
+	total = sum_squares(argc + 3) + buffer_length(argc);
+	printf("%d\n", total);
+	return 0;
+}
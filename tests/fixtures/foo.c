@@ -0,0 +1,9 @@
+#include <stdio.h>
+__thread int tls_counter;
+int foo_data = 42;
+static int hidden_counter;
+int foo_add(int a, int b) { hidden_counter++; tls_counter++; return a + b; }
+__asm__(".symver foo_old, foo@VERS_1");
+__asm__(".symver foo_new, foo@@VERS_2");
+int foo_old(void) { return 1; }
+int foo_new(void) { return 2; }
@@ -0,0 +1,14 @@
+// Definitions for `fw_aarch64_main.s`, with `far_data` on another page.
+    .globl lib_fn, lib_word, far_data, small
+    .set    small, 0x1234
+    .text
+lib_fn:
+    ret
+
+    .data
+    .p2align 3
+lib_word:
+    .xword  42
+    .p2align 12
+far_data:
+    .fill   32, 1, 7
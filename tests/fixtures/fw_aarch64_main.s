@@ -0,0 +1,39 @@
+// Every AArch64 relocation `LinkedImage` applies, against the symbols of
+// `fw_aarch64_lib.s`.
+    .text
+    .globl _start
+_start:
+    bl      lib_fn
+    b       lib_fn
+    b.eq    lib_fn
+    tbz     x0, #3, lib_fn
+    ldr     x1, lib_word
+    adr     x2, lib_word
+    adrp    x3, far_data
+    add     x3, x3, :lo12:far_data
+    adrp    x4, :pg_hi21_nc:far_data
+    ldrb    w5, [x4, :lo12:far_data]
+    ldrh    w5, [x4, :lo12:far_data]
+    ldr     w5, [x4, :lo12:far_data]
+    ldr     x5, [x4, :lo12:far_data]
+    ldr     q5, [x4, :lo12:far_data]
+    movz    x6, #:abs_g0:small
+    movk    x6, #:abs_g0_nc:lib_word
+    movz    x7, #:abs_g1:lib_word
+    movk    x7, #:abs_g1_nc:lib_word
+    movz    x8, #:abs_g2:lib_word
+    movk    x8, #:abs_g2_nc:lib_word
+    movk    x8, #:abs_g3:lib_word
+1:  b       1b
+
+    .section .rodata.words, "a"
+    .p2align 3
+    .xword  lib_word + 8
+    .word   lib_word
+    .hword  small
+    .p2align 3
+    .xword  lib_word - .
+    .word   lib_fn - .
+    .hword  lib_fn - . + 0x100
+    .reloc  ., R_AARCH64_NONE, lib_fn
+    .hword  0
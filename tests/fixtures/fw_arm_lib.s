@@ -0,0 +1,22 @@
+@ Definitions for `fw_arm_main.s`.
+    .syntax unified
+    .arch   armv7-a
+    .globl arm_fn, thumb_fn, thumb_near, lib_word
+    .text
+    .arm
+    .type   arm_fn, %function
+arm_fn:
+    bx      lr
+
+    .thumb
+    .thumb_func
+thumb_fn:
+    bx      lr
+    .thumb_func
+thumb_near:
+    bx      lr
+
+    .data
+    .p2align 2
+lib_word:
+    .word   42
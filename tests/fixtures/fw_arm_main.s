@@ -0,0 +1,50 @@
+@ Every ARM relocation `LinkedImage` applies, in ARM and Thumb code,
+@ against the symbols of `fw_arm_lib.s`.
+    .syntax unified
+    .arch   armv7-a
+    .globl  thumb_near
+    .text
+    .arm
+    .globl _start
+_start:
+    bl      arm_fn
+    bl      thumb_fn
+    blx     thumb_fn
+    b       arm_fn
+    movw    r0, #:lower16:lib_word
+    movt    r0, #:upper16:lib_word
+    .reloc  ., R_ARM_V4BX, 0
+    bx      lr
+    blx     thumb_code
+1:  b       1b
+
+    .thumb
+    .thumb_func
+thumb_code:
+    bl      thumb_fn
+    bl      arm_fn
+    b.w     thumb_fn
+    beq.w   thumb_fn
+    @ llvm-mc widens narrow branches to other files, `b.n` and `beq.n`
+    @ with the addend -4.
+    .reloc  ., R_ARM_THM_JUMP11, thumb_near
+    .hword  0xe7fe
+    .reloc  ., R_ARM_THM_JUMP8, thumb_near
+    .hword  0xd0fe
+    movw    r1, #:lower16:lib_word
+    movt    r1, #:upper16:lib_word
+    movw    r2, #:lower16:thumb_fn
+    bx      lr
+    @ LLD pads code with 0xd4 rather than zeros.
+    nop
+
+    .section .rodata.words, "a"
+    .p2align 2
+    .word   lib_word + 4
+    .word   thumb_fn
+    .word   lib_word - .
+    .word   thumb_fn - .
+    .reloc  ., R_ARM_PREL31, arm_fn
+    .word   0x80000000
+    .reloc  ., R_ARM_NONE, arm_fn
+    .word   0
@@ -0,0 +1,17 @@
+int counter = 1;
+const char banner[] = "firmware";
+static const int table[] = { 1, 2, 3, 4 };
+__attribute__((weak)) int optional_hook(void);
+
+int table_sum(void)
+{
+    int sum = 0;
+    for (int i = 0; i < 4; i++)
+        sum += table[i] * counter;
+    return sum;
+}
+
+int call_hook(void)
+{
+    return optional_hook ? optional_hook() : 0;
+}
@@ -0,0 +1,15 @@
+extern int counter;
+extern const char banner[];
+extern int table_sum(void);
+extern int call_hook(void);
+static int state = 3;
+int *const state_pointer = &state;
+int scratch[16];
+
+void _start(void)
+{
+    scratch[2] = call_hook();
+    counter = table_sum() + banner[1] + *state_pointer;
+    for (;;)
+        ;
+}
@@ -0,0 +1,6 @@
+extern int counter;
+
+int read_counter(void)
+{
+    return counter;
+}
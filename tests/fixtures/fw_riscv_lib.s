@@ -0,0 +1,11 @@
+# Definitions for `fw_riscv_main.s`.
+    .globl  lib_fn, lib_word, lib_end
+    .text
+lib_fn:
+    ret
+
+    .data
+    .p2align 3
+lib_word:
+    .dword  42
+lib_end:
@@ -0,0 +1,53 @@
+# Every RISC-V relocation `LinkedImage` applies, against the symbols of
+# `fw_riscv_lib.s`. The objects are linked without relaxation.
+    .globl  _start
+    .text
+_start:
+    call    lib_fn
+    call    lib_fn@plt
+    jal     lib_fn
+    beq     a0, a1, lib_fn
+    # llvm-mc widens `c.beqz a0, lib_fn` and `c.j lib_fn`.
+    .reloc  ., R_RISCV_RVC_BRANCH, lib_fn
+    .half   0xc101
+    .reloc  ., R_RISCV_RVC_JUMP, lib_fn
+    .half   0xa001
+1:  auipc   a0, %pcrel_hi(lib_word)
+    lw      a1, %pcrel_lo(1b)(a0)
+2:  auipc   a2, %pcrel_hi(lib_word)
+    sw      a1, %pcrel_lo(2b)(a2)
+    lui     a3, %hi(lib_word)
+    lw      a4, %lo(lib_word)(a3)
+    sw      a4, %lo(lib_word)(a3)
+    .p2align 3
+3:  j       3b
+
+    .section .rodata.words, "a"
+    .p2align 3
+    .dword  lib_word + 8
+    .word   lib_word
+    .reloc  ., R_RISCV_32_PCREL, lib_word
+    .word   0
+    .reloc  ., R_RISCV_ADD8, lib_end
+    .reloc  ., R_RISCV_SUB8, lib_word
+    .byte   1
+    .reloc  ., R_RISCV_ADD16, lib_end
+    .reloc  ., R_RISCV_SUB16, lib_word
+    .half   2
+    .reloc  ., R_RISCV_ADD32, lib_end
+    .reloc  ., R_RISCV_SUB32, lib_word
+    .word   3
+    .reloc  ., R_RISCV_ADD64, lib_end
+    .reloc  ., R_RISCV_SUB64, lib_word
+    .dword  4
+    .reloc  ., R_RISCV_SET6, lib_end
+    .reloc  ., R_RISCV_SUB6, lib_word
+    .byte   0xc0
+    .reloc  ., R_RISCV_SET8, lib_end
+    .byte   0
+    .reloc  ., R_RISCV_SET16, lib_end
+    .half   0
+    .reloc  ., R_RISCV_SET32, lib_end
+    .word   0
+    .reloc  ., R_RISCV_NONE, lib_end
+    .word   0
@@ -0,0 +1,10 @@
+#include <stdio.h>
+#include <stdlib.h>
+extern int foo_add(int, int);
+extern int foo_data;
+int counter;
+int main(int argc, char **argv) {
+    counter = foo_add(argc, foo_data);
+    printf("hello %d\n", counter);
+    return getenv("X") != NULL;
+}
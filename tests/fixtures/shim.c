@@ -0,0 +1,2 @@
+int foo_data = 7;
+int foo(void) { return 3; }
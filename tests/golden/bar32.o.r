@@ -0,0 +1,18 @@
+
+Relocation section '.rel.text' at offset 0x28c contains 8 entries:
+ Offset     Info    Type                Sym. Value  Symbol's Name
+00000001  00000802 R_386_PC32             00000000   __x86.get_pc_thunk.dx
+00000007  0000090a R_386_GOTPC            00000000   _GLOBAL_OFFSET_TABLE_
+0000000d  00000309 R_386_GOTOFF           00000000   .bss
+00000016  00000309 R_386_GOTOFF           00000000   .bss
+0000001c  00000a2b R_386_GOT32X           00000000   bar_data
+00000028  00000c02 R_386_PC32             00000000   __x86.get_pc_thunk.bx
+0000002e  0000090a R_386_GOTPC            00000000   _GLOBAL_OFFSET_TABLE_
+00000039  00000d04 R_386_PLT32            00000000   ext_fn
+
+Relocation section '.rel.eh_frame' at offset 0x2cc contains 4 entries:
+ Offset     Info    Type                Sym. Value  Symbol's Name
+00000020  00000202 R_386_PC32             00000000   .text
+00000034  00000202 R_386_PC32             00000000   .text
+00000058  00000502 R_386_PC32             00000000   .text.__x86.get_pc_thunk.dx
+0000006c  00000602 R_386_PC32             00000000   .text.__x86.get_pc_thunk.bx
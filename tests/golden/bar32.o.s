@@ -0,0 +1,17 @@
+
+Symbol table '.symtab' contains 14 entries:
+   Num:    Value  Size Type    Bind   Vis      Ndx Name
+     0: 00000000     0 NOTYPE  LOCAL  DEFAULT  UND 
+     1: 00000000     0 FILE    LOCAL  DEFAULT  ABS bar.c
+     2: 00000000     0 SECTION LOCAL  DEFAULT    3 .text
+     3: 00000000     0 SECTION LOCAL  DEFAULT    6 .bss
+     4: 00000000     4 OBJECT  LOCAL  DEFAULT    6 bar_hidden
+     5: 00000000     0 SECTION LOCAL  DEFAULT    7 .text.__x86.get_pc_thunk.dx
+     6: 00000000     0 SECTION LOCAL  DEFAULT    8 .text.__x86.get_pc_thunk.bx
+     7: 00000000    35 FUNC    GLOBAL DEFAULT    3 bar_get
+     8: 00000000     0 FUNC    GLOBAL HIDDEN     7 __x86.get_pc_thunk.dx
+     9: 00000000     0 NOTYPE  GLOBAL DEFAULT  UND _GLOBAL_OFFSET_TABLE_
+    10: 00000000     4 OBJECT  GLOBAL DEFAULT    5 bar_data
+    11: 00000023    32 FUNC    GLOBAL DEFAULT    3 bar_call
+    12: 00000000     0 FUNC    GLOBAL HIDDEN     8 __x86.get_pc_thunk.bx
+    13: 00000000     0 NOTYPE  GLOBAL DEFAULT  UND ext_fn
@@ -0,0 +1,14 @@
+
+Relocation section '.rela.text.startup' at offset 0x228 contains 7 entries:
+    Offset             Info             Type               Symbol's Value  Symbol's Name + Addend
+0000000000000003  0000000600000002 R_X86_64_PC32          0000000000000000 foo_data - 4
+0000000000000008  0000000700000004 R_X86_64_PLT32         0000000000000000 foo_add - 4
+000000000000000f  0000000300000002 R_X86_64_PC32          0000000000000000 .LC0 - 4
+0000000000000017  0000000800000002 R_X86_64_PC32          0000000000000000 counter - 4
+000000000000001e  0000000900000004 R_X86_64_PLT32         0000000000000000 printf - 4
+0000000000000025  0000000400000002 R_X86_64_PC32          000000000000000a .LC1 - 4
+000000000000002a  0000000a00000004 R_X86_64_PLT32         0000000000000000 getenv - 4
+
+Relocation section '.rela.eh_frame' at offset 0x2d0 contains 1 entry:
+    Offset             Info             Type               Symbol's Value  Symbol's Name + Addend
+0000000000000020  0000000200000002 R_X86_64_PC32          0000000000000000 .text.startup + 0
@@ -0,0 +1,14 @@
+
+Symbol table '.symtab' contains 11 entries:
+   Num:    Value          Size Type    Bind   Vis      Ndx Name
+     0: 0000000000000000     0 NOTYPE  LOCAL  DEFAULT  UND 
+     1: 0000000000000000     0 FILE    LOCAL  DEFAULT  ABS hello.c
+     2: 0000000000000000     0 SECTION LOCAL  DEFAULT    5 .text.startup
+     3: 0000000000000000     0 NOTYPE  LOCAL  DEFAULT    4 .LC0
+     4: 000000000000000a     0 NOTYPE  LOCAL  DEFAULT    4 .LC1
+     5: 0000000000000000    57 FUNC    GLOBAL DEFAULT    5 main
+     6: 0000000000000000     0 NOTYPE  GLOBAL DEFAULT  UND foo_data
+     7: 0000000000000000     0 NOTYPE  GLOBAL DEFAULT  UND foo_add
+     8: 0000000000000000     4 OBJECT  GLOBAL DEFAULT    3 counter
+     9: 0000000000000000     0 NOTYPE  GLOBAL DEFAULT  UND printf
+    10: 0000000000000000     0 NOTYPE  GLOBAL DEFAULT  UND getenv
@@ -0,0 +1,18 @@
+
+Relocation section '.rela.dyn' at offset 0x5e0 contains 9 entries:
+    Offset             Info             Type               Symbol's Value  Symbol's Name + Addend
+0000000000003db0  0000000000000008 R_X86_64_RELATIVE                         1190
+0000000000003db8  0000000000000008 R_X86_64_RELATIVE                         1150
+0000000000004020  0000000000000008 R_X86_64_RELATIVE                         4020
+0000000000003fc0  0000000300000006 R_X86_64_GLOB_DAT      0000000000000000 __libc_start_main@GLIBC_2.34 + 0
+0000000000003fc8  0000000400000006 R_X86_64_GLOB_DAT      0000000000000000 _ITM_deregisterTMCloneTable + 0
+0000000000003fd0  0000000600000006 R_X86_64_GLOB_DAT      0000000000000000 __gmon_start__ + 0
+0000000000003fd8  0000000700000006 R_X86_64_GLOB_DAT      0000000000000000 _ITM_registerTMCloneTable + 0
+0000000000003fe0  0000000900000006 R_X86_64_GLOB_DAT      0000000000000000 __cxa_finalize@GLIBC_2.2.5 + 0
+0000000000004028  0000000800000005 R_X86_64_COPY          0000000000004028 foo_data@VERS_1 + 0
+
+Relocation section '.rela.plt' at offset 0x6b8 contains 3 entries:
+    Offset             Info             Type               Symbol's Value  Symbol's Name + Addend
+0000000000004000  0000000100000007 R_X86_64_JUMP_SLOT     0000000000000000 getenv@GLIBC_2.2.5 + 0
+0000000000004008  0000000200000007 R_X86_64_JUMP_SLOT     0000000000000000 foo_add@VERS_1 + 0
+0000000000004010  0000000500000007 R_X86_64_JUMP_SLOT     0000000000000000 printf@GLIBC_2.2.5 + 0
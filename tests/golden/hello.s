@@ -0,0 +1,56 @@
+
+Symbol table '.dynsym' contains 10 entries:
+   Num:    Value          Size Type    Bind   Vis      Ndx Name
+     0: 0000000000000000     0 NOTYPE  LOCAL  DEFAULT  UND 
+     1: 0000000000000000     0 FUNC    GLOBAL DEFAULT  UND getenv@GLIBC_2.2.5 (2)
+     2: 0000000000000000     0 FUNC    GLOBAL DEFAULT  UND foo_add@VERS_1 (3)
+     3: 0000000000000000     0 FUNC    GLOBAL DEFAULT  UND __libc_start_main@GLIBC_2.34 (4)
+     4: 0000000000000000     0 NOTYPE  WEAK   DEFAULT  UND _ITM_deregisterTMCloneTable
+     5: 0000000000000000     0 FUNC    GLOBAL DEFAULT  UND printf@GLIBC_2.2.5 (2)
+     6: 0000000000000000     0 NOTYPE  WEAK   DEFAULT  UND __gmon_start__
+     7: 0000000000000000     0 NOTYPE  WEAK   DEFAULT  UND _ITM_registerTMCloneTable
+     8: 0000000000004028     4 OBJECT  GLOBAL DEFAULT   26 foo_data@VERS_1 (3)
+     9: 0000000000000000     0 FUNC    WEAK   DEFAULT  UND __cxa_finalize@GLIBC_2.2.5 (2)
+
+Symbol table '.symtab' contains 40 entries:
+   Num:    Value          Size Type    Bind   Vis      Ndx Name
+     0: 0000000000000000     0 NOTYPE  LOCAL  DEFAULT  UND 
+     1: 0000000000000000     0 FILE    LOCAL  DEFAULT  ABS Scrt1.o
+     2: 000000000000037c    32 OBJECT  LOCAL  DEFAULT    4 __abi_tag
+     3: 0000000000000000     0 FILE    LOCAL  DEFAULT  ABS hello.c
+     4: 0000000000000000     0 FILE    LOCAL  DEFAULT  ABS crtstuff.c
+     5: 00000000000010e0     0 FUNC    LOCAL  DEFAULT   15 deregister_tm_clones
+     6: 0000000000001110     0 FUNC    LOCAL  DEFAULT   15 register_tm_clones
+     7: 0000000000001150     0 FUNC    LOCAL  DEFAULT   15 __do_global_dtors_aux
+     8: 000000000000402c     1 OBJECT  LOCAL  DEFAULT   26 completed.0
+     9: 0000000000003db8     0 OBJECT  LOCAL  DEFAULT   21 __do_global_dtors_aux_fini_array_entry
+    10: 0000000000001190     0 FUNC    LOCAL  DEFAULT   15 frame_dummy
+    11: 0000000000003db0     0 OBJECT  LOCAL  DEFAULT   20 __frame_dummy_init_array_entry
+    12: 0000000000000000     0 FILE    LOCAL  DEFAULT  ABS crtstuff.c
+    13: 00000000000020e0     0 OBJECT  LOCAL  DEFAULT   19 __FRAME_END__
+    14: 0000000000000000     0 FILE    LOCAL  DEFAULT  ABS 
+    15: 0000000000003dc0     0 OBJECT  LOCAL  DEFAULT   22 _DYNAMIC
+    16: 0000000000002010     0 NOTYPE  LOCAL  DEFAULT   18 __GNU_EH_FRAME_HDR
+    17: 0000000000003fe8     0 OBJECT  LOCAL  DEFAULT   24 _GLOBAL_OFFSET_TABLE_
+    18: 0000000000000000     0 FUNC    GLOBAL DEFAULT  UND getenv@GLIBC_2.2.5
+    19: 0000000000000000     0 FUNC    GLOBAL DEFAULT  UND foo_add@VERS_1
+    20: 0000000000000000     0 FUNC    GLOBAL DEFAULT  UND __libc_start_main@GLIBC_2.34
+    21: 0000000000000000     0 NOTYPE  WEAK   DEFAULT  UND _ITM_deregisterTMCloneTable
+    22: 0000000000004018     0 NOTYPE  WEAK   DEFAULT   25 data_start
+    23: 0000000000004028     0 NOTYPE  GLOBAL DEFAULT   25 _edata
+    24: 000000000000119c     0 FUNC    GLOBAL HIDDEN    16 _fini
+    25: 0000000000004028     4 OBJECT  GLOBAL DEFAULT   26 foo_data@VERS_1
+    26: 0000000000000000     0 FUNC    GLOBAL DEFAULT  UND printf@GLIBC_2.2.5
+    27: 0000000000004018     0 NOTYPE  GLOBAL DEFAULT   25 __data_start
+    28: 0000000000000000     0 NOTYPE  WEAK   DEFAULT  UND __gmon_start__
+    29: 0000000000004020     0 OBJECT  GLOBAL HIDDEN    25 __dso_handle
+    30: 0000000000002000     4 OBJECT  GLOBAL DEFAULT   17 _IO_stdin_used
+    31: 0000000000004038     0 NOTYPE  GLOBAL DEFAULT   26 _end
+    32: 00000000000010b0    34 FUNC    GLOBAL DEFAULT   15 _start
+    33: 0000000000004030     4 OBJECT  GLOBAL DEFAULT   26 counter
+    34: 0000000000004028     0 NOTYPE  GLOBAL DEFAULT   26 __bss_start
+    35: 0000000000001070    57 FUNC    GLOBAL DEFAULT   15 main
+    36: 0000000000004028     0 OBJECT  GLOBAL HIDDEN    25 __TMC_END__
+    37: 0000000000000000     0 NOTYPE  WEAK   DEFAULT  UND _ITM_registerTMCloneTable
+    38: 0000000000000000     0 FUNC    WEAK   DEFAULT  UND __cxa_finalize@GLIBC_2.2.5
+    39: 0000000000001000     0 FUNC    GLOBAL HIDDEN    12 _init
@@ -0,0 +1,8 @@
+
+Relocation section '.rel.dyn' at offset 0x290 contains 1 entry:
+ Offset     Info    Type                Sym. Value  Symbol's Name
+00003ff0  00000206 R_386_GLOB_DAT         00004004   bar_data@@BAR_1.0
+
+Relocation section '.rel.plt' at offset 0x298 contains 1 entry:
+ Offset     Info    Type                Sym. Value  Symbol's Name
+00004000  00000107 R_386_JUMP_SLOT        00000000   ext_fn
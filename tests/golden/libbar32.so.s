@@ -0,0 +1,25 @@
+
+Symbol table '.dynsym' contains 6 entries:
+   Num:    Value  Size Type    Bind   Vis      Ndx Name
+     0: 00000000     0 NOTYPE  LOCAL  DEFAULT  UND 
+     1: 00000000     0 NOTYPE  GLOBAL DEFAULT  UND ext_fn
+     2: 00004004     4 OBJECT  GLOBAL DEFAULT   16 bar_data@@BAR_1.0
+     3: 00000000     0 OBJECT  GLOBAL DEFAULT  ABS BAR_1.0
+     4: 00001020    35 FUNC    GLOBAL DEFAULT   11 bar_get@@BAR_1.0
+     5: 00001043    32 FUNC    GLOBAL DEFAULT   11 bar_call@@BAR_1.0
+
+Symbol table '.symtab' contains 13 entries:
+   Num:    Value  Size Type    Bind   Vis      Ndx Name
+     0: 00000000     0 NOTYPE  LOCAL  DEFAULT  UND 
+     1: 00000000     0 FILE    LOCAL  DEFAULT  ABS bar.c
+     2: 00004008     4 OBJECT  LOCAL  DEFAULT   17 bar_hidden
+     3: 00000000     0 FILE    LOCAL  DEFAULT  ABS 
+     4: 00003f38     0 OBJECT  LOCAL  DEFAULT   13 _DYNAMIC
+     5: 00001063     0 FUNC    LOCAL  DEFAULT   11 __x86.get_pc_thunk.dx
+     6: 00001067     0 FUNC    LOCAL  DEFAULT   11 __x86.get_pc_thunk.bx
+     7: 00003ff4     0 OBJECT  LOCAL  DEFAULT   15 _GLOBAL_OFFSET_TABLE_
+     8: 00004004     4 OBJECT  GLOBAL DEFAULT   16 bar_data
+     9: 00000000     0 NOTYPE  GLOBAL DEFAULT  UND ext_fn
+    10: 00001020    35 FUNC    GLOBAL DEFAULT   11 bar_get
+    11: 00001043    32 FUNC    GLOBAL DEFAULT   11 bar_call
+    12: 00000000     0 OBJECT  GLOBAL DEFAULT  ABS BAR_1.0
@@ -0,0 +1,15 @@
+
+Relocation section '.rela.dyn' at offset 0x540 contains 8 entries:
+    Offset             Info             Type               Symbol's Value  Symbol's Name + Addend
+0000000000003dc8  0000000000000008 R_X86_64_RELATIVE                         1100
+0000000000003dd0  0000000000000008 R_X86_64_RELATIVE                         10c0
+0000000000004008  0000000000000008 R_X86_64_RELATIVE                         4008
+0000000000003fb8  0000000000000010 R_X86_64_DTPMOD64                         0
+0000000000003fc8  0000000100000006 R_X86_64_GLOB_DAT      0000000000000000 __cxa_finalize + 0
+0000000000003fd0  0000000200000006 R_X86_64_GLOB_DAT      0000000000000000 _ITM_registerTMCloneTable + 0
+0000000000003fd8  0000000300000006 R_X86_64_GLOB_DAT      0000000000000000 _ITM_deregisterTMCloneTable + 0
+0000000000003fe0  0000000500000006 R_X86_64_GLOB_DAT      0000000000000000 __gmon_start__ + 0
+
+Relocation section '.rela.plt' at offset 0x600 contains 1 entry:
+    Offset             Info             Type               Symbol's Value  Symbol's Name + Addend
+0000000000004000  0000000400000007 R_X86_64_JUMP_SLOT     0000000000000000 __tls_get_addr@GLIBC_2.3 + 0
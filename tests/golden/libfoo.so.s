@@ -0,0 +1,53 @@
+
+Symbol table '.dynsym' contains 12 entries:
+   Num:    Value          Size Type    Bind   Vis      Ndx Name
+     0: 0000000000000000     0 NOTYPE  LOCAL  DEFAULT  UND 
+     1: 0000000000000000     0 NOTYPE  WEAK   DEFAULT  UND __cxa_finalize
+     2: 0000000000000000     0 NOTYPE  WEAK   DEFAULT  UND _ITM_registerTMCloneTable
+     3: 0000000000000000     0 NOTYPE  WEAK   DEFAULT  UND _ITM_deregisterTMCloneTable
+     4: 0000000000000000     0 FUNC    GLOBAL DEFAULT  UND __tls_get_addr@GLIBC_2.3 (4)
+     5: 0000000000000000     0 NOTYPE  WEAK   DEFAULT  UND __gmon_start__
+     6: 000000000000112f     6 FUNC    GLOBAL DEFAULT   13 foo@VERS_1
+     7: 0000000000001135     6 FUNC    GLOBAL DEFAULT   13 foo@@VERS_2
+     8: 0000000000000000     0 OBJECT  GLOBAL DEFAULT  ABS VERS_1
+     9: 0000000000004010     4 OBJECT  GLOBAL DEFAULT   23 foo_data@@VERS_1
+    10: 0000000000000000     0 OBJECT  GLOBAL DEFAULT  ABS VERS_2
+    11: 0000000000001109    38 FUNC    GLOBAL DEFAULT   13 foo_add@@VERS_1
+
+Symbol table '.symtab' contains 35 entries:
+   Num:    Value          Size Type    Bind   Vis      Ndx Name
+     0: 0000000000000000     0 NOTYPE  LOCAL  DEFAULT  UND 
+     1: 0000000000000000     0 FILE    LOCAL  DEFAULT  ABS crtstuff.c
+     2: 0000000000001050     0 FUNC    LOCAL  DEFAULT   13 deregister_tm_clones
+     3: 0000000000001080     0 FUNC    LOCAL  DEFAULT   13 register_tm_clones
+     4: 00000000000010c0     0 FUNC    LOCAL  DEFAULT   13 __do_global_dtors_aux
+     5: 0000000000004014     1 OBJECT  LOCAL  DEFAULT   24 completed.0
+     6: 0000000000003dd0     0 OBJECT  LOCAL  DEFAULT   19 __do_global_dtors_aux_fini_array_entry
+     7: 0000000000001100     0 FUNC    LOCAL  DEFAULT   13 frame_dummy
+     8: 0000000000003dc8     0 OBJECT  LOCAL  DEFAULT   18 __frame_dummy_init_array_entry
+     9: 0000000000000000     0 FILE    LOCAL  DEFAULT  ABS foo.c
+    10: 0000000000004018     4 OBJECT  LOCAL  DEFAULT   24 hidden_counter
+    11: 0000000000000000     0 FILE    LOCAL  DEFAULT  ABS crtstuff.c
+    12: 00000000000020e0     0 OBJECT  LOCAL  DEFAULT   16 __FRAME_END__
+    13: 0000000000000000     0 FILE    LOCAL  DEFAULT  ABS 
+    14: 0000000000000000     4 TLS     LOCAL  DEFAULT   17 tls_counter
+    15: 0000000000003dd8     0 OBJECT  LOCAL  DEFAULT   20 _DYNAMIC
+    16: 0000000000004018     0 OBJECT  LOCAL  DEFAULT   23 __TMC_END__
+    17: 000000000000112f     6 FUNC    LOCAL  DEFAULT   13 foo_old
+    18: 0000000000004008     0 OBJECT  LOCAL  DEFAULT   23 __dso_handle
+    19: 0000000000001000     0 FUNC    LOCAL  DEFAULT   10 _init
+    20: 0000000000002000     0 NOTYPE  LOCAL  DEFAULT   15 __GNU_EH_FRAME_HDR
+    21: 000000000000113c     0 FUNC    LOCAL  DEFAULT   14 _fini
+    22: 0000000000001135     6 FUNC    LOCAL  DEFAULT   13 foo_new
+    23: 0000000000003fe8     0 OBJECT  LOCAL  DEFAULT   22 _GLOBAL_OFFSET_TABLE_
+    24: 000000000000112f     6 FUNC    GLOBAL DEFAULT   13 foo@VERS_1
+    25: 0000000000004010     4 OBJECT  GLOBAL DEFAULT   23 foo_data
+    26: 0000000000000000     0 NOTYPE  WEAK   DEFAULT  UND __cxa_finalize
+    27: 0000000000001135     6 FUNC    GLOBAL DEFAULT   13 foo@@VERS_2
+    28: 0000000000001109    38 FUNC    GLOBAL DEFAULT   13 foo_add
+    29: 0000000000000000     0 NOTYPE  WEAK   DEFAULT  UND _ITM_registerTMCloneTable
+    30: 0000000000000000     0 NOTYPE  WEAK   DEFAULT  UND _ITM_deregisterTMCloneTable
+    31: 0000000000000000     0 OBJECT  GLOBAL DEFAULT  ABS VERS_2
+    32: 0000000000000000     0 FUNC    GLOBAL DEFAULT  UND __tls_get_addr@GLIBC_2.3
+    33: 0000000000000000     0 OBJECT  GLOBAL DEFAULT  ABS VERS_1
+    34: 0000000000000000     0 NOTYPE  WEAK   DEFAULT  UND __gmon_start__
@@ -0,0 +1,115 @@
+//! Parses `ld.so.cache` files in both formats, built for the libraries of
+//! `tests/fixtures`.
+
+extern crate elf;
+
+mod common;
+
+use common::{new_cache, old_cache, CacheEntry};
+use elf::*;
+
+fn fixture(name: &str) -> String {
+    common::fixture(name).to_string_lossy().into_owned()
+}
+
+fn entries() -> Vec<CacheEntry> {
+    vec![
+        CacheEntry {
+            flags: FLAG_X8664_LIB64 | FLAG_ELF_LIBC6,
+            soname: "libfoo.so",
+            path: fixture("libfoo.so"),
+            hwcap: DL_CACHE_HWCAP_EXTENSION | 2,
+        },
+        CacheEntry {
+            flags: FLAG_ELF_LIBC6,
+            soname: "libbar.so.1",
+            path: fixture("libbar32.so"),
+            hwcap: 0,
+        },
+        // A 32-bit library replaced the 64-bit one since ldconfig ran.
+        CacheEntry {
+            flags: FLAG_X8664_LIB64 | FLAG_ELF_LIBC6,
+            soname: "libbar.so.1",
+            path: fixture("libbar32.so"),
+            hwcap: 0,
+        },
+    ]
+}
+
+fn check_entries(cache: &LdCache, hwcap: bool) {
+    let expected = entries();
+    assert_eq!(cache.entries.len(), expected.len());
+    for (entry, e) in cache.entries.iter().zip(&expected) {
+        assert_eq!(entry.flags, e.flags);
+        assert_eq!(entry.soname, e.soname);
+        assert_eq!(entry.path, e.path);
+        assert_eq!(entry.hwcap, if hwcap { e.hwcap } else { 0 });
+    }
+}
+
+#[test]
+fn old_format() {
+    let data = old_cache(&entries());
+    check_entries(&LdCache::parse(&data).unwrap(), false);
+}
+
+#[test]
+fn new_format() {
+    let data = new_cache(&entries());
+    let cache = LdCache::parse(&data).unwrap();
+    check_entries(&cache, true);
+    assert_eq!(cache.entries[0].hwcaps_index(), Some(2));
+    assert_eq!(cache.entries[1].hwcaps_index(), None);
+    assert_eq!(cache.lookup("libbar.so.1").count(), 2);
+}
+
+#[test]
+fn compat_format() {
+    // `ldconfig -c compat` writes the old format, then the new one aligned
+    // to 8 bytes, which takes precedence.
+    let mut data = old_cache(&entries());
+    data.truncate(16 + 12 * entries().len());
+    while data.len() % 8 != 0 {
+        data.push(0);
+    }
+    data.extend(new_cache(&entries()));
+    check_entries(&LdCache::parse(&data).unwrap(), true);
+}
+
+#[test]
+fn flags() {
+    let data = new_cache(&entries());
+    let cache = LdCache::parse(&data).unwrap();
+    let foo = &cache.entries[0];
+    assert_eq!(foo.class(), Some(ElfClass::Class64));
+    assert_eq!(foo.machine(), Some(ElfMachine::MachineX8664 as u16));
+    let bar = &cache.entries[1];
+    assert_eq!(bar.class(), Some(ElfClass::Class32));
+    assert_eq!(bar.machine(), None);
+    assert!(bar.matches(ElfClass::Class32, ElfMachine::Machine386 as u16));
+    assert!(!bar.matches(ElfClass::Class64, ElfMachine::MachineX8664 as u16));
+}
+
+#[test]
+fn cross_check() {
+    let data = new_cache(&entries());
+    let cache = LdCache::parse(&data).unwrap();
+    let checked: Vec<bool> = cache
+        .entries
+        .iter()
+        .map(|entry| {
+            let file = ElfFile::open(entry.path).unwrap();
+            entry.check(&file.view().unwrap())
+        })
+        .collect();
+    assert_eq!(checked, vec![true, true, false]);
+}
+
+#[test]
+fn bad_caches() {
+    assert!(LdCache::parse(b"ld.so-1.7.0").is_err());
+    assert!(LdCache::parse(b"not a cache").is_err());
+    let mut data = new_cache(&entries());
+    data.truncate(100);
+    assert!(LdCache::parse(&data).is_err());
+}
@@ -0,0 +1,244 @@
+//! Links the freestanding objects of `tests/fixtures` and checks the image
+//! against the one GNU ld, or LLD for other machines, links from them.
+
+extern crate elf;
+
+mod common;
+
+use common::{fixture, open};
+use elf::*;
+use std::fs;
+use std::process;
+
+fn link(files: &[&ElfFile], config: &LinkConfig) -> Result<LinkedImage, Error> {
+    let objects: Vec<Elf> = files.iter().map(|f| f.parse().unwrap()).collect();
+    LinkedImage::new(&objects, config)
+}
+
+#[test]
+fn matches_ld() {
+    let (main, lib) = (open("fw_main.o"), open("fw_lib.o"));
+    let image = link(&[&main, &lib], &LinkConfig::new(0x10_0000)).unwrap();
+    assert_eq!(image.flat(), fs::read(fixture("fw.bin")).unwrap());
+    assert_eq!(image.entry, Some(0x10_0000));
+    assert_eq!(image.symbols["table_sum"], 0x10_002a);
+    assert_eq!(image.symbols["banner"], 0x10_0050);
+    assert_eq!(image.symbols["counter"], 0x10_0060);
+    assert_eq!(image.symbols["_end"], 0x10_00c0);
+    assert!(!image.symbols.contains_key("optional_hook"));
+
+    let names: Vec<&str> = image.sections.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec![".text", ".rodata", ".data", ".bss"]);
+    // The common symbol `scratch` and its alignment.
+    let bss = image.section(".bss").unwrap();
+    assert_eq!(bss.sh_type, SHT_NOBITS);
+    assert_eq!((bss.addr, bss.size, bss.align), (0x10_0080, 0x40, 32));
+    assert_eq!(image.symbols["scratch"], 0x10_0080);
+}
+
+#[test]
+fn other_machines_match_lld() {
+    for arch in &["aarch64", "arm", "riscv"] {
+        let main = open(&format!("fw_{}_main.o", arch));
+        let lib = open(&format!("fw_{}_lib.o", arch));
+        let image = link(&[&main, &lib], &LinkConfig::new(0x10_0000)).unwrap();
+        let expected = fs::read(fixture(&format!("fw_{}.bin", arch))).unwrap();
+        assert!(image.flat() == expected, "fw_{}.bin differs", arch);
+        assert_eq!(image.entry, Some(0x10_0000));
+    }
+}
+
+/// Returns the `e_type` and `e_entry` of `elf` and its `PT_LOAD` segments
+/// as `(p_flags, p_vaddr, p_filesz, p_memsz)`.
+fn load_segments(elf: &Elf) -> (u16, u64, Vec<(u32, u64, u64, u64)>) {
+    match *elf {
+        Elf::Elf32(ref e) => (
+            e.header.e_type,
+            u64::from(e.header.e_entry),
+            e.segments
+                .iter()
+                .filter(|p| p.p_type == PT_LOAD)
+                .map(|p| {
+                    let (vaddr, filesz, memsz) = (p.p_vaddr, p.p_filesz, p.p_memsz);
+                    (p.p_flags, vaddr.into(), filesz.into(), memsz.into())
+                })
+                .collect(),
+        ),
+        Elf::Elf64(ref e) => (
+            e.header.e_type,
+            e.header.e_entry,
+            e.segments
+                .iter()
+                .filter(|p| p.p_type == PT_LOAD)
+                .map(|p| (p.p_flags, p.p_vaddr, p.p_filesz, p.p_memsz))
+                .collect(),
+        ),
+    }
+}
+
+#[test]
+fn executables_load() {
+    let files = [
+        ("fw_main.o", "fw_lib.o"),
+        ("fw_aarch64_main.o", "fw_aarch64_lib.o"),
+        ("fw_arm_main.o", "fw_arm_lib.o"),
+        ("fw_riscv_main.o", "fw_riscv_lib.o"),
+    ];
+    for &(main, lib) in &files {
+        let (main, lib) = (open(main), open(lib));
+        let image = link(&[&main, &lib], &LinkConfig::new(0x10_0000)).unwrap();
+        let file = ElfFile::from_vec(image.executable());
+        let elf = file.parse().unwrap();
+        let end = image.symbols["_end"];
+        let (kind, entry, segments) = load_segments(&elf);
+        assert_eq!((kind, entry), (ElfType::Exec as u16, 0x10_0000));
+        assert_eq!(segments[0].1, 0x10_0000);
+        let last = segments.last().unwrap();
+        assert_eq!(last.1 + last.3, end);
+
+        let loaded = elf
+            .load_image(&LoadOptions::new(0x10_0000), |_, _| None)
+            .unwrap();
+        let mut expected = image.flat();
+        expected.resize((end - 0x10_0000) as usize, 0);
+        assert_eq!(&loaded.data[..expected.len()], &expected[..]);
+    }
+}
+
+#[test]
+fn executable_segments() {
+    let (exit, lib) = (open("fw_exit.o"), open("fw_lib.o"));
+    let mut config = LinkConfig::new(0x40_0000);
+    config.sections[2].address = Some(0x60_0000);
+    let image = link(&[&exit, &lib], &config).unwrap();
+    let file = ElfFile::from_vec(image.executable());
+    let elf = file.parse().unwrap();
+    let text = image.section(".text").unwrap();
+    let rodata = image.section(".rodata").unwrap();
+    let data = image.section(".data").unwrap();
+    assert_eq!(
+        load_segments(&elf).2,
+        vec![
+            (
+                PF_R | PF_X,
+                0x40_0000,
+                rodata.addr + rodata.size - text.addr,
+                rodata.addr + rodata.size - text.addr
+            ),
+            (PF_R | PF_W, 0x60_0000, data.size, data.size),
+        ]
+    );
+
+    // `_start` exits with the sum of the table of `.rodata`, scaled by
+    // `counter` of `.data`.
+    if cfg!(all(target_os = "linux", target_arch = "x86_64")) {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join(format!("elf-link-exit-{}", process::id()));
+        fs::write(&path, file.data()).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        let status = process::Command::new(&path).status().unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(status.code(), Some(10));
+    }
+}
+
+#[test]
+fn gotpcrelx_loads_become_lea() {
+    let (pic, lib) = (open("fw_pic.o"), open("fw_lib.o"));
+    let image = link(&[&pic, &lib], &LinkConfig::new(0x40_0000)).unwrap();
+    let text = image.section(".text").unwrap();
+    let offset = (image.symbols["read_counter"] - text.addr) as usize;
+    let lea = &text.data[offset..offset + 7];
+    assert_eq!(&lea[..3], &[0x48, 0x8d, 0x05]);
+    let mut disp = [0; 4];
+    disp.copy_from_slice(&lea[3..]);
+    let next = image.symbols["read_counter"] + 7;
+    assert_eq!(
+        next.wrapping_add(i32::from_le_bytes(disp) as u64),
+        image.symbols["counter"]
+    );
+}
+
+#[test]
+fn errors() {
+    let (main, lib, hello) = (open("fw_main.o"), open("fw_lib.o"), open("hello"));
+    let config = LinkConfig::new(0x10_0000);
+    let undefined = link(&[&main], &config).unwrap_err();
+    assert!(format!("{}", undefined).contains("counter"));
+    let multiple = link(&[&lib, &lib], &config).unwrap_err();
+    assert!(format!("{}", multiple).contains("multiple definition"));
+    assert!(link(&[&hello], &config).is_err());
+
+    let mut config = config;
+    config.sections.retain(|s| s.name != ".rodata");
+    let orphan = link(&[&main, &lib], &config).unwrap_err();
+    assert!(format!("{}", orphan).contains(".rodata"));
+}
+
+/// Returns `fw_lib.o` with the 64-bit field at `field` of the header of
+/// section `idx`, or at the offset `field` of the file when `idx` is
+/// `None`, set to `value`.
+fn patched_lib(idx: Option<usize>, field: usize, value: u64) -> ElfFile {
+    let mut data = open("fw_lib.o").data().to_vec();
+    let mut shoff = [0; 8];
+    shoff.copy_from_slice(&data[0x28..0x30]);
+    let at = match idx {
+        Some(idx) => u64::from_le_bytes(shoff) as usize + idx * 64 + field,
+        None => field,
+    };
+    data[at..at + 8].copy_from_slice(&value.to_le_bytes());
+    ElfFile::from_vec(data)
+}
+
+#[test]
+fn malformed_objects() {
+    let main = open("fw_main.o");
+    let config = LinkConfig::new(0x10_0000);
+    let (text, data, bss) = (1, 3, 4);
+    let (sh_size, sh_addralign) = (32, 48);
+    let cases = vec![
+        // Larger than the file.
+        patched_lib(Some(text), sh_size, 1 << 40),
+        // Ends beyond 2^64.
+        patched_lib(Some(bss), sh_size, u64::max_value() - 0x10),
+        patched_lib(Some(data), sh_addralign, 1 << 63),
+        // The first relocation of `.rela.text` beyond `.text`.
+        patched_lib(None, 0x180, 0x1000),
+    ];
+    for lib in &cases {
+        assert!(link(&[&main, lib], &config).is_err());
+    }
+}
+
+/// Returns the fixture `name` with `e_flags` set to `flags`.
+fn with_flags(name: &str, flags: u32) -> ElfFile {
+    let mut data = open(name).data().to_vec();
+    // After e_entry, e_phoff and e_shoff, of 4 or 8 bytes.
+    let at = if data[4] == 1 { 0x24 } else { 0x30 };
+    data[at..at + 4].copy_from_slice(&flags.to_le_bytes());
+    ElfFile::from_vec(data)
+}
+
+#[test]
+fn incompatible_flags() {
+    let config = LinkConfig::new(0x10_0000);
+    let eabi5 = 0x0500_0000;
+    let soft = with_flags("fw_arm_main.o", eabi5 | EF_ARM_ABI_FLOAT_SOFT);
+    let hard = with_flags("fw_arm_lib.o", eabi5 | EF_ARM_ABI_FLOAT_HARD);
+    assert!(link(&[&soft, &hard], &config).is_err());
+    let eabi4 = with_flags("fw_arm_lib.o", 0x0400_0000);
+    assert!(link(&[&soft, &eabi4], &config).is_err());
+    // fw_arm_lib.o leaves the float ABI unspecified.
+    let lib = open("fw_arm_lib.o");
+    let image = link(&[&lib, &soft], &config).unwrap();
+    assert_eq!(image.flags, eabi5 | EF_ARM_ABI_FLOAT_SOFT);
+
+    // The double-float ABI, against the soft-float one of fw_riscv_main.o.
+    let (main, lib) = (open("fw_riscv_main.o"), open("fw_riscv_lib.o"));
+    let double = with_flags("fw_riscv_lib.o", EF_RISCV_RVC | 0x4);
+    assert!(link(&[&main, &double], &config).is_err());
+    let no_rvc = with_flags("fw_riscv_main.o", 0);
+    let image = link(&[&no_rvc, &lib], &config).unwrap();
+    assert_eq!(image.flags, EF_RISCV_RVC);
+}
@@ -0,0 +1,150 @@
+//! Loads the libraries of `tests/fixtures` and checks their relocated
+//! images.
+
+extern crate elf;
+
+mod common;
+
+use common::open;
+use elf::*;
+
+fn word64(image: &LoadedImage, vaddr: u64) -> u64 {
+    let offset = (image.address(vaddr) - image.base) as usize;
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&image.data[offset..offset + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+fn word32(image: &LoadedImage, vaddr: u64) -> u32 {
+    let offset = (image.address(vaddr) - image.base) as usize;
+    let mut bytes = [0; 4];
+    bytes.copy_from_slice(&image.data[offset..offset + 4]);
+    u32::from_le_bytes(bytes)
+}
+
+#[test]
+fn elf64() {
+    let file = open("libfoo.so");
+    let elf = file.parse().unwrap();
+    let base = 0x7f00_0000_0000;
+    let mut lookups = Vec::new();
+    let image = elf
+        .load_image(&LoadOptions::new(base), |symbol, version| {
+            lookups.push((symbol.name, version.map(|v| v.name)));
+            match symbol.name {
+                "__tls_get_addr" => Some(0x7f10_0000_1234),
+                _ => None,
+            }
+        })
+        .unwrap();
+    assert_eq!(image.bias, base);
+    assert_eq!(&image.data[..4], b"\x7fELF");
+    // .init_array and .fini_array
+    assert_eq!(word64(&image, 0x3dc8), base + 0x1100);
+    assert_eq!(word64(&image, 0x3dd0), base + 0x10c0);
+    assert_eq!(word64(&image, 0x4000), 0x7f10_0000_1234);
+    // Weak undefined symbols
+    assert_eq!(word64(&image, 0x3fc8), 0);
+    assert!(lookups.contains(&("__tls_get_addr", Some("GLIBC_2.3"))));
+    assert!(image.unresolved.is_empty());
+    assert_eq!(image.skipped.len(), 1);
+}
+
+#[test]
+fn elf32() {
+    let file = open("libbar32.so");
+    let elf = file.parse().unwrap();
+    let base = 0x10000;
+    let image = elf
+        .load_image(&LoadOptions::new(base), |symbol, _| {
+            if symbol.is_undefined() {
+                None
+            } else {
+                Some(base + symbol.value)
+            }
+        })
+        .unwrap();
+    assert_eq!(image.data.len(), 0x3f38 + 0xd4);
+    assert_eq!(u64::from(word32(&image, 0x3ff0)), base + 0x4004);
+    assert_eq!(image.unresolved.len(), 1);
+    assert_eq!(image.unresolved[0].offset, 0x4000);
+    assert!(image.skipped.is_empty());
+}
+
+#[test]
+fn relocatable_files_have_no_image() {
+    let file = open("hello.o");
+    let elf = file.parse().unwrap();
+    assert!(elf.load_image(&LoadOptions::new(0), |_, _| None).is_err());
+}
+
+#[test]
+fn without_section_headers() {
+    let (file, stripped) = (open("debug"), open("debug.nosections"));
+    let (elf, stripped) = (file.parse().unwrap(), stripped.parse().unwrap());
+    let image = elf
+        .load_image(&LoadOptions::new(0x5000_0000), |_, _| None)
+        .unwrap();
+    let stripped_image = stripped
+        .load_image(&LoadOptions::new(0x5000_0000), |_, _| None)
+        .unwrap();
+    assert!(!image.unresolved.is_empty());
+    assert_eq!(stripped_image.unresolved, image.unresolved);
+    assert_eq!(stripped_image.skipped, image.skipped);
+    // Only the section header fields of the ELF header differ.
+    assert_eq!(stripped_image.data.len(), image.data.len());
+    assert_eq!(stripped_image.data[0x1000..], image.data[0x1000..]);
+}
+
+#[test]
+fn image_size_limit() {
+    let file = open("libfoo.so");
+    let elf = file.parse().unwrap();
+    let mut options = LoadOptions::new(0x10000);
+    let size = elf.load_image(&options, |_, _| None).unwrap().data.len();
+    options.max_size = size as u64;
+    assert!(elf.load_image(&options, |_, _| None).is_ok());
+    options.max_size -= 1;
+    assert!(elf.load_image(&options, |_, _| None).is_err());
+}
+
+#[test]
+fn malformed_images() {
+    let data = open("libfoo.so").data().to_vec();
+    let load = |data: Vec<u8>| {
+        let file = ElfFile::from_vec(data);
+        let elf = file.parse().unwrap();
+        elf.load_image(&LoadOptions::new(0x10000), |_, _| None)
+    };
+
+    // A first PT_LOAD segment of 1 TiB.
+    let mut huge = data.clone();
+    huge[64 + 40..64 + 48].copy_from_slice(&(1u64 << 40).to_le_bytes());
+    assert!(load(huge).is_err());
+
+    let mut truncated = open("debug.nosections").data().to_vec();
+    truncated.truncate(0x2000);
+    assert!(load(truncated).is_err());
+
+    // The symbol of the `GLOB_DAT` relocation of `__cxa_finalize`, the
+    // fifth of `.rela.dyn`, out of the dynamic symbol table. The first
+    // segment is mapped at offset 0.
+    let file = open("libfoo.so");
+    let rela = file
+        .parse()
+        .unwrap()
+        .dynamic()
+        .unwrap()
+        .iter()
+        .find(|d| d.tag == DT_RELA)
+        .unwrap()
+        .val as usize;
+    let mut bad_symbol = data.clone();
+    let info = rela + 4 * 24 + 8;
+    bad_symbol[info + 4..info + 8].copy_from_slice(&0x00ff_ffffu32.to_le_bytes());
+    let image = load(bad_symbol).unwrap();
+    let unresolved: Vec<u64> = image.unresolved.iter().map(|r| r.offset).collect();
+    // The weak reference is not resolved to 0, unlike `__tls_get_addr`
+    // that the resolver does not find.
+    assert_eq!(unresolved, vec![0x3fc8, 0x4000]);
+}
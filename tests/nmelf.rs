@@ -0,0 +1,68 @@
+//! Compares the output of `nmelf` on the files of `tests/fixtures` with the
+//! golden files of `tests/golden`, which match `nm` from binutils 2.40 with
+//! the same options.
+//!
+//! The golden files are generated by nm, see `make golden` in
+//! `tests/fixtures`.
+
+extern crate elf;
+
+mod common;
+
+use common::check_golden;
+
+const FIXTURES: &[&str] = &[
+    "hello",
+    "hello.o",
+    "libfoo.so",
+    "libbar32.so",
+    "bar32.o",
+    "libhello.a",
+];
+const DYNAMIC: &[&str] = &["hello", "libfoo.so", "libbar32.so"];
+
+fn nmelf(args: &[&str], files: &[&str]) -> String {
+    common::run(env!("CARGO_BIN_EXE_nmelf"), args, files)
+}
+
+fn check_options(fixtures: &[&str], options: &[&str], suffix: &str) {
+    for fixture in fixtures {
+        let actual = nmelf(options, &[fixture]);
+        check_golden(&format!("{}.{}", fixture, suffix), &actual);
+    }
+}
+
+#[test]
+fn sort_by_name() {
+    check_options(FIXTURES, &[], "nm");
+}
+
+#[test]
+fn sort_by_address() {
+    check_options(FIXTURES, &["-n"], "nm-n");
+}
+
+#[test]
+fn sort_by_size() {
+    check_options(FIXTURES, &["-S", "--size-sort"], "nm-size");
+}
+
+#[test]
+fn undefined_only() {
+    check_options(FIXTURES, &["-u"], "nm-u");
+}
+
+#[test]
+fn defined_only() {
+    check_options(FIXTURES, &["--defined-only", "-r"], "nm-defined");
+}
+
+#[test]
+fn dynamic() {
+    check_options(DYNAMIC, &["-D"], "nm-D");
+}
+
+#[test]
+fn all_files() {
+    check_golden("all.nm", &nmelf(&[], FIXTURES));
+}
@@ -0,0 +1,92 @@
+//! Reads the notes of the files of `tests/fixtures`, as listed by
+//! `readelf -n`.
+
+extern crate elf;
+
+mod common;
+
+use common::fixture;
+use elf::*;
+use std::fs;
+
+fn hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[test]
+fn executable_notes() {
+    let data = fs::read(fixture("hello")).unwrap();
+    let notes = parse_elf64(&data).unwrap().1.notes().unwrap();
+    let kinds: Vec<(&str, u32)> = notes.iter().map(|n| (n.name, n.n_type)).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            ("GNU", NT_GNU_PROPERTY_TYPE_0),
+            ("GNU", NT_GNU_BUILD_ID),
+            ("GNU", NT_GNU_ABI_TAG),
+        ]
+    );
+    assert_eq!(
+        hex(notes[1].desc),
+        "e3333790483cf5c25791c951ff531c151dbdb929"
+    );
+    // OS: Linux, ABI: 3.2.0
+    assert_eq!(
+        notes[2].desc,
+        &[0, 0, 0, 0, 3, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0]
+    );
+    assert_eq!(abi_tag_os(&notes), Some("linux"));
+    assert_eq!(os_name(&ElfOSAbi::OSAbiSysv, &notes), "linux");
+    assert_eq!(os_name(&ElfOSAbi::OSAbiFreebsd, &notes), "freebsd");
+}
+
+#[test]
+fn library_notes() {
+    let data = fs::read(fixture("libbar32.so")).unwrap();
+    let notes = parse_elf32(&data).unwrap().1.notes().unwrap();
+    assert_eq!(notes.len(), 1);
+    assert_eq!(
+        hex(notes[0].desc),
+        "9f9e2b91bdf7ae2b33e94e9c7fed8eceeeb3e9a8"
+    );
+    assert_eq!(abi_tag_os(&notes), None);
+    assert_eq!(os_name(&ElfOSAbi::OSAbiSysv, &notes), "linux");
+    assert_eq!(arch_name(ElfMachine::Machine386 as u16), "x86");
+    assert_eq!(arch_name(ElfMachine::MachineX8664 as u16), "x86_64");
+    assert_eq!(arch_name(ElfMachine::MachineAarch64 as u16), "aarch64");
+    assert_eq!(arch_name(ElfMachine::MachinePpc64 as u16), "powerpc");
+}
+
+#[test]
+fn notes_without_sections() {
+    // The notes are read from the PT_NOTE segments instead.
+    let data = fs::read(fixture("debug")).unwrap();
+    let stripped = fs::read(fixture("debug.nosections")).unwrap();
+    let elf = parse_elf64(&stripped).unwrap().1;
+    assert!(elf.sections.is_empty());
+    let notes = elf.notes().unwrap();
+    let expected = parse_elf64(&data).unwrap().1.notes().unwrap();
+    let kinds: Vec<(&str, u32, &[u8])> = notes.iter().map(|n| (n.name, n.n_type, n.desc)).collect();
+    let expected: Vec<(&str, u32, &[u8])> = expected
+        .iter()
+        .map(|n| (n.name, n.n_type, n.desc))
+        .collect();
+    assert_eq!(kinds, expected);
+    assert_eq!(abi_tag_os(&notes), Some("linux"));
+}
+
+#[test]
+fn malformed_notes() {
+    // namesz 4, descsz 8 and type 3, with a truncated descriptor.
+    let mut data = vec![4, 0, 0, 0, 8, 0, 0, 0, 3, 0, 0, 0];
+    data.extend_from_slice(b"GNU\0");
+    data.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+    let notes = parse_notes(&data, 4).unwrap();
+    assert_eq!(notes[0].desc, &[1, 2, 3, 4, 5, 6, 7, 8]);
+
+    data.truncate(data.len() - 1);
+    assert!(parse_notes(&data, 4).is_err());
+    let mut iter = iter_notes(&data, 4);
+    assert!(iter.next().unwrap().is_err());
+    assert!(iter.next().is_none());
+}
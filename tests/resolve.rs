@@ -0,0 +1,120 @@
+//! Resolves the dependencies of `tests/fixtures/hello` in a sysroot built
+//! from the fixtures.
+
+extern crate elf;
+
+mod common;
+
+use common::{fixture, new_cache, CacheEntry};
+use elf::*;
+use std::env;
+use std::fs;
+use std::os::unix::fs::symlink;
+use std::path::{Path, PathBuf};
+use std::process;
+
+/// Builds a sysroot where `hello` finds `libfoo.so` through its
+/// `$ORIGIN` runpath and an absolute symbolic link, and `libc.so.6` in
+/// `LD_LIBRARY_PATH` after a 32-bit library of the same name. Each test
+/// `name` gets its own sysroot, as the tests run in parallel.
+fn sysroot(name: &str) -> PathBuf {
+    let root = env::temp_dir().join(format!("elf-resolve-{}-{}", name, process::id()));
+    let _ = fs::remove_dir_all(&root);
+    for dir in &["usr/bin", "store", "opt/32", "opt/lib64"] {
+        fs::create_dir_all(root.join(dir)).unwrap();
+    }
+    fs::copy(fixture("hello"), root.join("usr/bin/hello")).unwrap();
+    fs::copy(fixture("libfoo.so"), root.join("store/libfoo.so")).unwrap();
+    symlink("/store/libfoo.so", root.join("usr/bin/libfoo.so")).unwrap();
+    fs::copy(fixture("libbar32.so"), root.join("opt/32/libc.so.6")).unwrap();
+    fs::copy(fixture("libfoo.so"), root.join("opt/lib64/libc.so.6")).unwrap();
+    root
+}
+
+#[test]
+fn sysroot_search() {
+    let root = sysroot("sysroot_search");
+    let mut resolver = LibraryResolver::new(&root);
+    resolver.library_path = split_library_path("/opt/32:/opt/$LIB");
+    resolver.default_dirs = Some(Vec::new());
+    resolver.use_cache = false;
+    let tree = resolver.resolve("/usr/bin/hello");
+    fs::remove_dir_all(&root).unwrap();
+    let tree = tree.expect("cannot resolve hello");
+
+    let found: Vec<(&Path, SearchSource)> = tree
+        .objects
+        .iter()
+        .map(|o| (o.path.as_path(), o.source))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (Path::new("/usr/bin/hello"), SearchSource::Executable),
+            (Path::new("/usr/bin/libfoo.so"), SearchSource::Runpath),
+            (
+                Path::new("/opt/lib64/libc.so.6"),
+                SearchSource::LdLibraryPath
+            ),
+        ]
+    );
+    let needed: Vec<Option<usize>> = tree.objects[0].needed.iter().map(|d| d.object).collect();
+    assert_eq!(needed, vec![Some(1), Some(2)]);
+    assert_eq!(tree.missing(), vec!["ld-linux-x86-64.so.2"]);
+}
+
+#[test]
+fn symbolic_links_stay_in_the_root() {
+    let root = sysroot("symbolic_links_stay_in_the_root");
+    let resolver = LibraryResolver::new(&root);
+    let host = resolver.host_path(Path::new("/usr/bin/../bin/libfoo.so"));
+    let escaped = resolver.host_path(Path::new("/../../usr/bin/hello"));
+    fs::remove_dir_all(&root).unwrap();
+    assert_eq!(host, Some(root.join("store/libfoo.so")));
+    assert_eq!(escaped, Some(root.join("usr/bin/hello")));
+}
+
+#[test]
+fn cache_skips_hwcap_entries() {
+    let root = sysroot("cache_skips_hwcap_entries");
+    fs::create_dir_all(root.join("etc")).unwrap();
+    fs::create_dir_all(root.join("opt/hw")).unwrap();
+    fs::copy(fixture("libfoo.so"), root.join("opt/hw/libc.so.6")).unwrap();
+    let libc = |path: &str, hwcap: u64| CacheEntry {
+        flags: FLAG_X8664_LIB64 | FLAG_ELF_LIBC6,
+        soname: "libc.so.6",
+        path: path.to_string(),
+        hwcap: hwcap,
+    };
+    let cache = new_cache(&[
+        libc("/opt/hw/libc.so.6", DL_CACHE_HWCAP_EXTENSION | 1),
+        libc("/opt/lib64/libc.so.6", 0),
+    ]);
+    fs::write(root.join("etc/ld.so.cache"), cache).unwrap();
+
+    let mut resolver = LibraryResolver::new(&root);
+    resolver.library_path = Vec::new();
+    resolver.default_dirs = Some(Vec::new());
+    let libc_of = |resolver: &LibraryResolver| {
+        let tree = resolver
+            .resolve("/usr/bin/hello")
+            .expect("cannot resolve hello");
+        let libc = tree.objects[0].needed[1]
+            .object
+            .expect("libc.so.6 not found");
+        (tree.objects[libc].path.clone(), tree.objects[libc].source)
+    };
+    let default = libc_of(&resolver);
+    resolver.use_hwcap_entries = true;
+    let hwcap = libc_of(&resolver);
+    fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(
+        default,
+        (PathBuf::from("/opt/lib64/libc.so.6"), SearchSource::Cache)
+    );
+    assert_eq!(
+        hwcap,
+        (PathBuf::from("/opt/hw/libc.so.6"), SearchSource::Cache)
+    );
+}
@@ -0,0 +1,308 @@
+//! Checks the section lookups and the section to segment mapping of the
+//! files of `tests/fixtures`, the latter against the golden `readelf -l`
+//! output of `tests/golden`.
+
+extern crate elf;
+
+mod common;
+
+use common::{open, tests_dir};
+use elf::*;
+use std::fs;
+
+const FIXTURES: &[&str] = &["hello", "hello.o", "libfoo.so", "libbar32.so", "bar32.o"];
+
+/// Returns the section names of each segment listed by `readelf -l`.
+fn golden_mapping(fixture: &str) -> Vec<Vec<String>> {
+    let path = tests_dir().join("golden").join(format!("{}.l", fixture));
+    let golden = fs::read_to_string(path).unwrap();
+    golden
+        .lines()
+        .skip_while(|l| !l.contains("Segment Sections..."))
+        .skip(1)
+        .map(|l| l.split_whitespace().skip(1).map(String::from).collect())
+        .collect()
+}
+
+#[test]
+fn lookup_by_name_and_type() {
+    for fixture in FIXTURES {
+        let file = open(fixture);
+        match file.parse().unwrap() {
+            Elf::Elf64(ref e) => {
+                for (idx, name, section, _) in e.iter_sections() {
+                    let first = e
+                        .sections
+                        .iter()
+                        .position(|s| e.section_name(s) == Some(name));
+                    assert_eq!(e.section_index(name), first, "{}: {}", fixture, name);
+                    if first == Some(idx) {
+                        assert_eq!(e.section_by_name(name), Some(section));
+                    }
+                }
+                let symtab = e.sections_by_type(SHT_SYMTAB);
+                assert_eq!(symtab, vec![e.section_by_name(".symtab").unwrap()]);
+            }
+            Elf::Elf32(ref e) => {
+                for (idx, name, section, _) in e.iter_sections() {
+                    let first = e
+                        .sections
+                        .iter()
+                        .position(|s| e.section_name(s) == Some(name));
+                    assert_eq!(e.section_index(name), first, "{}: {}", fixture, name);
+                    if first == Some(idx) {
+                        assert_eq!(e.section_by_name(name), Some(section));
+                    }
+                }
+                let symtab = e.sections_by_type(SHT_SYMTAB);
+                assert_eq!(symtab, vec![e.section_by_name(".symtab").unwrap()]);
+            }
+        }
+    }
+    let file = open("hello.o");
+    let elf = file.parse().unwrap();
+    if let Elf::Elf64(ref e) = elf {
+        assert!(e.section_index(".no_such_section").is_none());
+        assert_eq!(e.sections_by_type(SHT_RELA).len(), 2);
+    }
+}
+
+#[test]
+fn segment_to_section_mapping() {
+    for fixture in &["hello", "libfoo.so", "libbar32.so"] {
+        let file = open(fixture);
+        let (actual, unloaded): (Vec<Vec<String>>, Vec<usize>) = match file.parse().unwrap() {
+            Elf::Elf64(ref e) => (
+                e.segment_section_map()
+                    .iter()
+                    .map(|s| {
+                        s.iter()
+                            .map(|&i| e.section_name(&e.sections[i]).unwrap().to_string())
+                            .collect()
+                    })
+                    .collect(),
+                e.unloaded_sections(),
+            ),
+            Elf::Elf32(ref e) => (
+                e.segment_section_map()
+                    .iter()
+                    .map(|s| {
+                        s.iter()
+                            .map(|&i| e.section_name(&e.sections[i]).unwrap().to_string())
+                            .collect()
+                    })
+                    .collect(),
+                e.unloaded_sections(),
+            ),
+        };
+        assert_eq!(actual, golden_mapping(fixture), "{}", fixture);
+        assert!(unloaded.is_empty(), "{}", fixture);
+    }
+}
+
+#[test]
+fn section_segments() {
+    let file = open("libbar32.so");
+    let elf = file.parse().unwrap();
+    let e = match elf {
+        Elf::Elf32(ref e) => e,
+        _ => panic!("libbar32.so is a 32-bit file"),
+    };
+    let dynamic = e.section_index(".dynamic").unwrap();
+    // The RW PT_LOAD, PT_DYNAMIC and PT_GNU_RELRO.
+    assert_eq!(e.section_segments(dynamic), vec![3, 4, 7]);
+    assert!(e.section_segments(0).is_empty());
+    assert!(e.segment_sections(e.segments.len()).is_empty());
+    for idx in 1..e.sections.len() {
+        for segment in e.section_segments(idx) {
+            assert!(e.segment_sections(segment).contains(&idx));
+        }
+    }
+
+    // Relocatable files have no segments, so all their sections are
+    // unloaded.
+    let file = open("bar32.o");
+    if let Elf::Elf32(ref e) = file.parse().unwrap() {
+        let text = e.section_index(".text").unwrap();
+        assert!(e.unloaded_sections().contains(&text));
+    }
+}
+
+fn shared<T: Send + Sync>(_: &T) {}
+
+#[test]
+fn lookups_on_shared_files() {
+    let file = open("hello");
+    let mut elf = match file.parse().unwrap() {
+        Elf::Elf64(e) => e,
+        _ => panic!("hello is a 64-bit file"),
+    };
+    shared(&elf);
+    let text = elf.section_index(".text").unwrap();
+    // Sections removed after the first lookup are not found any more.
+    elf.sections.truncate(text);
+    assert_eq!(elf.section_index(".text"), None);
+    assert_eq!(elf.section_by_name(".text"), None);
+}
+
+const TDATA: u64 = SHF_ALLOC | SHF_WRITE | SHF_TLS;
+const DATA: u64 = SHF_ALLOC | SHF_WRITE;
+
+/// `(sh_type, sh_flags, sh_addr, sh_offset, sh_size)` of a section,
+/// `(p_type, p_offset, p_vaddr, p_filesz, p_memsz)` of a segment, and
+/// whether the segment contains the section.
+const MAPPING_CASES: &[((u32, u64, u64, u64, u64), (u32, u64, u64, u64, u64), bool)] = &[
+    // .tdata belongs to PT_TLS, PT_LOAD and PT_GNU_RELRO only.
+    (
+        (SHT_PROGBITS, TDATA, 0x3000, 0x2000, 0x10),
+        (PT_TLS, 0x2000, 0x3000, 0x10, 0x20),
+        true,
+    ),
+    (
+        (SHT_PROGBITS, TDATA, 0x3000, 0x2000, 0x10),
+        (PT_LOAD, 0x2000, 0x3000, 0x100, 0x100),
+        true,
+    ),
+    (
+        (SHT_PROGBITS, TDATA, 0x3000, 0x2000, 0x10),
+        (PT_GNU_RELRO, 0x2000, 0x3000, 0x100, 0x100),
+        true,
+    ),
+    (
+        (SHT_PROGBITS, TDATA, 0x3000, 0x2000, 0x10),
+        (PT_DYNAMIC, 0x2000, 0x3000, 0x100, 0x100),
+        false,
+    ),
+    // .tbss only takes room in PT_TLS.
+    (
+        (SHT_NOBITS, TDATA, 0x3010, 0x2010, 0x10),
+        (PT_TLS, 0x2000, 0x3000, 0x10, 0x20),
+        true,
+    ),
+    (
+        (SHT_NOBITS, TDATA, 0x3010, 0x2010, 0x10),
+        (PT_LOAD, 0x2000, 0x3000, 0x100, 0x100),
+        false,
+    ),
+    // PT_TLS contains TLS sections only.
+    (
+        (SHT_PROGBITS, DATA, 0x3000, 0x2000, 0x10),
+        (PT_TLS, 0x2000, 0x3000, 0x10, 0x20),
+        false,
+    ),
+    // .bss only needs to fit in memory, past the end of the file image.
+    (
+        (SHT_NOBITS, DATA, 0x3100, 0x2100, 0x100),
+        (PT_LOAD, 0x2000, 0x3000, 0x100, 0x200),
+        true,
+    ),
+    (
+        (SHT_NOBITS, DATA, 0x3100, 0x2100, 0x200),
+        (PT_LOAD, 0x2000, 0x3000, 0x100, 0x200),
+        false,
+    ),
+    // Unallocated sections are not in PT_LOAD even when their bytes are.
+    (
+        (SHT_PROGBITS, 0, 0, 0x2010, 0x10),
+        (PT_LOAD, 0x2000, 0x3000, 0x100, 0x100),
+        false,
+    ),
+    // Empty sections at the start of PT_DYNAMIC and PT_NOTE are left out,
+    // but not inside them nor at the start of PT_LOAD.
+    (
+        (SHT_PROGBITS, DATA, 0x3000, 0x2000, 0),
+        (PT_DYNAMIC, 0x2000, 0x3000, 0x100, 0x100),
+        false,
+    ),
+    (
+        (SHT_PROGBITS, SHF_ALLOC, 0x3000, 0x2000, 0),
+        (PT_NOTE, 0x2000, 0x3000, 0x20, 0x20),
+        false,
+    ),
+    (
+        (SHT_PROGBITS, SHF_ALLOC, 0x3010, 0x2010, 0),
+        (PT_NOTE, 0x2000, 0x3000, 0x20, 0x20),
+        true,
+    ),
+    (
+        (SHT_PROGBITS, DATA, 0x3000, 0x2000, 0),
+        (PT_LOAD, 0x2000, 0x3000, 0x100, 0x100),
+        true,
+    ),
+    // Sections ending past the segment, or wrapping around.
+    (
+        (SHT_PROGBITS, DATA, 0x3010, 0x2010, 0x100),
+        (PT_LOAD, 0x2000, 0x3000, 0x100, 0x100),
+        false,
+    ),
+    (
+        (SHT_PROGBITS, DATA, 0x3010, 0x2010, !0),
+        (PT_LOAD, 0x2000, 0x3000, 0x100, 0x100),
+        false,
+    ),
+];
+
+#[test]
+fn section_in_segment_edge_cases() {
+    for &(s, p, expected) in MAPPING_CASES {
+        let section = Elf64Section {
+            sh_name: 0,
+            sh_type: s.0,
+            sh_flags: s.1,
+            sh_addr: s.2,
+            sh_offset: s.3,
+            sh_size: s.4,
+            sh_link: 0,
+            sh_info: 0,
+            sh_addralign: 1,
+            sh_entsize: 0,
+        };
+        let segment = Elf64Segment {
+            p_type: p.0,
+            p_flags: 0,
+            p_offset: p.1,
+            p_vaddr: p.2,
+            p_paddr: p.2,
+            p_filesz: p.3,
+            p_memsz: p.4,
+            p_align: 1,
+        };
+        assert_eq!(
+            section_in_elf64_segment(&section, &segment),
+            expected,
+            "{:?} in {:?}",
+            section,
+            segment
+        );
+
+        let section = Elf32Section {
+            sh_name: 0,
+            sh_type: s.0,
+            sh_flags: s.1 as u32,
+            sh_addr: s.2 as u32,
+            sh_offset: s.3 as u32,
+            sh_size: s.4 as u32,
+            sh_link: 0,
+            sh_info: 0,
+            sh_addralign: 1,
+            sh_entsize: 0,
+        };
+        let segment = Elf32Segment {
+            p_type: p.0,
+            p_offset: p.1 as u32,
+            p_vaddr: p.2 as u32,
+            p_paddr: p.2 as u32,
+            p_filesz: p.3 as u32,
+            p_memsz: p.4 as u32,
+            p_flags: 0,
+            p_align: 1,
+        };
+        assert_eq!(
+            section_in_elf32_segment(&section, &segment),
+            expected,
+            "{:?} in {:?}",
+            section,
+            segment
+        );
+    }
+}
@@ -0,0 +1,102 @@
+//! Checks the size reports of the files of `tests/fixtures`.
+
+extern crate elf;
+
+mod common;
+
+use elf::*;
+
+const FIXTURES: &[&str] = &["hello", "hello.o", "libfoo.so", "libbar32.so", "bar32.o"];
+
+fn report(fixture: &str) -> SizeReport {
+    common::open(fixture)
+        .parse()
+        .and_then(|elf| elf.size_report())
+        .expect("cannot build size report")
+}
+
+fn find<'a>(entries: &'a [SizeEntry], name: &str) -> &'a SizeEntry {
+    entries
+        .iter()
+        .find(|e| e.name == name)
+        .unwrap_or_else(|| panic!("no entry named {}", name))
+}
+
+#[test]
+fn views_cover_the_file() {
+    for fixture in FIXTURES {
+        let report = report(fixture);
+        for entries in &[&report.sections, &report.segments, &report.symbols] {
+            let file_size: u64 = entries.iter().map(|e| e.file_size).sum();
+            assert_eq!(file_size, report.file_size, "{}", fixture);
+        }
+        let vm_size: u64 = report.symbols.iter().map(|e| e.vm_size).sum();
+        assert_eq!(vm_size, report.vm_size, "{}", fixture);
+    }
+}
+
+#[test]
+fn symbols_and_unclaimed_bytes() {
+    let report = report("hello.o");
+    let text = find(&report.sections, ".text.startup");
+    let main = find(&report.symbols, "main");
+    assert_eq!((main.file_size, main.vm_size), (57, 57));
+    assert_eq!(text.file_size, main.file_size);
+    let counter = find(&report.symbols, "counter");
+    assert_eq!((counter.file_size, counter.vm_size), (0, 4));
+    let rodata = find(&report.symbols, "[.rodata.str1.1]");
+    assert_eq!(
+        rodata.file_size,
+        find(&report.sections, ".rodata.str1.1").file_size
+    );
+}
+
+#[test]
+fn segments() {
+    let report = report("hello");
+    let loads: Vec<&SizeEntry> = report
+        .segments
+        .iter()
+        .filter(|e| e.name.starts_with("LOAD #"))
+        .collect();
+    assert_eq!(loads.len(), 4);
+    assert!(loads.iter().any(|e| e.name.ends_with("[RX]")));
+}
+
+#[test]
+fn diff() {
+    let old = report("hello.o");
+    let new = report("hello");
+    let deltas = diff_sizes(&old.sections, &new.sections);
+    let text = deltas.iter().find(|d| d.name == ".text").expect("no .text");
+    assert_eq!(text.old_file_size, 0);
+    assert!(text.file_delta() > 0);
+    assert!(deltas
+        .iter()
+        .all(|d| d.file_delta() != 0 || d.vm_delta() != 0));
+    assert!(diff_sizes(&new.symbols, &new.symbols).is_empty());
+}
+
+#[test]
+fn overflowing_offsets() {
+    let file = common::open("hello");
+    let (load, text, shoff) = match file.parse().unwrap() {
+        Elf::Elf64(ref e) => (
+            e.segments.iter().position(|p| p.p_type == PT_LOAD).unwrap(),
+            e.section_index(".text").unwrap(),
+            e.header.e_shoff as usize,
+        ),
+        _ => panic!("hello is a 64-bit file"),
+    };
+    // Move the first PT_LOAD, which maps the headers, and the offset of
+    // .text, which holds main, to the very end of the address space: the
+    // report is still built, without overflowing.
+    let mut data = file.data().to_vec();
+    let vaddr = 64 + load * 56 + 16;
+    data[vaddr..vaddr + 8].copy_from_slice(&(!0u64 - 0x10).to_le_bytes());
+    let offset = shoff + text * 64 + 24;
+    data[offset..offset + 8].copy_from_slice(&(!0u64 - 0x10).to_le_bytes());
+
+    let report = Elf::parse(&data).unwrap().size_report().unwrap();
+    assert_eq!(report.file_size, data.len() as u64);
+}
@@ -0,0 +1,77 @@
+//! Symbolizes addresses of the files of `tests/fixtures` with the symbols
+//! `nm -S` lists for them.
+
+extern crate elf;
+
+mod common;
+
+use common::fixture;
+use elf::*;
+use std::fs;
+
+#[test]
+fn functions_and_objects() {
+    let data = fs::read(fixture("debug")).unwrap();
+    let elf = parse_elf64(&data).unwrap().1;
+    let symbolizer = Symbolizer::from_elf64(&elf).unwrap();
+    for &(name, start, size) in &[
+        ("main", 0x1070, 0x2f),
+        ("_start", 0x10a0, 0x22),
+        ("sum_squares", 0x1190, 0x2d),
+        ("buffer_length", 0x11c0, 0x3e),
+        ("total", 0x402c, 4),
+    ] {
+        assert_eq!(symbolizer.symbolize(start), Some((name, 0)));
+        assert_eq!(
+            symbolizer.symbolize(start + size - 1),
+            Some((name, size - 1))
+        );
+    }
+    // Between `buffer_length` and `_fini`, and before the first symbol.
+    assert_eq!(symbolizer.symbolize(0x11fe), None);
+    assert_eq!(symbolizer.symbolize(0x10), None);
+    // Local functions without a size extend up to the next symbol.
+    assert_eq!(symbolizer.symbolize(0x1185), Some(("frame_dummy", 5)));
+}
+
+#[test]
+fn aliases_and_load_bias() {
+    let data = fs::read(fixture("libfoo.so")).unwrap();
+    let elf = parse_elf64(&data).unwrap().1;
+    let mut symbolizer = Symbolizer::from_elf64(&elf).unwrap();
+    assert!(!symbolizer.is_empty());
+    // The `.symver` aliases keep their version in `.symtab`, which comes
+    // before the `.dynsym` names.
+    assert_eq!(symbolizer.symbolize(0x1130), Some(("foo@VERS_1", 1)));
+    assert_eq!(symbolizer.symbolize(0x1136), Some(("foo@@VERS_2", 1)));
+    assert_eq!(symbolizer.symbolize(0x1109 + 0x25), Some(("foo_add", 0x25)));
+    assert_eq!(symbolizer.symbolize(0x4010), Some(("foo_data", 0)));
+
+    symbolizer.set_load_bias(0x7f00_0000_0000);
+    assert_eq!(symbolizer.load_bias(), 0x7f00_0000_0000);
+    assert_eq!(
+        symbolizer.symbolize(0x7f00_0000_4012),
+        Some(("foo_data", 2))
+    );
+    assert_eq!(symbolizer.symbolize(0x4010), None);
+}
+
+#[test]
+fn nested_symbols() {
+    // `outer` encloses `inner`, which encloses `innermost`.
+    let info = STB_GLOBAL << 4 | STT_FUNC;
+    let symbols = vec![
+        Symbol::new("outer", 0x1000, 0x100, info, 0, 1),
+        Symbol::new("inner", 0x1010, 0x40, info, 0, 1),
+        Symbol::new("innermost", 0x1020, 0x10, info, 0, 1),
+        Symbol::new("next", 0x1100, 0x10, info, 0, 1),
+    ];
+    let symbolizer = Symbolizer::new(&[symbols], false);
+    assert_eq!(symbolizer.symbolize(0x1008), Some(("outer", 8)));
+    assert_eq!(symbolizer.symbolize(0x1024), Some(("innermost", 4)));
+    assert_eq!(symbolizer.symbolize(0x1030), Some(("inner", 0x20)));
+    assert_eq!(symbolizer.symbolize(0x1050), Some(("outer", 0x50)));
+    assert_eq!(symbolizer.symbolize(0x10ff), Some(("outer", 0xff)));
+    assert_eq!(symbolizer.symbolize(0x1104), Some(("next", 4)));
+    assert_eq!(symbolizer.symbolize(0x1110), None);
+}
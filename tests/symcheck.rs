@@ -0,0 +1,73 @@
+//! Checks the symbol bindings between the files of `tests/fixtures`.
+
+extern crate elf;
+
+mod common;
+
+use common::open;
+use elf::*;
+
+fn unresolved<'a>(check: &'a SymbolCheck) -> Vec<(usize, &'a str, Option<&'a str>)> {
+    check
+        .unresolved
+        .iter()
+        .map(|u| (u.object, u.name, u.version))
+        .collect()
+}
+
+#[test]
+fn executable_and_library() {
+    let (hello, foo) = (open("hello"), open("libfoo.so"));
+    let objects = vec![hello.parse().unwrap(), foo.parse().unwrap()];
+    let check = SymbolCheck::new(&objects).unwrap();
+    assert_eq!(
+        unresolved(&check),
+        vec![
+            (0, "getenv", Some("GLIBC_2.2.5")),
+            (0, "__libc_start_main", Some("GLIBC_2.34")),
+            (0, "printf", Some("GLIBC_2.2.5")),
+            (1, "__tls_get_addr", Some("GLIBC_2.3")),
+        ]
+    );
+    assert_eq!(check.unresolved[0].file, Some("libc.so.6"));
+    // The copy of `foo_data` in the executable is not a definition.
+    assert!(check.interposed.is_empty());
+}
+
+#[test]
+fn interposition_across_versions() {
+    let (hello, foo, shim) = (open("hello"), open("libfoo.so"), open("libshim.so"));
+    let objects = vec![
+        hello.parse().unwrap(),
+        foo.parse().unwrap(),
+        shim.parse().unwrap(),
+    ];
+    let check = SymbolCheck::new(&objects).unwrap();
+    assert_eq!(
+        check.interposed,
+        vec![
+            InterposedSymbol {
+                name: "foo",
+                objects: vec![1, 1, 2],
+                versions: vec![Some("VERS_1"), Some("VERS_2"), None],
+            },
+            InterposedSymbol {
+                name: "foo_data",
+                objects: vec![1, 2],
+                versions: vec![Some("VERS_1"), None],
+            },
+        ]
+    );
+}
+
+#[test]
+fn missing_version() {
+    let (hello, bar) = (open("hello"), open("libbar32.so"));
+    let objects = vec![hello.parse().unwrap(), bar.parse().unwrap()];
+    let check = SymbolCheck::new(&objects).unwrap();
+    assert!(unresolved(&check).contains(&(0, "foo_add", Some("VERS_1"))));
+    assert!(unresolved(&check).contains(&(1, "ext_fn", None)));
+    // The variable the executable copies has no definition left.
+    assert!(unresolved(&check).contains(&(0, "foo_data", Some("VERS_1"))));
+    assert!(check.interposed.is_empty());
+}
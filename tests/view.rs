@@ -0,0 +1,161 @@
+//! Checks that the lazy views and the streaming readers of the files of
+//! `tests/fixtures` agree with the fully parsed files.
+
+extern crate elf;
+
+mod common;
+
+use common::open;
+use elf::*;
+use std::io::Cursor;
+
+const FIXTURES: &[&str] = &[
+    "hello",
+    "hello.o",
+    "libfoo.so",
+    "libbar32.so",
+    "bar32.o",
+    "debug",
+    "debug.nosections",
+];
+
+fn check_view64(view: &Elf64View, elf: &Elf64) {
+    assert_eq!(view.header, elf.header);
+    assert_eq!(view.segments().iter().collect::<Vec<_>>(), elf.segments);
+    assert_eq!(view.sections().iter().collect::<Vec<_>>(), elf.sections);
+    for (idx, section) in elf.sections.iter().enumerate() {
+        let lazy = view.sections().get(idx).unwrap();
+        assert_eq!(view.section_name(&lazy), elf.section_name(section));
+        assert_eq!(view.section_data(&lazy), elf.section_data(section));
+    }
+    for (idx, segment) in elf.segments.iter().enumerate() {
+        let lazy = view.segments().get(idx).unwrap();
+        assert_eq!(view.segment_data(&lazy), elf.segment_data(segment));
+    }
+    assert!(view.sections().get(elf.sections.len()).is_none());
+}
+
+fn check_view32(view: &Elf32View, elf: &Elf32) {
+    assert_eq!(view.header, elf.header);
+    assert_eq!(view.segments().iter().collect::<Vec<_>>(), elf.segments);
+    assert_eq!(view.sections().iter().collect::<Vec<_>>(), elf.sections);
+    for (idx, section) in elf.sections.iter().enumerate() {
+        let lazy = view.sections().get(idx).unwrap();
+        assert_eq!(view.section_name(&lazy), elf.section_name(section));
+        assert_eq!(view.section_data(&lazy), elf.section_data(section));
+    }
+    for (idx, segment) in elf.segments.iter().enumerate() {
+        let lazy = view.segments().get(idx).unwrap();
+        assert_eq!(view.segment_data(&lazy), elf.segment_data(segment));
+    }
+    assert!(view.sections().get(elf.sections.len()).is_none());
+}
+
+fn build_id(elf: &Elf) -> Option<Vec<u8>> {
+    elf.notes()
+        .unwrap()
+        .into_iter()
+        .find(|n| n.name == "GNU" && n.n_type == NT_GNU_BUILD_ID)
+        .map(|n| n.desc.to_vec())
+}
+
+#[test]
+fn views() {
+    for fixture in FIXTURES {
+        let file = open(fixture);
+        let view = file.view().unwrap();
+        let elf = file.parse().unwrap();
+        assert_eq!(view.class(), elf.class());
+        assert_eq!(view.machine(), elf.machine());
+        assert_eq!(view.build_id().map(|b| b.to_vec()), build_id(&elf));
+        match (&view, &elf) {
+            (&ElfView::Elf64(ref v), &Elf::Elf64(ref e)) => check_view64(v, e),
+            (&ElfView::Elf32(ref v), &Elf::Elf32(ref e)) => check_view32(v, e),
+            _ => panic!("{}: view and file classes differ", fixture),
+        }
+        match (view.to_elf().unwrap(), &elf) {
+            (Elf::Elf64(ref v), &Elf::Elf64(ref e)) => assert_eq!(v.sections, e.sections),
+            (Elf::Elf32(ref v), &Elf::Elf32(ref e)) => assert_eq!(v.sections, e.sections),
+            _ => panic!("{}: view and file classes differ", fixture),
+        }
+    }
+}
+
+#[test]
+fn readers() {
+    for fixture in FIXTURES {
+        let file = open(fixture);
+        let elf = file.parse().unwrap();
+        let mut reader = ElfReader::new(Cursor::new(file.data())).unwrap();
+        assert_eq!(reader.class(), elf.class());
+        assert_eq!(reader.machine(), elf.machine());
+        assert_eq!(reader.build_id().unwrap(), build_id(&elf), "{}", fixture);
+        match (&mut reader, &elf) {
+            (&mut ElfReader::Elf64(ref mut r), &Elf::Elf64(ref e)) => {
+                assert_eq!(r.header, e.header);
+                assert_eq!(r.segments, e.segments);
+                assert_eq!(r.sections, e.sections);
+                for (idx, section) in e.sections.iter().enumerate() {
+                    let name = e.section_name(section);
+                    assert_eq!(r.section_name(section), name);
+                    assert_eq!(
+                        &r.section_data(idx).unwrap()[..],
+                        e.section_data(section).unwrap()
+                    );
+                }
+                for (idx, segment) in e.segments.iter().enumerate() {
+                    assert_eq!(
+                        &r.segment_data(idx).unwrap()[..],
+                        e.segment_data(segment).unwrap()
+                    );
+                }
+            }
+            (&mut ElfReader::Elf32(ref mut r), &Elf::Elf32(ref e)) => {
+                assert_eq!(r.header, e.header);
+                assert_eq!(r.segments, e.segments);
+                assert_eq!(r.sections, e.sections);
+                for (idx, section) in e.sections.iter().enumerate() {
+                    let name = e.section_name(section);
+                    assert_eq!(r.section_name(section), name);
+                    assert_eq!(
+                        &r.section_data(idx).unwrap()[..],
+                        e.section_data(section).unwrap()
+                    );
+                }
+                for (idx, segment) in e.segments.iter().enumerate() {
+                    assert_eq!(
+                        &r.segment_data(idx).unwrap()[..],
+                        e.segment_data(segment).unwrap()
+                    );
+                }
+            }
+            _ => panic!("{}: reader and file classes differ", fixture),
+        }
+    }
+}
+
+#[test]
+fn malformed_readers() {
+    // Each corruption of the header is rejected by the parser and the
+    // reader alike: program headers past the end of the file, section
+    // headers overlapping them, and an e_shoff so large the end of the
+    // table wraps around.
+    let cases: &[(&str, usize, &[u8])] = &[
+        ("hello", 32, &[0xff, 0xff, 0, 0]),
+        ("hello", 40, &[64, 0, 0, 0, 0, 0, 0, 0]),
+        ("hello", 40, &[0xff; 8]),
+        ("libbar32.so", 28, &[0xff, 0xff, 0, 0]),
+        ("libbar32.so", 32, &[52, 0, 0, 0]),
+    ];
+    for &(fixture, offset, bytes) in cases {
+        let mut data = open(fixture).data().to_vec();
+        data[offset..offset + bytes.len()].copy_from_slice(bytes);
+        assert!(Elf::parse(&data).is_err(), "{} at {}", fixture, offset);
+        assert!(
+            ElfReader::new(Cursor::new(&data[..])).is_err(),
+            "{} at {}",
+            fixture,
+            offset
+        );
+    }
+}